@@ -0,0 +1,62 @@
+//! Per-job overhead of batch conversions with a configuration compiled for each job or shared by them.
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use structopt::StructOpt;
+
+use vcf2rdf::cli::converter::{Converter, Options};
+use vcf2rdf::config::{CompiledConfig, Config};
+
+const CONFIG: &str = "base: http://example.org/\nsubject_template: \"{chrom}-{pos}-{ref}-{alt}\"\nreference:\n  \"20\":\n    reference: http://identifiers.org/hco/20/GRCh37\n";
+
+const INPUT: &str = "test/vcf_spec.vcf.gz";
+
+const JOBS: usize = 8;
+
+fn batch(c: &mut Criterion) {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.yaml");
+    std::fs::write(&config, CONFIG).unwrap();
+
+    let options = || {
+        Options::from_iter(vec![
+            "convert",
+            "--config",
+            config.to_str().unwrap(),
+            "--no-provenance",
+            INPUT,
+        ])
+    };
+    let inputs = vec![PathBuf::from(INPUT)];
+    let output = dir.path().join("out.ttl");
+
+    let mut group = c.benchmark_group("batch");
+
+    group.bench_function("compile per job", |b| {
+        b.iter(|| {
+            for _ in 0..JOBS {
+                Converter::from_config(Config::from_path(&config).unwrap(), options())
+                    .unwrap()
+                    .convert(&inputs, Some(output.clone()))
+                    .unwrap();
+            }
+        })
+    });
+
+    let compiled = Arc::new(CompiledConfig::compile(Config::from_path(&config).unwrap()).unwrap());
+    group.bench_function("shared compiled config", |b| {
+        b.iter(|| {
+            for _ in 0..JOBS {
+                Converter::new(Arc::clone(&compiled), options())
+                    .convert(&inputs, Some(output.clone()))
+                    .unwrap();
+            }
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, batch);
+criterion_main!(benches);
@@ -0,0 +1,49 @@
+//! Resolution of contigs to sequences by key, `contig_aliases`, assembly aliases and `reference_default`.
+use std::collections::BTreeMap;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use vcf2rdf::config::Sequence;
+use vcf2rdf::util::template::Template;
+use vcf2rdf::vcf::reader::{ReaderBuilder, REFERENCE_DEFAULT_PLACEHOLDERS};
+
+fn resolve(c: &mut Criterion) {
+    let mut reference = BTreeMap::new();
+    let mut aliases = BTreeMap::new();
+    for i in 1..=22 {
+        reference.insert(
+            i.to_string(),
+            Some(Sequence {
+                name: Some(i.to_string()),
+                reference: Some(format!("http://identifiers.org/hco/{}/GRCh38", i)),
+                ..Default::default()
+            }),
+        );
+        aliases.insert(format!("chr{}_alias", i), i.to_string());
+    }
+
+    let builder = ReaderBuilder::new()
+        .reference(reference)
+        .contig_aliases(aliases)
+        .reference_default(
+            Template::parse(
+                "https://identifiers.org/insdc/{contig}",
+                REFERENCE_DEFAULT_PLACEHOLDERS,
+            )
+            .unwrap(),
+        );
+
+    let mut group = c.benchmark_group("resolve");
+    for (label, contig) in &[
+        ("key", "1"),
+        ("contig alias", "chr1_alias"),
+        ("assembly alias", "NC_000001.11"),
+        ("reference default", "MN908947.3"),
+    ] {
+        group.bench_function(*label, |b| b.iter(|| builder.resolve(black_box(contig))));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, resolve);
+criterion_main!(benches);
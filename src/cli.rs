@@ -5,6 +5,7 @@ use structopt::StructOpt;
 pub mod compressor;
 pub mod converter;
 pub mod generator;
+pub mod normalizer;
 pub mod statistics;
 
 #[derive(StructOpt, Debug)]
@@ -21,4 +22,7 @@ pub enum Command {
 
     /// Generates template.
     Generate(generator::Options),
+
+    /// Normalizes variants of VCF or TSV (chrom, pos, ref and alt) from stdin.
+    Normalize(normalizer::Options),
 }
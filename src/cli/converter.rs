@@ -1,80 +1,2056 @@
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::ffi::CStr;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
+use chrono::{SecondsFormat, Utc};
+use log::*;
+use rust_htslib::htslib;
+use sha2::{Digest, Sha256};
 use structopt::StructOpt;
-use strum::{EnumString, EnumVariantNames, VariantNames};
+use strum::VariantNames;
+use tempfile::TempDir;
 
-use crate::config::Config;
-use crate::errors::Result;
+use crate::cli::generator::{self, Naming};
+use crate::config::{CompiledConfig, Config};
+use crate::errors::{Error, Result};
+use crate::rdf::iri;
 use crate::rdf::namespace::Namespace;
-use crate::rdf::turtle_writer::{SubjectFormatter, TurtleWriter};
+use crate::rdf::turtle_writer::provenance::Provenance;
+use crate::rdf::turtle_writer::{
+    AlleleAlphabet, CrosscheckVC, EntryNodes, EntrySeparator, RdfStarMode, Subject,
+    SubjectFormatter, TurtleWriter, WriterOptions,
+};
 use crate::rdf::writer::Writer;
+use crate::summary::Summary;
+use crate::util::path;
+use crate::util::vcf::{self, compress, tabix};
+use crate::vcf::assembly::AssemblyDefinition;
+use crate::vcf::exclude::ExcludeList;
+use crate::vcf::fasta::Fasta;
 use crate::vcf::reader::ReaderBuilder;
+use crate::vcf::region::{self, Region};
+use crate::vcf::sample;
 
-#[derive(EnumString, EnumVariantNames, Debug)]
-#[strum(serialize_all = "snake_case")]
-pub enum Subject {
-    ID,
-    Location,
-    Reference,
-    NormalizedLocation,
-    NormalizedReference,
-}
+use self::error_report::ErrorReport;
+use self::output::{input_stem, Output, OutputTemplate};
+
+mod error_report;
+mod output;
+
+/// Fraction of failures to decode an INFO value, above which a warning is emitted.
+const INFO_FAILURE_THRESHOLD: f64 = 0.5;
 
 #[derive(StructOpt, Debug)]
 pub struct Options {
-    /// Path to configuration yaml.
-    #[structopt(short, long, parse(from_os_str))]
-    pub config: PathBuf,
+    /// Path to configuration in YAML, JSON or TOML, which may be omitted if `--assembly` or `--assembly-file` is passed.
+    #[structopt(
+        short,
+        long,
+        parse(from_os_str),
+        required_unless_one = &["assembly", "assembly-file"]
+    )]
+    pub config: Option<PathBuf>,
 
-    /// Processes only one record and exit.
+    /// Print the effective configuration merged with those it `extends` and exit without converting.
     #[structopt(long)]
+    pub print_config: bool,
+
+    /// Pre-defined assembly to map contigs not configured in `reference`, or `auto` to detect it from contigs.
+    #[structopt(long, possible_values = generator::Assembly::VARIANTS)]
+    pub assembly: Option<generator::Assembly>,
+
+    /// Path to YAML defining an assembly, of which sequences fill contigs not configured in `reference`.
+    #[structopt(long, parse(from_os_str))]
+    pub assembly_file: Option<PathBuf>,
+
+    /// Processes only one record and exit. Same as `--limit 1`.
+    #[structopt(long, conflicts_with = "limit")]
     pub rehearsal: bool,
 
+    /// Skip the first N records without converting them.
+    #[structopt(long)]
+    pub skip: Option<u64>,
+
+    /// Stop after writing N records. Entries of all alternate alleles of the last record are written.
+    #[structopt(long)]
+    pub limit: Option<u64>,
+
     /// Do not normalize faldo representation.
     #[structopt(long)]
     pub no_normalize: bool,
 
-    /// Strategy to generate a subject (use blank node if not specified).
+    /// Strategy to generate a subject (use `subject` or `subject_template` in configuration or blank node if not specified).
     /// If use `id`, ensure that all values at ID column are present and unique.
+    /// The first of multiple IDs is percent-encoded and resolved against `base` in configuration.
     #[structopt(short, long, possible_values = Subject::VARIANTS)]
     pub subject: Option<Subject>,
 
-    /// Path to file to process.
-    #[structopt(parse(from_os_str))]
-    pub input: PathBuf,
+    /// INFO key whose value is used as a subject if `--subject info` is passed.
+    #[structopt(long, required_if("subject", "info"))]
+    pub subject_key: Option<String>,
+
+    /// Path to FASTA of the reference genome to normalize alleles if `--subject vrs` is passed,
+    /// indexed by `samtools faidx` (built if missing).
+    #[structopt(long, parse(from_os_str), required_if("subject", "vrs"))]
+    pub fasta: Option<PathBuf>,
+
+    /// Append the allele index to subjects of multi-allelic records (`#<N>`, or `-alt<N>` if the subject has a fragment).
+    /// Subjects of records with one entry, after skipping e.g. `*` alleles, are not changed.
+    #[structopt(long)]
+    pub subject_suffix_index: bool,
+
+    /// IRI prepended to relative subjects instead of resolving them against `base`.
+    #[structopt(long)]
+    pub subject_prefix: Option<String>,
+
+    /// Write columns of the original VCF line (`gvo:vcfLine`) to reconstruct it from RDF.
+    #[structopt(long)]
+    pub emit_vcf_roundtrip: bool,
+
+    /// Write INFO numbers and flags as bare tokens (e.g. `0.5`) instead of typed literals (e.g. `"0.5"^^xsd:float`).
+    #[structopt(long)]
+    pub plain_literals: bool,
+
+    /// Comma-separated INFO keys to write instead of `info` in configuration, e.g. `RS,CAF,CLNSIG`.
+    #[structopt(long, use_delimiter = true)]
+    pub info: Option<Vec<String>>,
+
+    /// Do not write INFO values regardless of `info` in configuration.
+    #[structopt(long, conflicts_with = "info")]
+    pub no_info: bool,
+
+    /// Comma-separated INFO keys not to write, excluded from `info` in configuration.
+    #[structopt(long, use_delimiter = true)]
+    pub exclude_info: Vec<String>,
+
+    /// Write INFO values of `info_predicates` (including built-in `AF`, `AC` and `AN`) in `gvo:info` as well.
+    #[structopt(long)]
+    pub keep_raw_info: bool,
+
+    /// Write the INFO column as is in `gvo:infoString` in addition to `gvo:info`.
+    #[structopt(long)]
+    pub raw_info: bool,
+
+    /// Write values of INFO keys with Number=G or Number=. as ordered lists, e.g. `rdf:value ( 1 2 3 )`.
+    #[structopt(long)]
+    pub info_lists: bool,
+
+    /// Link each INFO node to the term of the key (e.g. `gvo:RS`), which is defined once with the description in the header.
+    #[structopt(long)]
+    pub info_key_iris: bool,
+
+    /// Predicate linking INFO nodes to the terms of keys if `--info-key-iris` is passed.
+    /// `gvo:infoKey` is reserved for the INFO key of RDF-star statements, which is a literal.
+    #[structopt(long, default_value = "gvo:infoField")]
+    pub info_key_predicate: String,
+
+    /// Abort if the same subject is generated for different records (tracked as `--strict-duplicates`), a built-in prefix is overridden,
+    /// or a Number=A INFO value does not have one value per alternate allele.
+    /// Malformed records abort the conversion as well unless `--lenient` is passed.
+    #[structopt(long)]
+    pub strict: bool,
+
+    /// Skip records failing to be read or converted with a warning instead of aborting.
+    #[structopt(long, conflicts_with = "strict")]
+    pub lenient: bool,
+
+    /// Track subjects exactly to report both locations of duplicates, instead of by a bloom filter
+    /// which may report false positives. Memory grows with the number of subjects, though the bloom filter
+    /// takes only about 1.6 MiB up to a million subjects and 240 MiB up to 100 million.
+    #[structopt(long)]
+    pub strict_duplicates: bool,
+
+    /// Path to write records skipped by `--lenient` as TSV of input, contig, position and error.
+    #[structopt(long, parse(from_os_str), requires = "lenient")]
+    pub error_report: Option<PathBuf>,
+
+    /// Allow namespaces in configuration to map built-in prefixes (e.g. `gvo`) to different IRIs.
+    #[structopt(long)]
+    pub allow_prefix_override: bool,
+
+    /// Use blank nodes labeled by record and allele index (e.g. `_:r42_0`) for entries without subjects.
+    #[structopt(long)]
+    pub labeled_blank_nodes: bool,
+
+    /// Use skolem IRIs under the IRI instead of blank nodes for entries without subjects.
+    #[structopt(long, conflicts_with = "labeled-blank-nodes")]
+    pub skolem_base: Option<String>,
+
+    /// Replace alleles in a subject with a hash if the combined length of ref and alt exceeds the value.
+    #[structopt(long, requires = "subject")]
+    pub subject_hash_threshold: Option<usize>,
+
+    /// Compare computed variant type against dbSNP `VC` tag and report them in the summary.
+    #[structopt(long)]
+    pub crosscheck_vc: bool,
+
+    /// Use the class of `VC` for rdf:type if it disagrees with computed variant type.
+    #[structopt(long, requires = "crosscheck-vc")]
+    pub trust_vc: bool,
+
+    /// Write a comment line with chromosome, position and ID before each entry.
+    /// Comments are allowed only for Turtle output, not for outputs named `*.nt` or `*.nq`.
+    #[structopt(long)]
+    pub record_comments: bool,
+
+    /// Separator between entries.
+    #[structopt(long, default_value = "blank_line", possible_values = EntrySeparator::VARIANTS)]
+    pub entry_separator: EntrySeparator,
+
+    /// Path to output file (use standard output if not specified).
+    #[structopt(short, long, parse(from_os_str))]
+    pub output: Option<PathBuf>,
+
+    /// Template of output file names with placeholders {input_stem}, {contig}, {chunk}, {ext} and {date}.
+    /// Zero-padded width can be specified like {chunk:04}.
+    #[structopt(long, conflicts_with = "output")]
+    pub output_template: Option<String>,
+
+    /// Write a file per contig, named by inserting the contig name before the extension of output.
+    #[structopt(long)]
+    pub split_by_contig: bool,
+
+    /// Write a new file after the number of records, named by inserting the chunk number before the extension of output.
+    #[structopt(long)]
+    pub split_size: Option<u64>,
+
+    /// Write a new file before the bytes of entries exceed the number, named like --split-size.
+    /// Entries of a record are never split, so a record larger than the number is written to a file by itself.
+    #[structopt(long, conflicts_with = "split-size")]
+    pub chunk_bytes: Option<u64>,
+
+    /// Write paths of output files, one per line, to the path.
+    #[structopt(long, parse(from_os_str))]
+    pub manifest: Option<PathBuf>,
+
+    /// Do not write comment lines describing how the output is generated.
+    #[structopt(long)]
+    pub no_banner: bool,
+
+    /// Do not write provenance of the output (the banner comment and a dataset resource).
+    #[structopt(long)]
+    pub no_provenance: bool,
+
+    /// Describe samples with `##SAMPLE` and `##PEDIGREE` meta-information lines.
+    #[structopt(long)]
+    pub samples: bool,
+
+    /// Write `gvo:genotype` of samples carrying the alternate allele of each entry.
+    #[structopt(long)]
+    pub with_genotypes: bool,
+
+    /// Write homozygous reference and missing genotypes as well.
+    #[structopt(long, requires = "with-genotypes")]
+    pub include_ref_genotypes: bool,
+
+    /// Write counts of genotypes and the allele frequency of samples instead of `gvo:genotype`.
+    #[structopt(long, conflicts_with = "with-genotypes")]
+    pub genotype_summary: bool,
+
+    /// Replace sample names with pseudonyms of HMAC-SHA256 keyed by the secret, which are stable across files.
+    #[structopt(long, value_name = "secret")]
+    pub pseudonymize_samples: Option<String>,
+
+    /// Write a TSV of sample names and their pseudonyms to the path, readable only by the owner.
+    #[structopt(long, parse(from_os_str), requires = "pseudonymize-samples")]
+    pub pseudonym_map: Option<PathBuf>,
+
+    /// Write INFO values as RDF-star annotations (experimental): `allele` annotates the `gvo:alt` triple with keys
+    /// listed in `rdf_star` of configuration, and `statement` annotates triples of `info_predicates`.
+    #[structopt(long, possible_values = RdfStarMode::VARIANTS)]
+    pub rdf_star_mode: Option<RdfStarMode>,
+
+    /// Write a VoID description of the output to the path.
+    #[structopt(long, parse(from_os_str))]
+    pub void: Option<PathBuf>,
+
+    /// Path to YAML listing `rsIDs` and `positions` of variants to exclude from conversion.
+    #[structopt(long, parse(from_os_str))]
+    pub exclude_list: Option<PathBuf>,
+
+    /// Skip records with filters other than PASS. Records without filters (`.`) are converted.
+    #[structopt(long)]
+    pub require_pass: bool,
+
+    /// Comma-separated filters of which records are skipped, e.g. `LowQual,RF`.
+    #[structopt(long, use_delimiter = true)]
+    pub exclude_filter: Vec<String>,
+
+    /// Skip symbolic alleles, e.g. `<DEL>`, and breakends to convert only SNVs and indels.
+    #[structopt(long)]
+    pub skip_symbolic: bool,
+
+    /// Write `*` alleles overlapped by upstream deletions as `gvo:OverlapDeletion` instead of skipping them.
+    #[structopt(long)]
+    pub emit_star_alleles: bool,
+
+    /// Accept only alleles of uppercase IUPAC codes, rejecting lowercase bases.
+    #[structopt(long, conflicts_with = "iupac")]
+    pub strict_alleles: bool,
+
+    /// Accept alleles of IUPAC ambiguity codes in any case, besides ACGTN.
+    #[structopt(long)]
+    pub iupac: bool,
+
+    /// Path to index file (tbi or csi) if not located next to the input.
+    #[structopt(long, parse(from_os_str))]
+    pub index: Option<PathBuf>,
+
+    /// Comma-separated regions to convert, e.g. `chr1:1000-2000,chr2`, of which contigs may be aliases.
+    #[structopt(long, use_delimiter = true)]
+    pub regions: Vec<Region>,
+
+    /// BED file of regions to convert, which are combined with `--regions`.
+    #[structopt(long, parse(from_os_str))]
+    pub regions_file: Option<PathBuf>,
+
+    /// Number of threads to decompress the input with.
+    /// Records are still converted and written in the main thread.
+    #[structopt(long, default_value = "1")]
+    pub read_threads: usize,
+
+    /// Read records sequentially without an index, e.g. from a pipe.
+    #[structopt(
+        long,
+        conflicts_with_all = &["index", "regions", "regions-file", "auto-prepare"]
+    )]
+    pub no_index: bool,
+
+    /// Compress and index the input into a temporary copy if it is a plain-text VCF.
+    #[structopt(long, conflicts_with = "index")]
+    pub auto_prepare: bool,
+
+    /// Paths to files to process in order, or `-` to read from stdin.
+    ///
+    /// Wildcards in file names such as `chr*.vcf.gz` are expanded.
+    /// Records are written to one output unless `--output` contains `{}`, which is replaced with the stem of each input.
+    #[structopt(parse(from_os_str), required = true)]
+    pub input: Vec<PathBuf>,
 }
 
 pub fn run(options: Options) -> Result<()> {
-    let config = Config::from_path(options.config)?;
+    let inputs = options
+        .input
+        .iter()
+        .map(|x| match x.to_str() {
+            // `?` in URLs is a query
+            Some(url) if vcf::is_url(url) => Ok(vec![x.to_owned()]),
+            _ => path::glob(x),
+        })
+        .collect::<Result<Vec<_>>>()?
+        .concat();
+
+    let mut config = match options.config.as_ref() {
+        Some(path) => Config::from_path(path)?,
+        None => Config::default(),
+    };
+    if let Some(assembly) = options.assembly.as_ref() {
+        fill_reference_by_assembly(&mut config, assembly, &inputs)?;
+    }
+    if let Some(path) = options.assembly_file.as_ref() {
+        let definition = AssemblyDefinition::from_path(path)?;
+        config
+            .assembly
+            .get_or_insert_with(|| definition.name.clone());
+        config.fill_reference(&definition.as_assembly());
+    }
+
+    if options.print_config {
+        config.validate()?;
+        print!("{}", serde_yaml::to_string(&config)?);
+        return Ok(());
+    }
+
+    if !options.allow_prefix_override {
+        for (prefix, iri) in Namespace::shadowed_prefixes(&config) {
+            let message = format!(
+                "namespaces: {} is overridden with {}, pass --allow-prefix-override if intended",
+                prefix, iri
+            );
+            if options.strict {
+                Err(Error::InvalidConfigurationError(message.clone()))?;
+            }
+            warn!("{}", message);
+        }
+    }
+
+    let config = CompiledConfig::compile(config)?;
+
+    if options.index.is_some() && inputs.len() > 1 {
+        Err(Error::InvalidArgumentError(
+            "--index can not be used with multiple inputs".to_owned(),
+        ))?
+    }
+
+    if options.record_comments {
+        let outputs = options
+            .output
+            .iter()
+            .map(|x| x.to_string_lossy().to_string())
+            .chain(options.output_template.iter().cloned());
+
+        for output in outputs {
+            if output.ends_with(".nt") || output.ends_with(".nq") {
+                Err(Error::InvalidArgumentError(format!(
+                    "--record-comments is allowed only for Turtle output, not {}",
+                    output
+                )))?
+            }
+        }
+    }
+
+    // records skipped by `--lenient` are reported in one file across outputs
+    let mut report = ErrorReport::new(options.error_report.as_deref())?;
+    // records are counted across outputs to label entries uniquely in a run
+    let mut records: u64 = 0;
+
+    match options
+        .output
+        .as_ref()
+        .filter(|x| x.to_string_lossy().contains("{}"))
+    {
+        Some(pattern) => {
+            let paths: Vec<PathBuf> = inputs
+                .iter()
+                .map(|x| PathBuf::from(pattern.to_string_lossy().replace("{}", &input_stem(x))))
+                .collect();
+
+            if let Some(path) = paths
+                .iter()
+                .find(|&x| paths.iter().filter(|&y| y == x).count() > 1)
+            {
+                Err(Error::InvalidArgumentError(format!(
+                    "output file name collides: {}",
+                    path.display()
+                )))?
+            }
+
+            for (input, path) in inputs.iter().zip(paths) {
+                convert(
+                    &options,
+                    &config,
+                    std::slice::from_ref(input),
+                    Some(path),
+                    &mut report,
+                    &mut records,
+                )?
+                .report(std::io::stderr())?;
+            }
+        }
+        None => convert(
+            &options,
+            &config,
+            &inputs,
+            options.output.clone(),
+            &mut report,
+            &mut records,
+        )?
+        .report(std::io::stderr())?,
+    }
+
+    report.flush()
+}
+
+/// A converter for library use, e.g. in batch jobs, of which the configuration is compiled once.
+///
+/// It is `Send + Sync`, so that jobs can convert inputs concurrently with one converter,
+/// or with converters sharing an `Arc<CompiledConfig>`.
+pub struct Converter {
+    config: Arc<CompiledConfig>,
+    options: Options,
+}
+
+impl Converter {
+    /// Create a converter with `config` compiled in advance.
+    ///
+    /// `config` and `input` of `options` are ignored.
+    pub fn new(config: Arc<CompiledConfig>, options: Options) -> Self {
+        Converter { config, options }
+    }
+
+    /// Create a converter compiling `config`, which must be filled by assemblies if needed.
+    pub fn from_config(config: Config, options: Options) -> Result<Self> {
+        Ok(Converter::new(
+            Arc::new(CompiledConfig::compile(config)?),
+            options,
+        ))
+    }
+
+    /// Convert `inputs` in order into one output stream at `output`, or stdout if `None`.
+    ///
+    /// Returns the summary of the conversion.
+    pub fn convert(&self, inputs: &[PathBuf], output: Option<PathBuf>) -> Result<Summary> {
+        let mut report = ErrorReport::new(self.options.error_report.as_deref())?;
+        let mut records: u64 = 0;
+
+        let summary = convert(
+            &self.options,
+            &self.config,
+            inputs,
+            output,
+            &mut report,
+            &mut records,
+        )?;
+        report.flush()?;
+
+        Ok(summary)
+    }
+}
+
+/// Map contigs in headers of `inputs` to sequences of `assembly`, except those configured in `reference`.
+///
+/// The header of stdin can not be read in advance, so all sequences of the assembly are added instead.
+fn fill_reference_by_assembly(
+    config: &mut Config,
+    assembly: &generator::Assembly,
+    inputs: &[PathBuf],
+) -> Result<()> {
+    let mut contigs: Vec<(String, Option<u64>)> = Vec::new();
+    for input in inputs.iter().filter(|&x| x != Path::new("-")) {
+        let reader = ReaderBuilder::new().require_index(false).path(input)?;
+        for contig in reader.contig_lengths() {
+            if !contigs.contains(&contig) {
+                contigs.push(contig);
+            }
+        }
+    }
+
+    let builtin = match generator::resolve_assembly(assembly, &contigs)? {
+        Some(x) => x,
+        None => return Ok(()),
+    };
+    config
+        .assembly
+        .get_or_insert_with(|| builtin.name().to_owned());
+
+    let names: Vec<String> = contigs.into_iter().map(|(x, _)| x).collect();
+    let (reference, _) = generator::map_contigs(&names, Some(builtin), Naming::Name);
+    for (contig, sequence) in reference {
+        config.reference.entry(contig).or_insert(sequence);
+    }
+
+    if inputs.iter().any(|x| x == Path::new("-")) {
+        config.fill_reference(builtin);
+    }
+
+    Ok(())
+}
+
+/// Convert `inputs` in order into one output stream at `path`, or stdout if `None`.
+///
+/// `records` is the number of records read by former conversions of the run, which is advanced by this one.
+///
+/// Returns the summary of the conversion.
+fn convert(
+    options: &Options,
+    config: &CompiledConfig,
+    inputs: &[PathBuf],
+    path: Option<PathBuf>,
+    report: &mut ErrorReport,
+    records: &mut u64,
+) -> Result<Summary> {
+    let template = match options.output_template.as_ref() {
+        Some(v) => Some(OutputTemplate::new(v, &inputs[0])?),
+        None => None,
+    };
+
+    let mut output = Output::new(
+        path,
+        template,
+        options.split_by_contig,
+        options.split_size,
+        options.chunk_bytes,
+    )?;
+
+    let mut writer = TurtleWriter::new(output.open()?);
+
+    writer.configure(config);
+    writer.first_record(*records);
+
+    let formatter = match options.subject.as_ref() {
+        Some(Subject::Info) => match options.subject_key.as_ref() {
+            Some(key) => Some(SubjectFormatter::info(key.to_owned())),
+            None => Err(Error::InvalidArgumentError(
+                "--subject-key is required for --subject info".to_owned(),
+            ))?,
+        },
+        Some(Subject::VRS) => match options.fasta.as_ref() {
+            Some(path) => Some(SubjectFormatter::vrs(Fasta::from_path(path)?)),
+            None => Err(Error::InvalidArgumentError(
+                "--fasta is required for --subject vrs".to_owned(),
+            ))?,
+        },
+        Some(v) => Some(SubjectFormatter::with_hash_threshold(
+            v,
+            options.subject_hash_threshold,
+        )),
+        None => match config.subject_key.as_ref() {
+            Some(key) => Some(SubjectFormatter::info(key.to_owned())),
+            None => config
+                .subject_template
+                .as_ref()
+                .map(|x| SubjectFormatter::from_template(x.clone())),
+        },
+    };
+
+    let uses_name = match options.subject.as_ref() {
+        Some(v) => matches!(v, Subject::Location | Subject::NormalizedLocation),
+        None => config
+            .subject_template
+            .as_ref()
+            .map_or(false, |x| x.uses_name()),
+    };
+
+    if uses_name {
+        config.check_sequence_names()?;
+    }
+
+    if let Some(mut formatter) = formatter {
+        match options
+            .subject_prefix
+            .as_ref()
+            .or_else(|| config.subject_prefix.as_ref())
+        {
+            Some(prefix) => formatter = formatter.prefix(prefix.to_owned()),
+            None if formatter.is_relative() => match config.namespace.base.as_ref() {
+                None if config.sequences_have_base() => {}
+                None => Err(Error::InvalidArgumentError(
+                    "relative subjects require base in configuration, base of every contig or --subject-prefix"
+                        .to_owned(),
+                ))?,
+                Some(base) if !base.ends_with('/') && !base.ends_with('#') => warn!(
+                    "base: {} does not end with / or #, subjects are joined with / but other relative IRIs resolve against its parent",
+                    base
+                ),
+                Some(_) => {}
+            },
+            None => {}
+        }
+
+        writer.subject_formatter(formatter);
+    }
+
+    writer.pseudonymize_samples(options.pseudonymize_samples.clone());
+
+    let info_key_predicate = if options.info_key_iris {
+        if !config.namespace.is_resolvable(&options.info_key_predicate) {
+            Err(Error::InvalidArgumentError(format!(
+                "undefined prefix in --info-key-predicate {}",
+                options.info_key_predicate
+            )))?
+        }
+        if options.info_key_predicate == "gvo:infoKey" {
+            Err(Error::InvalidArgumentError(format!(
+                "--info-key-predicate {} is reserved for INFO keys of RDF-star statements",
+                options.info_key_predicate
+            )))?
+        }
+        Some(options.info_key_predicate.clone())
+    } else {
+        None
+    };
+
+    if let Some(path) = options.exclude_list.as_ref() {
+        writer.exclude(ExcludeList::from_path(path)?);
+    }
+
+    let entry_nodes = if let Some(base) = options.skolem_base.as_ref() {
+        if !iri::is_absolute(base) {
+            Err(Error::InvalidArgumentError(format!(
+                "--skolem-base must be an absolute IRI: {}",
+                base
+            )))?;
+        }
+        EntryNodes::Skolem(base.to_owned())
+    } else if options.labeled_blank_nodes {
+        EntryNodes::Labeled
+    } else {
+        EntryNodes::Anonymous
+    };
+
+    if options.rdf_star_mode == Some(RdfStarMode::Allele) {
+        if config.rdf_star.is_empty() {
+            warn!("--rdf-star-mode allele is passed but no INFO keys are listed in rdf_star of configuration.");
+        }
+        writer.rdf_star(config.rdf_star.clone());
+    }
+
+    writer.options(WriterOptions {
+        subject_suffix_index: options.subject_suffix_index,
+        crosscheck_vc: match (options.crosscheck_vc, options.trust_vc) {
+            (true, true) => CrosscheckVC::Trust,
+            (true, false) => CrosscheckVC::Count,
+            _ => CrosscheckVC::Disabled,
+        },
+        record_comments: options.record_comments,
+        entry_separator: options.entry_separator,
+        entry_nodes,
+        vcf_roundtrip: options.emit_vcf_roundtrip,
+        plain_literals: options.plain_literals,
+        info_lists: options.info_lists,
+        info_key_predicate,
+        keep_raw_info: options.keep_raw_info,
+        info_string: options.raw_info,
+        genotypes: options.with_genotypes,
+        ref_genotypes: options.include_ref_genotypes,
+        genotype_summary: options.genotype_summary,
+        rdf_star_mode: options.rdf_star_mode,
+        require_pass: options.require_pass,
+        excluded_filters: options.exclude_filter.clone(),
+        skip_symbolic: options.skip_symbolic,
+        emit_star_alleles: options.emit_star_alleles,
+        allele_alphabet: if options.strict_alleles {
+            AlleleAlphabet::Strict
+        } else if options.iupac {
+            AlleleAlphabet::Iupac
+        } else {
+            AlleleAlphabet::Default
+        },
+        strict: options.strict,
+        strict_duplicates: options.strict_duplicates,
+    });
+
+    let created = Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true);
+
+    if !options.no_banner && !options.no_provenance {
+        writer.banner(banner(options, inputs, &created)?);
+    }
+
+    if !options.no_provenance {
+        writer.provenance(Provenance {
+            source: inputs
+                .iter()
+                .map(|input| {
+                    input.file_name().map_or_else(
+                        || input.display().to_string(),
+                        |x| x.to_string_lossy().to_string(),
+                    )
+                })
+                .collect(),
+            created,
+            version: format!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION")),
+            subject: match (options.subject.as_ref(), config.subject_key.as_ref()) {
+                (Some(v), _) => v.as_ref().to_owned(),
+                (None, Some(key)) => format!("info:{}", key),
+                (None, None) => match config.subject_template.as_ref() {
+                    Some(template) => template.as_str().to_owned(),
+                    None => "blank_node".to_owned(),
+                },
+            },
+        });
+    }
+
+    let mut builder = config
+        .reader_builder()
+        .normalize(!options.no_normalize)
+        .exclude_info_keys(options.exclude_info.clone());
+    if let Some(keys) = options.info.as_ref() {
+        builder = builder.info_keys(keys.clone());
+    }
+    if options.no_info {
+        builder = builder.info_keys(Vec::new());
+    }
+    if let Some(index) = options.index.as_ref() {
+        builder = builder.index_path(index.to_owned());
+    }
+    if options.no_index {
+        builder = builder.require_index(false);
+    }
+    if options.limit == Some(0) {
+        Err(Error::InvalidArgumentError(
+            "--limit must be greater than 0".to_owned(),
+        ))?
+    }
+    let limit = if options.rehearsal {
+        Some(1)
+    } else {
+        options.limit
+    };
+    let skip = options.skip.unwrap_or(0);
+
+    if options.read_threads == 0 {
+        Err(Error::InvalidArgumentError(
+            "--read-threads must be greater than 0".to_owned(),
+        ))?
+    }
+    builder = builder.threads(options.read_threads);
+    let mut regions = options.regions.clone();
+    if let Some(path) = options.regions_file.as_ref() {
+        let targets = region::read_bed(path)?;
+        if targets.is_empty() {
+            Err(Error::InvalidArgumentError(format!(
+                "no regions in {}",
+                path.display()
+            )))?
+        }
+        regions.extend(targets);
+    }
+    let restricted = !regions.is_empty();
+    if restricted {
+        builder = builder.regions(regions);
+    }
+    if options.with_genotypes || options.genotype_summary {
+        if let Some(samples) = config.samples.as_ref() {
+            builder = builder.samples(samples.clone());
+        }
+        builder = builder.exclude_samples(config.exclude_samples.clone());
+    }
+
+    let mut records_read: u64 = 0;
+    let mut records_written: u64 = 0;
+    let mut stopped = false;
+    let mut records_indexed: u64 = 0;
+    let skipped = report.skipped();
+    let mut samples: Vec<sample::Sample> = Vec::new();
+
+    'inputs: for input in inputs {
+        // the temporary copy is removed on drop at the end of conversion of the input
+        let prepared = if options.auto_prepare {
+            prepare(input)?
+        } else {
+            None
+        };
+        // caches of the header and sequences of the configuration are resolved per input
+        let mut reader = match prepared.as_ref() {
+            Some((_, path)) => builder.path(path)?,
+            None => builder.open(input)?,
+        };
+
+        if let Some(keys) = options.info.as_ref() {
+            check_info_keys(keys, reader.info())?;
+        }
+
+        let new_samples: Vec<sample::Sample> = sample::from_header(reader.header())
+            .into_iter()
+            .filter(|x| samples.iter().all(|y| y.id != x.id))
+            .collect();
+        if options.samples && !new_samples.is_empty() {
+            writer.samples(new_samples.clone());
+        }
+        samples.extend(new_samples);
+
+        if options.info_key_iris {
+            // keys written elsewhere than `gvo:info` are not linked to their terms
+            writer.info_definitions(
+                reader
+                    .info_keys()
+                    .iter()
+                    .filter(|&k| {
+                        !(options.rdf_star_mode == Some(RdfStarMode::Allele)
+                            && config.rdf_star.contains(k))
+                            && !config.info_parsers.contains_key(k)
+                            && (options.keep_raw_info || !config.info_predicates.contains_key(k))
+                    })
+                    .filter_map(|k| {
+                        let definition = reader.info_definitions().get(k)?;
+                        Some((k.to_owned(), definition.description.clone()))
+                    })
+                    .collect(),
+            );
+        }
+
+        for (contig, key) in reader.aliased_contigs() {
+            info!(
+                "{} of {} is resolved to {} via alias",
+                contig,
+                input.display(),
+                key
+            );
+        }
+        writer.summary_mut().add(
+            "Contigs resolved via alias",
+            reader.aliased_contigs().len() as u64,
+        );
+        writer.summary_mut().add(
+            "Contigs resolved via default",
+            reader.defaulted_contigs().len() as u64,
+        );
+        writer
+            .summary_mut()
+            .add_warning("Contigs unmapped", reader.unmapped_contigs().len() as u64);
+
+        records_indexed += reader.count();
+
+        for record in reader.records() {
+            let record = match record {
+                Ok(record) => record,
+                Err(Error::MalformedRecordError(contig, position, message)) if options.lenient => {
+                    report.skip(input, &contig, position, &message)?;
+                    continue;
+                }
+                Err(e) => Err(e)?,
+            };
+
+            if records_read < skip {
+                writer.skip_record();
+                writer.count("Records read");
+                writer.count("Records skipped by --skip");
+                records_read += 1;
+                continue;
+            }
 
-    let mut writer = TurtleWriter::new(std::io::stdout());
+            let rendered = match writer.render_record(&record) {
+                Ok(rendered) => rendered,
+                Err(e) if options.lenient => {
+                    let contig = record.chromosome().and_then(|x| x.ok());
+                    report.skip(
+                        input,
+                        contig.unwrap_or("unknown"),
+                        record.inner().pos().max(0) as u64 + 1,
+                        &e.to_string(),
+                    )?;
+                    continue;
+                }
+                Err(e) => Err(e)?,
+            };
+            report.succeed();
+            let bytes = rendered.len() as u64;
 
-    let ns = Namespace::from(&config);
-    writer.namespace(&ns);
+            output.prepare(
+                &mut writer,
+                record.chromosome().transpose()?.unwrap_or("unknown"),
+                bytes,
+            )?;
 
-    if let Some(v) = options.subject.as_ref() {
-        writer.subject_formatter(SubjectFormatter::from(v));
+            if !rendered.is_empty() {
+                writer
+                    .write_rendered(rendered)
+                    .map_err(|e| output.map_error(e))?;
+                output.record_written(bytes);
+                records_written += 1;
+            }
+
+            writer.count("Records read");
+            records_read += 1;
+
+            if limit.map_or(false, |n| records_written >= n) {
+                stopped = true;
+                break;
+            }
+        }
+
+        for (key, attempts, failures) in reader.info_stats().failing_keys(INFO_FAILURE_THRESHOLD) {
+            let typ = match reader.info().get(key) {
+                Some((typ, _)) => format!("{:?}", typ),
+                None => "String (not declared in the header)".to_owned(),
+            };
+
+            warn!(
+                "INFO/{} of {} failed to be decoded as {} in {} of {} attempts. Check the definition in the header.",
+                key,
+                input.display(),
+                typ,
+                failures,
+                attempts
+            );
+            writer.summary_mut().add_warning(
+                &format!("INFO/{} values failed to be decoded as {}", key, typ),
+                failures,
+            );
+        }
+
+        for (key, failures) in reader.info_stats().conversion_failures() {
+            if let Some(typ) = config.info_types.get(key) {
+                warn!(
+                    "{} values of INFO/{} in {} failed to be converted to {:?} and were skipped.",
+                    failures,
+                    key,
+                    input.display(),
+                    typ
+                );
+            }
+            writer.summary_mut().add_warning(
+                &format!("INFO/{} values skipped (conversion failed)", key),
+                *failures,
+            );
+        }
+
+        if stopped {
+            break 'inputs;
+        }
+    }
+
+    writer.flush().map_err(|e| output.map_error(e))?;
+    *records = writer.records_read();
+
+    if options.lenient {
+        writer
+            .summary_mut()
+            .add_warning("Records skipped (malformed)", report.skipped() - skipped);
+    }
+
+    // records after the limit are not read
+    if restricted && !stopped {
+        writer
+            .summary_mut()
+            .add("Records inside regions", records_read);
+        writer.summary_mut().add(
+            "Records outside regions",
+            records_indexed.saturating_sub(records_read),
+        );
+    }
+
+    if let (Some(secret), Some(path)) = (
+        options.pseudonymize_samples.as_ref(),
+        options.pseudonym_map.as_ref(),
+    ) {
+        write_pseudonym_map(path, secret, &samples)?;
+    }
+
+    if let Some(path) = options.void.as_ref() {
+        writer.write_void(File::create(path)?)?;
+    }
+
+    if let Some(path) = options.manifest.as_ref() {
+        let mut manifest = BufWriter::new(File::create(path)?);
+        for file in output.manifest() {
+            writeln!(manifest, "{}", file.display())?;
+        }
+        manifest.flush()?;
+    }
+
+    for (path, bytes) in output.sizes() {
+        writer
+            .summary_mut()
+            .add(&format!("Bytes written to {}", path.display()), bytes);
     }
 
-    let mut builder = ReaderBuilder::new()
-        .reference(config.reference)
-        .normalize(!options.no_normalize);
+    let summary = writer.summary().clone();
 
-    if let Some(keys) = config.info {
-        builder = builder.info_keys(keys);
+    Ok(summary)
+}
+
+/// Ensure that all `keys` passed by `--info` are declared in the header.
+fn check_info_keys<V>(keys: &[String], available: &BTreeMap<String, V>) -> Result<()> {
+    let unknown = keys
+        .iter()
+        .filter(|k| !available.contains_key(*k))
+        .map(|k| k.as_str())
+        .collect::<Vec<_>>();
+
+    if !unknown.is_empty() {
+        Err(Error::UnknownInfoKeyError(
+            unknown.join(", "),
+            available
+                .keys()
+                .map(|k| k.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+        ))?
+    }
+
+    Ok(())
+}
+
+/// Returns a copy of `input` compressed with bgzip and indexed in a temporary directory under `TMPDIR`,
+/// or `None` if `input` is already compressed with BGZF. The original file is never modified.
+fn prepare(input: &Path) -> Result<Option<(TempDir, PathBuf)>> {
+    if !input.exists() {
+        return Ok(None);
+    }
+
+    let format = vcf::get_format(input)?;
+    if format.compression == htslib::htsCompression_bgzf {
+        return Ok(None);
+    }
+    if format.compression != htslib::htsCompression_no_compression {
+        Err(Error::InvalidArgumentError(format!(
+            "{} is compressed but not with bgzip, decompress it or recompress it with bgzip",
+            input.display()
+        )))?
+    }
+
+    let dir = tempfile::tempdir()?;
+    let mut name = input.file_name().unwrap_or_default().to_os_string();
+    name.push(".gz");
+    let output = dir.path().join(name);
+
+    warn!(
+        "{} is not compressed, converting a copy compressed and indexed at {}",
+        input.display(),
+        output.display()
+    );
+
+    let mut reader = BufReader::new(File::open(input)?);
+    compress::from_reader(&mut reader, &output, None, false)?;
+    tabix::create(&output)?;
+
+    Ok(Some((dir, output)))
+}
+
+/// Returns lines describing the tool, the command line, the input and the configuration.
+fn banner(options: &Options, inputs: &[PathBuf], created: &str) -> Result<Vec<String>> {
+    let htslib_version = unsafe { CStr::from_ptr(htslib::hts_version()) };
+
+    let command = mask_secrets(std::env::args())
+        .iter()
+        .map(|x| shell_quote(x))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let mut lines = vec![
+        format!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION")),
+        format!("htslib {}", htslib_version.to_string_lossy()),
+        format!("created: {}", created),
+        format!("command: {}", command),
+        format!(
+            "input: {}",
+            inputs
+                .iter()
+                .map(|x| x.display().to_string())
+                .collect::<Vec<_>>()
+                .join(" ")
+        ),
+    ];
+
+    if let Some(path) = options.config.as_ref() {
+        let digest = Sha256::digest(std::fs::read(path)?);
+        lines.push(format!("config sha256: {:x}", digest));
     }
+    if let Some(assembly) = options.assembly.as_ref() {
+        lines.push(format!("assembly: {}", assembly.as_ref()));
+    }
+
+    Ok(lines)
+}
 
-    let mut reader = builder.path(options.input)?;
+/// Replace values of options holding secrets in `args` with `***`.
+fn mask_secrets<I: IntoIterator<Item = String>>(args: I) -> Vec<String> {
+    const SECRET_OPTIONS: &[&str] = &["--pseudonymize-samples"];
 
-    for record in reader.records() {
-        let record = record?;
+    let mut masked = Vec::new();
+    let mut secret = false;
 
-        writer.write_record(&record)?;
+    for arg in args {
+        if secret {
+            masked.push("***".to_owned());
+            secret = false;
+            continue;
+        }
 
-        if options.rehearsal {
-            break;
+        match arg.split_once('=') {
+            Some((name, _)) if SECRET_OPTIONS.contains(&name) => {
+                masked.push(format!("{}=***", name));
+            }
+            _ => {
+                secret = SECRET_OPTIONS.contains(&arg.as_str());
+                masked.push(arg);
+            }
         }
     }
 
+    masked
+}
+
+/// Write sample names and their pseudonyms to `path` as TSV, which is readable only by the owner.
+fn write_pseudonym_map(path: &Path, secret: &str, samples: &[sample::Sample]) -> Result<()> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let mut file = BufWriter::new(options.open(path)?);
+    writeln!(file, "sample\tpseudonym")?;
+    for sample in samples {
+        writeln!(
+            file,
+            "{}\t{}",
+            sample.id,
+            sample::pseudonym(secret, &sample.id)
+        )?;
+    }
+    file.flush()?;
+
     Ok(())
 }
+
+/// Quote `arg` for a shell if needed.
+fn shell_quote(arg: &str) -> String {
+    if !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:=,+@%".contains(c))
+    {
+        arg.to_owned()
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+
+    #[test]
+    fn test_mask_secrets() {
+        let args = |v: &[&str]| v.iter().map(|x| x.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            mask_secrets(args(&[
+                "vcf2rdf",
+                "convert",
+                "--pseudonymize-samples",
+                "s3cr3t",
+                "in.vcf.gz"
+            ])),
+            args(&[
+                "vcf2rdf",
+                "convert",
+                "--pseudonymize-samples",
+                "***",
+                "in.vcf.gz"
+            ])
+        );
+        assert_eq!(
+            mask_secrets(args(&["vcf2rdf", "--pseudonymize-samples=s3cr3t"])),
+            args(&["vcf2rdf", "--pseudonymize-samples=***"])
+        );
+        assert_eq!(
+            mask_secrets(args(&["vcf2rdf", "--info=DP"])),
+            args(&["vcf2rdf", "--info=DP"])
+        );
+    }
+
+    #[test]
+    fn test_write_pseudonym_map() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("map.tsv");
+        let samples = vec![sample::Sample {
+            id: "NA12878".to_owned(),
+            ..Default::default()
+        }];
+
+        write_pseudonym_map(&path, "secret", &samples).unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            format!(
+                "sample\tpseudonym\nNA12878\t{}\n",
+                sample::pseudonym("secret", "NA12878")
+            )
+        );
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("input.vcf.gz"), "input.vcf.gz");
+        assert_eq!(shell_quote("my file.vcf"), "'my file.vcf'");
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+    }
+
+    #[test]
+    fn test_check_info_keys() {
+        let mut available = BTreeMap::new();
+        available.insert("CAF".to_owned(), ());
+        available.insert("RS".to_owned(), ());
+
+        assert!(check_info_keys(&["RS".to_owned()], &available).is_ok());
+        assert_eq!(
+            check_info_keys(&["RS".to_owned(), "CLNSIG".to_owned()], &available)
+                .unwrap_err()
+                .to_string(),
+            "Unknown INFO keys: CLNSIG (available: CAF, RS)"
+        );
+    }
+
+    #[test]
+    fn test_banner() {
+        let mut config = tempfile::NamedTempFile::new().unwrap();
+        config.write_all(b"abc").unwrap();
+
+        let options = Options::from_iter(vec![
+            "convert",
+            "--config",
+            config.path().to_str().unwrap(),
+            "input.vcf.gz",
+        ]);
+        let banner = banner(&options, &options.input, "2021-01-01T00:00:00Z").unwrap();
+
+        assert!(banner[0].starts_with("vcf2rdf "));
+        assert!(banner.contains(&"created: 2021-01-01T00:00:00Z".to_owned()));
+        assert!(banner.contains(&"input: input.vcf.gz".to_owned()));
+        assert!(banner.contains(
+            &"config sha256: ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+                .to_owned()
+        ));
+    }
+
+    #[test]
+    fn test_print_config() {
+        let options = Options::from_iter(vec![
+            "convert",
+            "--config",
+            "config.yaml",
+            "--print-config",
+            "input.vcf.gz",
+        ]);
+
+        assert!(options.print_config);
+    }
+
+    #[test]
+    fn test_no_banner() {
+        let options = Options::from_iter(vec![
+            "convert",
+            "--config",
+            "config.yaml",
+            "--no-banner",
+            "input.vcf.gz",
+        ]);
+
+        assert!(options.no_banner);
+    }
+
+    #[test]
+    fn test_run_with_index() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("data")).unwrap();
+        std::fs::create_dir(dir.path().join("index")).unwrap();
+
+        let input = dir.path().join("data/vcf_spec.vcf.gz");
+        let index = dir.path().join("index/vcf_spec.vcf.gz.tbi");
+        std::fs::copy("test/vcf_spec.vcf.gz", &input).unwrap();
+        std::fs::copy("test/vcf_spec.vcf.gz.tbi", &index).unwrap();
+
+        let config = dir.path().join("config.yaml");
+        std::fs::write(
+            &config,
+            "reference:\n  \"20\":\n    name: chr20\n    reference: http://identifiers.org/hco/20/GRCh37\n",
+        )
+        .unwrap();
+        let output = dir.path().join("out.ttl");
+
+        let options = Options::from_iter(vec![
+            "convert",
+            "--config",
+            config.to_str().unwrap(),
+            "--index",
+            index.to_str().unwrap(),
+            "--output",
+            output.to_str().unwrap(),
+            input.to_str().unwrap(),
+        ]);
+
+        run(options).expect("Error converting.");
+
+        let ttl = std::fs::read_to_string(output).unwrap();
+        assert!(ttl.contains("faldo:position 14370"));
+    }
+
+    #[test]
+    fn test_run_with_assembly() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("out.ttl");
+
+        let options = Options::from_iter(vec![
+            "convert",
+            "--assembly",
+            "GRCh37",
+            "--output",
+            output.to_str().unwrap(),
+            "test/vcf_spec.vcf.gz",
+        ]);
+        run(options).expect("Error converting.");
+
+        let ttl = std::fs::read_to_string(&output).unwrap();
+        assert!(ttl.contains("<http://identifiers.org/hco/20/GRCh37>"));
+
+        // the configuration takes precedence per contig
+        let config = dir.path().join("config.yaml");
+        std::fs::write(
+            &config,
+            "reference:\n  \"20\":\n    name: \"20\"\n    reference: http://example.org/20\n",
+        )
+        .unwrap();
+
+        let options = Options::from_iter(vec![
+            "convert",
+            "--config",
+            config.to_str().unwrap(),
+            "--assembly",
+            "GRCh37",
+            "--output",
+            output.to_str().unwrap(),
+            "test/vcf_spec.vcf.gz",
+        ]);
+        run(options).expect("Error converting.");
+
+        let ttl = std::fs::read_to_string(&output).unwrap();
+        assert!(ttl.contains("<http://example.org/20>"));
+        assert!(!ttl.contains("identifiers.org/hco"));
+    }
+
+    #[test]
+    fn test_run_auto_prepare() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("vcf_spec.vcf");
+        std::fs::copy("test/vcf_spec.vcf", &input).unwrap();
+
+        let config = dir.path().join("config.yaml");
+        std::fs::write(
+            &config,
+            "reference:\n  \"20\":\n    name: chr20\n    reference: http://identifiers.org/hco/20/GRCh37\n",
+        )
+        .unwrap();
+        let output = dir.path().join("out.ttl");
+
+        let args = |extra: &[&str]| {
+            let mut args = vec![
+                "convert",
+                "--config",
+                config.to_str().unwrap(),
+                "--output",
+                output.to_str().unwrap(),
+            ];
+            args.extend_from_slice(extra);
+            args.push(input.to_str().unwrap());
+            Options::from_iter(args)
+        };
+
+        assert!(run(args(&[])).is_err());
+
+        run(args(&["--auto-prepare"])).expect("Error converting.");
+        let ttl = std::fs::read_to_string(&output).unwrap();
+        assert!(ttl.contains("faldo:position 14370"));
+
+        // the original file is left as is
+        let mut entries: Vec<_> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|x| x.unwrap().file_name())
+            .collect();
+        entries.sort();
+        assert_eq!(entries, vec!["config.yaml", "out.ttl", "vcf_spec.vcf"]);
+        assert_eq!(
+            std::fs::read("test/vcf_spec.vcf").unwrap(),
+            std::fs::read(&input).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_run_multiple_inputs() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = dir.path().join("config.yaml");
+        std::fs::write(
+            &config,
+            concat!(
+                "reference:\n",
+                "  \"20\":\n    name: chr20\n    reference: http://identifiers.org/hco/20/GRCh37\n",
+                "  NC_000001.10:\n    name: chr1\n    reference: http://identifiers.org/hco/1/GRCh37\n",
+            ),
+        )
+        .unwrap();
+
+        let args = |output: &Path| {
+            Options::from_iter(vec![
+                "convert",
+                "--config",
+                config.to_str().unwrap(),
+                "--output",
+                output.to_str().unwrap(),
+                "test/v*_spec.vcf.gz",
+            ])
+        };
+
+        let output = dir.path().join("out.ttl");
+        run(args(&output)).expect("Error converting.");
+
+        let ttl = std::fs::read_to_string(&output).unwrap();
+        // prefixes are declared once for all inputs
+        assert_eq!(
+            ttl.matches("<http://www.w3.org/2001/XMLSchema#> .\n")
+                .count(),
+            1
+        );
+        assert!(ttl.contains("dct:source \"vcf_spec.vcf.gz\", \"visc_spec.vcf.gz\""));
+        assert!(ttl.find("faldo:position 14370") < ttl.find("faldo:position 10001"));
+
+        run(args(&dir.path().join("{}.ttl"))).expect("Error converting.");
+
+        let ttl = std::fs::read_to_string(dir.path().join("vcf_spec.ttl")).unwrap();
+        assert!(ttl.contains("faldo:position 14370"));
+        assert!(!ttl.contains("faldo:position 10001"));
+        let ttl = std::fs::read_to_string(dir.path().join("visc_spec.ttl")).unwrap();
+        assert!(ttl.contains("faldo:position 10001"));
+    }
+
+    #[test]
+    fn test_run_skolem_base_per_input() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = dir.path().join("config.yaml");
+        std::fs::write(
+            &config,
+            concat!(
+                "reference:\n",
+                "  \"20\":\n    name: chr20\n    reference: http://identifiers.org/hco/20/GRCh37\n",
+                "  NC_000001.10:\n    name: chr1\n    reference: http://identifiers.org/hco/1/GRCh37\n",
+            ),
+        )
+        .unwrap();
+        let output = dir.path().join("{}.ttl");
+
+        let options = Options::from_iter(vec![
+            "convert",
+            "--config",
+            config.to_str().unwrap(),
+            "--skolem-base",
+            "http://example.org/genid/",
+            "--output",
+            output.to_str().unwrap(),
+            "test/v*_spec.vcf.gz",
+        ]);
+
+        run(options).expect("Error converting.");
+
+        let ttl = std::fs::read_to_string(dir.path().join("vcf_spec.ttl")).unwrap();
+        assert!(ttl.contains("<http://example.org/genid/r0_0>"));
+        // records of the former input are counted, so that nodes never collide between outputs
+        let ttl = std::fs::read_to_string(dir.path().join("visc_spec.ttl")).unwrap();
+        assert!(!ttl.contains("<http://example.org/genid/r0_0>"));
+        assert!(ttl.contains("<http://example.org/genid/r5_0>"));
+    }
+
+    #[test]
+    fn test_run_read_threads() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = dir.path().join("config.yaml");
+        std::fs::write(
+            &config,
+            "reference:\n  NC_000001.10:\n    name: chr1\n    reference: http://identifiers.org/hco/1/GRCh37\n",
+        )
+        .unwrap();
+
+        let convert = |threads: &str| {
+            let output = dir.path().join(format!("out{}.ttl", threads));
+            let options = Options::from_iter(vec![
+                "convert",
+                "--config",
+                config.to_str().unwrap(),
+                "--output",
+                output.to_str().unwrap(),
+                "--no-provenance",
+                "--read-threads",
+                threads,
+                "test/dbsnp_example.vcf.gz",
+            ]);
+            run(options).expect("Error converting.");
+
+            std::fs::read(output).unwrap()
+        };
+
+        let single = convert("1");
+        assert!(!single.is_empty());
+        assert_eq!(convert("4"), single);
+
+        assert!(run(Options::from_iter(vec![
+            "convert",
+            "--config",
+            config.to_str().unwrap(),
+            "--read-threads",
+            "0",
+            "test/dbsnp_example.vcf.gz",
+        ]))
+        .is_err());
+    }
+
+    #[test]
+    fn test_run_no_index() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = dir.path().join("config.yaml");
+        std::fs::write(
+            &config,
+            "reference:\n  \"20\":\n    name: chr20\n    reference: http://identifiers.org/hco/20/GRCh37\n",
+        )
+        .unwrap();
+        let output = dir.path().join("out.ttl");
+
+        let options = Options::from_iter(vec![
+            "convert",
+            "--config",
+            config.to_str().unwrap(),
+            "--output",
+            output.to_str().unwrap(),
+            "--no-index",
+            "test/vcf_spec.vcf",
+        ]);
+
+        run(options).expect("Error converting.");
+
+        let ttl = std::fs::read_to_string(output).unwrap();
+        assert!(ttl.contains("faldo:position 14370"));
+    }
+
+    #[test]
+    fn test_run_output_template_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = dir.path().join("config.yaml");
+        std::fs::write(
+            &config,
+            "reference:\n  \"20\":\n    name: chr20\n    reference: http://identifiers.org/hco/20/GRCh37\n",
+        )
+        .unwrap();
+        let template = dir.path().join("{input_stem}.{contig}.{chunk:02}.{ext}");
+        let manifest = dir.path().join("manifest.txt");
+
+        let options = Options::from_iter(vec![
+            "convert",
+            "--config",
+            config.to_str().unwrap(),
+            "--output-template",
+            template.to_str().unwrap(),
+            "--split-by-contig",
+            "--split-size",
+            "2",
+            "--manifest",
+            manifest.to_str().unwrap(),
+            "--no-index",
+            "test/vcf_spec.vcf",
+        ]);
+
+        run(options).expect("Error converting.");
+
+        let listed = std::fs::read_to_string(&manifest).unwrap();
+        let listed: Vec<&str> = listed.lines().collect();
+        assert_eq!(
+            listed,
+            vec![
+                dir.path().join("vcf_spec.20.01.ttl").to_str().unwrap(),
+                dir.path().join("vcf_spec.20.02.ttl").to_str().unwrap(),
+            ]
+        );
+        assert!(listed.iter().all(|x| Path::new(x).exists()));
+    }
+
+    #[test]
+    fn test_run_split_by_contig_breakends() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = dir.path().join("config.yaml");
+        std::fs::write(
+            &config,
+            concat!(
+                "reference:\n",
+                "  \"20\":\n    name: chr20\n    reference: http://identifiers.org/hco/20/GRCh37\n",
+                "  \"17\":\n    name: chr17\n    reference: http://identifiers.org/hco/17/GRCh37\n",
+            ),
+        )
+        .unwrap();
+        let output = dir.path().join("out.ttl");
+
+        let convert = |extra: &[&str]| {
+            let mut args = vec![
+                "convert",
+                "--config",
+                config.to_str().unwrap(),
+                "--output",
+                output.to_str().unwrap(),
+                "--split-by-contig",
+                "--no-index",
+            ];
+            args.extend_from_slice(extra);
+            args.push("test/bnd_example.vcf");
+            run(Options::from_iter(args)).expect("Error converting.");
+
+            (
+                std::fs::read_to_string(dir.path().join("out.20.ttl")).unwrap(),
+                std::fs::read_to_string(dir.path().join("out.17.ttl")).unwrap(),
+            )
+        };
+
+        // blank nodes of bnd_W and bnd_Y are in different files
+        let (chr20, chr17) = convert(&[]);
+        assert!(chr20.contains("gvo:alt \"G]17:198982]\""));
+        assert!(chr17.contains("gvo:alt \"A]20:321681]\""));
+        assert!(!chr17.contains("gvo:mate "));
+
+        let (_, chr17) = convert(&["--subject", "id", "--subject-prefix", "http://example.org/"]);
+        assert!(chr17.contains(
+            "<http://example.org/bnd_Y> gvo:mate <http://example.org/bnd_W> .\n<http://example.org/bnd_W> gvo:mate <http://example.org/bnd_Y> .\n"
+        ));
+    }
+
+    #[test]
+    fn test_run_skip_and_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = dir.path().join("config.yaml");
+        std::fs::write(
+            &config,
+            "reference:\n  \"20\":\n    name: chr20\n    reference: http://identifiers.org/hco/20/GRCh37\n",
+        )
+        .unwrap();
+        let output = dir.path().join("out.ttl");
+
+        let options = Options::from_iter(vec![
+            "convert",
+            "--config",
+            config.to_str().unwrap(),
+            "--output",
+            output.to_str().unwrap(),
+            "--skip",
+            "1",
+            "--limit",
+            "2",
+            "test/vcf_spec.vcf.gz",
+        ]);
+
+        run(options).expect("Error converting.");
+
+        let ttl = std::fs::read_to_string(output).unwrap();
+        assert!(!ttl.contains("faldo:position 14370"));
+        assert!(ttl.contains("faldo:position 17330"));
+        // both alternate alleles of the last record
+        assert!(ttl.contains("gvo:alt \"G\""));
+        assert!(ttl.contains("gvo:alt \"T\""));
+        assert!(!ttl.contains("faldo:position 1234567"));
+
+        assert!(Options::from_iter_safe(vec![
+            "convert",
+            "--config",
+            config.to_str().unwrap(),
+            "--rehearsal",
+            "--limit",
+            "2",
+            "test/vcf_spec.vcf.gz",
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_run_lenient() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = dir.path().join("config.yaml");
+        std::fs::write(
+            &config,
+            "reference:\n  \"20\":\n    name: chr20\n    reference: http://identifiers.org/hco/20/GRCh37\n",
+        )
+        .unwrap();
+        // the second record has too few columns
+        let input = dir.path().join("malformed.vcf");
+        std::fs::write(
+            &input,
+            "##fileformat=VCFv4.3\n\
+             ##contig=<ID=20,length=62435964>\n\
+             ##FORMAT=<ID=GT,Number=1,Type=String,Description=\"Genotype\">\n\
+             #CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\tFORMAT\tNA00001\n\
+             20\t14370\trs6054257\tG\tA\t29\tPASS\t.\tGT\t0|0\n\
+             20\t17330\t.\tT\n\
+             20\t1110696\trs6040355\tA\tG\t67\tPASS\t.\tGT\t1|2\n",
+        )
+        .unwrap();
+        let output = dir.path().join("out.ttl");
+        let report = dir.path().join("errors.tsv");
+
+        let args = |extra: &[&str]| {
+            let mut args = vec![
+                "convert",
+                "--config",
+                config.to_str().unwrap(),
+                "--output",
+                output.to_str().unwrap(),
+                "--no-index",
+            ];
+            args.extend_from_slice(extra);
+            args.push(input.to_str().unwrap());
+            Options::from_iter(args)
+        };
+
+        assert!(run(args(&[])).is_err());
+
+        run(args(&[
+            "--lenient",
+            "--error-report",
+            report.to_str().unwrap(),
+        ]))
+        .expect("Error converting.");
+
+        let ttl = std::fs::read_to_string(&output).unwrap();
+        assert!(ttl.contains("faldo:position 14370"));
+        assert!(!ttl.contains("faldo:position 17330"));
+        assert!(ttl.contains("faldo:position 1110696"));
+
+        let tsv = std::fs::read_to_string(&report).unwrap();
+        let lines: Vec<&str> = tsv.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[1].starts_with(&format!("{}\t20\t17330\t", input.display())));
+
+        assert!(Options::from_iter_safe(vec![
+            "convert",
+            "--config",
+            config.to_str().unwrap(),
+            "--lenient",
+            "--strict",
+            input.to_str().unwrap(),
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_convert_warns_failing_info_keys() {
+        // values of AA remain encoded as strings
+        let (dir, input) = crate::util::fixture::bcf_with_header("vcf_spec", |header| {
+            header.remove_info(b"AA").push_record(
+                b"##INFO=<ID=AA,Number=1,Type=Integer,Description=\"Ancestral Allele\">",
+            );
+        });
+        let config = dir.path().join("config.yaml");
+        std::fs::write(
+            &config,
+            "reference:\n  \"20\":\n    name: chr20\n    reference: http://identifiers.org/hco/20/GRCh37\n",
+        )
+        .unwrap();
+        let output = dir.path().join("out.ttl");
+
+        let options = Options::from_iter(vec![
+            "convert",
+            "--config",
+            config.to_str().unwrap(),
+            "--output",
+            output.to_str().unwrap(),
+            input.to_str().unwrap(),
+        ]);
+        let config = CompiledConfig::compile(Config::from_path(&config).unwrap()).unwrap();
+        let mut report = ErrorReport::new(None).unwrap();
+
+        let summary = convert(
+            &options,
+            &config,
+            &[input.clone()],
+            Some(output.clone()),
+            &mut report,
+            &mut 0,
+        )
+        .expect("Error converting.");
+
+        let key = "INFO/AA values failed to be decoded as Integer";
+        assert!(summary.has_warnings());
+        assert!(summary.is_warning(key));
+        assert!(!summary.is_warning("INFO/DP values failed to be decoded as Integer"));
+    }
+
+    #[test]
+    fn test_prepare() {
+        assert!(prepare(Path::new("test/vcf_spec.vcf.gz"))
+            .unwrap()
+            .is_none());
+
+        let (dir, path) = prepare(Path::new("test/vcf_spec.vcf")).unwrap().unwrap();
+        assert_eq!(path, dir.path().join("vcf_spec.vcf.gz"));
+        assert!(dir.path().join("vcf_spec.vcf.gz.tbi").exists());
+
+        drop(dir);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_converter_shared_across_threads() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.yaml");
+        std::fs::write(
+            &path,
+            "base: http://example.org/\nreference:\n  \"20\":\n    reference: http://identifiers.org/hco/20/GRCh37\n",
+        )
+        .unwrap();
+
+        let options = || {
+            Options::from_iter(vec![
+                "convert",
+                "--config",
+                path.to_str().unwrap(),
+                "--subject",
+                "id",
+                "--no-provenance",
+                "test/vcf_spec.vcf.gz",
+            ])
+        };
+        let inputs = vec![PathBuf::from("test/vcf_spec.vcf.gz")];
+
+        let compiled =
+            Arc::new(CompiledConfig::compile(Config::from_path(&path).unwrap()).unwrap());
+        let converter = Arc::new(Converter::new(Arc::clone(&compiled), options()));
+
+        let handles: Vec<_> = (0..2)
+            .map(|i| {
+                let converter = Arc::clone(&converter);
+                let (inputs, output) =
+                    (inputs.clone(), dir.path().join(format!("shared{}.ttl", i)));
+                std::thread::spawn(move || {
+                    converter
+                        .convert(&inputs, Some(output.clone()))
+                        .expect("Error converting.");
+                    std::fs::read_to_string(output).unwrap()
+                })
+            })
+            .collect();
+        let outputs: Vec<String> = handles.into_iter().map(|x| x.join().unwrap()).collect();
+
+        let output = dir.path().join("independent.ttl");
+        Converter::from_config(Config::from_path(&path).unwrap(), options())
+            .unwrap()
+            .convert(&inputs, Some(output.clone()))
+            .expect("Error converting.");
+        let expected = std::fs::read_to_string(output).unwrap();
+
+        assert!(expected.contains("\n<http://example.org/rs6054257> a gvo:SNV"));
+        assert_eq!(outputs, vec![expected.clone(), expected]);
+    }
+
+    #[test]
+    fn test_run_requires_base_for_relative_subjects() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = dir.path().join("config.yaml");
+        std::fs::write(
+            &config,
+            "reference:\n  \"20\":\n    name: chr20\n    reference: http://identifiers.org/hco/20/GRCh37\n",
+        )
+        .unwrap();
+        let output = dir.path().join("out.ttl");
+
+        let args = |extra: &[&str]| {
+            let mut args = vec![
+                "convert",
+                "--config",
+                config.to_str().unwrap(),
+                "--output",
+                output.to_str().unwrap(),
+                "--subject",
+                "location",
+            ];
+            args.extend_from_slice(extra);
+            args.push("test/vcf_spec.vcf.gz");
+            Options::from_iter(args)
+        };
+
+        let err = run(args(&[])).expect_err("unexpected result");
+        assert!(err.to_string().contains("--subject-prefix"));
+
+        run(args(&["--subject-prefix", "http://example.org/"])).expect("Error converting.");
+        let ttl = std::fs::read_to_string(&output).unwrap();
+        assert!(ttl.contains("<http://example.org/chr20-14370-G-A>"));
+    }
+
+    #[test]
+    fn test_run_info_key_predicate() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = dir.path().join("config.yaml");
+        std::fs::write(
+            &config,
+            "reference:\n  \"20\":\n    reference: http://identifiers.org/hco/20/GRCh37\n",
+        )
+        .unwrap();
+        let output = dir.path().join("out.ttl");
+
+        let args = |extra: &[&str]| {
+            let mut args = vec![
+                "convert",
+                "--config",
+                config.to_str().unwrap(),
+                "--output",
+                output.to_str().unwrap(),
+                "--info-key-iris",
+            ];
+            args.extend_from_slice(extra);
+            args.push("test/vcf_spec.vcf.gz");
+            Options::from_iter(args)
+        };
+
+        let err =
+            run(args(&["--info-key-predicate", "gvo:infoKey"])).expect_err("unexpected result");
+        assert!(err.to_string().contains("reserved"));
+
+        run(args(&[])).expect("Error converting.");
+        let ttl = std::fs::read_to_string(&output).unwrap();
+        assert!(ttl.contains("\n    gvo:infoField gvo:DP ;\n"));
+        assert!(!ttl.contains("gvo:infoKey"));
+    }
+
+    #[test]
+    fn test_run_record_comments_for_turtle_only() {
+        for output in &["out.nt", "out.nq"] {
+            let err = run(Options::from_iter(vec![
+                "convert",
+                "--assembly",
+                "GRCh37",
+                "--record-comments",
+                "--output",
+                output,
+                "test/vcf_spec.vcf.gz",
+            ]))
+            .expect_err("unexpected result");
+            assert!(err.to_string().contains("only for Turtle output"));
+        }
+    }
+
+    #[test]
+    fn test_run_subject_key() {
+        assert!(Options::from_iter_safe(vec![
+            "convert",
+            "--assembly",
+            "GRCh37",
+            "--subject",
+            "info",
+            "test/vcf_spec.vcf.gz",
+        ])
+        .is_err());
+
+        let dir = tempfile::tempdir().unwrap();
+        let config = dir.path().join("config.yaml");
+        std::fs::write(
+            &config,
+            "base: http://example.org/\nsubject: info:AA\nreference:\n  \"20\":\n    reference: http://identifiers.org/hco/20/GRCh37\n",
+        )
+        .unwrap();
+        let output = dir.path().join("out.ttl");
+
+        run(Options::from_iter(vec![
+            "convert",
+            "--config",
+            config.to_str().unwrap(),
+            "--output",
+            output.to_str().unwrap(),
+            "test/vcf_spec.vcf.gz",
+        ]))
+        .expect("Error converting.");
+
+        let ttl = std::fs::read_to_string(&output).unwrap();
+        assert!(ttl.contains("\n<http://example.org/T> a gvo:SNV"));
+    }
+
+    #[test]
+    fn test_run_relative_subjects_with_contig_base() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = dir.path().join("config.yaml");
+        std::fs::write(
+            &config,
+            "reference:\n  \"20\":\n    name: chr20\n    reference: http://identifiers.org/hco/20/GRCh37\n    base: http://example.org/chr20/\n",
+        )
+        .unwrap();
+        let output = dir.path().join("out.ttl");
+
+        let options = Options::from_iter(vec![
+            "convert",
+            "--config",
+            config.to_str().unwrap(),
+            "--output",
+            output.to_str().unwrap(),
+            "--subject",
+            "location",
+            "test/vcf_spec.vcf.gz",
+        ]);
+
+        run(options).expect("Error converting.");
+        let ttl = std::fs::read_to_string(&output).unwrap();
+        assert!(ttl.contains("<http://example.org/chr20/chr20-14370-G-A>"));
+    }
+}
@@ -0,0 +1,138 @@
+//! Module for reporting records skipped by `--lenient`
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use log::*;
+
+use crate::errors::{Error, Result};
+
+/// Number of consecutive records failing to be read, at which the input is regarded as corrupted.
+const MAX_CONSECUTIVE_FAILURES: u64 = 1000;
+
+/// Skipped records logged and optionally written as TSV of input, contig, position and error.
+#[derive(Debug, Default)]
+pub struct ErrorReport {
+    writer: Option<BufWriter<File>>,
+    skipped: u64,
+    consecutive: u64,
+}
+
+impl ErrorReport {
+    /// Create a report written to `path` if given.
+    pub fn new(path: Option<&Path>) -> Result<Self> {
+        let writer = match path {
+            Some(path) => {
+                let mut writer = BufWriter::new(File::create(path)?);
+                writeln!(writer, "input\tcontig\tposition\terror")?;
+                Some(writer)
+            }
+            None => None,
+        };
+
+        Ok(ErrorReport {
+            writer,
+            ..Default::default()
+        })
+    }
+
+    /// Report a record of `input` at `contig` and `position` skipped due to `error`.
+    ///
+    /// Returns an error if too many records fail in a row, since the rest of the input can not be read.
+    pub fn skip(&mut self, input: &Path, contig: &str, position: u64, error: &str) -> Result<()> {
+        warn!(
+            "Skipped a record of {} at {}:{}: {}",
+            input.display(),
+            contig,
+            position,
+            error
+        );
+
+        if let Some(writer) = self.writer.as_mut() {
+            let error = error.replace(|c| c == '\t' || c == '\n' || c == '\r', " ");
+            writeln!(
+                writer,
+                "{}\t{}\t{}\t{}",
+                input.display(),
+                contig,
+                position,
+                error
+            )?;
+        }
+
+        self.skipped += 1;
+        self.consecutive += 1;
+        if self.consecutive >= MAX_CONSECUTIVE_FAILURES {
+            Err(Error::InvalidArgumentError(format!(
+                "{} records of {} failed in a row, the input seems corrupted",
+                self.consecutive,
+                input.display()
+            )))?
+        }
+
+        Ok(())
+    }
+
+    /// Notify that a record is converted, resetting the count of consecutive failures.
+    pub fn succeed(&mut self) {
+        self.consecutive = 0;
+    }
+
+    /// Returns the number of records skipped.
+    pub fn skipped(&self) -> u64 {
+        self.skipped
+    }
+
+    pub fn flush(&mut self) -> Result<()> {
+        if let Some(writer) = self.writer.as_mut() {
+            writer.flush()?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_report() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("errors.tsv");
+
+        let mut report = ErrorReport::new(Some(&path)).unwrap();
+        report
+            .skip(Path::new("in.vcf"), "20", 14370, "invalid\trecord")
+            .unwrap();
+        report.succeed();
+        report.flush().unwrap();
+
+        assert_eq!(report.skipped(), 1);
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "input\tcontig\tposition\terror\nin.vcf\t20\t14370\tinvalid record\n"
+        );
+    }
+
+    #[test]
+    fn test_error_report_consecutive_failures() {
+        let mut report = ErrorReport::new(None).unwrap();
+
+        for _ in 1..MAX_CONSECUTIVE_FAILURES {
+            report
+                .skip(Path::new("in.vcf"), "20", 1, "invalid record")
+                .unwrap();
+        }
+        report.succeed();
+
+        for _ in 1..MAX_CONSECUTIVE_FAILURES {
+            report
+                .skip(Path::new("in.vcf"), "20", 1, "invalid record")
+                .unwrap();
+        }
+        assert!(report
+            .skip(Path::new("in.vcf"), "20", 1, "invalid record")
+            .is_err());
+    }
+}
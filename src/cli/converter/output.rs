@@ -0,0 +1,555 @@
+//! Module for routing converted records to output files
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use log::*;
+
+use crate::errors::{Error, Result};
+use crate::rdf::turtle_writer::TurtleWriter;
+use crate::util::file::LazyFile;
+use crate::util::template::Template;
+
+/// Placeholders available in an output template.
+pub const PLACEHOLDERS: &[&str] = &["input_stem", "contig", "chunk", "ext", "date"];
+
+/// A template of output file names, e.g. `{input_stem}.{contig}.{chunk:04}.{ext}`.
+#[derive(Debug)]
+pub struct OutputTemplate {
+    template: Template,
+    input_stem: String,
+    date: String,
+}
+
+impl OutputTemplate {
+    /// Compile `template` for files converted from `input`.
+    pub fn new(template: &str, input: &Path) -> Result<Self> {
+        Ok(OutputTemplate {
+            template: Template::parse(template, PLACEHOLDERS)
+                .map_err(|e| Error::InvalidArgumentError(format!("output template: {}", e)))?,
+            input_stem: input_stem(input),
+            date: Utc::now().format("%Y%m%d").to_string(),
+        })
+    }
+
+    /// Render a path for `contig` (`None` if not split by contig) and `chunk`.
+    pub fn render(&self, contig: Option<&str>, chunk: u64) -> PathBuf {
+        let rendered = self.template.render(|name| {
+            Some(match name {
+                "input_stem" => self.input_stem.clone(),
+                "contig" => contig.map_or_else(|| "all".to_owned(), sanitize),
+                "chunk" => chunk.to_string(),
+                "ext" => "ttl".to_owned(),
+                "date" => self.date.clone(),
+                _ => return None,
+            })
+        });
+
+        // all placeholders are known at parse time
+        PathBuf::from(rendered.unwrap_or_default())
+    }
+}
+
+/// Output destination switching files by contig and/or by the number of records or bytes.
+#[derive(Debug)]
+pub struct Output {
+    path: Option<PathBuf>,
+    template: Option<OutputTemplate>,
+    split_by_contig: bool,
+    split_size: Option<u64>,
+    chunk_bytes: Option<u64>,
+    contig: Option<String>,
+    finished_contigs: HashSet<String>,
+    chunk: u64,
+    records: u64,
+    bytes: u64,
+    started: bool,
+    files: Vec<PathBuf>,
+    // bytes of entries written to each of `files`
+    sizes: Vec<u64>,
+}
+
+impl Output {
+    pub fn new(
+        path: Option<PathBuf>,
+        template: Option<OutputTemplate>,
+        split_by_contig: bool,
+        split_size: Option<u64>,
+        chunk_bytes: Option<u64>,
+    ) -> Result<Self> {
+        if split_size == Some(0) {
+            Err(Error::InvalidArgumentError(
+                "split size must be greater than 0".to_owned(),
+            ))?
+        }
+
+        if chunk_bytes == Some(0) {
+            Err(Error::InvalidArgumentError(
+                "chunk bytes must be greater than 0".to_owned(),
+            ))?
+        }
+
+        let chunked = split_size.is_some() || chunk_bytes.is_some();
+
+        match template.as_ref() {
+            Some(template) => {
+                if split_by_contig && !template.template.contains("contig") {
+                    Err(Error::InvalidArgumentError(
+                        "output template must contain {contig} to split by contig".to_owned(),
+                    ))?
+                }
+                if chunked && !template.template.contains("chunk") {
+                    Err(Error::InvalidArgumentError(
+                        "output template must contain {chunk} to split by size".to_owned(),
+                    ))?
+                }
+            }
+            None if path.is_none() && (split_by_contig || chunked) => {
+                Err(Error::InvalidArgumentError(
+                    "output path or template is required to split output".to_owned(),
+                ))?
+            }
+            None => {}
+        }
+
+        Ok(Output {
+            path,
+            template,
+            split_by_contig,
+            split_size,
+            chunk_bytes,
+            contig: None,
+            finished_contigs: HashSet::new(),
+            chunk: 0,
+            records: 0,
+            bytes: 0,
+            started: false,
+            files: Vec::new(),
+            sizes: Vec::new(),
+        })
+    }
+
+    fn is_split(&self) -> bool {
+        self.template.is_some() || self.is_chunked() || self.split_by_contig
+    }
+
+    fn is_chunked(&self) -> bool {
+        self.split_size.is_some() || self.chunk_bytes.is_some()
+    }
+
+    /// Returns true if records are written to stdout.
+    pub fn is_stdout(&self) -> bool {
+        self.path.is_none() && !self.is_split()
+    }
+
+    /// Returns `Error::OutputClosedError` for a broken pipe on stdout, and `err` as is otherwise.
+    ///
+    /// Errors on output files are genuine failures even if they are broken pipes.
+    pub fn map_error(&self, err: Error) -> Error {
+        match err {
+            Error::IOError(e) if e.kind() == io::ErrorKind::BrokenPipe && self.is_stdout() => {
+                Error::OutputClosedError
+            }
+            err => err,
+        }
+    }
+
+    /// Returns paths of files switched to in order.
+    pub fn files(&self) -> &[PathBuf] {
+        &self.files
+    }
+
+    /// Returns paths of files with bytes of entries written to them.
+    pub fn sizes(&self) -> impl Iterator<Item = (&PathBuf, u64)> {
+        self.files.iter().zip(self.sizes.iter().copied())
+    }
+
+    /// Returns paths of files created, i.e. those with entries if split, in order.
+    pub fn manifest(&self) -> Vec<&Path> {
+        if !self.is_split() {
+            return self.path.iter().map(|x| x.as_path()).collect();
+        }
+
+        self.sizes()
+            .filter(|&(_, bytes)| bytes > 0)
+            .map(|(path, _)| path.as_path())
+            .collect()
+    }
+
+    /// Returns the writer to start with.
+    pub fn open(&self) -> Result<Box<dyn Write>> {
+        Ok(match self.path.as_ref() {
+            // files are switched before the first record
+            Some(_) if self.is_split() => Box::new(io::sink()),
+            Some(path) => Box::new(File::create(path)?),
+            None => Box::new(io::stdout()),
+        })
+    }
+
+    /// Switch the output of `writer` if needed before writing a record of `bytes` on `contig`.
+    ///
+    /// Files are created lazily, so no file is left for a contig or chunk without entries.
+    /// A record larger than the byte budget is written to a chunk by itself.
+    pub fn prepare(
+        &mut self,
+        writer: &mut TurtleWriter<Box<dyn Write>>,
+        contig: &str,
+        bytes: u64,
+    ) -> Result<()> {
+        if !self.is_split() {
+            return Ok(());
+        }
+
+        let mut rotate = !self.started;
+        self.started = true;
+
+        if self.split_by_contig && self.contig.as_deref() != Some(contig) {
+            if self.finished_contigs.contains(contig) {
+                Err(Error::UnsortedContigError(contig.to_owned()))?
+            }
+
+            // tabix-sorted input is contig-contiguous, so the previous file can be closed
+            if let Some(previous) = self.contig.take() {
+                self.finished_contigs.insert(previous);
+            }
+
+            self.contig = Some(contig.to_owned());
+            self.chunk = 0;
+            rotate = true;
+        }
+
+        if let Some(size) = self.split_size {
+            if rotate || self.chunk == 0 || self.records >= size {
+                self.chunk += 1;
+                rotate = true;
+            }
+        }
+
+        if let Some(budget) = self.chunk_bytes {
+            if bytes > budget {
+                warn!(
+                    "A record of {} bytes on {} exceeds the chunk budget of {} bytes",
+                    bytes, contig, budget
+                );
+            }
+
+            if rotate || self.chunk == 0 || (self.records > 0 && self.bytes + bytes > budget) {
+                self.chunk += 1;
+                rotate = true;
+            }
+        }
+
+        if rotate {
+            self.records = 0;
+            self.bytes = 0;
+
+            if let Some(path) = self.chunk_path() {
+                if self.files.contains(&path) {
+                    Err(Error::InvalidArgumentError(format!(
+                        "output file name collides: {}",
+                        path.display()
+                    )))?
+                }
+
+                writer.set_output(Box::new(LazyFile::new(&path)))?;
+                self.files.push(path);
+                self.sizes.push(0);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Notify that a record of `bytes` has been written to the current chunk.
+    pub fn record_written(&mut self, bytes: u64) {
+        self.records += 1;
+        self.bytes += bytes;
+
+        if let Some(size) = self.sizes.last_mut() {
+            *size += bytes;
+        }
+    }
+
+    fn chunk_path(&self) -> Option<PathBuf> {
+        if let Some(template) = self.template.as_ref() {
+            let contig = self.contig.as_deref().filter(|_| self.split_by_contig);
+            return Some(template.render(contig, self.chunk.max(1)));
+        }
+
+        let mut parts = Vec::new();
+
+        if self.split_by_contig {
+            if let Some(contig) = self.contig.as_ref() {
+                parts.push(sanitize(contig));
+            }
+        }
+
+        if self.is_chunked() {
+            parts.push(format!("{:04}", self.chunk));
+        }
+
+        self.path
+            .as_ref()
+            .map(|x| insert_before_extension(x, &parts))
+    }
+}
+
+/// Returns the file name of `input` without extensions of VCF.
+pub fn input_stem(input: &Path) -> String {
+    let name = input
+        .file_name()
+        .map_or_else(|| String::from("out"), |x| x.to_string_lossy().to_string());
+
+    [".gz", ".bgz", ".vcf", ".bcf"]
+        .iter()
+        .fold(name, |name, ext| match name.strip_suffix(ext) {
+            Some(stem) if !stem.is_empty() => stem.to_owned(),
+            _ => name,
+        })
+}
+
+/// Replace characters not suitable for a file name with `_`.
+fn sanitize(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || "._-".contains(c) {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Returns a path with `parts` inserted before the extension of `path`.
+fn insert_before_extension(path: &Path, parts: &[String]) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map_or_else(|| String::from("out"), |x| x.to_string_lossy().to_string());
+
+    let mut file_name = stem;
+    for part in parts {
+        file_name.push('.');
+        file_name.push_str(part);
+    }
+
+    if let Some(ext) = path.extension() {
+        file_name.push('.');
+        file_name.push_str(&ext.to_string_lossy());
+    }
+
+    path.with_file_name(file_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::config::Sequence;
+    use crate::vcf::reader::ReaderBuilder;
+
+    #[test]
+    fn test_insert_before_extension() {
+        assert_eq!(
+            insert_before_extension(Path::new("dir/out.ttl"), &[sanitize("NC_000001.10")]),
+            PathBuf::from("dir/out.NC_000001.10.ttl")
+        );
+        assert_eq!(
+            insert_before_extension(Path::new("out"), &[sanitize("HLA-A*01:01")]),
+            PathBuf::from("out.HLA-A_01_01")
+        );
+        assert_eq!(
+            insert_before_extension(Path::new("out.ttl"), &["1".to_owned(), "0002".to_owned()]),
+            PathBuf::from("out.1.0002.ttl")
+        );
+    }
+
+    #[test]
+    fn test_chunk_path() {
+        let mut output =
+            Output::new(Some(PathBuf::from("out.ttl")), None, false, Some(10), None).unwrap();
+        output.chunk = 1;
+
+        assert_eq!(output.chunk_path(), Some(PathBuf::from("out.0001.ttl")));
+    }
+
+    #[test]
+    fn test_split_size_requires_output() {
+        assert!(Output::new(None, None, false, Some(10), None).is_err());
+        assert!(Output::new(None, None, false, None, Some(1024)).is_err());
+        assert!(Output::new(Some(PathBuf::from("out.ttl")), None, false, Some(0), None).is_err());
+        assert!(Output::new(Some(PathBuf::from("out.ttl")), None, false, None, Some(0)).is_err());
+    }
+
+    /// A writer behaving as stdout piped to a process which has exited, e.g. `head`.
+    struct ClosedPipe;
+
+    impl Write for ClosedPipe {
+        fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+            Err(io::Error::from(io::ErrorKind::BrokenPipe))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Err(io::Error::from(io::ErrorKind::BrokenPipe))
+        }
+    }
+
+    #[test]
+    fn test_map_error_on_stdout() {
+        let output = Output::new(None, None, false, None, None).unwrap();
+        assert!(output.is_stdout());
+
+        let mut writer = TurtleWriter::new(Box::new(ClosedPipe) as Box<dyn Write>);
+
+        let err = writer.flush().unwrap_err();
+        assert!(matches!(output.map_error(err), Error::OutputClosedError));
+
+        let err = Error::IOError(io::Error::from(io::ErrorKind::PermissionDenied));
+        assert!(matches!(output.map_error(err), Error::IOError(_)));
+    }
+
+    #[test]
+    fn test_map_error_on_file() {
+        let output = Output::new(Some(PathBuf::from("out.ttl")), None, false, None, None).unwrap();
+        assert!(!output.is_stdout());
+
+        let err = Error::IOError(io::Error::from(io::ErrorKind::BrokenPipe));
+        assert!(matches!(output.map_error(err), Error::IOError(_)));
+    }
+
+    fn template(template: &str) -> OutputTemplate {
+        OutputTemplate::new(template, Path::new("dir/sample.vcf.gz")).unwrap()
+    }
+
+    #[test]
+    fn test_input_stem() {
+        assert_eq!(input_stem(Path::new("dir/sample.vcf.gz")), "sample");
+        assert_eq!(input_stem(Path::new("sample.bcf")), "sample");
+        assert_eq!(input_stem(Path::new("sample.v1.vcf")), "sample.v1");
+        assert_eq!(input_stem(Path::new(".vcf")), ".vcf");
+    }
+
+    #[test]
+    fn test_output_template() {
+        assert_eq!(
+            template("{input_stem}.{contig}.{chunk:04}.{ext}").render(Some("chr1"), 2),
+            PathBuf::from("sample.chr1.0002.ttl")
+        );
+        assert_eq!(
+            template("out/{input_stem}.{contig}.{ext}").render(None, 1),
+            PathBuf::from("out/sample.all.ttl")
+        );
+        assert_eq!(
+            template("{contig}-{chunk}.ttl").render(Some("HLA-A*01:01"), 10),
+            PathBuf::from("HLA-A_01_01-10.ttl")
+        );
+
+        let date = template("{date}.ttl").render(None, 1);
+        assert_eq!(date.to_string_lossy().len(), "YYYYMMDD.ttl".len());
+
+        assert!(OutputTemplate::new("{input}.ttl", Path::new("a.vcf")).is_err());
+    }
+
+    #[test]
+    fn test_output_template_requires_placeholders() {
+        assert!(Output::new(None, Some(template("{contig}.ttl")), true, None, None).is_ok());
+        assert!(Output::new(None, Some(template("{chunk}.ttl")), true, None, None).is_err());
+        assert!(Output::new(None, Some(template("{contig}.ttl")), false, Some(10), None).is_err());
+    }
+
+    #[test]
+    fn test_output_template_collision() {
+        let mut output =
+            Output::new(None, Some(template("{contig}.ttl")), true, None, None).unwrap();
+        let mut writer = TurtleWriter::new(output.open().unwrap());
+
+        assert!(output.prepare(&mut writer, "HLA-A*01", 0).is_ok());
+        assert!(output.prepare(&mut writer, "HLA-A:01", 0).is_err());
+        assert_eq!(output.files(), &[PathBuf::from("HLA-A_01.ttl")]);
+    }
+
+    fn write_chunks(budget: u64) -> (Output, Vec<Vec<u64>>) {
+        let dir = tempfile::tempdir().unwrap();
+
+        let mut reference = BTreeMap::new();
+        reference.insert(
+            "20".to_owned(),
+            Some(Sequence {
+                name: Some("20".to_owned()),
+                reference: Some("http://identifiers.org/hco/20/GRCh37".to_owned()),
+                ..Default::default()
+            }),
+        );
+        let mut reader = ReaderBuilder::new()
+            .reference(reference)
+            .path("test/vcf_spec.vcf.gz")
+            .expect("Error opening file.");
+
+        let mut output = Output::new(
+            Some(dir.path().join("out.ttl")),
+            None,
+            false,
+            None,
+            Some(budget),
+        )
+        .unwrap();
+        let mut writer = TurtleWriter::new(output.open().unwrap());
+
+        // bytes of records written to each chunk
+        let mut chunks: Vec<Vec<u64>> = Vec::new();
+
+        for record in reader.records() {
+            let record = record.expect("Error reading record.");
+            let rendered = writer.render_record(&record).unwrap();
+            let bytes = rendered.len() as u64;
+
+            output.prepare(&mut writer, "20", bytes).unwrap();
+            if chunks.len() < output.files().len() {
+                chunks.push(Vec::new());
+            }
+
+            if !rendered.is_empty() {
+                writer.write_rendered(rendered).unwrap();
+                output.record_written(bytes);
+                chunks.last_mut().unwrap().push(bytes);
+            }
+        }
+        writer.flush().unwrap();
+
+        (output, chunks)
+    }
+
+    #[test]
+    fn test_chunk_bytes() {
+        let (_, all) = write_chunks(u64::MAX);
+        let total: u64 = all.iter().flatten().sum();
+        let largest = *all.iter().flatten().max().unwrap();
+
+        for budget in &[largest, largest + 1, largest.max(total / 2)] {
+            let (output, chunks) = write_chunks(*budget);
+
+            assert!(chunks.len() > 1);
+            assert_eq!(chunks.iter().flatten().sum::<u64>(), total);
+
+            for ((_, size), records) in output.sizes().zip(chunks.iter()) {
+                assert_eq!(size, records.iter().sum::<u64>());
+                assert!(size <= *budget);
+            }
+        }
+    }
+
+    #[test]
+    fn test_chunk_bytes_with_large_record() {
+        let (_, all) = write_chunks(u64::MAX);
+        let largest = *all.iter().flatten().max().unwrap();
+
+        let (output, chunks) = write_chunks(largest - 1);
+
+        for ((_, size), records) in output.sizes().zip(chunks.iter()) {
+            assert!(size < largest || records.len() == 1);
+        }
+    }
+}
@@ -1,16 +1,23 @@
 use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::BufReader;
 use std::path::PathBuf;
 
+use log::*;
 use structopt::StructOpt;
 use strum::VariantNames;
-use strum::{EnumString, EnumVariantNames};
+use strum::{AsRefStr, EnumString, EnumVariantNames};
 
-use crate::config::{Config, Sequence};
-use crate::errors::Result;
-use crate::vcf::assembly::{GRCH37_P13, GRCH38_P13, GRCM38, GRCM39};
+use serde_yaml::{Mapping, Value};
+
+use crate::config::{schema, Config, Sequence};
+use crate::errors::{Error, Result};
+use crate::vcf::assembly::{
+    self, AssemblyDefinition, Detection, Scaffold, CHM13_V2, GRCH37_P13, GRCH38_P13, GRCM38, GRCM39,
+};
 use crate::vcf::reader::Reader;
 
-#[derive(EnumString, EnumVariantNames, Debug)]
+#[derive(EnumString, EnumVariantNames, AsRefStr, Debug)]
 pub enum Assembly {
     #[strum(serialize = "GRCh37")]
     GRCH37,
@@ -20,6 +27,69 @@ pub enum Assembly {
     GRCM38,
     #[strum(serialize = "GRCm39")]
     GRCM39,
+    #[strum(serialize = "CHM13v2")]
+    CHM13V2,
+    /// Detect from contigs in the header.
+    #[strum(serialize = "auto")]
+    Auto,
+}
+
+impl Assembly {
+    /// Returns the built-in assembly, or `None` for `auto`.
+    fn builtin(&self) -> Option<&'static assembly::Assembly<'static>> {
+        match self {
+            Assembly::GRCH37 => Some(&*GRCH37_P13),
+            Assembly::GRCH38 => Some(&*GRCH38_P13),
+            Assembly::GRCM38 => Some(&*GRCM38),
+            Assembly::GRCM39 => Some(&*GRCM39),
+            Assembly::CHM13V2 => Some(&*CHM13_V2),
+            Assembly::Auto => None,
+        }
+    }
+}
+
+#[derive(EnumString, EnumVariantNames, Debug, Copy, Clone, PartialEq)]
+#[strum(serialize_all = "snake_case")]
+pub enum ConfigFormat {
+    Yaml,
+    Json,
+    Toml,
+}
+
+/// Which name of a sequence is written into `name` of the configuration.
+#[derive(EnumString, EnumVariantNames, Debug, Copy, Clone, PartialEq)]
+#[strum(serialize_all = "snake_case")]
+pub enum Naming {
+    Name,
+    Ucsc,
+    Refseq,
+    Genbank,
+}
+
+impl Naming {
+    /// Returns the name of `sequence`, or its name in the assembly if the chosen one is not available.
+    fn of<'a>(&self, sequence: &assembly::Sequence<'a>) -> &'a str {
+        let name = match self {
+            Naming::Name => sequence.name,
+            Naming::Ucsc => sequence.ucsc_name,
+            Naming::Refseq => sequence.refseq,
+            Naming::Genbank => sequence.genbank,
+        };
+
+        if name.is_empty() || name == "na" {
+            sequence.name
+        } else {
+            name
+        }
+    }
+}
+
+#[derive(EnumString, EnumVariantNames, Debug, Copy, Clone, PartialEq)]
+#[strum(serialize_all = "snake_case")]
+pub enum AssemblyFormat {
+    Table,
+    Yaml,
+    Json,
 }
 
 #[derive(StructOpt, Debug)]
@@ -30,50 +100,92 @@ pub enum Options {
         #[structopt(short, long, possible_values = Assembly::VARIANTS)]
         assembly: Option<Assembly>,
 
+        /// Path to YAML defining an assembly and its sequences (name, genbank, refseq, ucsc_name and reference).
+        #[structopt(long, parse(from_os_str), conflicts_with = "assembly")]
+        assembly_file: Option<PathBuf>,
+
+        /// Path to NCBI assembly report (`*_assembly_report.txt`) defining an assembly.
+        #[structopt(
+            long,
+            parse(from_os_str),
+            conflicts_with_all = &["assembly", "assembly-file"]
+        )]
+        assembly_report: Option<PathBuf>,
+
+        /// Name of sequences written into `name` of each contig, which is used in subjects by `--subject location`.
+        #[structopt(long, default_value = "name", possible_values = Naming::VARIANTS)]
+        naming: Naming,
+
+        /// Output format. Comments are written in `_comment` keys in JSON, and contigs without a sequence are commented out in TOML.
+        #[structopt(long, default_value = "yaml", possible_values = ConfigFormat::VARIANTS)]
+        format: ConfigFormat,
+
         /// Path to file to process.
         #[structopt(name = "FILE", parse(from_os_str))]
         input: PathBuf,
     },
+
+    /// Prints JSON Schema of the configuration.
+    Schema,
+
+    /// Prints built-in assemblies and names of their sequences.
+    Assemblies {
+        /// Output format.
+        #[structopt(long, default_value = "table", possible_values = AssemblyFormat::VARIANTS)]
+        format: AssemblyFormat,
+    },
 }
 
 pub fn run(command: Options) -> Result<()> {
     match command {
-        Options::Config { assembly, input } => {
+        Options::Config {
+            assembly,
+            assembly_file,
+            assembly_report,
+            naming,
+            format,
+            input,
+        } => {
             let vcf = Reader::from_path(input)?;
-
-            let assembly = match assembly.as_ref() {
-                Some(v) => match v {
-                    Assembly::GRCH37 => Some(GRCH37_P13.clone()),
-                    Assembly::GRCH38 => Some(GRCH38_P13.clone()),
-                    Assembly::GRCM38 => Some(GRCM38.clone()),
-                    Assembly::GRCM39 => Some(GRCM39.clone()),
-                },
+            let definition = match (assembly_file, assembly_report) {
+                (Some(path), _) => Some(AssemblyDefinition::from_path(path)?),
+                (_, Some(path)) => Some(AssemblyDefinition::from_assembly_report(BufReader::new(
+                    File::open(path)?,
+                ))?),
+                _ => None,
+            };
+            let builtin = match assembly.as_ref() {
+                Some(v) => resolve_assembly(v, &vcf.contig_lengths())?,
                 None => None,
             };
+            let assembly = match builtin {
+                Some(x) => Some(x.clone()),
+                None => definition.as_ref().map(|x| x.as_assembly()),
+            };
 
-            let mut reference = BTreeMap::new();
-            for (_, name) in vcf.contigs().iter() {
-                // TODO: M -> MT
-                let seq = assembly
-                    .as_ref()
-                    .map(|x| {
-                        x.find_sequence(name).map(|x| Sequence {
-                            name: Some(String::from(x.name)),
-                            reference: Some(String::from(x.reference)),
-                        })
-                    })
-                    .unwrap_or(None);
-
-                reference.insert(name.to_owned(), seq.or(Some(Sequence::default())));
-            }
+            let contigs: Vec<String> = vcf.contigs().into_iter().map(|(_, x)| x).collect();
+            let (reference, decoys) = map_contigs(&contigs, assembly.as_ref(), naming);
 
             let config = Config {
                 base: None,
                 namespaces: None,
                 info: Some(vcf.info_keys().clone()),
                 reference,
+                ..Default::default()
             };
 
+            match format {
+                ConfigFormat::Json => {
+                    println!("{}", config_json(&config, &decoys)?);
+                    return Ok(());
+                }
+                ConfigFormat::Toml => {
+                    print!("{}", config_toml(&config, &decoys)?);
+                    return Ok(());
+                }
+                ConfigFormat::Yaml => {}
+            }
+
             let mut yaml = serde_yaml::to_string(&config)?;
 
             if let Some(i) = yaml.find("base:") {
@@ -88,13 +200,429 @@ pub fn run(command: Options) -> Result<()> {
                 yaml.insert_str(i, "\n# Sequence reference mapping.\n");
             }
 
+            annotate_decoys(&mut yaml, &decoys);
+            yaml = comment_contig_base(&yaml);
+
             if let Some(i) = yaml.find("info:") {
                 yaml.insert_str(i, "\n# Remove unnecessary keys to convert.\n");
             }
 
+            yaml += &info_predicates_skeleton(vcf.info_keys());
+            yaml += &samples_skeleton(&vcf.samples());
+
             println!("{}", &yaml);
         }
+        Options::Schema => print!("{}", schema::SCHEMA),
+        Options::Assemblies { format } => {
+            let definitions: Vec<_> = assembly::builtin()
+                .iter()
+                .map(|x| x.to_definition())
+                .collect();
+
+            match format {
+                AssemblyFormat::Table => print!("{}", assemblies_table(&definitions)),
+                AssemblyFormat::Yaml => print!("{}", serde_yaml::to_string(&definitions)?),
+                AssemblyFormat::Json => {
+                    println!("{}", serde_json::to_string_pretty(&definitions)?)
+                }
+            }
+        }
     }
 
     Ok(())
 }
+
+/// Returns the built-in assembly of `assembly`, which is detected from `contigs` with their lengths for `auto`.
+///
+/// Asks to specify the assembly if several ones match, and warns if the specified one differs from the detected one.
+pub(crate) fn resolve_assembly(
+    assembly: &Assembly,
+    contigs: &[(String, Option<u64>)],
+) -> Result<Option<&'static assembly::Assembly<'static>>> {
+    let detection = assembly::detect(contigs);
+
+    if let Some(x) = assembly.builtin() {
+        if let Detection::Found(y) = detection {
+            if x.name() != y.name() {
+                warn!("Contigs look like {} rather than {}", y.name(), x.name());
+            }
+        }
+        return Ok(Some(x));
+    }
+
+    match detection {
+        Detection::Found(x) => {
+            info!("Detected assembly: {}", x.name());
+            Ok(Some(x))
+        }
+        Detection::Ambiguous(x) => Err(Error::InvalidArgumentError(format!(
+            "contigs match {}, specify one of them with --assembly",
+            x.iter().map(|x| x.name()).collect::<Vec<_>>().join(", ")
+        )))?,
+        Detection::NotFound => {
+            warn!("No built-in assembly matches contigs");
+            Ok(None)
+        }
+    }
+}
+
+/// Map `contigs` to sequences of `assembly` named by `naming`, where unknown contigs have empty sequences.
+///
+/// Decoys of hs37d5 and the GRCh38 analysis set are mapped to `None`, and returned with the reason.
+pub(crate) fn map_contigs(
+    contigs: &[String],
+    assembly: Option<&assembly::Assembly>,
+    naming: Naming,
+) -> (
+    BTreeMap<String, Option<Sequence>>,
+    Vec<(String, &'static str)>,
+) {
+    let name = assembly.map(|x| x.name());
+    let with_decoys = matches!(name, Some("GRCh37") | Some("GRCh38"));
+    let with_scaffolds = matches!(name, Some("GRCh38"));
+
+    let mut reference = BTreeMap::new();
+    let mut decoys = Vec::new();
+    for name in contigs {
+        if let Some(reason) = assembly::decoy(name).filter(|_| with_decoys) {
+            reference.insert(name.to_owned(), None);
+            decoys.push((name.to_owned(), reason));
+            continue;
+        }
+
+        let seq = assembly
+            .and_then(|x| x.find_sequence(name))
+            .map(|x| Sequence {
+                name: Some(String::from(naming.of(x))),
+                reference: Some(String::from(x.reference)),
+                ..Default::default()
+            })
+            .or_else(|| {
+                Scaffold::parse(name)
+                    .filter(|_| with_scaffolds)
+                    .map(|x| Sequence {
+                        reference: Some(x.reference()),
+                        name: Some(x.genbank),
+                        ..Default::default()
+                    })
+            });
+
+        reference.insert(name.to_owned(), seq.or(Some(Sequence::default())));
+    }
+
+    (reference, decoys)
+}
+
+/// Returns `config` in JSON, where comments are written in `_comment` keys, which are ignored on load.
+fn config_json(config: &Config, decoys: &[(String, &str)]) -> Result<String> {
+    let mut comments = Mapping::new();
+    for (key, comment) in &[
+        ("base", "Set base IRI if needed."),
+        ("namespaces", "Additional namespaces."),
+        ("info", "Remove unnecessary keys to convert."),
+        ("reference", "Sequence reference mapping."),
+    ] {
+        comments.insert(Value::from(*key), Value::from(*comment));
+    }
+
+    let mut value = Mapping::new();
+    value.insert(Value::from("_comment"), Value::Mapping(comments));
+
+    for (key, mut x) in serde_yaml::to_value(config)?
+        .as_mapping()
+        .cloned()
+        .unwrap_or_default()
+    {
+        if key.as_str() == Some("reference") && !decoys.is_empty() {
+            if let Some(reference) = x.as_mapping_mut() {
+                let mut comments = Mapping::new();
+                for (name, reason) in decoys {
+                    let comment = format!("{}; records on it will be skipped.", reason);
+                    comments.insert(Value::from(name.as_str()), Value::from(comment));
+                }
+                reference.insert(Value::from("_comment"), Value::Mapping(comments));
+            }
+        }
+        value.insert(key, x);
+    }
+
+    Ok(serde_json::to_string_pretty(&Value::Mapping(value))?)
+}
+
+/// Returns `config` in TOML, which has no null, so that contigs without a sequence are listed in comments.
+fn config_toml(config: &Config, decoys: &[(String, &str)]) -> Result<String> {
+    let mut toml = toml::to_string_pretty(config)?;
+
+    if let Some(i) = toml.find("[reference.") {
+        toml.insert_str(i, "# Sequence reference mapping.\n");
+    }
+
+    if let Some(i) = toml.find("info = ") {
+        toml.insert_str(i, "# Remove unnecessary keys to convert.\n");
+    }
+
+    if config.base.is_none() {
+        toml.insert_str(0, "# Set base IRI if needed.\n# base = \"\"\n\n");
+    }
+
+    let unmapped: Vec<&String> = config
+        .reference
+        .iter()
+        .filter(|(_, v)| v.is_none())
+        .map(|(k, _)| k)
+        .collect();
+    if !unmapped.is_empty() {
+        toml += "\n# Contigs without a sequence, of which records will be skipped.\n";
+        for name in unmapped {
+            match decoys.iter().find(|(x, _)| x == name) {
+                Some((_, reason)) => toml += &format!("# {}: {}\n", name, reason),
+                None => toml += &format!("# {}\n", name),
+            }
+        }
+    }
+
+    Ok(toml)
+}
+
+/// Returns `yaml` with commented-out `base` following `reference` of each contig.
+fn comment_contig_base(yaml: &str) -> String {
+    let mut buf = String::with_capacity(yaml.len());
+
+    for line in yaml.lines() {
+        buf += line;
+        buf += "\n";
+        if line.starts_with("    reference:") {
+            buf += "    # base: ~ # Base IRI of subjects on this contig, overriding base\n";
+        }
+    }
+
+    buf
+}
+
+/// Returns sequences of `assemblies` in TSV, preceded by a comment line of accessions of each assembly.
+fn assemblies_table(assemblies: &[AssemblyDefinition]) -> String {
+    let mut buf = "assembly\tname\tgenbank\trefseq\tucsc_name\treference\n".to_owned();
+
+    for assembly in assemblies {
+        buf += &format!(
+            "# {} (GenBank: {}, RefSeq: {})\n",
+            assembly.name, assembly.genbank, assembly.refseq
+        );
+        for x in &assembly.sequences {
+            buf += &format!(
+                "{}\t{}\t{}\t{}\t{}\t{}\n",
+                assembly.name, x.name, x.genbank, x.refseq, x.ucsc_name, x.reference
+            );
+        }
+    }
+
+    buf
+}
+
+/// Insert a comment above each of `decoys` in the reference mapping of `yaml`, telling why it is not mapped.
+fn annotate_decoys(yaml: &mut String, decoys: &[(String, &str)]) {
+    for (name, reason) in decoys {
+        if let Some(i) = yaml.find(&format!("\n  {}: ~", name)) {
+            yaml.insert_str(
+                i + 1,
+                &format!("  # {}: {}; records on it will be skipped.\n", name, reason),
+            );
+        }
+    }
+}
+
+/// Returns a commented-out map of predicates for INFO `keys`.
+fn info_predicates_skeleton(keys: &[String]) -> String {
+    let mut buf =
+        "\n# Write INFO values as direct triples of predicates instead of gvo:info.\n# info_predicates:\n"
+            .to_owned();
+
+    for key in keys {
+        buf += &format!("#   {}: http://example.org/vocab#{}\n", key, key);
+    }
+
+    buf
+}
+
+/// Returns a commented-out list of `samples`, which is empty if there are no samples.
+fn samples_skeleton(samples: &[String]) -> String {
+    if samples.is_empty() {
+        return String::new();
+    }
+
+    let mut buf =
+        "\n# Write genotypes of the listed samples only with --with-genotypes.\n# samples:\n"
+            .to_owned();
+
+    for sample in samples {
+        buf += &format!("#   - {}\n", sample);
+    }
+
+    buf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_info_predicates_skeleton() {
+        let skeleton = info_predicates_skeleton(&["AF".to_owned(), "DP".to_owned()]);
+
+        assert!(skeleton
+            .contains("\n# info_predicates:\n#   AF: http://example.org/vocab#AF\n#   DP: "));
+        assert!(skeleton.lines().all(|x| x.is_empty() || x.starts_with('#')));
+    }
+
+    #[test]
+    fn test_assemblies_table() {
+        let table = assemblies_table(&[assembly::GRCH37_P13.to_definition()]);
+        let mut lines = table.lines();
+
+        assert_eq!(
+            lines.next(),
+            Some("assembly\tname\tgenbank\trefseq\tucsc_name\treference")
+        );
+        assert_eq!(
+            lines.next(),
+            Some("# GRCh37 (GenBank: GCA_000001405.14, RefSeq: GCF_000001405.25)")
+        );
+        assert_eq!(
+            lines.next(),
+            Some("GRCh37\t1\tCM000663.1\tNC_000001.10\tchr1\thttp://identifiers.org/hco/1/GRCh37")
+        );
+    }
+
+    #[test]
+    fn test_naming() {
+        let sequence = GRCH38_P13.find_sequence(&"chr1".to_owned()).unwrap();
+
+        assert_eq!(Naming::Name.of(sequence), "1");
+        assert_eq!(Naming::Ucsc.of(sequence), "chr1");
+        assert_eq!(Naming::Refseq.of(sequence), "NC_000001.11");
+        assert_eq!(Naming::Genbank.of(sequence), "CM000663.2");
+
+        let mitochondrion = CHM13_V2.find_sequence(&"chrM".to_owned()).unwrap();
+        assert_eq!(Naming::Refseq.of(mitochondrion), "MT");
+    }
+
+    #[test]
+    fn test_map_contigs() {
+        let contigs: Vec<String> = vec!["chr1", "chr1_KI270706v1_random", "chrEBV", "scaffold_1"]
+            .into_iter()
+            .map(|x| x.to_owned())
+            .collect();
+        let (reference, decoys) = map_contigs(&contigs, Some(&*GRCH38_P13), Naming::Ucsc);
+
+        let name = |x: &str| reference[x].as_ref().and_then(|x| x.name.as_deref());
+        assert_eq!(name("chr1"), Some("chr1"));
+        assert_eq!(name("chr1_KI270706v1_random"), Some("KI270706.1"));
+        assert!(reference["chrEBV"].is_none());
+        assert_eq!(name("scaffold_1"), None);
+        assert!(reference["scaffold_1"].is_some());
+        assert_eq!(decoys.len(), 1);
+
+        let (reference, decoys) = map_contigs(&contigs, None, Naming::Name);
+        assert!(reference
+            .values()
+            .all(|x| x.as_ref().unwrap().name.is_none()));
+        assert!(decoys.is_empty());
+    }
+
+    #[test]
+    fn test_resolve_assembly() {
+        let contigs = vec![("1".to_owned(), None), ("2".to_owned(), None)];
+
+        assert!(resolve_assembly(&Assembly::Auto, &contigs).is_err());
+        assert_eq!(
+            resolve_assembly(&Assembly::GRCH37, &contigs)
+                .unwrap()
+                .map(|x| x.name()),
+            Some("GRCh37")
+        );
+
+        let contigs = vec![("NC_000001.11".to_owned(), None)];
+        assert_eq!(
+            resolve_assembly(&Assembly::Auto, &contigs)
+                .unwrap()
+                .map(|x| x.name()),
+            Some("GRCh38")
+        );
+    }
+
+    #[test]
+    fn test_comment_contig_base() {
+        let yaml = "---\nreference:\n  \"20\":\n    name: \"20\"\n    reference: ~\n  \"21\": ~\n";
+
+        assert_eq!(
+            comment_contig_base(yaml),
+            "---\nreference:\n  \"20\":\n    name: \"20\"\n    reference: ~\n    # base: ~ # Base IRI of subjects on this contig, overriding base\n  \"21\": ~\n"
+        );
+    }
+
+    #[test]
+    fn test_config_json() {
+        let mut config = Config::default();
+        config.reference.insert("20".to_owned(), None);
+        config.reference.insert("hs37d5".to_owned(), None);
+
+        let json = config_json(&config, &[("hs37d5".to_owned(), "decoy")]).unwrap();
+        assert!(
+            json.starts_with("{\n  \"_comment\": {\n    \"base\": \"Set base IRI if needed.\",")
+        );
+        assert!(json.contains("\"hs37d5\": \"decoy; records on it will be skipped.\""));
+
+        let value: Value = serde_yaml::from_str(&json).unwrap();
+        let mut value = value.as_mapping().unwrap().clone();
+        value.remove(&Value::from("_comment"));
+        assert!(value.contains_key(&Value::from("reference")));
+    }
+
+    #[test]
+    fn test_config_toml() {
+        let mut config = Config::default();
+        config.info = Some(vec!["DP".to_owned()]);
+        config.reference.insert(
+            "20".to_owned(),
+            Some(Sequence {
+                name: Some("20".to_owned()),
+                reference: Some("http://identifiers.org/hco/20/GRCh37".to_owned()),
+                ..Default::default()
+            }),
+        );
+        config.reference.insert("hs37d5".to_owned(), None);
+
+        let toml = config_toml(&config, &[("hs37d5".to_owned(), "decoy")]).unwrap();
+        assert!(toml.starts_with("# Set base IRI if needed.\n"));
+        assert!(toml.contains("# Remove unnecessary keys to convert.\ninfo = [\"DP\"]\n"));
+        assert!(toml.contains("# Sequence reference mapping.\n[reference.20]\n"));
+        assert!(toml.ends_with("\n# hs37d5: decoy\n"));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, &toml).unwrap();
+        let loaded = Config::from_path(&path).unwrap();
+        assert_eq!(loaded.info, config.info);
+        assert_eq!(loaded.reference.keys().collect::<Vec<_>>(), vec!["20"]);
+    }
+
+    #[test]
+    fn test_annotate_decoys() {
+        let mut yaml = "---\nreference:\n  \"1\":\n    name: \"1\"\n  hs37d5: ~\n".to_owned();
+
+        annotate_decoys(&mut yaml, &[("hs37d5".to_owned(), "decoy")]);
+
+        assert!(
+            yaml.ends_with("\n  # hs37d5: decoy; records on it will be skipped.\n  hs37d5: ~\n")
+        );
+    }
+
+    #[test]
+    fn test_samples_skeleton() {
+        let skeleton = samples_skeleton(&["NA00001".to_owned(), "NA00002".to_owned()]);
+
+        assert!(skeleton.contains("\n# samples:\n#   - NA00001\n#   - NA00002\n"));
+        assert!(skeleton.lines().all(|x| x.is_empty() || x.starts_with('#')));
+        assert!(samples_skeleton(&[]).is_empty());
+    }
+}
@@ -0,0 +1,141 @@
+use std::io::{self, BufRead, BufWriter, Write};
+use std::path::PathBuf;
+
+use log::*;
+use structopt::StructOpt;
+
+use crate::errors::{Error, Result};
+use crate::vcf::alteration::{self, NormalizedVariant};
+use crate::vcf::fasta::Fasta;
+
+#[derive(StructOpt, Debug)]
+pub struct Options {
+    /// Path to FASTA of the reference genome to left-align indels, indexed by `samtools faidx` (built if missing).
+    #[structopt(long, parse(from_os_str))]
+    pub fasta: Option<PathBuf>,
+}
+
+/// Read variants from stdin and print them normalized as `chrom pos ref alt type` in TSV.
+///
+/// The input is VCF if it starts with `##fileformat` or `#CHROM`, and TSV of `chrom pos ref alt` otherwise.
+pub fn run(options: Options) -> Result<()> {
+    let fasta = match options.fasta.as_ref() {
+        Some(path) => Some(Fasta::from_path(path)?),
+        None => None,
+    };
+
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    let mut output = BufWriter::new(stdout.lock());
+
+    normalize(stdin.lock(), &mut output, fasta.as_ref())
+        .and_then(|_| Ok(output.flush()?))
+        .map_err(|err| match err {
+            Error::IOError(e) if e.kind() == io::ErrorKind::BrokenPipe => Error::OutputClosedError,
+            err => err,
+        })
+}
+
+/// Normalize variants of each line of `input` and write them to `output`.
+///
+/// Lines which can not be normalized are skipped with a warning.
+fn normalize<R: BufRead, W: Write>(input: R, output: &mut W, fasta: Option<&Fasta>) -> Result<()> {
+    let mut vcf = None;
+
+    for (i, line) in input.lines().enumerate() {
+        let line = line?;
+        if vcf.is_none() && !line.is_empty() {
+            vcf = Some(line.starts_with("##fileformat") || line.starts_with("#CHROM"));
+        }
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let columns: Vec<&str> = line.split('\t').collect();
+        let (chrom, pos, reference, alternates) = match (vcf, columns.as_slice()) {
+            (Some(true), [chrom, pos, _, reference, alternate, ..])
+            | (Some(false), [chrom, pos, reference, alternate, ..]) => {
+                (*chrom, *pos, *reference, *alternate)
+            }
+            _ => {
+                warn!("Too few columns at line {}", i + 1);
+                continue;
+            }
+        };
+
+        let position = match pos.parse::<u64>() {
+            Ok(x) if x > 0 => x,
+            _ => {
+                warn!("Invalid position at line {}: {}", i + 1, pos);
+                continue;
+            }
+        };
+
+        for alternate in alternates.split(',') {
+            let variant =
+                alteration::normalize_variant(position, reference, alternate).and_then(|x| {
+                    match fasta {
+                        Some(fasta) => alteration::left_align(fasta, chrom, &x),
+                        None => Ok(x),
+                    }
+                });
+
+            match variant {
+                Ok(x) => write_variant(output, chrom, &x)?,
+                Err(e) => warn!("{} at line {}", e, i + 1),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn write_variant<W: Write>(output: &mut W, chrom: &str, variant: &NormalizedVariant) -> Result<()> {
+    writeln!(
+        output,
+        "{}\t{}\t{}\t{}\t{}",
+        chrom,
+        variant.position,
+        variant.reference,
+        variant.alternate,
+        variant.typ.map_or(".", |x| x.as_str())
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_normalize(input: &str) -> String {
+        let mut output = Vec::new();
+        normalize(input.as_bytes(), &mut output, None).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_normalize_tsv() {
+        assert_eq!(
+            run_normalize("20\t100\tctg\tcag\n20\t200\tCAT\tCT\n20\t300\tA\t<DEL>\n20\tx\tA\tC\n"),
+            "20\t101\tT\tA\tSNV\n20\t200\tCA\tC\tDeletion\n"
+        );
+    }
+
+    #[test]
+    fn test_normalize_vcf() {
+        let input = std::fs::read_to_string("test/vcf_spec.vcf").unwrap();
+
+        assert_eq!(
+            run_normalize(&input),
+            concat!(
+                "20\t14370\tG\tA\tSNV\n",
+                "20\t17330\tT\tA\tSNV\n",
+                "20\t1110696\tA\tG\tSNV\n",
+                "20\t1110696\tA\tT\tSNV\n",
+                "20\t1234567\tGTC\tG\tDeletion\n",
+                "20\t1234569\tC\tCT\tInsertion\n",
+            )
+        );
+    }
+}
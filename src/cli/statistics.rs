@@ -1,24 +1,422 @@
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
 
+use rust_htslib::bcf;
+use rust_htslib::bcf::Read;
+use serde::Serialize;
 use structopt::StructOpt;
+use strum::{EnumString, EnumVariantNames, VariantNames};
 
-use crate::errors::Result;
-use crate::vcf::reader::Reader;
+use crate::errors::{Error, Result};
+use crate::util::vcf;
+use crate::vcf::fasta::{self, Fasta};
+use crate::vcf::header::{self, Definition, DefinitionKind};
+use crate::vcf::reader::ReaderBuilder;
+use crate::vcf::sample;
+
+#[derive(EnumString, EnumVariantNames, Debug, Copy, Clone, PartialEq)]
+#[strum(serialize_all = "snake_case")]
+pub enum HeaderFormat {
+    Yaml,
+    Json,
+}
 
 #[derive(StructOpt, Debug)]
 pub enum Options {
     /// Counts records.
+    ///
+    /// Records are counted by streaming if the input is `-` (stdin) or not indexed.
     Count {
-        /// Path to file to process.
+        /// Path to index file (tbi or csi) if not located next to the input.
+        #[structopt(long, parse(from_os_str))]
+        index: Option<PathBuf>,
+
+        /// Count records by streaming even if the input is indexed.
+        #[structopt(long, conflicts_with = "index")]
+        no_index: bool,
+
+        /// Number of threads to decompress the input with when counting by streaming.
+        #[structopt(long, default_value = "1")]
+        read_threads: usize,
+
+        /// Path to file to process, or `-` to read from stdin.
+        #[structopt(name = "FILE", parse(from_os_str))]
+        input: PathBuf,
+    },
+
+    /// Prints names of samples, one per line.
+    Samples {
+        /// Print the number of samples instead.
+        #[structopt(long)]
+        count: bool,
+
+        /// Path to index file (tbi or csi) if not located next to the input.
+        #[structopt(long, parse(from_os_str))]
+        index: Option<PathBuf>,
+
+        /// Path to file to process, or `-` to read from stdin.
         #[structopt(name = "FILE", parse(from_os_str))]
         input: PathBuf,
     },
+
+    /// Prints meta-information, samples and definitions of INFO, FORMAT and FILTER in the header.
+    Header {
+        /// Output format.
+        #[structopt(long, default_value = "yaml", possible_values = HeaderFormat::VARIANTS)]
+        format: HeaderFormat,
+
+        /// Path to file to process, or `-` to read from stdin.
+        #[structopt(name = "FILE", parse(from_os_str))]
+        input: PathBuf,
+    },
+
+    /// Compares REF with the reference genome and prints the numbers of mismatches per contig.
+    ///
+    /// Exits with an error if mismatches exceed `--max-mismatches`.
+    CheckRef {
+        /// Path to FASTA of the reference genome, indexed by `samtools faidx` (built if missing).
+        #[structopt(long, parse(from_os_str))]
+        fasta: PathBuf,
+
+        /// Number of mismatched positions to print.
+        #[structopt(long, default_value = "10")]
+        examples: usize,
+
+        /// Number of mismatches allowed before exiting with an error.
+        #[structopt(long, default_value = "0")]
+        max_mismatches: u64,
+
+        /// Path to file to process, or `-` to read from stdin.
+        #[structopt(name = "FILE", parse(from_os_str))]
+        input: PathBuf,
+    },
+}
+
+/// Contents of the header printed by `stat header`.
+#[derive(Debug, Serialize)]
+struct HeaderSummary {
+    fileformat: Option<String>,
+    /// Values of unstructured meta-information lines by key.
+    meta: BTreeMap<String, Vec<String>>,
+    samples: Vec<String>,
+    info: Vec<Definition>,
+    format: Vec<Definition>,
+    filter: Vec<Definition>,
+}
+
+/// Results of comparing REF with the reference genome by `stat check-ref`.
+#[derive(Debug, Default)]
+struct RefCheck {
+    /// Numbers of records checked and mismatched by contig.
+    contigs: BTreeMap<String, (u64, u64)>,
+    /// Numbers of records on contigs not found in FASTA.
+    missing: BTreeMap<String, u64>,
+    /// First mismatches as contig, position, REF and bases in FASTA.
+    mismatches: Vec<(String, u64, String, String)>,
+}
+
+impl RefCheck {
+    fn mismatched(&self) -> u64 {
+        self.contigs.values().map(|(_, x)| x).sum()
+    }
 }
 
 pub fn run(command: Options) -> Result<()> {
     match command {
-        Options::Count { input } => println!("{}", Reader::from_path(input)?.count()),
+        Options::Count {
+            input,
+            index,
+            no_index,
+            read_threads,
+        } => {
+            let mut builder = ReaderBuilder::new();
+            if let Some(index) = index {
+                builder = builder.index_path(index);
+            }
+
+            if no_index {
+                println!("{}", count_by_streaming(&input, read_threads)?);
+                return Ok(());
+            }
+            if !builder.is_indexed(&input) {
+                eprintln!(
+                    "Note: {} is not indexed, records are counted by streaming.",
+                    input.display()
+                );
+                println!("{}", count_by_streaming(&input, read_threads)?);
+                return Ok(());
+            }
+
+            match builder.path(&input)?.count() {
+                Some(count) => println!("{}", count),
+                None => println!("{}", count_by_streaming(&input, read_threads)?),
+            }
+        }
+        Options::Samples {
+            count,
+            input,
+            index,
+        } => {
+            let mut builder = ReaderBuilder::new();
+            if let Some(index) = index {
+                builder = builder.index_path(index);
+            }
+
+            let samples = if builder.is_indexed(&input) {
+                builder.path(input)?.samples()
+            } else {
+                samples_by_streaming(&input)?
+            };
+
+            if count {
+                println!("{}", samples.len());
+            } else {
+                for sample in samples {
+                    println!("{}", sample);
+                }
+            }
+        }
+        Options::Header { format, input } => {
+            let summary = header_summary(&input)?;
+
+            match format {
+                HeaderFormat::Yaml => print!("{}", serde_yaml::to_string(&summary)?),
+                HeaderFormat::Json => println!("{}", serde_json::to_string_pretty(&summary)?),
+            }
+        }
+        Options::CheckRef {
+            fasta,
+            examples,
+            max_mismatches,
+            input,
+        } => {
+            let check = check_ref(&input, &fasta, examples)?;
+
+            for (contig, n) in &check.missing {
+                eprintln!(
+                    "Note: {} is not found in {}, {} records are not checked.",
+                    contig,
+                    fasta.display(),
+                    n
+                );
+            }
+
+            println!("contig\tchecked\tmismatched");
+            for (contig, (checked, mismatched)) in &check.contigs {
+                println!("{}\t{}\t{}", contig, checked, mismatched);
+            }
+
+            if !check.mismatches.is_empty() {
+                println!();
+                println!("position\tref\tfasta");
+                for (contig, position, reference, bases) in &check.mismatches {
+                    println!("{}:{}\t{}\t{}", contig, position, reference, bases);
+                }
+            }
+
+            let mismatched = check.mismatched();
+            if mismatched > max_mismatches {
+                Err(Error::RefMismatchError(mismatched, max_mismatches))?
+            }
+        }
     }
 
     Ok(())
 }
+
+/// Count records by reading through `input`, or stdin if `-`, decompressing with `threads`.
+fn count_by_streaming(input: &Path, threads: usize) -> Result<u64> {
+    let mut reader = ReaderBuilder::new()
+        .require_index(false)
+        .threads(threads)
+        .open(input)?;
+
+    let mut count = 0;
+    for record in reader.records() {
+        record?;
+        count += 1;
+    }
+
+    Ok(count)
+}
+
+/// Returns names of samples in the header of `input`, or stdin if `-`.
+fn samples_by_streaming(input: &Path) -> Result<Vec<String>> {
+    Ok(ReaderBuilder::new()
+        .require_index(false)
+        .open(input)?
+        .samples())
+}
+
+/// Returns contents of the header of `input`, or stdin if `-`, which need not be indexed.
+fn header_summary(input: &Path) -> Result<HeaderSummary> {
+    let reader = if input == Path::new("-") {
+        bcf::Reader::from_stdin()?
+    } else {
+        vcf::open(input)?
+    };
+    let header = reader.header();
+
+    let mut meta: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (key, value) in header::meta(header) {
+        meta.entry(key).or_default().push(value);
+    }
+
+    Ok(HeaderSummary {
+        fileformat: header::fileformat(header),
+        meta,
+        samples: sample::names(header),
+        info: header::definitions(header, DefinitionKind::Info),
+        format: header::definitions(header, DefinitionKind::Format),
+        filter: header::definitions(header, DefinitionKind::Filter),
+    })
+}
+
+/// Compare REF of records in `input`, or stdin if `-`, with the sequences in `fasta`,
+/// keeping the first `examples` mismatches.
+fn check_ref(input: &Path, fasta: &Path, examples: usize) -> Result<RefCheck> {
+    let mut reader = if input == Path::new("-") {
+        bcf::Reader::from_stdin()?
+    } else {
+        vcf::open(input)?
+    };
+    let fasta = Fasta::from_path(fasta)?;
+
+    let mut check = RefCheck::default();
+    // sequence names in FASTA by rid
+    let mut names: HashMap<u32, Option<String>> = HashMap::new();
+    let mut record = reader.empty_record();
+
+    while let Some(r) = reader.read(&mut record) {
+        r?;
+
+        let rid = match record.rid() {
+            Some(rid) => rid,
+            None => continue,
+        };
+        let contig = String::from_utf8_lossy(record.header().rid2name(rid)?).to_string();
+        let name = names.entry(rid).or_insert_with(|| fasta.resolve(&contig));
+
+        let name = match name {
+            Some(name) => name,
+            None => {
+                *check.missing.entry(contig).or_default() += 1;
+                continue;
+            }
+        };
+
+        let reference = record
+            .alleles()
+            .first()
+            .map_or(String::new(), |x| String::from_utf8_lossy(x).to_string());
+        let position = record.pos() as u64 + 1;
+        let bases = fasta.fetch(name, position, reference.len())?;
+
+        let counts = check.contigs.entry(contig.clone()).or_default();
+        counts.0 += 1;
+
+        if !fasta::matches(&reference, &bases) {
+            counts.1 += 1;
+            if check.mismatches.len() < examples {
+                check.mismatches.push((contig, position, reference, bases));
+            }
+        }
+    }
+
+    Ok(check)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+
+    #[test]
+    fn test_count_by_streaming() {
+        let indexed = ReaderBuilder::new()
+            .path("test/vcf_spec.vcf.gz")
+            .expect("Error opening file.")
+            .count();
+
+        assert_eq!(
+            Some(count_by_streaming(Path::new("test/vcf_spec.vcf"), 1).unwrap()),
+            indexed
+        );
+        assert_eq!(
+            Some(count_by_streaming(Path::new("test/dbsnp_example.vcf"), 1).unwrap()),
+            ReaderBuilder::new()
+                .path("test/dbsnp_example.vcf.gz")
+                .expect("Error opening file.")
+                .count()
+        );
+        assert_eq!(
+            count_by_streaming(Path::new("test/dbsnp_example.vcf.gz"), 4).unwrap(),
+            250
+        );
+    }
+
+    #[test]
+    fn test_samples_by_streaming() {
+        let indexed = ReaderBuilder::new()
+            .path("test/vcf_spec.vcf.gz")
+            .expect("Error opening file.");
+
+        assert_eq!(
+            samples_by_streaming(Path::new("test/vcf_spec.vcf")).unwrap(),
+            indexed.samples()
+        );
+        assert_eq!(indexed.sample_count(), 3);
+        assert!(samples_by_streaming(Path::new("test/dbsnp_example.vcf"))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_header_summary() {
+        let summary = header_summary(Path::new("test/vcf_spec.vcf")).unwrap();
+
+        assert_eq!(summary.fileformat.as_deref(), Some("VCFv4.3"));
+        assert_eq!(
+            summary.meta.get("phasing"),
+            Some(&vec!["partial".to_owned()])
+        );
+        assert_eq!(summary.samples.len(), 3);
+        assert_eq!(summary.info.len(), 6);
+        assert_eq!(summary.format.len(), 4);
+        assert_eq!(summary.filter.len(), 3);
+
+        let yaml = serde_yaml::to_string(&summary).unwrap();
+        assert!(yaml.contains("type: Integer"));
+
+        let json = serde_json::to_string_pretty(&summary).unwrap();
+        assert!(json.contains("\"fileformat\": \"VCFv4.3\""));
+    }
+
+    #[test]
+    fn test_check_ref() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ref.fa");
+        let mut file = std::fs::File::create(&path).unwrap();
+        // REF at 14369 (TGA) and 14370 (G) do not match
+        writeln!(file, ">chr20\n{}TCac", "A".repeat(14368)).unwrap();
+        drop(file);
+
+        let check = check_ref(Path::new("test/star_allele.vcf"), &path, 1).unwrap();
+
+        assert_eq!(check.contigs.get("20"), Some(&(3, 2)));
+        assert_eq!(check.mismatched(), 2);
+        assert_eq!(
+            check.mismatches,
+            vec![("20".to_owned(), 14369, "TGA".to_owned(), "TCa".to_owned())]
+        );
+        assert!(check.missing.is_empty());
+
+        let path = dir.path().join("other.fa");
+        std::fs::write(&path, ">chr21\nACGT\n").unwrap();
+
+        let check = check_ref(Path::new("test/vcf_spec.vcf"), &path, 10).unwrap();
+        assert!(check.contigs.is_empty());
+        assert_eq!(check.missing.get("20"), Some(&5));
+    }
+}
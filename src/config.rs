@@ -1,31 +1,212 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs::File;
-use std::path::Path;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
 
 use log::*;
 use serde::{Deserialize, Serialize};
+use serde_yaml::Value;
+use strum::VariantNames;
 
-use crate::errors::Result;
+use crate::errors::{Error, Result};
+use crate::rdf::iri;
+use crate::rdf::namespace::{self, Namespace};
+use crate::rdf::template::SubjectTemplate;
+use crate::rdf::turtle_writer::{
+    default_info_predicates, default_pedigree_predicates, DEFAULT_CONSEQUENCE_PREDICATE,
+    DEFAULT_GENOTYPE_FREQUENCY_PREDICATE, DEFAULT_SAMPLE_TEMPLATE, FORMAT_KEYS,
+};
+use crate::util::template::Template;
+use crate::vcf::assembly::Assembly;
+use crate::vcf::genotype::Zygosity;
+use crate::vcf::reader::{ReaderBuilder, REFERENCE_DEFAULT_PLACEHOLDERS};
+
+pub mod schema;
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Sequence {
     pub name: Option<String>,
     pub reference: Option<String>,
+    /// Refget sequence identifier used by `--subject vrs`, e.g. `ga4gh:SQ.IIB53T8CNeJJdUqzn9V_JnRtQadwWCbl`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refget: Option<String>,
+    /// Base IRI of subjects on this contig, overriding `base` and `reference` of `--subject reference`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base: Option<String>,
+}
+
+/// A derived boolean property evaluated against QUAL and FILTER of each record.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QualityFlag {
+    /// Predicate to emit (prefixed name or absolute IRI).
+    pub predicate: String,
+    /// Minimum QUAL value to be regarded as true.
+    pub min_qual: Option<f32>,
+    /// Require FILTER to be PASS.
+    #[serde(default)]
+    pub require_pass: bool,
+    /// Do not emit the triple if the value is false.
+    #[serde(default)]
+    pub omit_when_false: bool,
+}
+
+impl QualityFlag {
+    /// Evaluate the flag for a record with `quality` and `filters`.
+    ///
+    /// Missing QUAL (NaN) never satisfies `min_qual`.
+    pub fn evaluate(&self, quality: f32, filters: &[&str]) -> bool {
+        let qual = self
+            .min_qual
+            .map_or(true, |min| quality.is_finite() && quality >= min);
+
+        let pass =
+            !self.require_pass || (!filters.is_empty() && filters.iter().all(|&x| x == "PASS"));
+
+        qual && pass
+    }
+}
+
+/// Options of an INFO key.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InfoOptions {
+    /// Separator to split String values into multiple values, e.g. `|`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub split: Option<String>,
+    /// Template of IRIs written instead of literals, e.g. `http://identifiers.org/dbsnp/rs{value}`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub as_iri_template: Option<String>,
+    /// Write literals as well, linked to the IRIs by `rdfs:seeAlso`.
+    #[serde(default)]
+    pub keep_literal: bool,
+}
+
+/// A parser of structured values of an INFO key.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InfoParser {
+    /// Consequence annotations of Ensembl VEP with columns listed in `Format:` of the description.
+    Vep,
+}
+
+/// A datatype of INFO values overriding the type declared in the header.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InfoType {
+    Integer,
+    Float,
+    Boolean,
+    String,
+    /// An absolute IRI written in angle brackets instead of a literal.
+    Iri,
 }
 
 /// A structure for user configuration.
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct Config {
     pub base: Option<String>,
+    #[serde(default, deserialize_with = "namespace::deserialize_prefixes")]
     pub namespaces: Option<BTreeMap<String, String>>,
     pub info: Option<Vec<String>>,
     pub reference: BTreeMap<String, Option<Sequence>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quality_flags: Option<Vec<QualityFlag>>,
+    /// Subjects from the value of an INFO key, e.g. `info:CLNACC`, used if `--subject` is not passed to the converter.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+    /// Template of subject IRIs used if `--subject` is not passed to the converter.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subject_template: Option<String>,
+    /// IRI prepended to relative subjects, e.g. of `--subject location`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subject_prefix: Option<String>,
+    /// Template of sample IRIs with `{id}` placeholder, relative to `base` unless absolute.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sample_template: Option<String>,
+    /// Predicates for relationships in `##PEDIGREE`, e.g. `Father: gvo:father`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pedigree_predicates: Option<BTreeMap<String, String>>,
+    /// INFO keys written as RDF-star annotations if `--rdf-star-mode allele` is passed to the converter.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rdf_star: Option<Vec<String>>,
+    /// Values of `gvo:zygosity` keyed by classification, e.g. `heterozygous: ex:Heterozygous`.
+    ///
+    /// Prefixed names and IRIs are written as is, and others as string literals.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub zygosity: Option<BTreeMap<String, String>>,
+    /// Parsers of INFO values keyed by INFO key, e.g. `CSQ: vep`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub info_parsers: Option<BTreeMap<String, InfoParser>>,
+    /// Predicate linking a variant to its consequences, defaults to `gvo:consequence`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub consequence_predicate: Option<String>,
+    /// Predicate of the allele frequency of samples written by `--genotype-summary`,
+    /// defaults to `gvo:sampleAlleleFrequency`. It must differ from predicates of `info_predicates`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub genotype_frequency_predicate: Option<String>,
+    /// Predicates of INFO keys written as direct triples instead of `gvo:info`,
+    /// e.g. `AF: http://example.org/vocab#alleleFrequency`.
+    ///
+    /// `AF`, `AC` and `AN` are written as `gvo:alleleFrequency`, `gvo:alleleCount` and `gvo:alleleNumber` unless configured.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub info_predicates: Option<BTreeMap<String, String>>,
+    /// Options of INFO keys, e.g. `CLNDN: { split: "|" }`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub info_options: Option<BTreeMap<String, InfoOptions>>,
+    /// Datatypes of INFO keys overriding the header, e.g. `dbSNPBuildID: integer`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub info_types: Option<BTreeMap<String, InfoType>>,
+    /// IRIs of samples keyed by sample name, relative to `base` unless absolute.
+    ///
+    /// Samples not listed are named by `sample_template`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sample_iris: Option<BTreeMap<String, String>>,
+    /// Samples of which genotypes are written, all if not given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub samples: Option<Vec<String>>,
+    /// Samples of which genotypes are not written.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exclude_samples: Option<Vec<String>>,
+    /// FORMAT keys read for genotypes, all of `DP`, `GQ` and `AD` if not given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<Vec<String>>,
+    /// Keys of `reference` for contigs of VCF named differently, e.g. `chr1: NC_000001.10`.
+    ///
+    /// Names of contigs in built-in assemblies are also tried if not listed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contig_aliases: Option<BTreeMap<String, String>>,
+    /// Path to a configuration overridden by this one, relative to this file.
+    ///
+    /// Scalars and lists are replaced, and maps (e.g. `namespaces` and `reference`) are merged key by key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extends: Option<String>,
+    /// Template of reference IRIs with `{contig}` placeholder for contigs not listed in `reference`,
+    /// e.g. `https://identifiers.org/insdc/{contig}`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reference_default: Option<String>,
+    /// Name of the assembly for `{assembly}` of `subject_template`, e.g. `GRCh38`.
+    ///
+    /// The converter fills it with the assembly of `--assembly` or `--assembly-file` if omitted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub assembly: Option<String>,
 }
 
 impl Config {
-    /// Read a yaml configuration from a given path.
+    /// Read a yaml configuration from a given path, merged onto configurations it `extends`.
+    ///
+    /// The configuration is validated by `CompiledConfig::compile`.
     pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Config> {
-        let config: Config = serde_yaml::from_reader(File::open(path)?)?;
+        let value = Self::load(path.as_ref(), &mut Vec::new())?;
+        let mut config: Config = serde_yaml::from_value(value)?;
+
+        for normalization in config.normalize() {
+            warn!("{}", normalization);
+        }
+
+        for (host, schemes) in config.mixed_schemes() {
+            warn!("Both {} are used for {}", schemes.join(" and "), host);
+        }
 
         if config
             .reference
@@ -39,4 +220,1326 @@ impl Config {
 
         Ok(config)
     }
+
+    /// Read YAML, JSON or TOML at `path` by the extension and merge it onto the configuration it extends,
+    /// where `chain` holds files being read.
+    fn load(path: &Path, chain: &mut Vec<PathBuf>) -> Result<Value> {
+        let canonical = path
+            .canonicalize()
+            .map_err(|_| Error::ConfigurationNotFoundError(path.to_string_lossy().to_string()))?;
+        if chain.contains(&canonical) {
+            Err(Error::InvalidConfigurationError(format!(
+                "extends: cycle detected at {}",
+                path.display()
+            )))?
+        }
+
+        let mut value: Value = match path.extension().and_then(|x| x.to_str()) {
+            Some("json") => {
+                let mut value = serde_json::from_reader(BufReader::new(File::open(path)?))?;
+                strip_comments(&mut value);
+                value
+            }
+            Some("toml") => toml::from_str(&std::fs::read_to_string(path)?)?,
+            _ => serde_yaml::from_reader(File::open(path)?)?,
+        };
+
+        let extends = match value.as_mapping_mut() {
+            Some(mapping) => mapping.remove(&Value::from("extends")),
+            None => None,
+        };
+        let base = match extends {
+            Some(Value::String(x)) => x,
+            Some(Value::Null) | None => return Ok(value),
+            Some(_) => Err(Error::InvalidConfigurationError(format!(
+                "extends: expected a path in {}",
+                path.display()
+            )))?,
+        };
+
+        chain.push(canonical);
+        let base = path.parent().unwrap_or_else(|| Path::new("")).join(base);
+        let mut merged = Self::load(&base, chain)?;
+        chain.pop();
+
+        merge(&mut merged, value);
+        Ok(merged)
+    }
+
+    /// Normalize IRIs and return descriptions of normalizations applied.
+    ///
+    /// - Repeated trailing slashes of `base`, `subject_prefix` and `namespaces` are collapsed into one.
+    /// - Trailing `/` and `#` of `reference` are removed, since `#pos-ref-alt` is appended to it.
+    pub fn normalize(&mut self) -> Vec<String> {
+        let mut normalizations = Vec::new();
+
+        let mut apply = |field: &str, key: Option<&str>, value: &mut String, normalized: String| {
+            if *value != normalized {
+                normalizations.push(match key {
+                    Some(k) => format!(
+                        "{}: {} of {} is normalized to {}",
+                        field, value, k, normalized
+                    ),
+                    None => format!("{}: {} is normalized to {}", field, value, normalized),
+                });
+                *value = normalized;
+            }
+        };
+
+        if let Some(base) = self.base.as_mut() {
+            let normalized = iri::collapse_trailing_slashes(base);
+            apply("base", None, base, normalized);
+        }
+
+        if let Some(prefix) = self.subject_prefix.as_mut() {
+            let normalized = iri::collapse_trailing_slashes(prefix);
+            apply("subject_prefix", None, prefix, normalized);
+        }
+
+        for (prefix, value) in self.namespaces.iter_mut().flatten() {
+            let normalized = iri::collapse_trailing_slashes(value);
+            apply("namespaces", Some(prefix.as_str()), value, normalized);
+        }
+
+        for (contig, sequence) in self.reference.iter_mut() {
+            if let Some(value) = sequence.as_mut().and_then(|x| x.reference.as_mut()) {
+                let normalized = value.trim_end_matches(|c| c == '/' || c == '#').to_owned();
+                apply("reference", Some(contig.as_str()), value, normalized);
+            }
+        }
+
+        normalizations
+    }
+
+    /// Fill `reference` with sequences of `assembly` for contigs not configured or without reference.
+    ///
+    /// Each sequence is added under all of its names, so that contigs are found in any naming convention.
+    pub fn fill_reference(&mut self, assembly: &Assembly) {
+        for sequence in assembly.sequences() {
+            let names = [
+                sequence.name,
+                sequence.genbank,
+                sequence.refseq,
+                sequence.ucsc_name,
+            ];
+
+            for name in names.iter().filter(|x| !x.is_empty()) {
+                let entry = self.reference.entry(name.to_string()).or_default();
+                if entry.as_ref().map_or(true, |x| x.reference.is_none()) {
+                    *entry = Some(Sequence {
+                        name: Some(sequence.name.to_owned()),
+                        reference: Some(sequence.reference.to_owned()),
+                        ..Default::default()
+                    });
+                }
+            }
+        }
+    }
+
+    /// Returns hosts with the schemes used for them if both `http` and `https` are used.
+    pub fn mixed_schemes(&self) -> Vec<(String, Vec<String>)> {
+        let mut hosts: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+
+        let iris = self
+            .base
+            .iter()
+            .chain(self.subject_prefix.iter())
+            .chain(self.namespaces.iter().flat_map(|x| x.values()))
+            .chain(
+                self.reference
+                    .values()
+                    .flatten()
+                    .filter_map(|x| x.reference.as_ref()),
+            );
+
+        for value in iris {
+            let components = iri::parse(value);
+            if let (Some(scheme), Some(authority)) = (components.scheme, components.authority) {
+                let scheme = scheme.to_ascii_lowercase();
+                if scheme == "http" || scheme == "https" {
+                    hosts
+                        .entry(authority.to_ascii_lowercase())
+                        .or_default()
+                        .insert(scheme);
+                }
+            }
+        }
+
+        hosts
+            .into_iter()
+            .filter(|(_, schemes)| schemes.len() > 1)
+            .map(|(host, schemes)| (host, schemes.into_iter().collect()))
+            .collect()
+    }
+
+    /// Validate values which can not be checked by deserialization.
+    pub fn validate(&self) -> Result<()> {
+        let ns = Namespace::from(self);
+
+        if let Some(base) = self.base.as_ref() {
+            if !iri::is_absolute(base) {
+                Err(Error::InvalidConfigurationError(format!(
+                    "base: {} is not an absolute IRI",
+                    base
+                )))?
+            }
+
+            if iri::parse(base).fragment.map_or(false, |x| !x.is_empty()) {
+                Err(Error::InvalidConfigurationError(format!(
+                    "base: {} must not have a fragment",
+                    base
+                )))?
+            }
+        }
+
+        for (prefix, value) in self.namespaces.iter().flatten() {
+            if !iri::is_absolute(value) {
+                Err(Error::InvalidConfigurationError(format!(
+                    "namespaces: {} of {} is not an absolute IRI",
+                    value, prefix
+                )))?
+            }
+        }
+
+        for (contig, sequence) in self.reference.iter() {
+            if let Some(value) = sequence.as_ref().and_then(|x| x.reference.as_ref()) {
+                if !iri::is_absolute(value) {
+                    Err(Error::InvalidConfigurationError(format!(
+                        "reference: {} of {} is not an absolute IRI",
+                        value, contig
+                    )))?
+                }
+
+                if iri::parse(value).fragment.is_some() {
+                    Err(Error::InvalidConfigurationError(format!(
+                        "reference: {} of {} must not have a fragment",
+                        value, contig
+                    )))?
+                }
+            }
+
+            if let Some(value) = sequence.as_ref().and_then(|x| x.base.as_ref()) {
+                if !iri::is_absolute(value) {
+                    Err(Error::InvalidConfigurationError(format!(
+                        "reference: base {} of {} is not an absolute IRI",
+                        value, contig
+                    )))?
+                }
+            }
+        }
+
+        for flag in self.quality_flags.iter().flatten() {
+            if !ns.is_resolvable(&flag.predicate) {
+                Err(Error::InvalidConfigurationError(format!(
+                    "quality_flags: undefined prefix in predicate {}",
+                    flag.predicate
+                )))?
+            }
+
+            if flag.min_qual.is_none() && !flag.require_pass {
+                Err(Error::InvalidConfigurationError(format!(
+                    "quality_flags: {} requires min_qual or require_pass",
+                    flag.predicate
+                )))?
+            }
+
+            if flag.min_qual.map_or(false, |x| !x.is_finite()) {
+                Err(Error::InvalidConfigurationError(format!(
+                    "quality_flags: min_qual of {} must be a finite number",
+                    flag.predicate
+                )))?
+            }
+        }
+
+        if let Some(subject) = self.subject.as_ref() {
+            if subject_key(subject).is_none() {
+                Err(Error::InvalidConfigurationError(format!(
+                    "subject: {} is not of the form info:<KEY>",
+                    subject
+                )))?
+            }
+
+            if self.subject_template.is_some() {
+                Err(Error::InvalidConfigurationError(
+                    "subject: cannot be used with subject_template".to_owned(),
+                ))?
+            }
+        }
+
+        if let Some(template) = self.subject_template.as_ref() {
+            if SubjectTemplate::parse(template)?.uses_assembly() && self.assembly.is_none() {
+                Err(Error::InvalidConfigurationError(
+                    "subject_template: {assembly} requires assembly or --assembly".to_owned(),
+                ))?
+            }
+        }
+
+        if let Some(prefix) = self.subject_prefix.as_ref() {
+            if !iri::is_absolute(prefix) {
+                Err(Error::InvalidConfigurationError(format!(
+                    "subject_prefix: {} is not an absolute IRI",
+                    prefix
+                )))?
+            }
+        }
+
+        if let Some(template) = self.sample_template.as_ref() {
+            if !template.contains("{id}") {
+                Err(Error::InvalidConfigurationError(format!(
+                    "sample_template: {} does not contain {{id}}",
+                    template
+                )))?
+            }
+        }
+
+        for (key, predicate) in self.pedigree_predicates.iter().flatten() {
+            if !ns.is_resolvable(predicate) {
+                Err(Error::InvalidConfigurationError(format!(
+                    "pedigree_predicates: undefined prefix in predicate {} for {}",
+                    predicate, key
+                )))?
+            }
+        }
+
+        for key in self.rdf_star.iter().flatten() {
+            if !iri::is_local_name(key) {
+                Err(Error::InvalidConfigurationError(format!(
+                    "rdf_star: {} can not be used as a local name of predicate",
+                    key
+                )))?
+            }
+        }
+
+        for (key, value) in self.zygosity.iter().flatten() {
+            if Zygosity::from_str(key).is_err() {
+                Err(Error::InvalidConfigurationError(format!(
+                    "zygosity: unknown classification {}, use one of {}",
+                    key,
+                    Zygosity::VARIANTS.join(", ")
+                )))?
+            }
+
+            if value.contains(':') && !ns.is_resolvable(value) {
+                Err(Error::InvalidConfigurationError(format!(
+                    "zygosity: undefined prefix in {} for {}",
+                    value, key
+                )))?
+            }
+        }
+
+        if let Some(predicate) = self.consequence_predicate.as_ref() {
+            if !ns.is_resolvable(predicate) {
+                Err(Error::InvalidConfigurationError(format!(
+                    "consequence_predicate: undefined prefix in {}",
+                    predicate
+                )))?
+            }
+        }
+
+        if let Some(predicate) = self.genotype_frequency_predicate.as_ref() {
+            if !ns.is_resolvable(predicate) {
+                Err(Error::InvalidConfigurationError(format!(
+                    "genotype_frequency_predicate: undefined prefix in {}",
+                    predicate
+                )))?
+            }
+        }
+
+        for (key, predicate) in self.info_predicates.iter().flatten() {
+            if !ns.is_resolvable(predicate) {
+                Err(Error::InvalidConfigurationError(format!(
+                    "info_predicates: undefined prefix in predicate {} for {}",
+                    predicate, key
+                )))?
+            }
+        }
+
+        // the frequency in samples would be mixed with the INFO value on the same subject
+        let frequency = self
+            .genotype_frequency_predicate
+            .as_deref()
+            .unwrap_or(DEFAULT_GENOTYPE_FREQUENCY_PREDICATE);
+        let mut info_predicates = default_info_predicates();
+        info_predicates.extend(self.info_predicates.clone().unwrap_or_default());
+        if let Some((key, _)) = info_predicates.iter().find(|(_, v)| *v == frequency) {
+            Err(Error::InvalidConfigurationError(format!(
+                "genotype_frequency_predicate: {} is the predicate of INFO/{}",
+                frequency, key
+            )))?
+        }
+
+        for (key, options) in self.info_options.iter().flatten() {
+            if options.split.as_ref().map_or(false, |x| x.is_empty()) {
+                Err(Error::InvalidConfigurationError(format!(
+                    "info_options: split of {} must not be empty",
+                    key
+                )))?
+            }
+
+            if let Some(template) = options.as_iri_template.as_ref() {
+                if !template.contains("{value}")
+                    || !iri::is_absolute(&template.replace("{value}", "0"))
+                {
+                    Err(Error::InvalidConfigurationError(format!(
+                        "info_options: as_iri_template of {} must be an absolute IRI with {{value}}: {}",
+                        key, template
+                    )))?
+                }
+            }
+        }
+
+        for key in self.format.iter().flatten() {
+            if !FORMAT_KEYS.contains(&key.as_str()) {
+                Err(Error::InvalidConfigurationError(format!(
+                    "format: unknown key {}, use one of {}",
+                    key,
+                    FORMAT_KEYS.join(", ")
+                )))?
+            }
+        }
+
+        for (contig, key) in self.contig_aliases.iter().flatten() {
+            if !self.reference.contains_key(key) {
+                Err(Error::InvalidConfigurationError(format!(
+                    "contig_aliases: {} of {} is not a key of reference",
+                    key, contig
+                )))?
+            }
+        }
+
+        if let Some(template) = self.reference_default.as_ref() {
+            parse_reference_default(template)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A validated configuration prepared once and shared by reference across conversions.
+///
+/// It is `Send + Sync`, so that an `Arc<CompiledConfig>` can be used from multiple threads.
+/// Readers share `reference` instead of copying it for each conversion.
+#[derive(Debug)]
+pub struct CompiledConfig {
+    pub namespace: Namespace,
+    pub info: Option<Vec<String>>,
+    pub reference: Arc<BTreeMap<String, Option<Sequence>>>,
+    pub quality_flags: Vec<QualityFlag>,
+    pub subject_key: Option<String>,
+    pub subject_template: Option<SubjectTemplate>,
+    pub subject_prefix: Option<String>,
+    pub sample_template: String,
+    pub pedigree_predicates: BTreeMap<String, String>,
+    pub rdf_star: Vec<String>,
+    pub zygosity: BTreeMap<String, String>,
+    pub info_parsers: BTreeMap<String, InfoParser>,
+    pub consequence_predicate: String,
+    pub genotype_frequency_predicate: String,
+    pub info_predicates: BTreeMap<String, String>,
+    pub info_options: BTreeMap<String, InfoOptions>,
+    pub info_types: BTreeMap<String, InfoType>,
+    pub sample_iris: BTreeMap<String, String>,
+    pub samples: Option<Vec<String>>,
+    pub exclude_samples: Vec<String>,
+    pub format: Vec<String>,
+    pub contig_aliases: BTreeMap<String, String>,
+    pub reference_default: Option<Template>,
+}
+
+impl CompiledConfig {
+    /// Validate `config` and build derived values.
+    pub fn compile(config: Config) -> Result<CompiledConfig> {
+        config.validate()?;
+
+        Ok(CompiledConfig {
+            namespace: Namespace::from(&config),
+            info: config.info,
+            reference: Arc::new(config.reference),
+            quality_flags: config.quality_flags.unwrap_or_default(),
+            subject_key: config
+                .subject
+                .as_deref()
+                .and_then(subject_key)
+                .map(|x| x.to_owned()),
+            subject_template: match config.subject_template.as_ref() {
+                Some(v) => Some(SubjectTemplate::parse(v)?.assembly(config.assembly.clone())),
+                None => None,
+            },
+            subject_prefix: config.subject_prefix,
+            sample_template: config
+                .sample_template
+                .unwrap_or_else(|| DEFAULT_SAMPLE_TEMPLATE.to_owned()),
+            pedigree_predicates: {
+                let mut map = default_pedigree_predicates();
+                map.extend(config.pedigree_predicates.unwrap_or_default());
+                map
+            },
+            rdf_star: config.rdf_star.unwrap_or_default(),
+            zygosity: config.zygosity.unwrap_or_default(),
+            info_parsers: config.info_parsers.unwrap_or_default(),
+            consequence_predicate: config
+                .consequence_predicate
+                .unwrap_or_else(|| DEFAULT_CONSEQUENCE_PREDICATE.to_owned()),
+            genotype_frequency_predicate: config
+                .genotype_frequency_predicate
+                .unwrap_or_else(|| DEFAULT_GENOTYPE_FREQUENCY_PREDICATE.to_owned()),
+            info_predicates: {
+                let mut map = default_info_predicates();
+                map.extend(config.info_predicates.unwrap_or_default());
+                map
+            },
+            info_options: config.info_options.unwrap_or_default(),
+            info_types: config.info_types.unwrap_or_default(),
+            sample_iris: config.sample_iris.unwrap_or_default(),
+            samples: config.samples,
+            exclude_samples: config.exclude_samples.unwrap_or_default(),
+            format: config
+                .format
+                .unwrap_or_else(|| FORMAT_KEYS.iter().map(|x| x.to_string()).collect()),
+            contig_aliases: config.contig_aliases.unwrap_or_default(),
+            reference_default: match config.reference_default.as_ref() {
+                Some(v) => Some(parse_reference_default(v)?),
+                None => None,
+            },
+        })
+    }
+
+    /// Returns true if every sequence in `reference` has its own `base`, and no others are mapped by `reference_default`.
+    pub fn sequences_have_base(&self) -> bool {
+        self.reference_default.is_none()
+            && self.reference.values().any(|x| x.is_some())
+            && self.reference.values().flatten().all(|x| x.base.is_some())
+    }
+
+    /// Ensure that names and reference IRIs of sequences correspond one-to-one.
+    ///
+    /// Contigs may share a sequence as aliases, e.g. `1` and `chr1`, but subjects containing names
+    /// would be forked if they had different names, or collide if a name referred to different sequences.
+    pub fn check_sequence_names(&self) -> Result<()> {
+        let mut by_name: BTreeMap<&str, (&str, &str)> = BTreeMap::new();
+        let mut by_reference: BTreeMap<&str, (&str, &str)> = BTreeMap::new();
+
+        for (contig, sequence) in self.reference.iter() {
+            let (name, reference) = match sequence {
+                Some(Sequence {
+                    name: Some(name),
+                    reference: Some(reference),
+                    ..
+                }) => (name.as_str(), reference.as_str()),
+                _ => continue,
+            };
+
+            if let Some((other, r)) = by_name.insert(name, (contig, reference)) {
+                if r != reference {
+                    Err(Error::InvalidConfigurationError(format!(
+                        "reference: {} and {} are named {} but refer to {} and {}",
+                        other, contig, name, r, reference
+                    )))?
+                }
+            }
+
+            if let Some((other, n)) = by_reference.insert(reference, (contig, name)) {
+                if n != name {
+                    Err(Error::InvalidConfigurationError(format!(
+                        "reference: {} and {} refer to {} but are named {} and {}",
+                        other, contig, reference, n, name
+                    )))?
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns a `ReaderBuilder` set up with references and INFO keys.
+    pub fn reader_builder(&self) -> ReaderBuilder {
+        let builder = ReaderBuilder::new()
+            .reference(Arc::clone(&self.reference))
+            .contig_aliases(self.contig_aliases.clone())
+            .info_separators(
+                self.info_options
+                    .iter()
+                    .filter_map(|(k, v)| Some((k.to_owned(), v.split.clone()?)))
+                    .collect(),
+            )
+            .info_types(self.info_types.clone());
+
+        let builder = match self.reference_default.as_ref() {
+            Some(template) => builder.reference_default(template.clone()),
+            None => builder,
+        };
+
+        match self.info.as_ref() {
+            Some(keys) => builder.info_keys(keys.clone()),
+            None => builder,
+        }
+    }
+}
+
+/// Compile `reference_default`, which must be an absolute IRI with `{contig}` and without a fragment.
+fn parse_reference_default(template: &str) -> Result<Template> {
+    let compiled = Template::parse(template, REFERENCE_DEFAULT_PLACEHOLDERS)
+        .map_err(|e| Error::InvalidConfigurationError(format!("reference_default: {}", e)))?;
+
+    let value = compiled
+        .render(|_| Some("1".to_owned()))
+        .unwrap_or_default();
+    if !compiled.contains("contig")
+        || !iri::is_absolute(&value)
+        || iri::parse(&value).fragment.is_some()
+    {
+        Err(Error::InvalidConfigurationError(format!(
+            "reference_default: {} must be an absolute IRI with {{contig}} and without a fragment",
+            template
+        )))?
+    }
+
+    Ok(compiled)
+}
+
+/// INFO key of `subject` in the form of `info:<KEY>`.
+fn subject_key(subject: &str) -> Option<&str> {
+    subject.strip_prefix("info:").filter(|x| !x.is_empty())
+}
+
+/// Remove `_comment` keys of maps in `value` recursively, which are written by the generator in JSON.
+fn strip_comments(value: &mut Value) {
+    match value {
+        Value::Mapping(x) => {
+            x.remove(&Value::from("_comment"));
+            for (_, v) in x.iter_mut() {
+                strip_comments(v);
+            }
+        }
+        Value::Sequence(x) => x.iter_mut().for_each(strip_comments),
+        _ => {}
+    }
+}
+
+/// Merge `overlay` onto `base`, of which maps at the top level are merged key by key and others are replaced.
+fn merge(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Mapping(base), Value::Mapping(overlay)) => {
+            for (key, value) in overlay {
+                match (base.get_mut(&key), value) {
+                    (Some(Value::Mapping(x)), Value::Mapping(y)) => x.extend(y),
+                    (_, value) => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::thread;
+
+    use std::io::Write;
+
+    use super::*;
+    use crate::rdf::turtle_writer::{Subject, SubjectFormatter, TurtleWriter};
+    use crate::rdf::writer::Writer;
+
+    fn flag(min_qual: Option<f32>, require_pass: bool) -> QualityFlag {
+        QualityFlag {
+            predicate: "gvo:highQuality".to_owned(),
+            min_qual,
+            require_pass,
+            omit_when_false: false,
+        }
+    }
+
+    #[test]
+    fn test_quality_flag_above_threshold() {
+        assert!(flag(Some(30.0), true).evaluate(67.0, &["PASS"]));
+        assert!(flag(Some(30.0), false).evaluate(30.0, &[]));
+    }
+
+    #[test]
+    fn test_quality_flag_below_threshold() {
+        assert!(!flag(Some(30.0), true).evaluate(29.0, &["PASS"]));
+        assert!(!flag(Some(30.0), false).evaluate(f32::NAN, &["PASS"]));
+    }
+
+    #[test]
+    fn test_quality_flag_non_pass() {
+        assert!(!flag(Some(30.0), true).evaluate(67.0, &["q10"]));
+        assert!(!flag(None, true).evaluate(67.0, &[]));
+        assert!(flag(Some(30.0), false).evaluate(67.0, &["q10"]));
+    }
+
+    #[test]
+    fn test_validate_quality_flags() {
+        let mut config = Config::default();
+
+        config.quality_flags = Some(vec![flag(Some(30.0), true)]);
+        assert!(config.validate().is_ok());
+
+        config.quality_flags = Some(vec![flag(None, false)]);
+        assert!(config.validate().is_err());
+
+        let mut f = flag(Some(30.0), true);
+        f.predicate = "ex:highQuality".to_owned();
+        config.quality_flags = Some(vec![f]);
+        assert!(config.validate().is_err());
+    }
+
+    fn config() -> Config {
+        let mut reference = BTreeMap::new();
+        reference.insert(
+            "20".to_owned(),
+            Some(Sequence {
+                name: Some("20".to_owned()),
+                reference: Some("http://identifiers.org/hco/20/GRCh37".to_owned()),
+                ..Default::default()
+            }),
+        );
+
+        Config {
+            base: Some("http://example.org/".to_owned()),
+            reference,
+            quality_flags: Some(vec![flag(Some(30.0), true)]),
+            ..Default::default()
+        }
+    }
+
+    fn convert(compiled: &CompiledConfig) -> Vec<u8> {
+        let mut reader = compiled
+            .reader_builder()
+            .path("test/vcf_spec.vcf.gz")
+            .expect("Error opening file.");
+
+        let mut writer = TurtleWriter::new(Vec::new());
+        writer.configure(compiled);
+
+        for record in reader.records() {
+            writer
+                .write_record(&record.expect("Error reading record."))
+                .expect("Error writing record.");
+        }
+
+        writer.into_inner().expect("Error flushing output.")
+    }
+
+    #[test]
+    fn test_compiled_config_shared_across_threads() {
+        let compiled = Arc::new(CompiledConfig::compile(config()).unwrap());
+
+        let handles: Vec<_> = (0..2)
+            .map(|_| {
+                let compiled = Arc::clone(&compiled);
+                thread::spawn(move || convert(&compiled))
+            })
+            .collect();
+
+        let expected = convert(&CompiledConfig::compile(config()).unwrap());
+
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_validate_subject_template() {
+        let mut config = Config::default();
+
+        config.subject_template = Some("https://example.org/var/{chrom}/{pos}".to_owned());
+        assert!(config.validate().is_ok());
+
+        config.subject_template = Some("https://example.org/var/{assembly}/{pos}".to_owned());
+        assert!(config.validate().is_err());
+
+        config.assembly = Some("GRCh38".to_owned());
+        assert!(config.validate().is_ok());
+
+        config.subject_template = Some("https://example.org/var/{build}/{pos}".to_owned());
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_subject() {
+        let mut config = Config::default();
+
+        config.subject = Some("info:CLNACC".to_owned());
+        assert!(config.validate().is_ok());
+
+        config.subject = Some("info:".to_owned());
+        assert!(config.validate().is_err());
+
+        config.subject = Some("location".to_owned());
+        assert!(config.validate().is_err());
+
+        config.subject = Some("info:CLNACC".to_owned());
+        config.subject_template = Some("https://example.org/var/{chrom}/{pos}".to_owned());
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_samples() {
+        let mut config = Config::default();
+
+        config.sample_template = Some("http://example.org/sample/{id}".to_owned());
+        assert!(config.validate().is_ok());
+
+        config.sample_template = Some("http://example.org/sample".to_owned());
+        assert!(config.validate().is_err());
+
+        let mut predicates = BTreeMap::new();
+        predicates.insert("Original".to_owned(), "ex:original".to_owned());
+        config.sample_template = None;
+        config.pedigree_predicates = Some(predicates);
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_rdf_star() {
+        let mut config = Config::default();
+
+        config.rdf_star = Some(vec!["AF".to_owned(), "1000G".to_owned()]);
+        assert!(config.validate().is_ok());
+
+        config.rdf_star = Some(vec!["A:F".to_owned()]);
+        assert!(config.validate().is_err());
+
+        config.rdf_star = Some(vec!["AF.".to_owned()]);
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_info_predicates() {
+        let mut config = Config::default();
+        let mut predicates = BTreeMap::new();
+
+        predicates.insert(
+            "AF".to_owned(),
+            "http://example.org/vocab#alleleFrequency".to_owned(),
+        );
+        predicates.insert("DP".to_owned(), "gvo:depth".to_owned());
+        config.info_predicates = Some(predicates.clone());
+        assert!(config.validate().is_ok());
+
+        predicates.insert("AA".to_owned(), "ex:ancestralAllele".to_owned());
+        config.info_predicates = Some(predicates);
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_genotype_frequency_predicate() {
+        let mut config = Config::default();
+        assert!(config.validate().is_ok());
+
+        config.genotype_frequency_predicate = Some("gvo:alleleFrequency".to_owned());
+        let err = config.validate().expect_err("unexpected result");
+        assert_eq!(
+            err.to_string(),
+            "Invalid configuration: genotype_frequency_predicate: gvo:alleleFrequency is the predicate of INFO/AF"
+        );
+
+        let mut predicates = BTreeMap::new();
+        predicates.insert("AF".to_owned(), "gvo:infoAlleleFrequency".to_owned());
+        config.info_predicates = Some(predicates);
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_zygosity() {
+        let mut config = Config::default();
+        let mut zygosity = BTreeMap::new();
+
+        zygosity.insert("heterozygous".to_owned(), "gvo:Heterozygous".to_owned());
+        zygosity.insert("unknown".to_owned(), "not called".to_owned());
+        config.zygosity = Some(zygosity.clone());
+        assert!(config.validate().is_ok());
+
+        zygosity.insert("compound".to_owned(), "gvo:Compound".to_owned());
+        config.zygosity = Some(zygosity.clone());
+        assert!(config.validate().is_err());
+
+        zygosity.remove("compound");
+        zygosity.insert("hemizygous".to_owned(), "ex:Hemizygous".to_owned());
+        config.zygosity = Some(zygosity);
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_default_info_predicates() {
+        let mut config = config();
+        let mut predicates = BTreeMap::new();
+        predicates.insert("AF".to_owned(), "ex:af".to_owned());
+        predicates.insert("DP".to_owned(), "ex:depth".to_owned());
+        config.namespaces = Some(
+            vec![("ex".to_owned(), "http://example.org/".to_owned())]
+                .into_iter()
+                .collect(),
+        );
+        config.info_predicates = Some(predicates);
+
+        let compiled = CompiledConfig::compile(config).unwrap();
+
+        assert_eq!(compiled.info_predicates["AF"], "ex:af");
+        assert_eq!(compiled.info_predicates["AC"], "gvo:alleleCount");
+        assert_eq!(compiled.info_predicates["AN"], "gvo:alleleNumber");
+        assert_eq!(compiled.info_predicates["DP"], "ex:depth");
+
+        let output = String::from_utf8(convert(&compiled)).unwrap();
+        assert!(output.contains(" ;\n  ex:af \"0.5\"^^xsd:float"));
+        assert!(!output.contains("rdfs:label \"AF\""));
+    }
+
+    #[test]
+    fn test_validate_as_iri_template() {
+        let mut config = Config::default();
+        let mut options = BTreeMap::new();
+        let mut option = InfoOptions {
+            as_iri_template: Some("http://identifiers.org/dbsnp/rs{value}".to_owned()),
+            ..Default::default()
+        };
+
+        options.insert("RS".to_owned(), option.clone());
+        config.info_options = Some(options.clone());
+        assert!(config.validate().is_ok());
+
+        option.as_iri_template = Some("http://identifiers.org/dbsnp/".to_owned());
+        options.insert("RS".to_owned(), option.clone());
+        config.info_options = Some(options.clone());
+        assert!(config.validate().is_err());
+
+        option.as_iri_template = Some("rs{value}".to_owned());
+        options.insert("RS".to_owned(), option);
+        config.info_options = Some(options);
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_format() {
+        let mut config = Config::default();
+
+        config.format = Some(vec!["DP".to_owned(), "AD".to_owned()]);
+        assert!(config.validate().is_ok());
+
+        config.format = Some(vec!["PL".to_owned()]);
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_info_types() {
+        let config: Config = serde_yaml::from_str(
+            "reference: {}\ninfo_types:\n  dbSNPBuildID: integer\n  URL: iri\n",
+        )
+        .unwrap();
+        let types = config.info_types.unwrap();
+
+        assert_eq!(types["dbSNPBuildID"], InfoType::Integer);
+        assert_eq!(types["URL"], InfoType::Iri);
+
+        assert!(serde_yaml::from_str::<Config>("reference: {}\ninfo_types:\n  DP: int\n").is_err());
+    }
+
+    #[test]
+    fn test_compile_invalid_config() {
+        let mut config = config();
+        config.quality_flags = Some(vec![flag(None, false)]);
+
+        assert!(CompiledConfig::compile(config).is_err());
+    }
+
+    #[test]
+    fn test_compile_validates_loaded_config() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(file, "reference:\n  \"20\":\n    reference: hco/20").unwrap();
+
+        let config = Config::from_path(file.path()).unwrap();
+        assert_eq!(
+            CompiledConfig::compile(config).unwrap_err().to_string(),
+            "Invalid configuration: reference: hco/20 of 20 is not an absolute IRI"
+        );
+    }
+
+    #[test]
+    fn test_normalize() {
+        let mut config = config();
+        config.base = Some("http://example.org/variant//".to_owned());
+        config.subject_prefix = Some("http://example.org/v/".to_owned());
+        config.reference.insert(
+            "21".to_owned(),
+            Some(Sequence {
+                reference: Some("http://identifiers.org/hco/21/GRCh37/".to_owned()),
+                ..Default::default()
+            }),
+        );
+
+        assert_eq!(
+            config.normalize(),
+            vec![
+                "base: http://example.org/variant// is normalized to http://example.org/variant/",
+                "reference: http://identifiers.org/hco/21/GRCh37/ of 21 is normalized to http://identifiers.org/hco/21/GRCh37",
+            ]
+        );
+        assert_eq!(config.base.as_deref(), Some("http://example.org/variant/"));
+        assert_eq!(
+            config.subject_prefix.as_deref(),
+            Some("http://example.org/v/")
+        );
+        assert!(config.normalize().is_empty());
+    }
+
+    #[test]
+    fn test_validate_base() {
+        let mut config = config();
+
+        config.base = Some("http://example.org/variant#".to_owned());
+        assert!(config.validate().is_ok());
+
+        config.base = Some("example.org/variant/".to_owned());
+        assert!(config.validate().is_err());
+
+        config.base = Some("http://example.org/variant#v".to_owned());
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_namespaces() {
+        let mut config = config();
+        let mut namespaces = BTreeMap::new();
+        namespaces.insert("ex".to_owned(), "example/".to_owned());
+        config.namespaces = Some(namespaces);
+
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_reference() {
+        let mut config = config();
+        let mut sequence = Sequence {
+            reference: Some("hco/20/GRCh37".to_owned()),
+            ..Default::default()
+        };
+        config
+            .reference
+            .insert("20".to_owned(), Some(sequence.clone()));
+        assert!(config.validate().is_err());
+
+        sequence.reference = Some("http://identifiers.org/hco/20/GRCh37#20".to_owned());
+        config.reference.insert("20".to_owned(), Some(sequence));
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_contig_aliases() {
+        let mut config = config();
+        let mut aliases = BTreeMap::new();
+        aliases.insert("chr20".to_owned(), "20".to_owned());
+        config.contig_aliases = Some(aliases.clone());
+        assert!(config.validate().is_ok());
+
+        aliases.insert("chr21".to_owned(), "21".to_owned());
+        config.contig_aliases = Some(aliases);
+        assert_eq!(
+            config.validate().unwrap_err().to_string(),
+            Error::InvalidConfigurationError(
+                "contig_aliases: 21 of chr21 is not a key of reference".to_owned()
+            )
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn test_validate_reference_default() {
+        let mut config = config();
+        config.reference_default = Some("https://identifiers.org/insdc/{contig}".to_owned());
+        assert!(config.validate().is_ok());
+
+        for template in &[
+            "https://identifiers.org/insdc/",
+            "insdc/{contig}",
+            "http://example.org/seq#{contig}",
+            "https://identifiers.org/{assembly}/{contig}",
+            "https://identifiers.org/insdc/{contig",
+        ] {
+            config.reference_default = Some(template.to_string());
+            assert!(config.validate().is_err(), "{}", template);
+        }
+    }
+
+    fn sequence(name: &str, reference: &str) -> Option<Sequence> {
+        Some(Sequence {
+            name: Some(name.to_owned()),
+            reference: Some(reference.to_owned()),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_check_sequence_names() {
+        let mut config = Config::default();
+        config.reference.insert(
+            "1".to_owned(),
+            sequence("1", "http://identifiers.org/hco/1/GRCh37"),
+        );
+        config.reference.insert(
+            "chr1".to_owned(),
+            sequence("1", "http://identifiers.org/hco/1/GRCh37"),
+        );
+        config.reference.insert("chrUn".to_owned(), None);
+        assert!(CompiledConfig::compile(config)
+            .unwrap()
+            .check_sequence_names()
+            .is_ok());
+
+        let mut config = Config::default();
+        config.reference.insert(
+            "1".to_owned(),
+            sequence("1", "http://identifiers.org/hco/1/GRCh37"),
+        );
+        config.reference.insert(
+            "chr1".to_owned(),
+            sequence("chr1", "http://identifiers.org/hco/1/GRCh37"),
+        );
+        let err = CompiledConfig::compile(config)
+            .unwrap()
+            .check_sequence_names()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid configuration: reference: 1 and chr1 refer to http://identifiers.org/hco/1/GRCh37 but are named 1 and chr1"
+        );
+
+        let mut config = Config::default();
+        config.reference.insert(
+            "1".to_owned(),
+            sequence("1", "http://identifiers.org/hco/1/GRCh37"),
+        );
+        config.reference.insert(
+            "NC_000001.11".to_owned(),
+            sequence("1", "http://identifiers.org/hco/1/GRCh38"),
+        );
+        let err = CompiledConfig::compile(config)
+            .unwrap()
+            .check_sequence_names()
+            .unwrap_err();
+        assert!(err.to_string().contains("1 and NC_000001.11 are named 1"));
+    }
+
+    #[test]
+    fn test_sequences_have_base() {
+        let have_base = |base: Option<&str>, default: Option<&str>| {
+            let mut config = Config::default();
+            config.reference.insert(
+                "1".to_owned(),
+                Some(Sequence {
+                    base: base.map(|x| x.to_owned()),
+                    ..sequence("1", "http://identifiers.org/hco/1/GRCh37").unwrap()
+                }),
+            );
+            config.reference.insert("chrUn".to_owned(), None);
+            config.reference_default = default.map(|x| x.to_owned());
+
+            CompiledConfig::compile(config)
+                .unwrap()
+                .sequences_have_base()
+        };
+
+        assert!(have_base(Some("http://example.org/1/"), None));
+        assert!(!have_base(None, None));
+        assert!(!have_base(
+            Some("http://example.org/1/"),
+            Some("https://identifiers.org/insdc/{contig}")
+        ));
+        assert!(!CompiledConfig::compile(Config::default())
+            .unwrap()
+            .sequences_have_base());
+    }
+
+    #[test]
+    fn test_fill_reference() {
+        let definition = crate::vcf::assembly::AssemblyDefinition {
+            name: "test".to_owned(),
+            sequences: vec![crate::vcf::assembly::SequenceDefinition {
+                name: "1".to_owned(),
+                refseq: "NC_000001.1".to_owned(),
+                ucsc_name: "chr1".to_owned(),
+                reference: "http://example.org/1".to_owned(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let mut config = Config::default();
+        config
+            .reference
+            .insert("1".to_owned(), sequence("one", "http://example.org/one"));
+        config.reference.insert("chr1".to_owned(), None);
+        config.fill_reference(&definition.as_assembly());
+
+        let reference = |name: &str| {
+            config.reference[name]
+                .as_ref()
+                .and_then(|x| x.reference.as_deref())
+        };
+        assert_eq!(reference("1"), Some("http://example.org/one"));
+        assert_eq!(reference("chr1"), Some("http://example.org/1"));
+        assert_eq!(reference("NC_000001.1"), Some("http://example.org/1"));
+        assert_eq!(config.reference.len(), 3);
+    }
+
+    #[test]
+    fn test_mixed_schemes() {
+        let mut config = config();
+        assert!(config.mixed_schemes().is_empty());
+
+        config.base = Some("https://identifiers.org/".to_owned());
+        assert_eq!(
+            config.mixed_schemes(),
+            vec![(
+                "identifiers.org".to_owned(),
+                vec!["http".to_owned(), "https".to_owned()]
+            )]
+        );
+    }
+
+    #[test]
+    fn test_extends() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("datasets")).unwrap();
+        std::fs::write(
+            dir.path().join("base.yaml"),
+            "base: http://example.org/\nnamespaces:\n  ex: http://example.org/vocab#\ninfo:\n  - AF\n  - DP\nreference:\n  \"1\":\n    name: \"1\"\n    reference: http://example.org/1\n  \"2\":\n    name: \"2\"\n    reference: http://example.org/2\n",
+        )
+        .unwrap();
+        let path = dir.path().join("datasets/overlay.yaml");
+        std::fs::write(
+            &path,
+            "extends: ../base.yaml\nnamespaces:\n  ds: http://example.org/dataset#\ninfo:\n  - AC\nreference:\n  \"2\":\n    name: \"2\"\n    reference: http://example.org/two\n",
+        )
+        .unwrap();
+
+        let config = Config::from_path(&path).unwrap();
+
+        assert_eq!(config.base.as_deref(), Some("http://example.org/"));
+        assert_eq!(config.info, Some(vec!["AC".to_owned()]));
+        let namespaces = config.namespaces.as_ref().unwrap();
+        assert_eq!(namespaces.keys().collect::<Vec<_>>(), vec!["ds", "ex"]);
+        let reference = |x: &str| {
+            config.reference[x]
+                .as_ref()
+                .and_then(|x| x.reference.as_deref())
+        };
+        assert_eq!(reference("1"), Some("http://example.org/1"));
+        assert_eq!(reference("2"), Some("http://example.org/two"));
+        assert_eq!(config.extends, None);
+    }
+
+    #[test]
+    fn test_from_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        std::fs::write(
+            &path,
+            r#"{
+  "_comment": { "base": "Set base IRI if needed." },
+  "base": "http://example.org/",
+  "info": ["DP"],
+  "reference": {
+    "_comment": { "hs37d5": "decoy" },
+    "20": { "name": "20", "reference": "http://identifiers.org/hco/20/GRCh37" },
+    "hs37d5": null
+  }
+}"#,
+        )
+        .unwrap();
+
+        let config = Config::from_path(&path).unwrap();
+        assert_eq!(config.base.as_deref(), Some("http://example.org/"));
+        assert_eq!(
+            config.reference.keys().collect::<Vec<_>>(),
+            vec!["20", "hs37d5"]
+        );
+    }
+
+    #[test]
+    fn test_from_toml() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("base.yaml"), "info:\n  - DP\n").unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(
+            &path,
+            r#"extends = "base.yaml"
+base = "http://example.org/"
+
+[reference.20]
+name = "20"
+reference = "http://identifiers.org/hco/20/GRCh37"
+"#,
+        )
+        .unwrap();
+
+        let config = Config::from_path(&path).unwrap();
+        assert_eq!(config.base.as_deref(), Some("http://example.org/"));
+        assert_eq!(config.info, Some(vec!["DP".to_owned()]));
+        assert_eq!(
+            config.reference["20"]
+                .as_ref()
+                .unwrap()
+                .reference
+                .as_deref(),
+            Some("http://identifiers.org/hco/20/GRCh37")
+        );
+    }
+
+    #[test]
+    fn test_extends_cycle() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("a.yaml"),
+            "extends: b.yaml\nreference: {}\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("b.yaml"), "extends: a.yaml\n").unwrap();
+
+        let err = Config::from_path(dir.path().join("a.yaml")).unwrap_err();
+        assert!(
+            err.to_string().contains("extends: cycle detected"),
+            "{}",
+            err
+        );
+
+        std::fs::write(dir.path().join("c.yaml"), "extends: missing.yaml\n").unwrap();
+        assert!(matches!(
+            Config::from_path(dir.path().join("c.yaml")),
+            Err(Error::ConfigurationNotFoundError(_))
+        ));
+    }
+
+    #[test]
+    fn test_reference_with_trailing_slash() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(
+            b"reference:\n  \"20\":\n    name: \"20\"\n    reference: http://identifiers.org/hco/20/GRCh37/\n",
+        )
+        .unwrap();
+
+        let compiled = CompiledConfig::compile(Config::from_path(file.path()).unwrap()).unwrap();
+
+        let mut reader = compiled
+            .reader_builder()
+            .path("test/vcf_spec.vcf.gz")
+            .expect("Error opening file.");
+
+        let mut writer = TurtleWriter::new(Vec::new());
+        writer.configure(&compiled);
+        writer.subject_formatter(SubjectFormatter::from(&Subject::Reference));
+
+        for record in reader.records() {
+            writer
+                .write_record(&record.expect("Error reading record."))
+                .expect("Error writing record.");
+        }
+
+        let output = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+        assert!(output.contains("\n<http://identifiers.org/hco/20/GRCh37#14370-G-A> a gvo:SNV"));
+    }
 }
@@ -0,0 +1,304 @@
+//! Module for JSON Schema of the configuration
+//!
+//! The schema is maintained by hand. Only keywords used in the schema are supported by [`validate`].
+use serde_yaml::Value;
+
+/// JSON Schema describing `Config`.
+pub const SCHEMA: &str = include_str!("schema.json");
+
+/// Validate `value` against [`SCHEMA`].
+///
+/// Returns messages prefixed with the path to the invalid value, e.g. `/quality_flags/0/min_qual`.
+pub fn validate(value: &Value) -> Result<(), Vec<String>> {
+    let schema: Value = serde_yaml::from_str(SCHEMA).expect("Invalid schema");
+
+    let mut errors = Vec::new();
+    validate_value(&schema, value, "", &mut errors);
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Sequence(_) => "array",
+        Value::Mapping(_) => "object",
+    }
+}
+
+fn matches_type(typ: &str, value: &Value) -> bool {
+    match (typ, value) {
+        ("integer", Value::Number(n)) => n.is_i64() || n.is_u64(),
+        (typ, value) => typ == type_name(value),
+    }
+}
+
+fn validate_value(schema: &Value, value: &Value, path: &str, errors: &mut Vec<String>) {
+    let types: Vec<&str> = match schema.get("type") {
+        Some(Value::String(t)) => vec![t.as_str()],
+        Some(Value::Sequence(ts)) => ts.iter().filter_map(|x| x.as_str()).collect(),
+        _ => vec![],
+    };
+
+    if !types.is_empty() && !types.iter().any(|t| matches_type(t, value)) {
+        errors.push(format!(
+            "{}: expected {}, found {}",
+            if path.is_empty() { "/" } else { path },
+            types.join(" or "),
+            type_name(value)
+        ));
+        return;
+    }
+
+    match value {
+        Value::Mapping(map) => {
+            if let Some(Value::Sequence(required)) = schema.get("required") {
+                for key in required {
+                    if !map.contains_key(key) {
+                        errors.push(format!(
+                            "{}/{}: required property is missing",
+                            path,
+                            key.as_str().unwrap_or("?")
+                        ));
+                    }
+                }
+            }
+
+            for (k, v) in map {
+                let key = k
+                    .as_str()
+                    .map_or_else(|| format!("{:?}", k), |x| x.to_owned());
+                let path = format!("{}/{}", path, key);
+
+                match schema.get("properties").and_then(|x| x.get(k)) {
+                    Some(s) => validate_value(s, v, &path, errors),
+                    None => match schema.get("additionalProperties") {
+                        Some(Value::Bool(false)) => {
+                            errors.push(format!("{}: unknown property", path))
+                        }
+                        Some(s @ Value::Mapping(_)) => validate_value(s, v, &path, errors),
+                        _ => {}
+                    },
+                }
+            }
+        }
+        Value::Sequence(seq) => {
+            if let Some(s) = schema.get("items") {
+                for (i, v) in seq.iter().enumerate() {
+                    validate_value(s, v, &format!("{}/{}", path, i), errors);
+                }
+            }
+        }
+        _ => {
+            if let (Some(n), Value::String(v)) =
+                (schema.get("minLength").and_then(|x| x.as_u64()), value)
+            {
+                if (v.chars().count() as u64) < n {
+                    errors.push(format!("{}: expected at least {} characters", path, n));
+                }
+            }
+
+            if let Some(Value::Sequence(values)) = schema.get("enum") {
+                if !values.contains(value) {
+                    errors.push(format!(
+                        "{}: expected one of {}",
+                        path,
+                        values
+                            .iter()
+                            .filter_map(|x| x.as_str())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::config::{Config, InfoOptions, InfoParser, InfoType, QualityFlag, Sequence};
+
+    fn yaml(s: &str) -> Value {
+        serde_yaml::from_str(s).unwrap()
+    }
+
+    /// A configuration with all fields filled in.
+    fn full_config() -> Config {
+        let mut reference = BTreeMap::new();
+        reference.insert(
+            "20".to_owned(),
+            Some(Sequence {
+                name: Some("chr20".to_owned()),
+                reference: Some("http://identifiers.org/hco/20/GRCh37".to_owned()),
+                refget: Some("ga4gh:SQ.-A1QmD_MatoqxvgVxBLZTONHz9-c7nQo".to_owned()),
+                base: Some("http://example.org/chr20/".to_owned()),
+            }),
+        );
+        reference.insert("21".to_owned(), None);
+
+        let mut namespaces = BTreeMap::new();
+        namespaces.insert("ex".to_owned(), "http://example.org/".to_owned());
+
+        let mut pedigree = BTreeMap::new();
+        pedigree.insert("Father".to_owned(), "ex:father".to_owned());
+
+        let mut zygosity = BTreeMap::new();
+        zygosity.insert("heterozygous".to_owned(), "ex:Heterozygous".to_owned());
+
+        let mut info_parsers = BTreeMap::new();
+        info_parsers.insert("CSQ".to_owned(), InfoParser::Vep);
+
+        let mut info_predicates = BTreeMap::new();
+        info_predicates.insert("AF".to_owned(), "ex:alleleFrequency".to_owned());
+
+        let mut info_options = BTreeMap::new();
+        info_options.insert(
+            "CLNDN".to_owned(),
+            InfoOptions {
+                split: Some("|".to_owned()),
+                ..Default::default()
+            },
+        );
+        info_options.insert(
+            "RS".to_owned(),
+            InfoOptions {
+                as_iri_template: Some("http://identifiers.org/dbsnp/rs{value}".to_owned()),
+                keep_literal: true,
+                ..Default::default()
+            },
+        );
+
+        let mut info_types = BTreeMap::new();
+        info_types.insert("dbSNPBuildID".to_owned(), InfoType::Integer);
+
+        let mut contig_aliases = BTreeMap::new();
+        contig_aliases.insert("chr20".to_owned(), "20".to_owned());
+
+        let mut sample_iris = BTreeMap::new();
+        sample_iris.insert(
+            "NA00001".to_owned(),
+            "http://example.org/cohort/participant1".to_owned(),
+        );
+
+        Config {
+            base: Some("http://example.org/".to_owned()),
+            namespaces: Some(namespaces),
+            info: Some(vec!["DP".to_owned()]),
+            reference,
+            quality_flags: Some(vec![QualityFlag {
+                predicate: "ex:highQuality".to_owned(),
+                min_qual: Some(30.0),
+                require_pass: true,
+                omit_when_false: false,
+            }]),
+            subject: Some("info:CLNACC".to_owned()),
+            subject_template: Some("{chrom}-{pos}".to_owned()),
+            subject_prefix: Some("http://example.org/variant/".to_owned()),
+            sample_template: Some("sample/{id}".to_owned()),
+            pedigree_predicates: Some(pedigree),
+            rdf_star: Some(vec!["AF".to_owned()]),
+            zygosity: Some(zygosity),
+            info_parsers: Some(info_parsers),
+            consequence_predicate: Some("ex:consequence".to_owned()),
+            genotype_frequency_predicate: Some("ex:sampleAlleleFrequency".to_owned()),
+            info_predicates: Some(info_predicates),
+            info_options: Some(info_options),
+            info_types: Some(info_types),
+            sample_iris: Some(sample_iris),
+            samples: Some(vec!["NA00001".to_owned(), "NA00002".to_owned()]),
+            exclude_samples: Some(vec!["NA00002".to_owned()]),
+            format: Some(vec!["DP".to_owned(), "GQ".to_owned()]),
+            contig_aliases: Some(contig_aliases),
+            extends: Some("base.yaml".to_owned()),
+            reference_default: Some("https://identifiers.org/insdc/{contig}".to_owned()),
+            assembly: Some("GRCh37".to_owned()),
+        }
+    }
+
+    #[test]
+    fn test_schema_covers_all_fields() {
+        let schema: Value = serde_yaml::from_str(SCHEMA).unwrap();
+        let properties = schema.get("properties").unwrap().as_mapping().unwrap();
+
+        let config = serde_yaml::to_value(&full_config()).unwrap();
+        let fields = config.as_mapping().unwrap();
+
+        assert_eq!(
+            properties.iter().map(|(k, _)| k).collect::<Vec<_>>(),
+            fields.iter().map(|(k, _)| k).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_validate_full_config() {
+        let config = serde_yaml::to_value(&full_config()).unwrap();
+
+        assert_eq!(validate(&config), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_generated_config() {
+        let config = yaml("---\nbase: ~\nnamespaces: ~\ninfo:\n  - DP\nreference:\n  \"20\":\n    name: ~\n    reference: ~\n");
+
+        assert_eq!(validate(&config), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_min_length() {
+        let config = yaml("reference: {}\ninfo_options:\n  CLNDN:\n    split: \"\"\n");
+
+        assert_eq!(
+            validate(&config),
+            Err(vec![
+                "/info_options/CLNDN/split: expected at least 1 characters".to_owned()
+            ])
+        );
+    }
+
+    #[test]
+    fn test_validate_enum() {
+        let config = yaml("reference: {}\ninfo_parsers:\n  CSQ: snpeff\n");
+
+        assert_eq!(
+            validate(&config),
+            Err(vec!["/info_parsers/CSQ: expected one of vep".to_owned()])
+        );
+    }
+
+    #[test]
+    fn test_validate_wrong_type() {
+        let config =
+            yaml("reference: {}\nquality_flags:\n  - predicate: ex:q\n    min_qual: high\n");
+
+        assert_eq!(
+            validate(&config),
+            Err(vec![
+                "/quality_flags/0/min_qual: expected number or null, found string".to_owned()
+            ])
+        );
+    }
+
+    #[test]
+    fn test_validate_unknown_and_missing() {
+        let config = yaml("base: http://example.org/\nsubjects: id\n");
+
+        assert_eq!(
+            validate(&config),
+            Err(vec![
+                "/reference: required property is missing".to_owned(),
+                "/subjects: unknown property".to_owned()
+            ])
+        );
+    }
+}
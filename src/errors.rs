@@ -17,6 +17,15 @@ pub enum Error {
     #[error(transparent)]
     SerdeYamlError(#[from] serde_yaml::Error),
 
+    #[error(transparent)]
+    SerdeJsonError(#[from] serde_json::Error),
+
+    #[error(transparent)]
+    TomlDeError(#[from] toml::de::Error),
+
+    #[error(transparent)]
+    TomlSerError(#[from] toml::ser::Error),
+
     #[error(transparent)]
     VcfLibError(#[from] vcf_lib::errors::Error),
 
@@ -26,12 +35,24 @@ pub enum Error {
     #[error("Path contains invalid character: {0}")]
     FilePathError(String),
 
+    #[error("Failed to read remote input {0}: {1}")]
+    RemoteInputError(String, String),
+
     #[error("Index file not found: {0}")]
     IndexNotFoundError(String),
 
+    #[error("Index file not found at the specified path: {0}")]
+    ExplicitIndexNotFoundError(String),
+
+    #[error("Index {0} does not match the data file: {1}")]
+    IndexMismatchError(String, String),
+
     #[error("Both reference and alternate must not be empty.")]
     InvalidRefAltError,
 
+    #[error("Invalid allele: {0}")]
+    InvalidAlleleError(String),
+
     #[error("Invalid reference ID.")]
     ReferenceIndexError,
 
@@ -50,9 +71,51 @@ pub enum Error {
     #[error("tbx_index_build failed: {0}")]
     IndexBuildFailedError(String),
 
+    #[error("bcf_index_build failed: {0}")]
+    BcfIndexBuildFailedError(String),
+
     #[error("Missing configuration: {0}")]
     ConfigurationNotFoundError(String),
 
     #[error("Invalid configuration: {0}")]
     InvalidConfigurationError(String),
+
+    #[error("Invalid template: {0}")]
+    InvalidTemplateError(String),
+
+    #[error("Invalid argument: {0}")]
+    InvalidArgumentError(String),
+
+    #[error("Duplicate subject {0} at {1} and {2}")]
+    DuplicateSubjectError(String, String, String),
+
+    #[error("Input is not sorted by contig: {0} appears again")]
+    UnsortedContigError(String),
+
+    #[error("INFO/{0} of Number=A has {1} values for {2} alternate alleles at {3}")]
+    InfoLengthError(String, usize, usize, String),
+
+    #[error("Unknown INFO keys: {0} (available: {1})")]
+    UnknownInfoKeyError(String, String),
+
+    #[error("Unknown contig: {0} (available: {1})")]
+    UnknownContigError(String, String),
+
+    #[error("Invalid BED at line {1} of {0}: {2}")]
+    BedFormatError(String, usize, String),
+
+    #[error("Invalid assembly file at line {1} of {0}: {2}")]
+    AssemblyFileError(String, usize, String),
+
+    #[error("Invalid assembly report at line {0}: {1}")]
+    AssemblyReportError(usize, String),
+
+    #[error("Malformed record at {0}:{1}: {2}")]
+    MalformedRecordError(String, u64, String),
+
+    #[error("REF of {0} records do not match the reference (allowed: {1})")]
+    RefMismatchError(u64, u64),
+
+    #[error("Output closed by the reader")]
+    OutputClosedError,
 }
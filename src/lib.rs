@@ -2,5 +2,6 @@ pub mod cli;
 pub mod config;
 pub mod errors;
 pub mod rdf;
+pub mod summary;
 pub mod util;
 pub mod vcf;
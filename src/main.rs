@@ -3,8 +3,8 @@ use std::process::exit;
 
 use structopt::StructOpt;
 
-use vcf2rdf::cli::{compressor, converter, generator, statistics, Command};
-use vcf2rdf::errors::Result;
+use vcf2rdf::cli::{compressor, converter, generator, normalizer, statistics, Command};
+use vcf2rdf::errors::{Error, Result};
 
 fn main() -> Result<()> {
     env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("warn")).init();
@@ -16,11 +16,17 @@ fn main() -> Result<()> {
         Command::Convert(opts) => converter::run(opts),
         Command::Stat(cmd) => statistics::run(cmd),
         Command::Generate(cmd) => generator::run(cmd),
+        Command::Normalize(opts) => normalizer::run(opts),
     };
 
-    if let Err(err) = ret {
-        eprintln!("Error: {}", err);
-        exit(1);
+    match ret {
+        // e.g. `vcf2rdf convert ... | head`
+        Err(Error::OutputClosedError) => {}
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            exit(1);
+        }
+        Ok(_) => {}
     }
 
     Ok(())
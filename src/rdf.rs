@@ -1,4 +1,8 @@
 //! Module for working with RDF
+pub mod iri;
 pub mod namespace;
+pub mod template;
 pub mod turtle_writer;
+pub mod void;
+pub mod vrs;
 pub mod writer;
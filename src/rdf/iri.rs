@@ -0,0 +1,213 @@
+//! Helpers to mint IRIs
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+static REGEX_SCHEME: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[A-Za-z][A-Za-z0-9+.\-]*:").unwrap());
+
+static REGEX_LOCAL_NAME: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\A[A-Za-z0-9_]([A-Za-z0-9_.\-]*[A-Za-z0-9_\-])?\z").unwrap());
+
+/// Regular expression of RFC 3986 Appendix B to split an IRI reference into components.
+static REGEX_COMPONENTS: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(?:([^:/?#]+):)?(?://([^/?#]*))?([^?#]*)(?:\?([^#]*))?(?:#(.*))?$").unwrap()
+});
+
+/// Components of an IRI reference.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Components<'a> {
+    pub scheme: Option<&'a str>,
+    pub authority: Option<&'a str>,
+    pub path: &'a str,
+    pub query: Option<&'a str>,
+    pub fragment: Option<&'a str>,
+}
+
+/// Split `iri` into components.
+pub fn parse(iri: &str) -> Components {
+    let caps = REGEX_COMPONENTS
+        .captures(iri)
+        .expect("RFC 3986 regular expression matches any string");
+    let get = |i| caps.get(i).map(|m| m.as_str());
+
+    Components {
+        scheme: get(1),
+        authority: get(2),
+        path: get(3).unwrap_or(""),
+        query: get(4),
+        fragment: get(5),
+    }
+}
+
+/// Percent-encode all characters except unreserved ones (RFC 3986).
+pub fn percent_encode(segment: &str) -> String {
+    let mut buf = String::with_capacity(segment.len());
+
+    for b in segment.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                buf.push(b as char)
+            }
+            _ => buf.push_str(&format!("%{:02X}", b)),
+        }
+    }
+
+    buf
+}
+
+/// Join `relative` to `base` with exactly one slash.
+///
+/// A base ending with `#` is concatenated as is.
+pub fn join(base: &str, relative: &str) -> String {
+    let relative = relative.trim_start_matches('/');
+
+    if base.ends_with('#') {
+        format!("{}{}", base, relative)
+    } else {
+        format!("{}/{}", base.trim_end_matches('/'), relative)
+    }
+}
+
+/// Returns true if `iri` starts with a scheme.
+pub fn has_scheme(iri: &str) -> bool {
+    REGEX_SCHEME.is_match(iri)
+}
+
+/// Returns true if `iri` is an absolute IRI that can be written in `<>`.
+pub fn is_absolute(iri: &str) -> bool {
+    match REGEX_SCHEME.find(iri) {
+        Some(m) if m.end() < iri.len() => !iri
+            .chars()
+            .any(|c| c.is_control() || c.is_whitespace() || "<>\"{}|^`\\".contains(c)),
+        _ => false,
+    }
+}
+
+/// Collapse repeated trailing slashes into one, e.g. `http://example.org/a//` to `http://example.org/a/`.
+///
+/// Slashes right after `scheme://` are kept as is.
+pub fn collapse_trailing_slashes(iri: &str) -> String {
+    let trimmed = iri.trim_end_matches('/');
+
+    if iri.len() - trimmed.len() < 2 || trimmed.ends_with(':') {
+        return iri.to_owned();
+    }
+
+    format!("{}/", trimmed)
+}
+
+/// Returns true if `name` can be used as a local name of a prefixed name without escapes.
+pub fn is_local_name(name: &str) -> bool {
+    REGEX_LOCAL_NAME.is_match(name)
+}
+
+/// Returns the first ID in a semicolon separated ID column, or `None` if missing.
+pub fn first_id(id: &str) -> Option<&str> {
+    id.split(';')
+        .map(|x| x.trim())
+        .find(|x| !x.is_empty() && *x != ".")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_id() {
+        assert_eq!(first_id("rs123"), Some("rs123"));
+        assert_eq!(first_id("esv3585040;esv3585041"), Some("esv3585040"));
+        assert_eq!(first_id(".;rs123"), Some("rs123"));
+        assert_eq!(first_id("."), None);
+        assert_eq!(first_id(""), None);
+    }
+
+    #[test]
+    fn test_percent_encode() {
+        assert_eq!(percent_encode("COSM12345"), "COSM12345");
+        assert_eq!(percent_encode("a/b#c?d"), "a%2Fb%23c%3Fd");
+        assert_eq!(percent_encode("x y"), "x%20y");
+        assert_eq!(percent_encode("é"), "%C3%A9");
+    }
+
+    #[test]
+    fn test_join() {
+        assert_eq!(
+            join("http://example.org/variant", "rs1"),
+            "http://example.org/variant/rs1"
+        );
+        assert_eq!(
+            join("http://example.org/variant/", "rs1"),
+            "http://example.org/variant/rs1"
+        );
+        assert_eq!(
+            join("http://example.org/variant//", "/rs1"),
+            "http://example.org/variant/rs1"
+        );
+        assert_eq!(
+            join("http://example.org/variant#", "rs1"),
+            "http://example.org/variant#rs1"
+        );
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            parse("https://example.org/a/b?x=1#frag"),
+            Components {
+                scheme: Some("https"),
+                authority: Some("example.org"),
+                path: "/a/b",
+                query: Some("x=1"),
+                fragment: Some("frag"),
+            }
+        );
+        assert_eq!(
+            parse("urn:uuid:1234"),
+            Components {
+                scheme: Some("urn"),
+                authority: None,
+                path: "uuid:1234",
+                query: None,
+                fragment: None,
+            }
+        );
+        assert_eq!(parse("http://example.org/a#").fragment, Some(""));
+        assert_eq!(parse("rs1").scheme, None);
+    }
+
+    #[test]
+    fn test_collapse_trailing_slashes() {
+        assert_eq!(
+            collapse_trailing_slashes("http://example.org/a//"),
+            "http://example.org/a/"
+        );
+        assert_eq!(
+            collapse_trailing_slashes("http://example.org/a/"),
+            "http://example.org/a/"
+        );
+        assert_eq!(
+            collapse_trailing_slashes("http://example.org/a"),
+            "http://example.org/a"
+        );
+        assert_eq!(collapse_trailing_slashes("file:///"), "file:///");
+    }
+
+    #[test]
+    fn test_is_local_name() {
+        assert!(is_local_name("AF"));
+        assert!(is_local_name("1000G"));
+        assert!(is_local_name("CAF.x"));
+        assert!(!is_local_name("AF."));
+        assert!(!is_local_name("A:F"));
+        assert!(!is_local_name(""));
+    }
+
+    #[test]
+    fn test_is_absolute() {
+        assert!(is_absolute("http://example.org/rs1"));
+        assert!(is_absolute("urn:uuid:1234"));
+        assert!(!is_absolute("rs1"));
+        assert!(!is_absolute("http:"));
+        assert!(!is_absolute("http://example.org/a b"));
+        assert!(!is_absolute("http://example.org/<a>"));
+    }
+}
@@ -1,12 +1,18 @@
 use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::de::{self, Deserialize, Deserializer, MapAccess, Visitor};
 
 use crate::config::Config;
 
-const DCT: &str = "http://purl.org/dc/terms/";
+pub const DCT: &str = "http://purl.org/dc/terms/";
 const FALDO: &str = "http://biohackathon.org/resource/faldo#";
-const GVO: &str = "http://genome-variation.org/resource#";
+pub const GVO: &str = "http://genome-variation.org/resource#";
+const PROV: &str = "http://www.w3.org/ns/prov#";
 const RDF: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#";
 const RDFS: &str = "http://www.w3.org/2000/01/rdf-schema#";
+pub const VOID: &str = "http://rdfs.org/ns/void#";
+const XSD: &str = "http://www.w3.org/2001/XMLSchema#";
 
 /// RDF namespace.
 #[derive(Debug)]
@@ -23,8 +29,11 @@ impl Default for Namespace {
         prefixes.insert("dct".to_owned(), DCT.to_owned());
         prefixes.insert("faldo".to_owned(), FALDO.to_owned());
         prefixes.insert("gvo".to_owned(), GVO.to_owned());
+        prefixes.insert("prov".to_owned(), PROV.to_owned());
         prefixes.insert("rdf".to_owned(), RDF.to_owned());
         prefixes.insert("rdfs".to_owned(), RDFS.to_owned());
+        prefixes.insert("void".to_owned(), VOID.to_owned());
+        prefixes.insert("xsd".to_owned(), XSD.to_owned());
 
         Namespace {
             base: None,
@@ -51,3 +60,125 @@ impl From<&Config> for Namespace {
         ns
     }
 }
+
+impl Namespace {
+    /// Returns prefixes in `config` which map a built-in prefix to a different IRI.
+    pub fn shadowed_prefixes(config: &Config) -> Vec<(String, String)> {
+        let defaults = Namespace::default();
+
+        config
+            .namespaces
+            .iter()
+            .flatten()
+            .filter(|(k, v)| defaults.prefixes.get(*k).map_or(false, |x| x != *v))
+            .map(|(k, v)| (k.to_owned(), v.to_owned()))
+            .collect()
+    }
+
+    /// Returns true if `term` is an IRI or a prefixed name with a known prefix.
+    pub fn is_resolvable(&self, term: &str) -> bool {
+        if (term.starts_with('<') && term.ends_with('>')) || term.contains("://") {
+            return true;
+        }
+
+        match term.split_once(':') {
+            Some((prefix, _)) => self.prefixes.contains_key(prefix),
+            None => false,
+        }
+    }
+}
+
+/// User namespaces rejecting a prefix declared twice with different IRIs.
+struct Prefixes(BTreeMap<String, String>);
+
+impl<'de> Deserialize<'de> for Prefixes {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct PrefixVisitor;
+
+        impl<'de> Visitor<'de> for PrefixVisitor {
+            type Value = Prefixes;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a map of prefixes to IRIs")
+            }
+
+            fn visit_map<M: MapAccess<'de>>(self, mut access: M) -> Result<Self::Value, M::Error> {
+                let mut map = BTreeMap::new();
+
+                while let Some((k, v)) = access.next_entry::<String, String>()? {
+                    match map.get(&k) {
+                        Some(x) if x != &v => {
+                            return Err(de::Error::custom(format!(
+                                "prefix {} is declared twice with different IRIs: {} and {}",
+                                k, x, v
+                            )))
+                        }
+                        _ => {
+                            map.insert(k, v);
+                        }
+                    }
+                }
+
+                Ok(Prefixes(map))
+            }
+        }
+
+        deserializer.deserialize_map(PrefixVisitor)
+    }
+}
+
+/// Deserialize `namespaces` of configuration.
+pub fn deserialize_prefixes<'de, D>(
+    deserializer: D,
+) -> Result<Option<BTreeMap<String, String>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(Option::<Prefixes>::deserialize(deserializer)?.map(|x| x.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(yaml: &str) -> serde_yaml::Result<Config> {
+        serde_yaml::from_str(&format!("reference: {{}}\n{}", yaml))
+    }
+
+    #[test]
+    fn test_shadowed_prefixes() {
+        let config =
+            config("namespaces:\n  gvo: http://example.org/gvo#\n  ex: http://example.org/\n")
+                .unwrap();
+
+        assert_eq!(
+            Namespace::shadowed_prefixes(&config),
+            vec![("gvo".to_owned(), "http://example.org/gvo#".to_owned())]
+        );
+    }
+
+    #[test]
+    fn test_same_prefix_as_default() {
+        let config = config(&format!("namespaces:\n  gvo: {}\n", GVO)).unwrap();
+
+        assert!(Namespace::shadowed_prefixes(&config).is_empty());
+    }
+
+    #[test]
+    fn test_null_namespaces() {
+        assert!(config("namespaces: ~\n").unwrap().namespaces.is_none());
+        assert!(config("").unwrap().namespaces.is_none());
+    }
+
+    #[test]
+    fn test_duplicate_prefixes() {
+        assert!(
+            config("namespaces:\n  ex: http://example.org/a#\n  ex: http://example.org/b#\n")
+                .is_err()
+        );
+        assert!(
+            config("namespaces:\n  ex: http://example.org/a#\n  ex: http://example.org/a#\n")
+                .is_ok()
+        );
+    }
+}
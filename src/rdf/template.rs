@@ -0,0 +1,206 @@
+//! Module for subject IRI templates
+use crate::errors::{Error, Result};
+use crate::rdf::iri;
+use crate::util::template::Template;
+use crate::vcf::alteration::Alteration;
+use crate::vcf::record::Entry;
+
+/// Placeholders available in a template.
+pub const PLACEHOLDERS: &[&str] = &[
+    "assembly",
+    "chrom",
+    "name",
+    "reference",
+    "id",
+    "pos",
+    "ref",
+    "alt",
+    "normalized_pos",
+    "normalized_ref",
+    "normalized_alt",
+];
+
+/// A compiled template to format subject IRIs, e.g. `http://example.org/{chrom}/{pos}/{ref}/{alt}`.
+///
+/// Values except `{reference}`, which is an IRI, are percent-encoded.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubjectTemplate {
+    template: Template,
+    assembly: Option<String>,
+}
+
+impl SubjectTemplate {
+    /// Compile `template`. Fails on unknown placeholders or unbalanced braces.
+    pub fn parse(template: &str) -> Result<SubjectTemplate> {
+        Ok(SubjectTemplate {
+            template: Template::parse(template, PLACEHOLDERS).map_err(|e| {
+                Error::InvalidConfigurationError(format!("subject_template: {}", e))
+            })?,
+            assembly: None,
+        })
+    }
+
+    /// Set the name of the assembly for `{assembly}`.
+    pub fn assembly(mut self, assembly: Option<String>) -> Self {
+        self.assembly = assembly;
+        self
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.template.as_str()
+    }
+
+    /// Returns true if formatted IRIs are relative to `@base`.
+    pub fn is_relative(&self) -> bool {
+        !(iri::has_scheme(self.as_str()) || self.as_str().starts_with("{reference}"))
+    }
+
+    /// Returns true if formatted IRIs contain the name of the assembly.
+    pub fn uses_assembly(&self) -> bool {
+        self.template.contains("assembly")
+    }
+
+    /// Returns true if formatted IRIs contain the name of the sequence.
+    pub fn uses_name(&self) -> bool {
+        self.template.contains("name")
+    }
+
+    /// Format a subject for `entry`, or returns `None` if any value is not available.
+    pub fn format(&self, entry: &Entry) -> Option<String> {
+        let normalized = if self
+            .template
+            .any_placeholder(|x| x.starts_with("normalized_"))
+        {
+            Alteration::normalize(
+                entry.position(),
+                entry.reference_bases(),
+                entry.alternate_bases(),
+            )
+            .ok()
+        } else {
+            None
+        };
+
+        self.template.render(|name| {
+            let value = match name {
+                "reference" => return entry.record().sequence()?.reference.clone(),
+                "assembly" => self.assembly.as_ref()?.to_owned(),
+                "chrom" => entry.chromosome()?.ok()?.to_owned(),
+                "name" => entry.record().sequence()?.name.as_ref()?.to_owned(),
+                "id" => iri::first_id(&String::from_utf8_lossy(&entry.record().inner().id()))?
+                    .to_owned(),
+                "pos" => entry.position().to_string(),
+                "ref" => entry.reference_bases().to_owned(),
+                "alt" => entry.alternate_bases().to_owned(),
+                "normalized_pos" => normalized?.position.to_string(),
+                "normalized_ref" => normalized?.reference.to_owned(),
+                "normalized_alt" => normalized?.alternate.to_owned(),
+                _ => return None,
+            };
+
+            Some(iri::percent_encode(&value))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::config::Sequence;
+    use crate::vcf::reader::ReaderBuilder;
+
+    fn format_all(template: &str, assembly: Option<&str>) -> Vec<Option<String>> {
+        let template = SubjectTemplate::parse(template)
+            .unwrap()
+            .assembly(assembly.map(|x| x.to_owned()));
+
+        let mut reference = BTreeMap::new();
+        reference.insert(
+            "20".to_owned(),
+            Some(Sequence {
+                name: Some("chr20".to_owned()),
+                reference: Some("http://identifiers.org/hco/20/GRCh37".to_owned()),
+                ..Default::default()
+            }),
+        );
+
+        let mut reader = ReaderBuilder::new()
+            .reference(reference)
+            .path("test/vcf_spec.vcf.gz")
+            .expect("Error opening file.");
+
+        let mut subjects = Vec::new();
+        for record in reader.records() {
+            let record = record.expect("Error reading record.");
+            for entry in record.each_alternate_alleles() {
+                subjects.push(template.format(&entry));
+            }
+        }
+
+        subjects
+    }
+
+    #[test]
+    fn test_parse() {
+        assert!(SubjectTemplate::parse("http://example.org/{chrom}/{pos}").is_ok());
+        assert!(SubjectTemplate::parse("no placeholders").is_ok());
+        assert!(SubjectTemplate::parse("http://example.org/{assembly}").is_ok());
+        assert!(SubjectTemplate::parse("http://example.org/{build}").is_err());
+        assert!(SubjectTemplate::parse("http://example.org/{chrom").is_err());
+        assert!(SubjectTemplate::parse("http://example.org/chrom}").is_err());
+    }
+
+    #[test]
+    fn test_is_relative() {
+        assert!(!SubjectTemplate::parse("http://example.org/{id}")
+            .unwrap()
+            .is_relative());
+        assert!(!SubjectTemplate::parse("{reference}#{pos}")
+            .unwrap()
+            .is_relative());
+        assert!(SubjectTemplate::parse("{chrom}-{pos}")
+            .unwrap()
+            .is_relative());
+    }
+
+    #[test]
+    fn test_format() {
+        let subjects = format_all("https://example.org/var/{chrom}/{pos}/{ref}/{alt}", None);
+
+        assert_eq!(
+            subjects[0].as_deref(),
+            Some("https://example.org/var/20/14370/G/A")
+        );
+    }
+
+    #[test]
+    fn test_format_assembly() {
+        let template = "https://example.org/var/{assembly}/{chrom}/{pos}/{ref}/{alt}";
+
+        assert_eq!(
+            format_all(template, Some("GRCh37"))[0].as_deref(),
+            Some("https://example.org/var/GRCh37/20/14370/G/A")
+        );
+        assert_eq!(format_all(template, None)[0], None);
+    }
+
+    #[test]
+    fn test_format_reference_and_name() {
+        let subjects = format_all("{reference}#{name}-{normalized_pos}", None);
+
+        assert_eq!(
+            subjects[0].as_deref(),
+            Some("http://identifiers.org/hco/20/GRCh37#chr20-14370")
+        );
+    }
+
+    #[test]
+    fn test_format_missing_id() {
+        let subjects = format_all("http://example.org/{id}", None);
+
+        assert_eq!(subjects[0].as_deref(), Some("http://example.org/rs6054257"));
+        assert_eq!(subjects[1], None);
+    }
+}
@@ -1,135 +1,276 @@
 use log::*;
 use once_cell::sync::Lazy;
 use regex::Regex;
+use sha2::{Digest, Sha256};
+use std::cell::{Cell, Ref, RefCell, RefMut};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fs::File;
-use std::io::{BufWriter, Write};
+use std::io::{self, BufWriter, Seek, SeekFrom, Write};
 use std::path::Path;
-use vcf_lib::record::normalize;
+use std::sync::Arc;
+use strum::{AsRefStr, EnumString, EnumVariantNames};
+use tempfile::SpooledTempFile;
 
-use crate::cli::converter::Subject;
-use crate::errors::Result;
+use crate::config::{CompiledConfig, InfoOptions, InfoParser, QualityFlag, Sequence};
+use crate::errors::{Error, Result};
+use crate::rdf::iri;
 use crate::rdf::namespace::Namespace;
+use crate::rdf::template::SubjectTemplate;
+use crate::rdf::void::Void;
 use crate::rdf::writer::Writer;
-use crate::vcf::record::{Entry, Record};
+use crate::summary::Summary;
+use crate::util::bloom::GrowingBloomFilter;
+use crate::vcf::alteration::Alteration;
+use crate::vcf::exclude::ExcludeList;
+use crate::vcf::genotype::Zygosity;
+use crate::vcf::record::as_turtle::{push_info_key_definition, Buffer};
+use crate::vcf::record::{Entry, InfoValue, Record};
+use crate::vcf::sample::{self, Sample};
+use crate::vcf::sv;
+
+use self::provenance::Provenance;
+
+pub mod provenance;
+mod vrs;
 
 pub trait AsTurtle<W> {
-    fn as_ttl_string(&self, wtr: &TurtleWriter<W>) -> Result<Option<String>>
+    /// Returns Turtle statements with the number of triples and the prefixes used in them.
+    fn as_ttl_string(&self, wtr: &TurtleWriter<W>) -> Result<Option<Buffer>>
     where
         W: Write;
 }
 
 pub struct SubjectFormatter {
-    func: fn(&Entry) -> Option<String>,
+    func: Box<dyn Fn(&Entry) -> Option<String> + Send + Sync>,
+    /// The formatted value is relative to `@base`.
+    relative: bool,
+    /// Summary key counted if no subject is formatted for an entry.
+    missing: Option<String>,
+    /// Formatter tried next if no subject is formatted for an entry.
+    fallback: Option<Box<SubjectFormatter>>,
 }
 
 impl Default for SubjectFormatter {
     fn default() -> Self {
         SubjectFormatter {
-            func: |_: &Entry| None,
+            func: Box::new(|_: &Entry| None),
+            relative: false,
+            missing: None,
+            fallback: None,
         }
     }
 }
 
 impl From<&Subject> for SubjectFormatter {
     fn from(v: &Subject) -> Self {
-        match v {
+        SubjectFormatter::with_hash_threshold(v, None)
+    }
+}
+
+/// Returns `ref-alt`, or a hash of `ref>alt` if the combined length exceeds `threshold`.
+///
+/// Alleles are percent-encoded, e.g. `<DEL>` to `%3CDEL%3E`, `G]17:198982]` to `G%5D17%3A198982%5D` and `*` to `%2A`.
+fn alleles(reference: &str, alternate: &str, threshold: Option<usize>) -> String {
+    match threshold {
+        Some(n) if reference.len() + alternate.len() > n => {
+            let hash = Sha256::digest(format!("{}>{}", reference, alternate).as_bytes());
+            hash[..8].iter().map(|x| format!("{:02x}", x)).collect()
+        }
+        _ => format!(
+            "{}-{}",
+            iri::percent_encode(reference),
+            iri::percent_encode(alternate)
+        ),
+    }
+}
+
+/// Returns `{reference}#{position}-{alleles}` of `sequence`, or `{position}-{alleles}` resolved against its `base`.
+fn reference_subject(sequence: &Sequence, position: u64, alleles: &str) -> Option<String> {
+    match sequence.base.as_ref() {
+        Some(base) => Some(iri::join(base, &format!("{}-{}", position, alleles))),
+        None => Some(format!(
+            "{}#{}-{}",
+            sequence.reference.as_ref()?,
+            position,
+            alleles
+        )),
+    }
+}
+
+impl SubjectFormatter {
+    /// Create a formatter for `subject`.
+    ///
+    /// Alleles in `location` and `reference` strategies are replaced with a short hash
+    /// if the combined length of ref and alt exceeds `threshold`.
+    pub fn with_hash_threshold(subject: &Subject, threshold: Option<usize>) -> Self {
+        match subject {
             Subject::ID => SubjectFormatter {
-                func: |entry: &Entry| unsafe {
-                    match String::from_utf8_unchecked(entry.record().inner().id()).as_str() {
-                        "." => None,
-                        v if v.is_empty() => None,
-                        v => Some(v.to_owned()),
-                    }
-                },
+                func: Box::new(|entry: &Entry| {
+                    let id = entry.record().inner().id();
+                    iri::first_id(&String::from_utf8_lossy(&id)).map(iri::percent_encode)
+                }),
+                relative: true,
+                missing: None,
+                fallback: None,
             },
             Subject::Location => SubjectFormatter {
-                func: |entry: &Entry| {
-                    if let Some(seq) = entry.record().sequence() {
-                        if let Some(name) = seq.name.as_ref() {
-                            Some(format!(
-                                "{}-{}-{}-{}",
-                                name,
-                                entry.position(),
-                                entry.reference_bases(),
-                                entry.alternate_bases()
-                            ))
-                        } else {
-                            None
-                        }
-                    } else {
-                        None
-                    }
-                },
+                func: Box::new(move |entry: &Entry| {
+                    let name = entry.record().sequence()?.name.as_ref()?;
+                    Some(format!(
+                        "{}-{}-{}",
+                        name,
+                        entry.position(),
+                        alleles(entry.reference_bases(), entry.alternate_bases(), threshold)
+                    ))
+                }),
+                relative: true,
+                missing: None,
+                fallback: None,
             },
             Subject::Reference => SubjectFormatter {
-                func: |entry: &Entry| {
-                    if let Some(seq) = entry.record().sequence() {
-                        if let Some(uri) = seq.reference.as_ref() {
-                            Some(format!(
-                                "{}#{}-{}-{}",
-                                uri,
-                                entry.position(),
-                                entry.reference_bases(),
-                                entry.alternate_bases()
-                            ))
-                        } else {
-                            None
-                        }
-                    } else {
-                        None
-                    }
-                },
+                func: Box::new(move |entry: &Entry| {
+                    reference_subject(
+                        entry.record().sequence()?,
+                        entry.position(),
+                        &alleles(entry.reference_bases(), entry.alternate_bases(), threshold),
+                    )
+                }),
+                relative: false,
+                missing: None,
+                fallback: None,
             },
             Subject::NormalizedLocation => SubjectFormatter {
-                func: |entry: &Entry| match normalize(
-                    entry.position(),
-                    entry.reference_bases(),
-                    entry.alternate_bases(),
-                ) {
-                    Ok((position, reference, alternate)) => {
-                        if let Some(seq) = entry.record().sequence() {
-                            if let Some(name) = seq.name.as_ref() {
-                                Some(format!("{}-{}-{}-{}", name, position, reference, alternate))
-                            } else {
-                                None
-                            }
-                        } else {
-                            None
-                        }
-                    }
-                    Err(_) => None,
-                },
+                func: Box::new(move |entry: &Entry| {
+                    let alteration = Alteration::normalize(
+                        entry.position(),
+                        entry.reference_bases(),
+                        entry.alternate_bases(),
+                    )
+                    .ok()?;
+                    let name = entry.record().sequence()?.name.as_ref()?;
+                    Some(format!(
+                        "{}-{}-{}",
+                        name,
+                        alteration.position,
+                        alleles(alteration.reference, alteration.alternate, threshold)
+                    ))
+                }),
+                relative: true,
+                missing: None,
+                fallback: None,
             },
             Subject::NormalizedReference => SubjectFormatter {
-                func: |entry: &Entry| match normalize(
-                    entry.position(),
-                    entry.reference_bases(),
-                    entry.alternate_bases(),
-                ) {
-                    Ok((position, reference, alternate)) => {
-                        if let Some(seq) = entry.record().sequence() {
-                            if let Some(uri) = seq.reference.as_ref() {
-                                Some(format!("{}#{}-{}-{}", uri, position, reference, alternate))
-                            } else {
-                                None
-                            }
-                        } else {
-                            None
+                func: Box::new(move |entry: &Entry| {
+                    let alteration = Alteration::normalize(
+                        entry.position(),
+                        entry.reference_bases(),
+                        entry.alternate_bases(),
+                    )
+                    .ok()?;
+                    reference_subject(
+                        entry.record().sequence()?,
+                        alteration.position,
+                        &alleles(alteration.reference, alteration.alternate, threshold),
+                    )
+                }),
+                relative: false,
+                missing: None,
+                fallback: None,
+            },
+            // requires a key, see `SubjectFormatter::info`
+            Subject::Info => SubjectFormatter::default(),
+            // requires reference sequences, see `SubjectFormatter::vrs`
+            Subject::VRS => SubjectFormatter::default(),
+            Subject::Dbsnp => SubjectFormatter {
+                func: Box::new(|entry: &Entry| {
+                    match entry.record().info_by_key("RS")?.value.into_iter().next()? {
+                        InfoValue::Integer(v) if v > 0 => {
+                            Some(format!("http://identifiers.org/dbsnp/rs{}", v))
                         }
+                        _ => None,
                     }
-                    Err(_) => None,
-                },
+                }),
+                relative: false,
+                missing: Some("Subjects fell back to ID (missing RS)".to_owned()),
+                fallback: Some(Box::new(SubjectFormatter::from(&Subject::ID))),
             },
         }
     }
-}
 
-impl SubjectFormatter {
+    /// Create a formatter from a compiled template in configuration.
+    pub fn from_template(template: SubjectTemplate) -> Self {
+        SubjectFormatter {
+            relative: template.is_relative(),
+            missing: None,
+            fallback: None,
+            func: Box::new(move |entry: &Entry| template.format(entry)),
+        }
+    }
+
+    /// Create a formatter that uses the first value of INFO `key`, relative to `@base`.
+    pub fn info(key: String) -> Self {
+        SubjectFormatter {
+            missing: Some(format!(
+                "Subjects fell back to blank node (missing INFO/{})",
+                key
+            )),
+            fallback: None,
+            func: Box::new(move |entry: &Entry| {
+                let value = match entry.record().info_by_key(&key)?.value.into_iter().next()? {
+                    InfoValue::Integer(v) => v.to_string(),
+                    InfoValue::Float(v) => v.to_string(),
+                    InfoValue::String(v) | InfoValue::Iri(v) => v,
+                    InfoValue::Flag(_) | InfoValue::Missing => return None,
+                };
+
+                iri::first_id(value.split(',').next()?).map(iri::percent_encode)
+            }),
+            relative: true,
+        }
+    }
+
+    /// Prepend `prefix` to relative subjects, which are then absolute.
+    pub fn prefix(mut self, prefix: String) -> Self {
+        self.fallback = self.fallback.map(|x| Box::new(x.prefix(prefix.clone())));
+
+        if !self.relative {
+            return self;
+        }
+
+        let func = self.func;
+        SubjectFormatter {
+            func: Box::new(move |entry: &Entry| func(entry).map(|x| format!("{}{}", prefix, x))),
+            relative: false,
+            missing: self.missing,
+            fallback: self.fallback,
+        }
+    }
+
+    /// Returns true if formatted subjects, including those of fallbacks, may be relative to `@base`.
+    pub fn is_relative(&self) -> bool {
+        self.relative || self.fallback.as_ref().map_or(false, |x| x.is_relative())
+    }
+
     pub fn format(&self, entry: &Entry) -> Option<String> {
         (self.func)(entry)
     }
 }
 
+/// Subjects written so far to detect duplicates.
+#[derive(Debug)]
+enum SubjectSet {
+    /// Subjects in a bloom filter growing with them, of which duplicates may be false positives.
+    /// Subjects of the current record are kept aside with whether they are possible duplicates,
+    /// since alleles of a record may share a subject.
+    Bloom {
+        filter: GrowingBloomFilter,
+        record: u64,
+        current: Vec<(String, bool)>,
+    },
+    /// Subjects mapped to the record index and the location of the first occurrence.
+    Exact(HashMap<String, (u64, String)>),
+}
+
 pub struct TurtleWriter<'a, W: Write> {
     wtr: BufWriter<W>,
     state: WriterState,
@@ -137,11 +278,291 @@ pub struct TurtleWriter<'a, W: Write> {
     info_key: Option<&'a Vec<String>>,
     pub subject_id: Option<Subject>,
     subject_formatter: SubjectFormatter,
+    options: WriterOptions,
+    // number of entries of the record being rendered, which passed filters on alleles
+    record_entries: usize,
+    quality_flags: Vec<QualityFlag>,
+    records_written: u64,
+    records_read: u64,
+    bytes_written: u64,
+    // INFO keys whose terms are defined so far, with their descriptions
+    defined_info_keys: RefCell<BTreeMap<String, Option<String>>>,
+    // INFO keys and descriptions defined before the next entry
+    info_definitions: Vec<(String, Option<String>)>,
+    exclude: Option<ExcludeList>,
+    // chosen at the first subject by `strict` and `strict_duplicates`
+    subjects: RefCell<Option<SubjectSet>>,
+    // subjects of breakends by ID, waiting for their mates
+    breakends: RefCell<HashMap<String, String>>,
+    // ID, mate ID and subject of the breakend being rendered
+    pending_mate: RefCell<Option<(String, String, String)>>,
+    banner: Vec<String>,
+    provenance: Option<Provenance>,
+    samples: Vec<Sample>,
+    // number of samples described in the current output
+    samples_written: usize,
+    sample_template: String,
+    sample_iris: BTreeMap<String, String>,
+    sample_secret: Option<String>,
+    // names of samples of the records written and their IRIs, resolved once per header
+    header_sample_iris: RefCell<(Vec<String>, Vec<String>)>,
+    format_keys: Vec<String>,
+    pedigree_predicates: BTreeMap<String, String>,
+    rdf_star: Vec<String>,
+    zygosity: BTreeMap<String, String>,
+    info_parsers: BTreeMap<String, InfoParser>,
+    consequence_predicate: String,
+    genotype_frequency_predicate: String,
+    info_predicates: BTreeMap<String, String>,
+    info_options: BTreeMap<String, InfoOptions>,
+    /// Columns of VEP consequences keyed by INFO key, `None` if not listed in the description.
+    vep_formats: RefCell<HashMap<String, Option<Arc<Vec<String>>>>>,
+    blank_nodes: Cell<u64>,
+    void: Void,
+    // entries written until the header of the current output, which declares prefixes used in them
+    body: BufWriter<SpooledTempFile>,
+    // prefixes used in the current output, and those declared in the header so far
+    used_prefixes: BTreeSet<String>,
+    declared_prefixes: BTreeSet<String>,
+    summary: RefCell<Summary>,
+}
+
+/// Size of entries kept in memory until the header is written, beyond which they are spooled to a file.
+const SPOOL_MAX_SIZE: usize = 16 * 1024 * 1024;
+
+/// Default template of sample IRIs, relative to `@base`.
+pub const DEFAULT_SAMPLE_TEMPLATE: &str = "sample/{id}";
+
+/// Default predicate linking a variant to its consequences parsed by `info_parsers`.
+pub const DEFAULT_CONSEQUENCE_PREDICATE: &str = "gvo:consequence";
+
+/// Default predicate of the allele frequency of samples written with the genotype summary,
+/// which differs from `gvo:alleleFrequency` of INFO/AF.
+pub const DEFAULT_GENOTYPE_FREQUENCY_PREDICATE: &str = "gvo:sampleAlleleFrequency";
+
+/// FORMAT keys written in `gvo:genotype`, all of which are read unless configured.
+pub const FORMAT_KEYS: &[&str] = &["DP", "GQ", "AD"];
+
+/// Returns default predicates for relationships in `##PEDIGREE`.
+pub fn default_pedigree_predicates() -> BTreeMap<String, String> {
+    let mut map = BTreeMap::new();
+    map.insert("Father".to_owned(), "gvo:father".to_owned());
+    map.insert("Mother".to_owned(), "gvo:mother".to_owned());
+    map
+}
+
+/// Returns default predicates of INFO keys written as direct triples, which are commonly queried.
+pub fn default_info_predicates() -> BTreeMap<String, String> {
+    let mut map = BTreeMap::new();
+    map.insert("AC".to_owned(), "gvo:alleleCount".to_owned());
+    map.insert("AF".to_owned(), "gvo:alleleFrequency".to_owned());
+    map.insert("AN".to_owned(), "gvo:alleleNumber".to_owned());
+    map
+}
+
+/// Escape `value` for the content of a Turtle string literal (`STRING_LITERAL_QUOTE`).
+pub fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            _ => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Mode to compare computed variant type against dbSNP `VC` tag.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum CrosscheckVC {
+    Disabled,
+    /// Count agreements and disagreements.
+    Count,
+    /// Count and use the class of `VC` for rdf:type if they disagree.
+    Trust,
+}
+
+/// Strategy to generate subjects of entries, see `SubjectFormatter`.
+#[derive(EnumString, EnumVariantNames, AsRefStr, Debug)]
+#[strum(serialize_all = "snake_case")]
+pub enum Subject {
+    ID,
+    Location,
+    Reference,
+    NormalizedLocation,
+    NormalizedReference,
+    Info,
+    VRS,
+    Dbsnp,
+}
+
+/// How INFO values are written as RDF-star annotations.
+#[derive(EnumString, EnumVariantNames, Debug, Copy, Clone, PartialEq)]
+#[strum(serialize_all = "snake_case")]
+pub enum RdfStarMode {
+    /// Annotate the `gvo:alt` triple with values of keys listed in `rdf_star` of configuration,
+    /// e.g. `<< s gvo:alt "A" >> gvo:AF 0.5 .`, where `gvo:AF` is defined with the description of the key.
+    Allele,
+    /// Annotate direct triples of `info_predicates` with the source INFO key and the allele index,
+    /// e.g. `<< s gvo:alleleFrequency 0.5 >> gvo:infoKey "AF" .`
+    Statement,
+}
+
+/// Separator written between entries.
+#[derive(EnumString, EnumVariantNames, Debug, Copy, Clone, PartialEq)]
+#[strum(serialize_all = "snake_case")]
+pub enum EntrySeparator {
+    BlankLine,
+    None,
+}
+
+/// Bases accepted in alleles, which are uppercased unless `Strict`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum AlleleAlphabet {
+    /// `ACGTN` in any case.
+    Default,
+    /// IUPAC codes in uppercase only.
+    Strict,
+    /// IUPAC codes in any case.
+    Iupac,
+}
+
+impl AlleleAlphabet {
+    /// Returns true if `bases` of `entry` are accepted, where `Strict` rejects entries written in lowercase.
+    fn accepts(&self, entry: &Entry, bases: &str) -> bool {
+        match self {
+            AlleleAlphabet::Default => REGEX_ALLELES_ACGTN.is_match(bases),
+            AlleleAlphabet::Strict => {
+                !entry.is_case_normalized() && REGEX_ALLELES_IUPAC.is_match(bases)
+            }
+            AlleleAlphabet::Iupac => REGEX_ALLELES_IUPAC.is_match(bases),
+        }
+    }
+
+    /// Returns the accepted characters for warnings of rejected alleles.
+    fn description(&self) -> &'static str {
+        match self {
+            AlleleAlphabet::Default => "ACGTN",
+            AlleleAlphabet::Strict => "uppercase IUPAC codes",
+            AlleleAlphabet::Iupac => "IUPAC codes",
+        }
+    }
+}
+
+/// Nodes used for entries without subjects.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EntryNodes {
+    /// Anonymous blank nodes `[]`.
+    Anonymous,
+    /// Blank nodes labeled by record and allele index, e.g. `_:r42_0`.
+    Labeled,
+    /// Skolem IRIs under the base IRI, e.g. `<http://example.org/.well-known/genid/r42_0>`.
+    Skolem(String),
+}
+
+/// Options of how entries are selected and written, of which each has a setter of the same name.
+#[derive(Debug, Clone)]
+pub struct WriterOptions {
+    pub subject_suffix_index: bool,
+    pub crosscheck_vc: CrosscheckVC,
+    pub record_comments: bool,
+    pub entry_separator: EntrySeparator,
+    pub entry_nodes: EntryNodes,
+    pub vcf_roundtrip: bool,
+    pub plain_literals: bool,
+    pub info_lists: bool,
+    /// Set by `info_key_iris`.
+    pub info_key_predicate: Option<String>,
+    pub keep_raw_info: bool,
+    pub info_string: bool,
+    pub genotypes: bool,
+    pub ref_genotypes: bool,
+    pub genotype_summary: bool,
+    pub rdf_star_mode: Option<RdfStarMode>,
+    pub require_pass: bool,
+    /// Set by `exclude_filters`.
+    pub excluded_filters: Vec<String>,
+    pub skip_symbolic: bool,
+    pub emit_star_alleles: bool,
+    pub allele_alphabet: AlleleAlphabet,
+    pub strict: bool,
+    pub strict_duplicates: bool,
+}
+
+impl Default for WriterOptions {
+    fn default() -> Self {
+        WriterOptions {
+            subject_suffix_index: false,
+            crosscheck_vc: CrosscheckVC::Disabled,
+            record_comments: false,
+            entry_separator: EntrySeparator::BlankLine,
+            entry_nodes: EntryNodes::Anonymous,
+            vcf_roundtrip: false,
+            plain_literals: false,
+            info_lists: false,
+            info_key_predicate: None,
+            keep_raw_info: false,
+            info_string: false,
+            genotypes: false,
+            ref_genotypes: false,
+            genotype_summary: false,
+            rdf_star_mode: None,
+            require_pass: false,
+            excluded_filters: Vec::new(),
+            skip_symbolic: false,
+            emit_star_alleles: false,
+            allele_alphabet: AlleleAlphabet::Default,
+            strict: false,
+            strict_duplicates: false,
+        }
+    }
+}
+
+/// Entries of a record serialized by `TurtleWriter::render_record`.
+#[derive(Debug, Default)]
+pub struct RenderedRecord {
+    entries: Vec<RenderedEntry>,
+}
+
+#[derive(Debug)]
+struct RenderedEntry {
+    comment: String,
+    body: String,
+    triples: u64,
+    prefixes: BTreeSet<String>,
+    separator: &'static str,
+    chromosome: Option<String>,
+    mate: Option<(String, String, String)>,
+}
+
+impl RenderedEntry {
+    fn len(&self) -> usize {
+        self.comment.len() + self.body.len() + self.separator.len()
+    }
+}
+
+impl RenderedRecord {
+    /// Returns the number of bytes to be written, excluding prefix declarations.
+    pub fn len(&self) -> usize {
+        self.entries.iter().map(|x| x.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
 }
 
 #[derive(Debug)]
 enum HeaderState {
     DidNotWrite,
+    /// Entries are spooled until the header is written.
+    Pending,
     DidWrite,
 }
 
@@ -167,11 +588,66 @@ impl<'a, W: Write> TurtleWriter<'a, W> {
             info_key: None,
             subject_id: None,
             subject_formatter: Default::default(),
+            options: Default::default(),
+            record_entries: 0,
+            quality_flags: Vec::new(),
+            records_written: 0,
+            records_read: 0,
+            bytes_written: 0,
+            defined_info_keys: RefCell::new(BTreeMap::new()),
+            info_definitions: Vec::new(),
+            exclude: None,
+            subjects: RefCell::new(None),
+            breakends: RefCell::new(HashMap::new()),
+            pending_mate: RefCell::new(None),
+            banner: Vec::new(),
+            provenance: None,
+            samples: Vec::new(),
+            samples_written: 0,
+            sample_template: DEFAULT_SAMPLE_TEMPLATE.to_owned(),
+            sample_iris: BTreeMap::new(),
+            sample_secret: None,
+            header_sample_iris: RefCell::new((Vec::new(), Vec::new())),
+            format_keys: FORMAT_KEYS.iter().map(|x| x.to_string()).collect(),
+            pedigree_predicates: default_pedigree_predicates(),
+            rdf_star: Vec::new(),
+            zygosity: BTreeMap::new(),
+            info_parsers: BTreeMap::new(),
+            consequence_predicate: DEFAULT_CONSEQUENCE_PREDICATE.to_owned(),
+            genotype_frequency_predicate: DEFAULT_GENOTYPE_FREQUENCY_PREDICATE.to_owned(),
+            info_predicates: BTreeMap::new(),
+            info_options: BTreeMap::new(),
+            vep_formats: RefCell::new(HashMap::new()),
+            blank_nodes: Cell::new(0),
+            void: Void::new(),
+            body: BufWriter::new(SpooledTempFile::new(SPOOL_MAX_SIZE)),
+            used_prefixes: BTreeSet::new(),
+            declared_prefixes: BTreeSet::new(),
+            summary: RefCell::new(Summary::new()),
         }
     }
 
+    /// Set namespace and quality flags from `config`.
+    pub fn configure(&mut self, config: &'a CompiledConfig) -> &TurtleWriter<'a, W> {
+        self.namespace = Some(&config.namespace);
+        self.header_sample_iris.get_mut().0.clear();
+        self.quality_flags = config.quality_flags.clone();
+        self.sample_template = config.sample_template.clone();
+        self.sample_iris = config.sample_iris.clone();
+        self.format_keys = config.format.clone();
+        self.pedigree_predicates = config.pedigree_predicates.clone();
+        self.zygosity = config.zygosity.clone();
+        self.info_parsers = config.info_parsers.clone();
+        self.consequence_predicate = config.consequence_predicate.clone();
+        self.genotype_frequency_predicate = config.genotype_frequency_predicate.clone();
+        self.info_predicates = config.info_predicates.clone();
+        self.info_options = config.info_options.clone();
+        self
+    }
+
     pub fn namespace(&mut self, namespace: &'a Namespace) -> &TurtleWriter<'a, W> {
         self.namespace = Some(namespace);
+        self.header_sample_iris.get_mut().0.clear();
         self
     }
 
@@ -190,77 +666,3142 @@ impl<'a, W: Write> TurtleWriter<'a, W> {
         self
     }
 
-    fn write_headers(&mut self) -> Result<()> {
-        let mut buf = String::with_capacity(4096);
+    /// Replace all options at once, e.g. those given on the command line.
+    pub fn options(&mut self, options: WriterOptions) -> &TurtleWriter<'a, W> {
+        self.options = options;
+        self
+    }
 
-        let max_len = self
-            .namespace
-            .unwrap()
-            .prefixes
-            .keys()
-            .max_by_key(|x| x.len())
-            .unwrap()
-            .len();
+    pub fn get_options(&self) -> &WriterOptions {
+        &self.options
+    }
+
+    /// Append the allele index to subjects of multi-allelic records, e.g. `rs6040355#2`.
+    ///
+    /// Records with one entry left after skipping alleles keep the plain subject.
+    /// `-alt<N>` is appended instead if the subject already has a fragment.
+    pub fn subject_suffix_index(&mut self, flag: bool) -> &TurtleWriter<'a, W> {
+        self.options.subject_suffix_index = flag;
+        self
+    }
+
+    pub fn quality_flags(&mut self, flags: Vec<QualityFlag>) -> &TurtleWriter<'a, W> {
+        self.quality_flags = flags;
+        self
+    }
+
+    pub fn get_quality_flags(&self) -> &Vec<QualityFlag> {
+        &self.quality_flags
+    }
+
+    pub fn crosscheck_vc(&mut self, mode: CrosscheckVC) -> &TurtleWriter<'a, W> {
+        self.options.crosscheck_vc = mode;
+        self
+    }
+
+    pub fn get_crosscheck_vc(&self) -> CrosscheckVC {
+        self.options.crosscheck_vc
+    }
+
+    /// Write a comment line before each entry.
+    ///
+    /// Comments are allowed in Turtle only, which is the format of this writer.
+    pub fn record_comments(&mut self, flag: bool) -> &TurtleWriter<'a, W> {
+        self.options.record_comments = flag;
+        self
+    }
+
+    /// Write `gvo:vcfLine` with columns of the original VCF line for each entry.
+    pub fn vcf_roundtrip(&mut self, flag: bool) -> &TurtleWriter<'a, W> {
+        self.options.vcf_roundtrip = flag;
+        self
+    }
 
-        if let Some(ref base) = self.namespace.unwrap().base {
-            buf += &format!("@base {:>width$}<{}> .\n", "", base, width = max_len + 4);
+    pub fn get_vcf_roundtrip(&self) -> bool {
+        self.options.vcf_roundtrip
+    }
+
+    /// Write INFO numbers and flags as bare tokens instead of typed literals like `"0.5"^^xsd:float`.
+    pub fn plain_literals(&mut self, flag: bool) -> &TurtleWriter<'a, W> {
+        self.options.plain_literals = flag;
+        self
+    }
+
+    pub fn get_plain_literals(&self) -> bool {
+        self.options.plain_literals
+    }
+
+    /// Write values of INFO keys with Number=G or Number=. as Turtle collections to keep the order.
+    ///
+    /// A key without values is written as an empty list `()`.
+    pub fn info_lists(&mut self, flag: bool) -> &TurtleWriter<'a, W> {
+        self.options.info_lists = flag;
+        self
+    }
+
+    pub fn get_info_lists(&self) -> bool {
+        self.options.info_lists
+    }
+
+    /// Link each `gvo:info` node to the term of the INFO key with `predicate`, e.g. `gvo:infoField gvo:RS`.
+    ///
+    /// The term is defined with the description in the header once before the first use.
+    pub fn info_key_iris(&mut self, predicate: Option<String>) -> &TurtleWriter<'a, W> {
+        self.options.info_key_predicate = predicate;
+        self
+    }
+
+    pub fn get_info_key_predicate(&self) -> Option<&str> {
+        self.options.info_key_predicate.as_deref()
+    }
+
+    /// Define terms of INFO keys with descriptions before the first entry, e.g. if `info_key_iris` is set.
+    ///
+    /// Keys not listed here are defined before the first use.
+    pub fn info_definitions(
+        &mut self,
+        definitions: Vec<(String, Option<String>)>,
+    ) -> &TurtleWriter<'a, W> {
+        // listed keys are not defined again on the first use
+        for (key, description) in definitions {
+            if !self.is_info_key_defined(&key) {
+                self.define_info_key(&key, description.as_deref());
+                self.info_definitions.push((key, description));
+            }
         }
+        self
+    }
+
+    pub fn is_info_key_defined(&self, key: &str) -> bool {
+        self.defined_info_keys.borrow().contains_key(key)
+    }
+
+    /// Mark the term of INFO `key` as defined, which is defined again at the top of each following output.
+    pub fn define_info_key(&self, key: &str, description: Option<&str>) {
+        self.defined_info_keys
+            .borrow_mut()
+            .insert(key.to_owned(), description.map(|x| x.to_owned()));
+    }
+
+    pub fn entry_separator(&mut self, separator: EntrySeparator) -> &TurtleWriter<'a, W> {
+        self.options.entry_separator = separator;
+        self
+    }
+
+    /// Set comment lines written at the top of the output (Turtle only).
+    pub fn banner(&mut self, lines: Vec<String>) -> &TurtleWriter<'a, W> {
+        self.banner = lines;
+        self
+    }
+
+    /// Set metadata written as a dataset resource before the first entry.
+    pub fn provenance(&mut self, provenance: Provenance) -> &TurtleWriter<'a, W> {
+        self.provenance = Some(provenance);
+        self
+    }
 
-        for (k, v) in &self.namespace.unwrap().prefixes {
-            buf += &format!("@prefix {:>width$}: <{}> .\n", k, v, width = max_len);
+    /// Add samples described before the next entry, and again at the top of each following output.
+    pub fn samples(&mut self, samples: Vec<Sample>) -> &TurtleWriter<'a, W> {
+        for sample in &samples {
+            for key in sample.pedigree.keys() {
+                if !self.pedigree_predicates.contains_key(key) {
+                    self.count_warning(&format!("PEDIGREE keys without predicate: {}", key));
+                }
+            }
         }
+        self.samples.extend(samples);
+        self
+    }
 
-        buf += "\n";
+    /// Set IRIs of samples keyed by sample name, which take precedence over the sample template.
+    pub fn sample_iris(&mut self, iris: BTreeMap<String, String>) -> &TurtleWriter<'a, W> {
+        self.sample_iris = iris;
+        self.header_sample_iris.get_mut().0.clear();
+        self
+    }
 
-        Ok(self.wtr.write_all(buf.as_bytes())?)
+    /// Replace sample names in IRIs and literals with pseudonyms keyed by `secret`.
+    pub fn pseudonymize_samples(&mut self, secret: Option<String>) -> &TurtleWriter<'a, W> {
+        self.sample_secret = secret;
+        self.header_sample_iris.get_mut().0.clear();
+        self
     }
 
-    fn write_entry(&mut self, entry: &Entry) -> Result<()> {
-        if let HeaderState::DidNotWrite = self.state.header {
-            self.write_headers()?;
-            self.state.header = HeaderState::DidWrite;
+    /// Returns the name of the sample `id` written in the output, which is a pseudonym if enabled.
+    pub fn sample_name(&self, id: &str) -> String {
+        match self.sample_secret.as_ref() {
+            Some(secret) => sample::pseudonym(secret, id),
+            None => id.to_owned(),
         }
+    }
+
+    /// Returns the IRI of the sample `id` configured in `sample_iris` or formatted by the sample template.
+    ///
+    /// A relative IRI is resolved against `@base`.
+    pub fn sample_iri(&self, id: &str) -> String {
+        let iri = match self.sample_iris.get(id) {
+            Some(iri) => iri.to_owned(),
+            None => self
+                .sample_template
+                .replace("{id}", &iri::percent_encode(&self.sample_name(id))),
+        };
 
-        if let Some(r) = entry.as_ttl_string(&self)? {
-            self.wtr.write_all(r.as_bytes())?;
+        match self.namespace.and_then(|ns| ns.base.as_ref()) {
+            Some(base) if !iri::has_scheme(&iri) => iri::join(base, &iri),
+            _ => iri,
         }
+    }
 
-        Ok(())
+    /// Returns IRIs of `samples` in the same order, which are computed once for the samples of a header.
+    pub fn header_sample_iris(&self, samples: &[String]) -> Ref<'_, [String]> {
+        if self.header_sample_iris.borrow().0 != samples {
+            let iris = samples.iter().map(|x| self.sample_iri(x)).collect();
+            *self.header_sample_iris.borrow_mut() = (samples.to_vec(), iris);
+        }
+
+        Ref::map(self.header_sample_iris.borrow(), |(_, iris)| {
+            iris.as_slice()
+        })
     }
-}
 
-static REGEX_ALLELES: Lazy<Regex> = Lazy::new(|| Regex::new(r"\A[ACGTURYKMSWBDHVN]+\z").unwrap());
+    /// Push descriptions of samples not described in the current output yet.
+    fn push_samples(&self, buf: &mut Buffer) {
+        for sample in &self.samples[self.samples_written..] {
+            buf.push_iri(&self.sample_iri(&sample.id));
+            buf.push_class(" ", "gvo:Sample");
+            buf.push_predicate(" ;\n  ", "rdfs:label");
+            buf.push_quoted(&self.sample_name(&sample.id), '"');
 
-impl<'a, W: Write> Writer for TurtleWriter<'a, W> {
-    fn write_record<'b>(&mut self, record: &Record<'b>) -> Result<()> {
-        for e in record.each_alternate_alleles() {
-            if e.reference_bases().len() == 0 {
-                warn!("Reference bases must not be empty. {}", e);
-                continue;
+            if let Some(description) = sample.description.as_ref() {
+                buf.push_predicate(" ;\n  ", "dct:description");
+                buf.push_quoted(description, '"');
             }
 
-            if e.alternate_bases().len() == 0 {
-                warn!("Alternate bases must not be empty. {}", e);
-                continue;
+            // keys without predicates are counted when samples are added
+            for (key, value) in &sample.pedigree {
+                if let Some(predicate) = self.pedigree_predicates.get(key) {
+                    buf.push_predicate(" ;\n  ", predicate);
+                    buf.push_iri(&self.sample_iri(value));
+                }
             }
 
-            if !REGEX_ALLELES.is_match(e.reference_bases()) {
-                warn!("Reference bases contains non-ACGT characters. {}", e);
-                continue;
-            }
+            buf.push_str(" .\n\n");
+        }
+    }
 
-            if !REGEX_ALLELES.is_match(e.alternate_bases()) {
-                warn!("Alternate bases contains non-ACGT characters. {}", e);
-                continue;
-            }
+    /// Write `gvo:genotype` of samples carrying the alternate allele of each entry.
+    pub fn genotypes(&mut self, flag: bool) -> &TurtleWriter<'a, W> {
+        self.options.genotypes = flag;
+        self
+    }
+
+    pub fn get_genotypes(&self) -> bool {
+        self.options.genotypes
+    }
+
+    /// Write homozygous reference and missing genotypes as well.
+    pub fn ref_genotypes(&mut self, flag: bool) -> &TurtleWriter<'a, W> {
+        self.options.ref_genotypes = flag;
+        self
+    }
+
+    pub fn get_ref_genotypes(&self) -> bool {
+        self.options.ref_genotypes
+    }
+
+    /// Write counts of genotypes and the allele frequency of samples for each entry.
+    pub fn genotype_summary(&mut self, flag: bool) -> &TurtleWriter<'a, W> {
+        self.options.genotype_summary = flag;
+        self
+    }
+
+    pub fn get_genotype_summary(&self) -> bool {
+        self.options.genotype_summary
+    }
+
+    /// Set the predicate of the allele frequency of samples in the genotype summary, e.g. `gvo:sampleAlleleFrequency`.
+    pub fn genotype_frequency_predicate(&mut self, predicate: String) -> &TurtleWriter<'a, W> {
+        self.genotype_frequency_predicate = predicate;
+        self
+    }
+
+    pub fn get_genotype_frequency_predicate(&self) -> &str {
+        &self.genotype_frequency_predicate
+    }
+
+    /// Set FORMAT keys read for `gvo:genotype`, e.g. `DP`.
+    pub fn format_keys(&mut self, keys: Vec<String>) -> &TurtleWriter<'a, W> {
+        self.format_keys = keys;
+        self
+    }
+
+    pub fn get_format_keys(&self) -> &[String] {
+        &self.format_keys
+    }
+
+    /// Set values of `gvo:zygosity` keyed by classification, e.g. `heterozygous` => `ex:Heterozygous`.
+    pub fn zygosity(&mut self, terms: BTreeMap<String, String>) -> &TurtleWriter<'a, W> {
+        self.zygosity = terms;
+        self
+    }
 
-            self.write_entry(&e)?;
+    /// Returns the object of `gvo:zygosity` for `zygosity`.
+    ///
+    /// A configured prefixed name or IRI is returned as a term, and others as a string literal.
+    pub fn zygosity_object(&self, zygosity: Zygosity) -> String {
+        let mut buf = Buffer::new();
+        self.push_zygosity_object(&mut buf, zygosity);
+        buf.string
+    }
+
+    pub(crate) fn push_zygosity_object(&self, buf: &mut Buffer, zygosity: Zygosity) {
+        let value = self
+            .zygosity
+            .get(zygosity.as_ref())
+            .map_or(zygosity.as_ref(), |x| x.as_str());
+
+        match self.namespace {
+            Some(ns) if ns.is_resolvable(value) => buf.push_term(value),
+            _ => buf.push_quoted(value, '"'),
         }
+    }
 
-        Ok(())
+    /// Set parsers of INFO values keyed by INFO key, e.g. `CSQ` => `InfoParser::Vep`.
+    pub fn info_parsers(&mut self, parsers: BTreeMap<String, InfoParser>) -> &TurtleWriter<'a, W> {
+        self.info_parsers = parsers;
+        self
     }
 
-    fn format_subject(&self, entry: &Entry) -> Option<String> {
-        self.subject_formatter.format(entry)
+    pub fn get_info_parser(&self, key: &str) -> Option<InfoParser> {
+        self.info_parsers.get(key).copied()
+    }
+
+    /// Set the predicate linking a variant to its consequences, e.g. `gvo:consequence`.
+    pub fn consequence_predicate(&mut self, predicate: String) -> &TurtleWriter<'a, W> {
+        self.consequence_predicate = predicate;
+        self
+    }
+
+    pub fn get_consequence_predicate(&self) -> &str {
+        &self.consequence_predicate
+    }
+
+    /// Set predicates of INFO keys written as direct triples instead of `gvo:info`.
+    ///
+    /// Prefixes used in the predicates are declared before the first use as other prefixes.
+    pub fn info_predicates(
+        &mut self,
+        predicates: BTreeMap<String, String>,
+    ) -> &TurtleWriter<'a, W> {
+        self.info_predicates = predicates;
+        self
+    }
+
+    pub fn get_info_predicate(&self, key: &str) -> Option<&str> {
+        self.info_predicates.get(key).map(|x| x.as_str())
+    }
+
+    /// Set options of INFO keys, of which `as_iri_template` is applied by the writer.
+    pub fn info_options(&mut self, options: BTreeMap<String, InfoOptions>) -> &TurtleWriter<'a, W> {
+        self.info_options = options;
+        self
+    }
+
+    pub fn get_info_options(&self, key: &str) -> Option<&InfoOptions> {
+        self.info_options.get(key)
+    }
+
+    /// Write values of INFO keys in `info_predicates` in `gvo:info` as well.
+    pub fn keep_raw_info(&mut self, flag: bool) -> &TurtleWriter<'a, W> {
+        self.options.keep_raw_info = flag;
+        self
+    }
+
+    pub fn get_keep_raw_info(&self) -> bool {
+        self.options.keep_raw_info
+    }
+
+    /// Write the INFO column as is in `gvo:infoString`.
+    pub fn info_string(&mut self, flag: bool) -> &TurtleWriter<'a, W> {
+        self.options.info_string = flag;
+        self
+    }
+
+    pub fn get_info_string(&self) -> bool {
+        self.options.info_string
+    }
+
+    /// Returns columns of VEP consequences in INFO `key`, loading them by `load` on the first call.
+    ///
+    /// Values of `key` are written as usual if the columns are not available.
+    pub fn vep_format<F>(&self, key: &str, load: F) -> Option<Arc<Vec<String>>>
+    where
+        F: FnOnce() -> Option<Vec<String>>,
+    {
+        self.vep_formats
+            .borrow_mut()
+            .entry(key.to_owned())
+            .or_insert_with(|| {
+                let format = load();
+                if format.is_none() {
+                    warn!(
+                        "Description of INFO/{} does not list columns after `Format:`",
+                        key
+                    );
+                }
+                format.map(Arc::new)
+            })
+            .clone()
+    }
+
+    /// Set INFO keys written as RDF-star annotations on `gvo:alt` instead of `gvo:info` in `RdfStarMode::Allele`.
+    pub fn rdf_star(&mut self, keys: Vec<String>) -> &TurtleWriter<'a, W> {
+        self.rdf_star = keys;
+        self
+    }
+
+    pub fn get_rdf_star(&self) -> &[String] {
+        &self.rdf_star
+    }
+
+    /// Set how INFO values are written as RDF-star annotations, or `None` not to write annotations.
+    pub fn rdf_star_mode(&mut self, mode: Option<RdfStarMode>) -> &TurtleWriter<'a, W> {
+        self.options.rdf_star_mode = mode;
+        self
+    }
+
+    pub fn get_rdf_star_mode(&self) -> Option<RdfStarMode> {
+        self.options.rdf_star_mode
+    }
+
+    /// Skip entries matching `list`.
+    pub fn exclude(&mut self, list: ExcludeList) -> &TurtleWriter<'a, W> {
+        self.exclude = Some(list);
+        self
+    }
+
+    /// Skip records with filters other than `PASS`. Records without filters are not skipped.
+    pub fn require_pass(&mut self, flag: bool) -> &TurtleWriter<'a, W> {
+        self.options.require_pass = flag;
+        self
+    }
+
+    /// Skip records with any of `filters`, e.g. `LowQual`.
+    pub fn exclude_filters(&mut self, filters: Vec<String>) -> &TurtleWriter<'a, W> {
+        self.options.excluded_filters = filters;
+        self
+    }
+
+    /// Skip entries with symbolic alternate alleles, e.g. `<DEL>`, and breakends.
+    pub fn skip_symbolic(&mut self, flag: bool) -> &TurtleWriter<'a, W> {
+        self.options.skip_symbolic = flag;
+        self
+    }
+
+    /// Write `*` alleles overlapped by upstream deletions as `gvo:OverlapDeletion` instead of skipping them.
+    pub fn emit_star_alleles(&mut self, flag: bool) -> &TurtleWriter<'a, W> {
+        self.options.emit_star_alleles = flag;
+        self
+    }
+
+    /// Set bases accepted in alleles. Entries with other bases are skipped with a warning.
+    pub fn allele_alphabet(&mut self, alphabet: AlleleAlphabet) -> &TurtleWriter<'a, W> {
+        self.options.allele_alphabet = alphabet;
+        self
+    }
+
+    /// Returns filters of `record` for which it is skipped by `require_pass` or `exclude_filters`.
+    fn rejected_filters<'r>(&self, record: &'r Record) -> Vec<&'r str> {
+        record
+            .filters()
+            .into_iter()
+            .filter(|&x| {
+                (self.options.require_pass && x != "PASS")
+                    || self.options.excluded_filters.iter().any(|y| y == x)
+            })
+            .collect()
+    }
+
+    /// Abort on duplicate subjects instead of warning, which are tracked exactly as `strict_duplicates`.
+    pub fn strict(&mut self, flag: bool) -> &TurtleWriter<'a, W> {
+        self.options.strict = flag;
+        self
+    }
+
+    pub fn get_strict(&self) -> bool {
+        self.options.strict
+    }
+
+    /// Link the breakend `id` of the entry being rendered with its mate `mate_id` when the entry is written.
+    pub fn defer_mate(&self, id: String, mate_id: String, subject: String) {
+        self.pending_mate.replace(Some((id, mate_id, subject)));
+    }
+
+    /// Remember `subject` of the breakend `id` until its mate `mate_id` is written.
+    ///
+    /// Returns the subject of the mate if it has been written before.
+    fn pair_breakend(&self, id: &str, mate_id: &str, subject: &str) -> Option<String> {
+        let mut breakends = self.breakends.borrow_mut();
+        match breakends.remove(mate_id) {
+            Some(mate) => Some(mate),
+            None => {
+                breakends.insert(id.to_owned(), subject.to_owned());
+                None
+            }
+        }
+    }
+
+    /// Track subjects exactly, reporting both locations of duplicates, instead of by a bloom filter.
+    ///
+    /// Memory grows with the number of subjects, by far faster than that of the bloom filter.
+    pub fn strict_duplicates(&mut self, flag: bool) -> &TurtleWriter<'a, W> {
+        self.options.strict_duplicates = flag;
+        self
+    }
+
+    /// Remember `subject` of `entry` and report if another record already used it.
+    ///
+    /// Alleles of the same record may share a subject.
+    pub fn track_subject(&self, subject: &str, entry: &Entry) -> Result<()> {
+        let location = format!(
+            "{}:{}",
+            entry.chromosome().and_then(|x| x.ok()).unwrap_or("?"),
+            entry.position()
+        );
+
+        let mut subjects = self.subjects.borrow_mut();
+        let subjects = subjects.get_or_insert_with(|| {
+            if self.options.strict || self.options.strict_duplicates {
+                SubjectSet::Exact(HashMap::new())
+            } else {
+                SubjectSet::Bloom {
+                    filter: GrowingBloomFilter::default(),
+                    record: self.records_read,
+                    current: Vec::new(),
+                }
+            }
+        });
+        match subjects {
+            SubjectSet::Bloom {
+                filter,
+                record,
+                current,
+            } => {
+                if *record != self.records_read {
+                    *record = self.records_read;
+                    current.clear();
+                }
+                let duplicate = match current.iter().find(|(x, _)| x == subject) {
+                    Some(&(_, duplicate)) => duplicate,
+                    None => {
+                        let duplicate = filter.insert(subject);
+                        current.push((subject.to_owned(), duplicate));
+                        duplicate
+                    }
+                };
+
+                // the filter does not keep where subjects occurred
+                if duplicate {
+                    warn!(
+                        "Possible duplicate subject {} at {} (first location unknown), pass --strict-duplicates to confirm",
+                        subject, location
+                    );
+                    self.count_warning("Possible duplicate subjects");
+                }
+            }
+            SubjectSet::Exact(subjects) => match subjects.get(subject) {
+                Some((index, _)) if *index == self.records_read => {}
+                Some((_, first)) => {
+                    if self.options.strict {
+                        Err(Error::DuplicateSubjectError(
+                            subject.to_owned(),
+                            first.to_owned(),
+                            location.clone(),
+                        ))?;
+                    }
+                    warn!(
+                        "Duplicate subject {} at {} and {}",
+                        subject, first, location
+                    );
+                    self.count_warning("Duplicate subjects");
+                }
+                None => {
+                    subjects.insert(subject.to_owned(), (self.records_read, location));
+                }
+            },
+        }
+
+        Ok(())
+    }
+
+    /// Set nodes used for entries without subjects.
+    pub fn entry_nodes(&mut self, nodes: EntryNodes) -> &TurtleWriter<'a, W> {
+        self.options.entry_nodes = nodes;
+        self
+    }
+
+    /// Returns a node for the allele `index` of the current record with `suffix`,
+    /// or `None` for an anonymous blank node.
+    ///
+    /// The same entry always gets the same node within a run, given the index of the first record of the writer.
+    pub fn entry_node(&self, index: usize, suffix: &str) -> Option<String> {
+        let label = format!("r{}_{}{}", self.records_read, index, suffix);
+
+        match &self.options.entry_nodes {
+            EntryNodes::Anonymous => None,
+            EntryNodes::Labeled => Some(format!("_:{}", label)),
+            EntryNodes::Skolem(base) => Some(format!("<{}>", iri::join(base, &label))),
+        }
+    }
+
+    /// Returns a new blank node label.
+    pub fn next_blank_node(&self) -> String {
+        let n = self.blank_nodes.get() + 1;
+        self.blank_nodes.set(n);
+        format!("_:b{}", n)
+    }
+
+    /// Increment the counter named `key` in the summary.
+    pub fn count(&self, key: &str) {
+        self.summary.borrow_mut().increment(key);
+    }
+
+    /// Increment the counter named `key` in the summary as a warning.
+    pub fn count_warning(&self, key: &str) {
+        self.summary.borrow_mut().warn(key);
+    }
+
+    pub fn summary(&self) -> Ref<'_, Summary> {
+        self.summary.borrow()
+    }
+
+    pub fn summary_mut(&self) -> RefMut<'_, Summary> {
+        self.summary.borrow_mut()
+    }
+
+    /// Spool provenance before the first entry, and samples and terms of INFO keys defined since the last entry.
+    fn write_preamble(&mut self) -> Result<()> {
+        if self.namespace.is_none() {
+            return Ok(());
+        }
+
+        let mut buf = Buffer::new();
+
+        if let HeaderState::DidNotWrite = self.state.header {
+            if let Some(provenance) = self.provenance.as_ref() {
+                provenance.push_turtle(&mut buf);
+                buf.push_str("\n");
+            }
+            self.state.header = HeaderState::Pending;
+        }
+
+        // samples and INFO keys set for later inputs are described before their next entry
+        if self.samples_written < self.samples.len() {
+            self.push_samples(&mut buf);
+            self.samples_written = self.samples.len();
+        }
+
+        for (key, description) in std::mem::take(&mut self.info_definitions) {
+            push_info_key_definition(&mut buf, &key, description.as_deref());
+        }
+
+        self.used_prefixes.extend(buf.prefixes);
+        self.body.write_all(buf.string.as_bytes())?;
+
+        Ok(())
+    }
+
+    /// Write the header followed by the spooled body.
+    fn write_body(&mut self) -> Result<()> {
+        self.body.flush()?;
+
+        if let Some(ns) = self.namespace {
+            self.write_header(ns)?;
+        }
+
+        let body = self.body.get_mut();
+        body.seek(SeekFrom::Start(0))?;
+        io::copy(body, &mut self.wtr)?;
+        body.seek(SeekFrom::Start(0))?;
+        body.set_len(0)?;
+
+        Ok(())
+    }
+
+    /// Write the banner, `@base` and `@prefix` for prefixes used in the spooled body, so that unused prefixes
+    /// never appear.
+    ///
+    /// Prefixes used for the first time after the header was written are declared before the rest of the body.
+    fn write_header(&mut self, ns: &Namespace) -> Result<()> {
+        let mut buf = String::new();
+
+        let max_len = ns.prefixes.keys().map(|x| x.len()).max().unwrap_or(0);
+
+        match self.state.header {
+            HeaderState::DidNotWrite => return Ok(()),
+            HeaderState::Pending => {
+                for line in &self.banner {
+                    buf += &format!("# {}\n", line.replace(|c: char| c.is_control(), " "));
+                }
+                if !self.banner.is_empty() {
+                    buf += "\n";
+                }
+
+                if let Some(ref base) = ns.base {
+                    buf += &format!("@base {:>width$}<{}> .\n", "", base, width = max_len + 4);
+                }
+                self.state.header = HeaderState::DidWrite;
+            }
+            HeaderState::DidWrite => {}
+        }
+
+        for (k, v) in &ns.prefixes {
+            if self.used_prefixes.contains(k) && !self.declared_prefixes.contains(k) {
+                buf += &format!("@prefix {:>width$}: <{}> .\n", k, v, width = max_len);
+                self.declared_prefixes.insert(k.to_owned());
+            }
+        }
+
+        if !buf.is_empty() {
+            buf += "\n";
+            self.wtr.write_all(buf.as_bytes())?;
+        }
+
+        Ok(())
+    }
+
+    /// Replace the underlying writer.
+    ///
+    /// Prefixes, samples and terms of INFO keys defined so far are written again before the next entry,
+    /// so that each output is self-contained.
+    /// Breakends of blank nodes are no longer linked with their mates, since labels are scoped to a file.
+    pub fn set_output(&mut self, wtr: W) -> Result<()> {
+        self.flush()?;
+        self.wtr = BufWriter::new(wtr);
+        self.state.header = HeaderState::DidNotWrite;
+        self.declared_prefixes.clear();
+        self.used_prefixes.clear();
+        self.samples_written = 0;
+        self.info_definitions = self
+            .defined_info_keys
+            .borrow()
+            .iter()
+            .map(|(k, v)| (k.to_owned(), v.to_owned()))
+            .collect();
+        self.breakends
+            .borrow_mut()
+            .retain(|_, subject| !subject.starts_with("_:"));
+
+        Ok(())
+    }
+
+    /// Write the header and entries spooled so far, and flush the underlying writer.
+    pub fn flush(&mut self) -> Result<()> {
+        self.write_body()?;
+        Ok(self.wtr.flush()?)
+    }
+
+    /// Flush and return the underlying writer.
+    pub fn into_inner(mut self) -> Result<W> {
+        self.write_body()?;
+        self.wtr.into_inner().map_err(|e| e.into_error().into())
+    }
+
+    /// Returns statistics of entries written so far.
+    pub fn void(&self) -> &Void {
+        &self.void
+    }
+
+    /// Write a VoID description of entries written so far.
+    pub fn write_void<V: Write>(&self, mut wtr: V) -> Result<()> {
+        let default = Namespace::default();
+        let ns = self.namespace.unwrap_or(&default);
+
+        wtr.write_all(self.void.to_turtle(ns).as_bytes())?;
+
+        Ok(wtr.flush()?)
+    }
+
+    /// Returns the number of records which at least one entry was written for.
+    pub fn records_written(&self) -> u64 {
+        self.records_written
+    }
+
+    /// Returns the number of bytes of entries written so far, excluding prefix declarations and the banner.
+    pub fn bytes_written(&self) -> u64 {
+        self.bytes_written
+    }
+
+    /// Count a record as read without rendering it, so that labels of following records are kept.
+    pub fn skip_record(&mut self) {
+        self.records_read += 1;
+    }
+
+    /// Start counting records from `index`, so that labels of entries stay unique across writers of a run.
+    pub fn first_record(&mut self, index: u64) -> &TurtleWriter<'a, W> {
+        self.records_read = index;
+        self
+    }
+
+    /// Returns the number of records read so far, including those passed to `first_record`.
+    pub fn records_read(&self) -> u64 {
+        self.records_read
+    }
+
+    /// Serialize entries of `record` without writing them.
+    ///
+    /// The result must be passed to `write_rendered` before rendering the next record.
+    pub fn render_record(&mut self, record: &Record) -> Result<RenderedRecord> {
+        let mut rendered = RenderedRecord::default();
+
+        // a record with multiple filters rejected is counted for each of them
+        let rejected = self.rejected_filters(record);
+        if !rejected.is_empty() {
+            self.count("Records skipped by FILTER");
+            for filter in rejected {
+                self.count(&format!("Records skipped by FILTER {}", filter));
+            }
+            self.records_read += 1;
+            return Ok(rendered);
+        }
+
+        let mut case_normalized = false;
+
+        let entries: Vec<Entry> = record
+            .each_alternate_alleles()
+            .filter(|e| self.accepts_entry(e))
+            .collect();
+        self.record_entries = entries.len();
+
+        for e in entries {
+            let ttl = e.as_ttl_string(&self);
+            let mate = self.pending_mate.take();
+            if let Some(buf) = ttl? {
+                case_normalized |= e.is_case_normalized();
+                rendered.entries.push(RenderedEntry {
+                    comment: if self.options.record_comments {
+                        self.format_comment(&e)?
+                    } else {
+                        String::new()
+                    },
+                    body: buf.string,
+                    triples: buf.triples,
+                    prefixes: buf.prefixes,
+                    separator: if let EntrySeparator::BlankLine = self.options.entry_separator {
+                        "\n"
+                    } else {
+                        ""
+                    },
+                    chromosome: e.chromosome().and_then(|x| x.ok()).map(|x| x.to_owned()),
+                    mate,
+                });
+            }
+        }
+
+        if case_normalized {
+            self.count("Records with alleles uppercased");
+        }
+
+        self.records_read += 1;
+
+        Ok(rendered)
+    }
+
+    /// Returns true if alleles of `e` are to be written, otherwise warns or counts the reason.
+    fn accepts_entry(&self, e: &Entry) -> bool {
+        if e.reference_bases().len() == 0 {
+            warn!("Reference bases must not be empty. {}", e);
+            return false;
+        }
+
+        if e.alternate_bases().len() == 0 {
+            warn!("Alternate bases must not be empty. {}", e);
+            return false;
+        }
+
+        if !self.options.allele_alphabet.accepts(e, e.reference_bases()) {
+            warn!(
+                "Reference bases contains characters other than {}. {}",
+                self.options.allele_alphabet.description(),
+                e
+            );
+            return false;
+        }
+
+        let breakend = e.breakend().is_some();
+        if e.alternate_bases() == "*" {
+            if !self.options.emit_star_alleles {
+                self.count("Entries skipped (* allele)");
+                return false;
+            }
+        } else if breakend || sv::symbolic_id(e.alternate_bases()).is_some() {
+            if self.options.skip_symbolic {
+                self.count("Entries skipped by --skip-symbolic");
+                return false;
+            }
+            // e.g. `<NON_REF>` of gVCF, which is not a variant by itself
+            if !breakend && e.structural_variant().is_none() {
+                debug!("Unsupported symbolic allele. {}", e);
+                self.count("Entries skipped (unsupported symbolic allele)");
+                return false;
+            }
+        } else if !self.options.allele_alphabet.accepts(e, e.alternate_bases()) {
+            warn!(
+                "Alternate bases contains characters other than {}. {}",
+                self.options.allele_alphabet.description(),
+                e
+            );
+            return false;
+        }
+
+        if self.exclude.as_ref().map_or(false, |x| x.matches(e)) {
+            self.count("Entries excluded by policy");
+            return false;
+        }
+
+        true
+    }
+
+    /// Write entries serialized by `render_record`.
+    pub fn write_rendered(&mut self, rendered: RenderedRecord) -> Result<()> {
+        if rendered.entries.is_empty() {
+            return Ok(());
+        }
+
+        for entry in rendered.entries {
+            self.write_preamble()?;
+
+            self.body.write_all(entry.comment.as_bytes())?;
+            self.body.write_all(entry.body.as_bytes())?;
+
+            let mut mates = Buffer::new();
+            if let Some((id, mate_id, subject)) = entry.mate.as_ref() {
+                if let Some(mate) = self.pair_breakend(id, mate_id, subject) {
+                    for (a, b) in &[(subject, &mate), (&mate, subject)] {
+                        mates.push_str(a);
+                        mates.push_predicate(" ", "gvo:mate");
+                        mates.push_str(b);
+                        mates.push_str(" .\n");
+                    }
+                }
+            }
+            self.body.write_all(mates.string.as_bytes())?;
+
+            self.body.write_all(entry.separator.as_bytes())?;
+
+            for prefix in entry.prefixes.iter().chain(mates.prefixes.iter()) {
+                self.void.add_vocabulary(prefix);
+                self.used_prefixes.insert(prefix.to_owned());
+            }
+            self.void
+                .add_entry(entry.triples + mates.triples, entry.chromosome.as_deref());
+
+            self.bytes_written += (entry.len() + mates.string.len()) as u64;
+        }
+
+        self.records_written += 1;
+
+        Ok(())
+    }
+
+    fn format_comment(&self, entry: &Entry) -> Result<String> {
+        let chrom = entry.chromosome().transpose()?.unwrap_or("?");
+
+        Ok(match entry.id() {
+            Some(id) => format!("# {}:{} {}\n", chrom, entry.position(), id),
+            None => format!("# {}:{}\n", chrom, entry.position()),
+        })
+    }
+
+    /// Returns the formatted subject, where a relative one is resolved against `base` of the contig or `@base`
+    /// and validated.
+    fn resolve_subject(&self, entry: &Entry) -> Option<String> {
+        let mut formatter = &self.subject_formatter;
+        let (subject, relative) = loop {
+            match formatter.format(entry) {
+                Some(v) => break (v, formatter.relative),
+                None => {
+                    if let Some(key) = formatter.missing.as_ref() {
+                        self.count(key);
+                    }
+                    match formatter.fallback.as_deref() {
+                        Some(f) => formatter = f,
+                        None => return None,
+                    }
+                }
+            }
+        };
+
+        if !relative {
+            return Some(subject);
+        }
+
+        let base = entry
+            .record()
+            .sequence()
+            .and_then(|x| x.base.as_ref())
+            .or_else(|| self.namespace.and_then(|ns| ns.base.as_ref()));
+
+        match base {
+            Some(base) => {
+                let iri = iri::join(base, &subject);
+                if iri::is_absolute(&iri) {
+                    Some(iri)
+                } else {
+                    warn!("Invalid subject IRI, use blank node instead: {}", iri);
+                    self.count_warning("Subjects fell back to blank node");
+                    None
+                }
+            }
+            None => Some(subject),
+        }
+    }
+}
+
+static REGEX_ALLELES_ACGTN: Lazy<Regex> = Lazy::new(|| Regex::new(r"\A[ACGTN]+\z").unwrap());
+static REGEX_ALLELES_IUPAC: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\A[ACGTURYKMSWBDHVN]+\z").unwrap());
+
+impl<'a, W: Write> Writer for TurtleWriter<'a, W> {
+    fn write_record<'b>(&mut self, record: &Record<'b>) -> Result<()> {
+        let rendered = self.render_record(record)?;
+
+        self.write_rendered(rendered)
+    }
+
+    /// Returns the subject IRI for `entry`, or `None` to use a blank node.
+    fn format_subject(&self, entry: &Entry) -> Option<String> {
+        let subject = self.resolve_subject(entry)?;
+
+        if !self.options.subject_suffix_index || self.record_entries <= 1 {
+            return Some(subject);
+        }
+
+        let index = entry.index() + 1;
+        if subject.contains('#') {
+            Some(format!("{}-alt{}", subject, index))
+        } else {
+            Some(format!("{}#{}", subject, index))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::config::{InfoOptions, InfoParser, InfoType, Sequence};
+    use crate::rdf::namespace::GVO;
+    use crate::util::fixture;
+    use crate::util::vcf::compress;
+    use crate::vcf::fasta::Fasta;
+    use crate::vcf::reader::ReaderBuilder;
+    use crate::vcf::sample;
+
+    fn convert(configure: fn(&mut TurtleWriter<Vec<u8>>)) -> String {
+        convert_with_namespace(&Namespace::default(), configure)
+    }
+
+    fn convert_with_namespace(ns: &Namespace, configure: fn(&mut TurtleWriter<Vec<u8>>)) -> String {
+        let mut writer = TurtleWriter::new(Vec::new());
+        writer.namespace(ns);
+        configure(&mut writer);
+
+        write_records(&mut writer);
+
+        String::from_utf8(writer.into_inner().expect("Error flushing.")).unwrap()
+    }
+
+    fn reference() -> BTreeMap<String, Option<Sequence>> {
+        let mut reference = BTreeMap::new();
+        reference.insert(
+            "20".to_owned(),
+            Some(Sequence {
+                name: Some("20".to_owned()),
+                reference: Some("http://identifiers.org/hco/20/GRCh37".to_owned()),
+                refget: None,
+                base: None,
+            }),
+        );
+        reference
+    }
+
+    fn write_records(writer: &mut TurtleWriter<Vec<u8>>) {
+        let mut reader = ReaderBuilder::new()
+            .reference(reference())
+            .path("test/vcf_spec.vcf.gz")
+            .expect("Error opening file.");
+
+        for record in reader.records() {
+            writer
+                .write_record(&record.expect("Error reading record."))
+                .expect("Error writing record.");
+        }
+    }
+
+    /// Convert `test/{vcf}.vcf` by a writer set up by `configure`, and return the entries of variants.
+    fn render<F>(vcf: &str, configure: F) -> Vec<String>
+    where
+        F: FnOnce(&mut TurtleWriter<Vec<u8>>),
+    {
+        entries(&render_output(vcf, configure).0)
+    }
+
+    /// Convert `test/{vcf}.vcf` by a writer set up by `configure`, and return the output with the summary.
+    fn render_output<F>(vcf: &str, configure: F) -> (String, Summary)
+    where
+        F: FnOnce(&mut TurtleWriter<Vec<u8>>),
+    {
+        render_with(vcf, ReaderBuilder::new().reference(reference()), configure)
+    }
+
+    /// Convert `test/{vcf}.vcf` read by `builder`, and return the output with the summary.
+    fn render_with<F>(vcf: &str, builder: ReaderBuilder, configure: F) -> (String, Summary)
+    where
+        F: FnOnce(&mut TurtleWriter<Vec<u8>>),
+    {
+        let (_dir, path) = fixture::bgzip(vcf);
+        let mut reader = builder.path(path).expect("Error opening file.");
+
+        let ns = Namespace::default();
+        let mut writer = TurtleWriter::new(Vec::new());
+        writer.namespace(&ns);
+        configure(&mut writer);
+
+        for record in reader.records() {
+            writer
+                .write_record(&record.expect("Error reading record."))
+                .expect("Error writing record.");
+        }
+
+        let summary = writer.summary().clone();
+        let output = String::from_utf8(writer.into_inner().expect("Error flushing.")).unwrap();
+
+        (output, summary)
+    }
+
+    /// Returns blocks of `output` separated by blank lines which describe variants.
+    fn entries(output: &str) -> Vec<String> {
+        output
+            .split("\n\n")
+            .filter(|x| x.contains(" a gvo:"))
+            .map(|x| x.to_owned())
+            .collect()
+    }
+
+    #[test]
+    fn test_subjects_agree_with_locations() {
+        // all pairs of alleles of up to 3 bases of A and C
+        let alleles = (1..=3)
+            .flat_map(|n| {
+                (0..1u32 << n).map(move |bits| {
+                    (0..n)
+                        .map(|i| if bits >> i & 1 == 1 { 'C' } else { 'A' })
+                        .collect::<String>()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("alleles.vcf");
+        let mut vcf = String::from("##fileformat=VCFv4.2\n##contig=<ID=20,length=62435964>\n");
+        vcf.push_str("#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\n");
+        let mut count = 0;
+        for r in &alleles {
+            for a in alleles.iter().filter(|&a| a != r) {
+                count += 1;
+                vcf.push_str(&format!("20\t{}\t.\t{}\t{}\t.\t.\t.\n", count * 10, r, a));
+            }
+        }
+        std::fs::write(&input, vcf).unwrap();
+        let path = dir.path().join("alleles.vcf.gz");
+        compress::from_path(input, Some(path.clone()), None, true).unwrap();
+
+        let mut reader = ReaderBuilder::new()
+            .reference(reference())
+            .path(path)
+            .expect("Error opening file.");
+
+        let ns = Namespace::default();
+
+        let mut writer = TurtleWriter::new(Vec::new());
+
+        writer.namespace(&ns);
+        writer.subject_formatter(SubjectFormatter::from(&Subject::NormalizedReference));
+
+        for record in reader.records() {
+            writer
+                .write_record(&record.expect("Error reading record."))
+                .expect("Error writing record.");
+        }
+
+        let output = String::from_utf8(writer.into_inner().expect("Error flushing.")).unwrap();
+        let entries = entries(&output);
+        assert_eq!(entries.len(), count);
+
+        let subject = Regex::new(r"(?m)^<http://identifiers.org/hco/20/GRCh37#(\d+)-").unwrap();
+        let location = Regex::new(r"faldo:(?:position|begin|after) (\d+)").unwrap();
+
+        let capture = |re: &Regex, entry: &str| -> u64 {
+            re.captures(entry).expect(entry)[1].parse().unwrap()
+        };
+
+        for entry in entries.iter().map(String::as_str) {
+            let mut begin = capture(&location, entry);
+            // indels replace bases from the position, so the region begins after the preceding base
+            if entry.contains(" a gvo:Indel ;") {
+                begin += 1;
+            }
+            assert_eq!(capture(&subject, entry), begin, "{}", entry);
+        }
+    }
+
+    #[test]
+    fn test_buffer_prefixes() {
+        let mut buf = Buffer::new();
+        buf.push_str("[]");
+        buf.push_class(" ", "gvo:SNV");
+        buf.push_predicate(" ;\n  ", "rdfs:comment");
+        buf.push_quoted("see ex:foo", '"');
+        buf.push_predicate(" ;\n  ", "http://example.org/bar");
+        buf.push_typed("1", "xsd:integer");
+        buf.push_predicate(" ;\n  ", "<http://example.org/baz>");
+        buf.push_str("_:b0 .\n");
+
+        assert_eq!(
+            buf.string,
+            concat!(
+                "[] a gvo:SNV ;\n",
+                "  rdfs:comment \"see ex:foo\" ;\n",
+                "  <http://example.org/bar> \"1\"^^xsd:integer ;\n",
+                "  <http://example.org/baz> _:b0 .\n"
+            )
+        );
+        assert_eq!(buf.triples, 4);
+        assert_eq!(
+            buf.prefixes.iter().map(|x| x.as_str()).collect::<Vec<_>>(),
+            vec!["gvo", "rdfs", "xsd"]
+        );
+    }
+
+    #[test]
+    fn test_single_header() {
+        let output = convert(|w| {
+            w.info_key_iris(Some("gvo:infoField".to_owned()));
+        });
+
+        let (header, body) = output.split_once("\n\n").unwrap();
+
+        assert!(header.lines().all(|x| x.starts_with("@prefix")));
+        assert!(header.contains(" rdf: <http://www.w3.org/1999/02/22-rdf-syntax-ns#> ."));
+        assert!(!header.contains("void:"));
+        assert!(!body.contains("@prefix"));
+    }
+
+    #[test]
+    fn test_only_used_prefixes() {
+        let output = convert(|_| {});
+
+        assert!(output.starts_with("@prefix"));
+        assert!(output.contains("   gvo: <http://genome-variation.org/resource#> .\n"));
+        assert!(!output.contains("void:"));
+    }
+
+    #[test]
+    fn test_banner() {
+        let output = convert(|w| {
+            w.banner(vec!["vcf2rdf 1.0.0".to_owned(), "input: a\nb".to_owned()]);
+        });
+
+        assert!(output.starts_with("# vcf2rdf 1.0.0\n# input: a b\n\n@prefix"));
+    }
+
+    #[test]
+    fn test_subject_id_without_base() {
+        let output = convert(|w| {
+            w.subject_formatter(SubjectFormatter::from(&Subject::ID));
+        });
+
+        assert!(output.contains("\n<rs6054257> a gvo:SNV"));
+        assert!(output.contains("\n[] a gvo:SNV"));
+    }
+
+    #[test]
+    fn test_subject_id_with_base() {
+        let mut ns = Namespace::default();
+        ns.base = Some("http://example.org/variant/".to_owned());
+
+        let output = convert_with_namespace(&ns, |w| {
+            w.subject_formatter(SubjectFormatter::from(&Subject::ID));
+        });
+
+        assert!(output.contains("\n<http://example.org/variant/rs6054257> a gvo:SNV"));
+    }
+
+    #[test]
+    fn test_alleles() {
+        assert_eq!(alleles("G", "A", None), "G-A");
+        assert_eq!(alleles("G", "A", Some(2)), "G-A");
+        assert_eq!(alleles("G", "A", Some(1)), "f1a29d6e4feb5485");
+        assert_eq!(alleles("G", "A", Some(1)), alleles("G", "A", Some(0)));
+    }
+
+    #[test]
+    fn test_subject_hash_threshold() {
+        let output = convert(|w| {
+            w.subject_formatter(SubjectFormatter::with_hash_threshold(
+                &Subject::Reference,
+                Some(2),
+            ));
+        });
+
+        assert!(output.contains("\n<http://identifiers.org/hco/20/GRCh37#14370-G-A> a gvo:SNV"));
+        assert!(output.contains("\n<http://identifiers.org/hco/20/GRCh37#1234567-"));
+        assert!(!output.contains("#1234567-GTC-"));
+        assert!(output.contains("gvo:ref_vcf \"GTC\""));
+    }
+
+    #[test]
+    fn test_subject_info() {
+        let mut ns = Namespace::default();
+        ns.base = Some("http://example.org/aa/".to_owned());
+
+        let mut writer = TurtleWriter::new(Vec::new());
+        writer.namespace(&ns);
+        writer.subject_formatter(SubjectFormatter::info("AA".to_owned()));
+        write_records(&mut writer);
+
+        assert_eq!(
+            writer
+                .summary()
+                .get("Subjects fell back to blank node (missing INFO/AA)"),
+            2
+        );
+
+        let output = String::from_utf8(writer.into_inner().expect("Error flushing.")).unwrap();
+        assert!(output.contains("\n<http://example.org/aa/T> a gvo:SNV"));
+    }
+
+    #[test]
+    fn test_subject_dbsnp() {
+        let mut reference = BTreeMap::new();
+        reference.insert(
+            "NC_000001.10".to_owned(),
+            Some(Sequence {
+                name: Some("1".to_owned()),
+                reference: Some("http://identifiers.org/hco/1/GRCh37".to_owned()),
+                ..Default::default()
+            }),
+        );
+
+        let mut reader = ReaderBuilder::new()
+            .reference(reference)
+            .path("test/dbsnp_example.vcf.gz")
+            .expect("Error opening file.");
+
+        let ns = Namespace::default();
+        let mut writer = TurtleWriter::new(Vec::new());
+        writer.namespace(&ns);
+        writer.subject_formatter(SubjectFormatter::from(&Subject::Dbsnp));
+
+        for record in reader.records().take(1) {
+            writer
+                .write_record(&record.expect("Error reading record."))
+                .expect("Error writing record.");
+        }
+
+        assert_eq!(
+            writer
+                .summary()
+                .get("Subjects fell back to ID (missing RS)"),
+            0
+        );
+
+        let output = String::from_utf8(writer.into_inner().expect("Error flushing.")).unwrap();
+        assert!(output.contains("\n<http://identifiers.org/dbsnp/rs1570391677> a gvo:SNV"));
+    }
+
+    #[test]
+    fn test_subject_dbsnp_fallback() {
+        let mut ns = Namespace::default();
+        ns.base = Some("http://example.org/".to_owned());
+
+        let mut writer = TurtleWriter::new(Vec::new());
+        writer.namespace(&ns);
+        writer.subject_formatter(SubjectFormatter::from(&Subject::Dbsnp));
+        write_records(&mut writer);
+
+        assert_eq!(
+            writer
+                .summary()
+                .get("Subjects fell back to ID (missing RS)"),
+            6
+        );
+
+        let output = String::from_utf8(writer.into_inner().expect("Error flushing.")).unwrap();
+        assert!(output.contains("\n<http://example.org/rs6054257> a gvo:SNV"));
+    }
+
+    #[test]
+    fn test_subject_suffix_index() {
+        let mut ns = Namespace::default();
+        ns.base = Some("http://example.org/".to_owned());
+
+        let output = convert_with_namespace(&ns, |w| {
+            w.subject_formatter(SubjectFormatter::from(&Subject::ID));
+            w.subject_suffix_index(true);
+        });
+
+        assert!(output.contains("\n<http://example.org/rs6054257> a gvo:SNV"));
+        assert!(output.contains("\n<http://example.org/rs6040355#1> a gvo:SNV"));
+        assert!(output.contains("\n<http://example.org/rs6040355#2> a gvo:SNV"));
+        assert!(output.contains("\n<http://example.org/microsat1#2> a gvo:"));
+
+        let output = convert(|w| {
+            w.subject_formatter(SubjectFormatter::from(&Subject::Reference));
+            w.subject_suffix_index(true);
+        });
+
+        assert!(output.contains("\n<http://identifiers.org/hco/20/GRCh37#14370-G-A> a gvo:SNV"));
+        assert!(
+            output.contains("\n<http://identifiers.org/hco/20/GRCh37#1110696-A-T-alt2> a gvo:SNV")
+        );
+    }
+
+    #[test]
+    fn test_subject_suffix_index_after_filters() {
+        let write = |emit: bool| {
+            let (output, _) = render_output("star_allele", |w| {
+                w.subject_formatter(SubjectFormatter::from(&Subject::Reference));
+                w.subject_suffix_index(true);
+                w.emit_star_alleles(emit);
+            });
+            output
+        };
+
+        // `*` alleles are skipped, so `A,*` yields a single entry
+        let output = write(false);
+        assert!(output.contains("\n<http://identifiers.org/hco/20/GRCh37#14370-G-A> a gvo:SNV"));
+
+        let output = write(true);
+        assert!(
+            output.contains("\n<http://identifiers.org/hco/20/GRCh37#14370-G-A-alt1> a gvo:SNV")
+        );
+    }
+
+    #[test]
+    fn test_zygosity_object() {
+        let ns = Namespace::default();
+        let mut writer = TurtleWriter::new(Vec::new());
+        writer.namespace(&ns);
+
+        assert_eq!(
+            writer.zygosity_object(Zygosity::Heterozygous),
+            "\"heterozygous\""
+        );
+
+        let mut terms = BTreeMap::new();
+        terms.insert("heterozygous".to_owned(), "gvo:Heterozygous".to_owned());
+        terms.insert(
+            "hemizygous".to_owned(),
+            "http://example.org/Hemizygous".to_owned(),
+        );
+        terms.insert("unknown".to_owned(), "not called".to_owned());
+        writer.zygosity(terms);
+
+        assert_eq!(
+            writer.zygosity_object(Zygosity::Heterozygous),
+            "gvo:Heterozygous"
+        );
+        assert_eq!(
+            writer.zygosity_object(Zygosity::Hemizygous),
+            "<http://example.org/Hemizygous>"
+        );
+        assert_eq!(writer.zygosity_object(Zygosity::Unknown), "\"not called\"");
+        assert_eq!(
+            writer.zygosity_object(Zygosity::HomozygousAlt),
+            "\"homozygous_alt\""
+        );
+    }
+
+    #[test]
+    fn test_subject_prefix() {
+        let output = convert(|w| {
+            w.subject_formatter(
+                SubjectFormatter::from(&Subject::Location)
+                    .prefix("http://example.org/variant/".to_owned()),
+            );
+        });
+
+        assert!(output.contains("\n<http://example.org/variant/20-14370-G-A> a gvo:SNV"));
+    }
+
+    #[test]
+    fn test_subject_location_with_base() {
+        let mut ns = Namespace::default();
+        ns.base = Some("http://example.org/variant".to_owned());
+
+        let output = convert_with_namespace(&ns, |w| {
+            w.subject_formatter(SubjectFormatter::from(&Subject::Location));
+        });
+
+        assert!(output.contains("\n<http://example.org/variant/20-14370-G-A> a gvo:SNV"));
+    }
+
+    #[test]
+    fn test_subject_with_contig_base() {
+        let mut reference = reference();
+        if let Some(Some(sequence)) = reference.get_mut("20") {
+            sequence.base = Some("http://example.org/chr20/".to_owned());
+        }
+
+        let mut ns = Namespace::default();
+        ns.base = Some("http://example.org/variant/".to_owned());
+
+        for (subject, expected) in &[
+            (Subject::Location, "<http://example.org/chr20/20-14370-G-A>"),
+            (Subject::Reference, "<http://example.org/chr20/14370-G-A>"),
+            (
+                Subject::NormalizedReference,
+                "<http://example.org/chr20/1234567-GTC-G>",
+            ),
+        ] {
+            let mut writer = TurtleWriter::new(Vec::new());
+            writer.namespace(&ns);
+            writer.subject_formatter(SubjectFormatter::from(subject));
+
+            let mut reader = ReaderBuilder::new()
+                .reference(reference.clone())
+                .path("test/vcf_spec.vcf.gz")
+                .expect("Error opening file.");
+            for record in reader.records() {
+                writer
+                    .write_record(&record.expect("Error reading record."))
+                    .expect("Error writing record.");
+            }
+
+            let output = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+            assert!(output.contains(expected), "{:?}: {}", subject, output);
+            // the global base is only declared by @base
+            assert_eq!(output.matches("http://example.org/variant/").count(), 1);
+        }
+    }
+
+    #[test]
+    fn test_subject_vrs() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("grch38.vcf");
+        std::fs::write(
+            &input,
+            "##fileformat=VCFv4.2\n\
+             ##contig=<ID=chr20,length=64444167,assembly=GRCh38>\n\
+             #CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\n\
+             chr20\t14370\trs6054257\tG\tA\t29\tPASS\t.\n",
+        )
+        .unwrap();
+        let fasta = dir.path().join("grch38.fa");
+        std::fs::write(&fasta, format!(">chr20\n{}GAC\n", "N".repeat(14369))).unwrap();
+
+        // the refget identifier of chr20 of GRCh38
+        let mut reference = BTreeMap::new();
+        reference.insert(
+            "chr20".to_owned(),
+            Some(Sequence {
+                name: Some("chr20".to_owned()),
+                reference: Some("http://identifiers.org/hco/20/GRCh38".to_owned()),
+                refget: Some("ga4gh:SQ.-A1QmD_MatoqxvgVxBLZTONHz9-c7nQo".to_owned()),
+                base: None,
+            }),
+        );
+        let mut reader = ReaderBuilder::new()
+            .reference(reference)
+            .require_index(false)
+            .path(input)
+            .expect("Error opening file.");
+
+        let ns = Namespace::default();
+        let mut writer = TurtleWriter::new(Vec::new());
+        writer.namespace(&ns);
+        writer.subject_formatter(SubjectFormatter::vrs(Fasta::from_path(fasta).unwrap()));
+        for record in reader.records() {
+            writer
+                .write_record(&record.expect("Error reading record."))
+                .expect("Error writing record.");
+        }
+
+        let output = String::from_utf8(writer.into_inner().expect("Error flushing.")).unwrap();
+        assert!(output.contains("\n<ga4gh:VA.1NB6QMZst27hDngCqn4d2BWf_8K_mjmy> a gvo:SNV"));
+    }
+
+    #[test]
+    fn test_provenance() {
+        let output = convert(|w| {
+            w.provenance(Provenance {
+                source: vec!["vcf_spec.vcf.gz".to_owned()],
+                created: "2021-01-01T00:00:00Z".to_owned(),
+                version: "vcf2rdf 1.0.0".to_owned(),
+                subject: "blank_node".to_owned(),
+            });
+        });
+
+        assert!(output.contains("   xsd: <http://www.w3.org/2001/XMLSchema#> .\n"));
+        assert!(output.contains("  prov: <http://www.w3.org/ns/prov#> .\n"));
+        assert!(output.contains(concat!(
+            "\n\n[] a prov:Activity ;\n",
+            "  dct:source \"vcf_spec.vcf.gz\" ;\n",
+            "  dct:created \"2021-01-01T00:00:00Z\"^^xsd:dateTime ;\n",
+            "  dct:creator \"vcf2rdf 1.0.0\" ;\n",
+            "  dct:description \"subject: blank_node\" .\n\n[] a gvo:SNV"
+        )));
+    }
+
+    #[test]
+    fn test_void() {
+        let ns = Namespace::default();
+        let mut writer = TurtleWriter::new(Vec::new());
+        writer.namespace(&ns);
+
+        write_records(&mut writer);
+
+        let mut void = Vec::new();
+        writer.write_void(&mut void).unwrap();
+        let void = String::from_utf8(void).unwrap();
+
+        assert!(writer.void().entities() > 0);
+        assert!(writer.void().triples() > writer.void().entities());
+        assert!(void.contains("[] a void:Dataset ;\n"));
+        assert!(void.contains("<http://genome-variation.org/resource#>"));
+        assert!(void.contains("  dct:coverage \"20\" .\n"));
+    }
+
+    #[test]
+    fn test_typed_literals() {
+        let output = convert(|_| {});
+
+        assert!(output.contains(" xsd: <http://www.w3.org/2001/XMLSchema#> .\n"));
+        assert!(output.contains("rdfs:label \"DP\" ;\n    rdf:value \"14\"^^xsd:integer\n"));
+        assert!(output.contains("rdfs:label \"AF\" ;\n    rdf:value \"0.017\"^^xsd:float\n"));
+        assert!(output.contains("rdfs:label \"DB\" ;\n    rdf:value \"true\"^^xsd:boolean\n"));
+    }
+
+    #[test]
+    fn test_plain_literals() {
+        let output = convert(|w| {
+            w.plain_literals(true);
+        });
+
+        assert!(output.contains("rdfs:label \"DP\" ;\n    rdf:value 14\n"));
+        assert!(output.contains("rdfs:label \"AF\" ;\n    rdf:value 0.017\n"));
+        assert!(output.contains("rdfs:label \"DB\" ;\n    rdf:value true\n"));
+    }
+
+    #[test]
+    fn test_options() {
+        let output = convert(|w| {
+            w.options(WriterOptions {
+                plain_literals: true,
+                info_string: true,
+                ..Default::default()
+            });
+        });
+
+        assert_eq!(
+            output,
+            convert(|w| {
+                w.plain_literals(true);
+                w.info_string(true);
+            })
+        );
+    }
+
+    #[test]
+    fn test_info_key_iris() {
+        let mut reference = BTreeMap::new();
+        reference.insert(
+            "NC_000001.10".to_owned(),
+            Some(Sequence {
+                name: Some("1".to_owned()),
+                reference: Some("http://identifiers.org/hco/1/GRCh37".to_owned()),
+                ..Default::default()
+            }),
+        );
+
+        let mut reader = ReaderBuilder::new()
+            .reference(reference)
+            .path("test/dbsnp_example.vcf.gz")
+            .expect("Error opening file.");
+
+        let ns = Namespace::default();
+        let mut writer = TurtleWriter::new(Vec::new());
+        writer.namespace(&ns);
+        writer.info_key_iris(Some("gvo:infoField".to_owned()));
+
+        for record in reader.records() {
+            writer
+                .write_record(&record.expect("Error reading record."))
+                .expect("Error writing record.");
+        }
+
+        let output = String::from_utf8(writer.into_inner().expect("Error flushing.")).unwrap();
+
+        assert_eq!(
+            output
+                .matches("\ngvo:RS a rdf:Property ;\n  rdfs:label \"RS\" ;\n  rdfs:comment \"dbSNP ID (i.e. rs number)\" .\n")
+                .count(),
+            1
+        );
+        assert_eq!(output.matches("\ngvo:VC a rdf:Property ;").count(), 1);
+        assert!(output.contains("[\n    gvo:infoField gvo:RS ;\n    rdf:value \""));
+        assert!(!output.contains("\n    rdfs:label "));
+    }
+
+    #[test]
+    fn test_info_definitions() {
+        let mut reader = ReaderBuilder::new()
+            .reference(reference())
+            .path("test/vcf_spec.vcf.gz")
+            .expect("Error opening file.");
+
+        let definitions = reader
+            .info_keys()
+            .iter()
+            .map(|k| {
+                let description = reader
+                    .info_definitions()
+                    .get(k)
+                    .and_then(|x| x.description.clone());
+                (k.to_owned(), description)
+            })
+            .collect();
+
+        let ns = Namespace::default();
+        let mut writer = TurtleWriter::new(Vec::new());
+        writer.namespace(&ns);
+        writer.info_key_iris(Some("gvo:infoField".to_owned()));
+        writer.info_definitions(definitions);
+
+        for record in reader.records() {
+            writer
+                .write_record(&record.expect("Error reading record."))
+                .expect("Error writing record.");
+        }
+
+        let output = String::from_utf8(writer.into_inner().expect("Error flushing.")).unwrap();
+        let first_entry = output.find(" a gvo:").unwrap();
+
+        assert!(output[..first_entry].contains(
+            "gvo:AF a rdf:Property ;\n  rdfs:label \"AF\" ;\n  rdfs:comment \"Allele Frequency\" .\n"
+        ));
+        assert_eq!(output.matches(" a rdf:Property ;").count(), 6);
+    }
+
+    #[test]
+    fn test_vep_consequences() {
+        let (output, _) = render_output("vep_example", |w| {
+            let mut parsers = BTreeMap::new();
+            parsers.insert("CSQ".to_owned(), InfoParser::Vep);
+            w.info_parsers(parsers);
+        });
+
+        assert!(!output.contains("rdfs:label \"CSQ\""));
+        assert!(output.contains("rdfs:label \"DP\""));
+        assert_eq!(output.matches("    gvo:Allele ").count(), 6);
+        assert!(output.contains(concat!(
+            "  gvo:consequence [\n",
+            "    gvo:Allele \"A\" ;\n",
+            "    gvo:Consequence \"missense_variant\" ;\n",
+            "    gvo:IMPACT \"MODERATE\" ;\n",
+            "    gvo:SYMBOL \"GENE1\" ;\n",
+            "    gvo:Feature \"ENST00000000001\" ;\n",
+            "    gvo:HGVSc \"ENST00000000001.1:c.100G>A\" ;\n",
+            "    gvo:HGVSp \"ENSP00000000001.1:p.Val34Ile\"\n",
+            "  ], [\n",
+            "    gvo:Allele \"A\" ;\n",
+            "    gvo:Consequence \"upstream_gene_variant\" ;\n",
+        )));
+        assert!(output.contains("gvo:HGVSp \"ENSP00000000001.1:p.Leu100=\""));
+        assert!(output.contains(
+            "gvo:Allele \"T\" ;\n    gvo:Consequence \"stop_gained\", \"splice_region_variant\" ;\n"
+        ));
+        assert!(output.contains("gvo:Allele \"-\" ;\n    gvo:Consequence \"frameshift_variant\""));
+        assert!(output.contains("gvo:Allele \"TCT\" ;\n    gvo:Consequence \"inframe_insertion\""));
+        assert_eq!(output.matches("gvo:Consequence \"stop_gained\"").count(), 1);
+    }
+
+    #[test]
+    fn test_crosscheck_vc() {
+        let convert = |mode: CrosscheckVC| {
+            render_output("variant_class", |w| {
+                w.crosscheck_vc(mode);
+            })
+        };
+
+        let (output, summary) = convert(CrosscheckVC::Count);
+        for (class, vc) in &[
+            ("SNV", "SNV"),
+            ("Insertion", "INS"),
+            ("Deletion", "DEL"),
+            ("MNV", "MNV"),
+            ("Deletion", "INDEL"),
+        ] {
+            let key = format!("VC crosscheck: agreed (computed {}, VC {})", class, vc);
+            assert_eq!(summary.get(&key), 1, "{}", key);
+        }
+        // the last record is crafted to disagree
+        let key = "VC crosscheck: disagreed (computed SNV, VC DEL)";
+        assert_eq!(summary.get(key), 1);
+        assert!(summary.is_warning(key));
+        assert!(output.contains(" a gvo:SNV ;\n  dct:identifier \"rs6040362\""));
+
+        let (output, _) = convert(CrosscheckVC::Trust);
+        assert!(output.contains(" a gvo:Deletion ;\n  dct:identifier \"rs6040362\""));
+        assert!(output.contains(" a gvo:SNV ;\n  dct:identifier \"rs6054257\""));
+    }
+
+    #[test]
+    fn test_vep_consequences_disabled() {
+        let (output, _) = render_output("vep_example", |w| {
+            w.consequence_predicate("ex:consequence".to_owned());
+        });
+
+        assert!(output.contains("rdfs:label \"CSQ\""));
+        assert!(!output.contains("consequence"));
+    }
+
+    #[test]
+    fn test_info_predicates() {
+        let output = convert(|w| {
+            let mut predicates = BTreeMap::new();
+            predicates.insert(
+                "AF".to_owned(),
+                "http://example.org/vocab#alleleFrequency".to_owned(),
+            );
+            predicates.insert("DP".to_owned(), "dct:extent".to_owned());
+            w.info_predicates(predicates);
+        });
+
+        assert!(
+            output.contains(" ;\n  <http://example.org/vocab#alleleFrequency> \"0.5\"^^xsd:float")
+        );
+        assert!(output.contains(" ;\n  dct:extent \"14\"^^xsd:integer"));
+        assert!(!output.contains("rdfs:label \"AF\""));
+        assert!(!output.contains("rdfs:label \"DP\""));
+        assert!(output.contains("rdfs:label \"NS\""));
+        assert!(output.contains(" dct: <http://purl.org/dc/terms/> .\n"));
+    }
+
+    #[test]
+    fn test_missing_info_values() {
+        let (output, summary) = render_output("missing_values", |_| {});
+        assert!(!summary.has_warnings());
+
+        let entries = entries(&output);
+
+        assert_eq!(entries.len(), 5);
+        assert!(!output.contains("\".\""));
+        assert!(!entries[0].contains("gvo:info"));
+
+        assert!(!entries[1].contains("rdfs:label \"AF\""));
+        assert!(entries[1].contains("rdfs:label \"AC\" ;\n    rdf:value \"3\"^^xsd:integer\n"));
+        assert!(entries[1].contains("rdfs:label \"HQ\" ;\n    rdf:value \"51\"^^xsd:integer\n"));
+
+        assert!(entries[2].contains("rdfs:label \"AF\" ;\n    rdf:value \"0.667\"^^xsd:float\n"));
+        assert!(!entries[2].contains("rdfs:label \"AC\""));
+
+        // a single `.` of Number=A is missing for all alternate alleles, not mismatched
+        assert!(!entries[3].contains("rdfs:label \"AF\""));
+        assert!(entries[3].contains("rdfs:label \"AC\" ;\n    rdf:value \"2\"^^xsd:integer\n"));
+        assert!(!entries[4].contains("rdfs:label \"AF\""));
+        assert!(entries[4].contains("rdfs:label \"AC\" ;\n    rdf:value \"1\"^^xsd:integer\n"));
+    }
+
+    /// Parse a Turtle `STRING_LITERAL_QUOTE` at the beginning of `input` and return its value.
+    fn parse_literal(input: &str) -> Option<String> {
+        let mut chars = input.strip_prefix('"')?.chars();
+        let mut value = String::new();
+
+        loop {
+            match chars.next()? {
+                '"' => return Some(value),
+                '\n' | '\r' => return None,
+                '\\' => value.push(match chars.next()? {
+                    't' => '\t',
+                    'b' => '\u{8}',
+                    'n' => '\n',
+                    'r' => '\r',
+                    'f' => '\u{c}',
+                    c @ '"' | c @ '\'' | c @ '\\' => c,
+                    _ => return None,
+                }),
+                c => value.push(c),
+            }
+        }
+    }
+
+    #[test]
+    fn test_escape() {
+        for value in &[
+            "plain",
+            "C:\\data\\",
+            "\"quoted\"",
+            "line\nbreak\r\n",
+            "tab\tseparated",
+            "\\n is not a newline",
+            "café",
+            "",
+        ] {
+            let literal = format!("\"{}\"", escape(value));
+            assert!(!literal.contains('\n') && !literal.contains('\r'));
+            assert_eq!(parse_literal(&literal).as_deref(), Some(*value));
+        }
+    }
+
+    #[test]
+    fn test_escaped_info_values() {
+        let (output, _) = render_output("escaped_values", |_| {});
+        let label = "rdfs:label \"NOTE\" ;\n    rdf:value ";
+        let start = output.find(label).expect("INFO/NOTE not found.") + label.len();
+
+        assert_eq!(
+            parse_literal(&output[start..]).as_deref(),
+            Some("C:\\data\\\"x\"\nline\ttab\r")
+        );
+    }
+
+    #[test]
+    fn test_info_types() {
+        let mut types = BTreeMap::new();
+        types.insert("dbSNPBuildID".to_owned(), InfoType::Integer);
+        types.insert("GENEURL".to_owned(), InfoType::Iri);
+
+        let builder = ReaderBuilder::new()
+            .reference(reference())
+            .info_types(types);
+        let (output, _) = render_with("typed_values", builder, |_| {});
+        let entries = entries(&output);
+
+        assert!(entries[0]
+            .contains("rdfs:label \"dbSNPBuildID\" ;\n    rdf:value \"155\"^^xsd:integer\n"));
+        assert!(entries[0]
+            .contains("rdfs:label \"GENEURL\" ;\n    rdf:value <https://example.org/gene/1>\n"));
+        assert!(!entries[1].contains("rdfs:label \"dbSNPBuildID\""));
+        assert!(!entries[1].contains("rdfs:label \"GENEURL\""));
+        assert!(entries[1].contains("rdfs:label \"VALIDATED\" ;\n    rdf:value \"0\"\n"));
+    }
+
+    #[test]
+    fn test_structural_variants() {
+        let (output, summary) = render_output("sv_example", |_| {});
+        assert_eq!(
+            summary.get("Entries skipped (unsupported symbolic allele)"),
+            1
+        );
+
+        let entries = entries(&output);
+
+        assert_eq!(entries.len(), 5);
+
+        assert!(entries[0].contains("[] a gvo:Deletion ;"));
+        assert!(entries[0].contains("gvo:alt \"<DEL>\""));
+        assert!(entries[0].contains(
+            "faldo:location [\n    a faldo:Region ;\n    faldo:begin 14370 ;\n    faldo:end 14520 ;\n    faldo:reference <http://identifiers.org/hco/20/GRCh37>\n  ]"
+        ));
+
+        assert!(entries[1].contains("[] a gvo:Duplication ;"));
+        assert!(entries[1].contains("faldo:begin 17330 ;\n    faldo:end 17530 ;"));
+
+        assert!(entries[2].contains("[] a gvo:Inversion ;"));
+        assert!(entries[2].contains("faldo:begin 1110696 ;\n    faldo:end 1112000 ;"));
+
+        assert!(entries[3].contains("[] a gvo:CopyNumberVariation ;"));
+        assert!(entries[3].contains("faldo:begin 1230237 ;\n    faldo:end 1240000 ;"));
+
+        assert!(entries[4].contains("[] a gvo:Insertion ;"));
+        assert!(entries[4].contains(
+            "a faldo:InBetweenPosition ;\n    faldo:after 1234567 ;\n    faldo:before 1234568 ;"
+        ));
+
+        assert!(!output.contains("<NON_REF>"));
+    }
+
+    #[test]
+    fn test_breakends() {
+        let mut reference = reference();
+        reference.insert(
+            "17".to_owned(),
+            Some(Sequence {
+                name: Some("17".to_owned()),
+                reference: Some("http://identifiers.org/hco/17/GRCh37".to_owned()),
+                refget: None,
+                base: None,
+            }),
+        );
+
+        let builder = ReaderBuilder::new().reference(reference);
+        let (output, summary) = render_with("bnd_example", builder, |_| {});
+        assert_eq!(summary.get("Breakends with unresolved mate contig"), 2);
+
+        let entries = entries(&output);
+
+        // records on 13 are not in the reference
+        assert_eq!(entries.len(), 4);
+
+        assert!(entries[0].contains(" a gvo:Breakend ;"));
+        assert!(entries[0].contains("gvo:alt \"G]17:198982]\""));
+        assert!(entries[0].contains(
+            "faldo:location [\n    a faldo:ExactPosition ;\n    faldo:position 321681 ;\n    faldo:reference <http://identifiers.org/hco/20/GRCh37>\n  ]"
+        ));
+        assert!(entries[0].contains(
+            "gvo:mateLocation [\n    a faldo:ExactPosition ;\n    faldo:position 198982 ;\n    faldo:reference <http://identifiers.org/hco/17/GRCh37>\n  ]"
+        ));
+        assert!(entries[0].contains("gvo:orientation \"t]p]\""));
+        assert!(!entries[0].contains("gvo:insertedSequence"));
+
+        assert!(entries[1].contains(
+            "gvo:mateLocation [\n    a faldo:ExactPosition ;\n    faldo:position 123456\n  ]"
+        ));
+        assert!(entries[1].contains("gvo:orientation \"]p]t\""));
+        assert!(!entries[1].contains("gvo:mate "));
+
+        // bnd_Y is linked with bnd_W both ways
+        let w = entries[0].split_whitespace().next().unwrap();
+        let y = entries[2].split_whitespace().next().unwrap();
+        assert!(w.starts_with("_:"));
+        assert!(entries[2].contains(&format!("gvo:mate {}", w)));
+        assert!(entries[2].contains(&format!("{} gvo:mate {} .", w, y)));
+
+        assert!(entries[3].starts_with("[] a gvo:Breakend ;"));
+        assert!(entries[3].contains("gvo:orientation \"[p[t\""));
+        assert!(entries[3].contains("gvo:insertedSequence \"AGT\""));
+    }
+
+    #[test]
+    fn test_symbolic_alleles_in_subjects() {
+        let mut reference = reference();
+        reference.insert(
+            "17".to_owned(),
+            Some(Sequence {
+                name: Some("17".to_owned()),
+                reference: Some("http://identifiers.org/hco/17/GRCh37".to_owned()),
+                refget: None,
+                base: None,
+            }),
+        );
+
+        let mut subjects = Vec::new();
+        for name in &["sv_example", "bnd_example", "star_allele"] {
+            let builder = ReaderBuilder::new().reference(reference.clone());
+            let (output, _) = render_with(name, builder, |w| {
+                w.subject_formatter(SubjectFormatter::from(&Subject::Reference));
+                w.emit_star_alleles(true);
+            });
+
+            for triple in oxttl::TurtleParser::new().parse_read(output.as_bytes()) {
+                let triple = triple.unwrap_or_else(|e| panic!("{}\n{}", e, output));
+                if let oxrdf::Subject::NamedNode(node) = triple.subject {
+                    subjects.push(node.into_string());
+                }
+            }
+        }
+
+        for subject in &[
+            "http://identifiers.org/hco/20/GRCh37#14370-G-%3CDEL%3E",
+            "http://identifiers.org/hco/20/GRCh37#17330-T-%3CDUP%3ATANDEM%3E",
+            "http://identifiers.org/hco/20/GRCh37#321681-G-G%5D17%3A198982%5D",
+            "http://identifiers.org/hco/20/GRCh37#14370-G-%2A",
+        ] {
+            assert!(subjects.iter().any(|x| x == subject), "{}", subject);
+        }
+    }
+
+    #[test]
+    fn test_star_alleles() {
+        let write = |emit: bool| {
+            let (output, summary) = render_output("star_allele", |w| {
+                w.emit_star_alleles(emit);
+            });
+            (summary.get("Entries skipped (* allele)"), output)
+        };
+
+        let (skipped, output) = write(false);
+        assert_eq!(skipped, 2);
+        assert!(!output.contains("\"*\""));
+        assert!(!output.contains("gvo:OverlapDeletion"));
+
+        let (skipped, output) = write(true);
+        assert_eq!(skipped, 0);
+        let entries = entries(&output);
+
+        assert_eq!(entries.len(), 5);
+        assert!(entries[2].contains("[] a gvo:OverlapDeletion ;"));
+        assert!(entries[2].contains("gvo:ref \"G\" ;\n  gvo:alt \"*\""));
+        assert!(entries[2].contains("a faldo:ExactPosition ;\n    faldo:position 14370 ;"));
+        assert!(entries[4].contains("[] a gvo:OverlapDeletion ;"));
+        assert!(
+            entries[4].contains("a faldo:Region ;\n    faldo:begin 14371 ;\n    faldo:end 14372 ;")
+        );
+    }
+
+    #[test]
+    fn test_quality_flags() {
+        let mut writer = TurtleWriter::new(Vec::new());
+        let ns = Namespace::default();
+        writer.namespace(&ns);
+        writer.quality_flags(vec![
+            QualityFlag {
+                predicate: "gvo:highQuality".to_owned(),
+                min_qual: Some(30.0),
+                require_pass: true,
+                omit_when_false: true,
+            },
+            QualityFlag {
+                predicate: "gvo:passFilter".to_owned(),
+                min_qual: None,
+                require_pass: true,
+                omit_when_false: false,
+            },
+        ]);
+
+        write_records(&mut writer);
+
+        let output = String::from_utf8(writer.into_inner().expect("Error flushing.")).unwrap();
+        let qual = Regex::new(r"gvo:qual (\S+) ;").unwrap();
+
+        let entries = entries(&output);
+        for entry in &entries {
+            let high = entry.contains("gvo:highQuality \"true\"^^xsd:boolean");
+            let pass = entry.contains("gvo:passFilter \"true\"^^xsd:boolean");
+            assert!(!entry.contains("gvo:highQuality \"false\""), "{}", entry);
+
+            match &qual.captures(entry).expect(entry)[1] {
+                // below the threshold, the flag is omitted
+                "29" => assert!(!high && pass, "{}", entry),
+                // FILTER q10
+                "3" => {
+                    assert!(!high && !pass, "{}", entry);
+                    assert!(entry.contains("gvo:passFilter \"false\"^^xsd:boolean"));
+                }
+                _ => assert!(high && pass, "{}", entry),
+            }
+        }
+        assert!(entries.len() >= 6);
+    }
+
+    #[test]
+    fn test_allele_alphabet() {
+        let write = |alphabet: AlleleAlphabet| {
+            let (output, summary) = render_output("lowercase_alleles", |w| {
+                w.subject_formatter(SubjectFormatter::from(&Subject::NormalizedReference));
+                w.allele_alphabet(alphabet);
+            });
+            (summary.get("Records with alleles uppercased"), output)
+        };
+
+        let (uppercased, output) = write(AlleleAlphabet::Default);
+        assert_eq!(uppercased, 2);
+        assert!(output.contains("<http://identifiers.org/hco/20/GRCh37#14370-G-A> a gvo:SNV"));
+        assert!(output.contains("gvo:ref_vcf \"TC\""));
+        assert!(!output.contains("#17330-"));
+        assert!(!output.contains("\"g\""));
+
+        let (uppercased, output) = write(AlleleAlphabet::Strict);
+        assert_eq!(uppercased, 0);
+        assert!(!output.contains(" a gvo:"));
+
+        let (uppercased, output) = write(AlleleAlphabet::Iupac);
+        assert_eq!(uppercased, 3);
+        assert!(output.contains("gvo:alt \"R\""));
+    }
+
+    #[test]
+    fn test_skip_symbolic() {
+        let (output, summary) = render_output("sv_example", |w| {
+            w.skip_symbolic(true);
+        });
+
+        assert_eq!(summary.get("Entries skipped by --skip-symbolic"), 6);
+        assert_eq!(
+            summary.get("Entries skipped (unsupported symbolic allele)"),
+            0
+        );
+        assert!(!output.contains(" a gvo:"));
+    }
+
+    #[test]
+    fn test_imprecise_structural_variants() {
+        let entries = render("imprecise_sv", |_| {});
+
+        assert!(entries[0].contains(concat!(
+            "    faldo:begin [\n",
+            "      a faldo:FuzzyPosition ;\n",
+            "      faldo:position 5 ;\n",
+            "      gvo:confidenceIntervalStart 1 ;\n",
+            "      gvo:confidenceIntervalEnd 30\n",
+            "    ] ;\n",
+            "    faldo:end [\n",
+            "      a faldo:FuzzyPosition ;\n",
+            "      faldo:position 62435950 ;\n",
+            "      gvo:confidenceIntervalStart 62435945 ;\n",
+            "      gvo:confidenceIntervalEnd 62435964\n",
+            "    ] ;\n"
+        )));
+        assert!(entries[1].contains("faldo:begin 14370 ;\n    faldo:end 14520 ;"));
+    }
+
+    fn write_rs_iris(keep_literal: bool) -> String {
+        let mut reference = BTreeMap::new();
+        reference.insert(
+            "NC_000001.10".to_owned(),
+            Some(Sequence {
+                name: Some("1".to_owned()),
+                reference: Some("http://identifiers.org/hco/1/GRCh37".to_owned()),
+                ..Default::default()
+            }),
+        );
+
+        let mut reader = ReaderBuilder::new()
+            .reference(reference)
+            .info_keys(vec!["RS".to_owned()])
+            .path("test/dbsnp_example.vcf.gz")
+            .expect("Error opening file.");
+
+        let mut options = BTreeMap::new();
+        options.insert(
+            "RS".to_owned(),
+            InfoOptions {
+                as_iri_template: Some("http://identifiers.org/dbsnp/rs{value}".to_owned()),
+                keep_literal,
+                ..Default::default()
+            },
+        );
+
+        let ns = Namespace::default();
+        let mut writer = TurtleWriter::new(Vec::new());
+        writer.namespace(&ns);
+        writer.info_options(options);
+
+        for record in reader.records() {
+            writer
+                .write_record(&record.expect("Error reading record."))
+                .expect("Error writing record.");
+        }
+
+        String::from_utf8(writer.into_inner().expect("Error flushing.")).unwrap()
+    }
+
+    #[test]
+    fn test_info_iri_template() {
+        let output = write_rs_iris(false);
+
+        assert!(output.contains(
+            "rdfs:label \"RS\" ;\n    rdf:value <http://identifiers.org/dbsnp/rs1570391677>\n"
+        ));
+        assert!(!output.contains("^^xsd:integer"));
+
+        let output = write_rs_iris(true);
+
+        assert!(output.contains("rdfs:label \"RS\" ;\n    rdf:value \"1570391677\"^^xsd:integer ;\n    rdfs:seeAlso <http://identifiers.org/dbsnp/rs1570391677>\n"));
+    }
+
+    #[test]
+    fn test_keep_raw_info() {
+        let output = convert(|w| {
+            w.info_predicates(default_info_predicates());
+        });
+
+        assert!(output.contains(" ;\n  gvo:alleleFrequency \"0.5\"^^xsd:float"));
+        assert!(output.contains(" ;\n  gvo:alleleFrequency \"0.667\"^^xsd:float"));
+        assert!(!output.contains("rdfs:label \"AF\""));
+
+        let output = convert(|w| {
+            w.info_predicates(default_info_predicates());
+            w.keep_raw_info(true);
+        });
+
+        assert!(output.contains(" ;\n  gvo:alleleFrequency \"0.5\"^^xsd:float"));
+        assert!(output.contains("rdfs:label \"AF\" ;\n    rdf:value \"0.5\"^^xsd:float\n"));
+    }
+
+    #[test]
+    fn test_info_string() {
+        let output = convert(|w| {
+            w.info_string(true);
+        });
+
+        assert!(output.contains(" ;\n  gvo:infoString \"NS=3;DP=14;AF=0.5;DB;H2\""));
+        assert!(output.contains("rdfs:label \"DP\" ;\n    rdf:value \"14\"^^xsd:integer\n"));
+
+        let output = convert(|_| {});
+
+        assert!(!output.contains("gvo:infoString"));
+    }
+
+    #[test]
+    fn test_escaped_info_string() {
+        let (output, _) = render_output("escaped_values", |w| {
+            w.info_string(true);
+        });
+        let predicate = "gvo:infoString ";
+        let start = output.find(predicate).expect("gvo:infoString not found.") + predicate.len();
+
+        assert_eq!(
+            parse_literal(&output[start..]).as_deref(),
+            Some("NOTE=C:\\data\\\"x\"%0Aline%09tab%0D")
+        );
+    }
+
+    #[test]
+    fn test_genotypes() {
+        let output = convert(|w| {
+            w.genotypes(true);
+            w.format_keys(Vec::new());
+        });
+        let entries = entries(&output);
+
+        assert!(entries[0].contains(concat!(
+            " ;\n  gvo:genotype [\n    gvo:sample <sample/NA00002> ;\n",
+            "    gvo:alleleCount 1 ;\n    gvo:zygosity \"heterozygous\" ;\n",
+            "    gvo:phased \"true\"^^xsd:boolean ;\n    gvo:haplotypeIndex \"1\"^^xsd:integer\n  ], [\n",
+            "    gvo:sample <sample/NA00003> ;\n    gvo:alleleCount 2 ;\n",
+            "    gvo:zygosity \"homozygous_alt\" ;\n    gvo:phased \"false\"^^xsd:boolean\n  ]"
+        )));
+        assert!(!entries[0].contains("<sample/NA00001>"));
+
+        let output = convert(|w| {
+            w.genotypes(true);
+            w.format_keys(Vec::new());
+            w.ref_genotypes(true);
+        });
+
+        assert!(output.contains(concat!(
+            "    gvo:sample <sample/NA00001> ;\n    gvo:alleleCount 0 ;\n",
+            "    gvo:zygosity \"homozygous_ref\" ;\n    gvo:phased \"true\"^^xsd:boolean\n"
+        )));
+
+        assert!(!convert(|_| {}).contains("gvo:genotype"));
+    }
+
+    #[test]
+    fn test_zygosity_per_allele() {
+        let entries = render("cohort", |w| {
+            let mut terms = BTreeMap::new();
+            terms.insert("heterozygous".to_owned(), "gvo:Heterozygous".to_owned());
+
+            w.genotypes(true);
+            w.ref_genotypes(true);
+            w.zygosity(terms);
+        });
+
+        let zygosity = |entry: &str, sample: &str| -> String {
+            let node = format!("gvo:sample <sample/{}> ;", sample);
+            let start = entry.find(&node).expect(&node);
+            let line = entry[start..]
+                .lines()
+                .find(|x| x.trim_start().starts_with("gvo:zygosity "))
+                .expect(entry);
+            line.trim_start()["gvo:zygosity ".len()..]
+                .trim_end_matches(" ;")
+                .to_owned()
+        };
+
+        // G>A and G>T of `0/1 1/2 2/2 ./1`
+        assert_eq!(zygosity(&entries[1], "S1"), "gvo:Heterozygous");
+        assert_eq!(zygosity(&entries[2], "S1"), "\"homozygous_ref\"");
+        assert_eq!(zygosity(&entries[1], "S2"), "gvo:Heterozygous");
+        assert_eq!(zygosity(&entries[2], "S2"), "gvo:Heterozygous");
+        assert_eq!(zygosity(&entries[1], "S3"), "\"homozygous_ref\"");
+        assert_eq!(zygosity(&entries[2], "S3"), "\"homozygous_alt\"");
+        assert_eq!(zygosity(&entries[1], "S4"), "\"unknown\"");
+        assert_eq!(zygosity(&entries[2], "S4"), "\"unknown\"");
+    }
+
+    #[test]
+    fn test_format_values() {
+        let output = convert(|w| {
+            w.genotypes(true);
+        });
+
+        assert!(output.contains(concat!(
+            "    gvo:sample <sample/NA00002> ;\n    gvo:alleleCount 1 ;\n",
+            "    gvo:zygosity \"heterozygous\" ;\n    gvo:phased \"true\"^^xsd:boolean ;\n",
+            "    gvo:haplotypeIndex \"1\"^^xsd:integer ;\n    gvo:readDepth \"8\"^^xsd:integer ;\n",
+            "    gvo:genotypeQuality \"48\"^^xsd:integer\n  ]"
+        )));
+
+        let output = convert(|w| {
+            w.genotypes(true);
+            w.format_keys(vec!["GQ".to_owned()]);
+        });
+
+        assert!(output.contains("gvo:genotypeQuality \"48\"^^xsd:integer"));
+        assert!(!output.contains("gvo:readDepth"));
+    }
+
+    #[test]
+    fn test_allelic_depth() {
+        let (output, _) = render_output("format_values", |w| {
+            w.genotypes(true);
+        });
+        let entries = entries(&output);
+
+        assert_eq!(entries.len(), 2);
+        assert!(entries[0].contains(concat!(
+            "    gvo:sample <sample/S1> ;\n    gvo:alleleCount 1 ;\n",
+            "    gvo:zygosity \"heterozygous\" ;\n    gvo:phased \"false\"^^xsd:boolean ;\n",
+            "    gvo:readDepth \"30\"^^xsd:integer ;\n    gvo:genotypeQuality \"99\"^^xsd:integer ;\n",
+            "    gvo:allelicDepth \"12\"^^xsd:integer\n  ]"
+        )));
+        assert!(entries[1].contains("gvo:allelicDepth \"6\"^^xsd:integer"));
+        // missing values
+        assert!(entries[0].contains(concat!(
+            "    gvo:sample <sample/S2> ;\n    gvo:alleleCount 2 ;\n",
+            "    gvo:zygosity \"homozygous_alt\" ;\n    gvo:phased \"false\"^^xsd:boolean\n  ]"
+        )));
+        assert!(!output.contains("-2147483648"));
+    }
+
+    #[test]
+    fn test_pseudonymize_samples() {
+        let ns = Namespace::default();
+        let mut writer = TurtleWriter::new(Vec::new());
+        writer.namespace(&ns);
+        writer.pseudonymize_samples(Some("secret".to_owned()));
+
+        let pseudonym = sample::pseudonym("secret", "NA00002");
+        assert_eq!(writer.sample_name("NA00002"), pseudonym);
+        assert_eq!(
+            writer.sample_iri("NA00002"),
+            format!("sample/{}", pseudonym)
+        );
+
+        let output = convert(|w| {
+            w.genotypes(true);
+            w.pseudonymize_samples(Some("secret".to_owned()));
+        });
+
+        assert!(!output.contains("NA0000"));
+        assert!(output.contains(&format!("gvo:sample <sample/{}>", pseudonym)));
+    }
+
+    #[test]
+    fn test_header_sample_iris() {
+        let ns = Namespace::default();
+        let mut writer = TurtleWriter::new(Vec::new());
+        writer.namespace(&ns);
+
+        let samples = vec!["NA00001".to_owned(), "NA00002".to_owned()];
+        assert_eq!(
+            &*writer.header_sample_iris(&samples),
+            &["sample/NA00001", "sample/NA00002"]
+        );
+        assert_eq!(
+            &*writer.header_sample_iris(&samples[1..]),
+            &["sample/NA00002"]
+        );
+
+        // resolved again when a setter changes the IRIs
+        writer.pseudonymize_samples(Some("secret".to_owned()));
+        assert_eq!(
+            &*writer.header_sample_iris(&samples[1..]),
+            &[format!("sample/{}", sample::pseudonym("secret", "NA00002"))]
+        );
+    }
+
+    #[test]
+    fn test_genotype_summary() {
+        let entries = render("cohort", |w| {
+            w.genotype_summary(true);
+            w.plain_literals(true);
+        });
+
+        // AC_Het, AC_Hom / 2, N_MISSING and AF of `bcftools +fill-tags`
+        let expected = [
+            (1, 1, 1, "0.5"),
+            (2, 0, 1, "0.42857143"),
+            (1, 1, 1, "0.42857143"),
+            (0, 0, 4, ""),
+        ];
+
+        assert_eq!(entries.len(), expected.len());
+        for (entry, (het, hom, missing, af)) in entries.iter().zip(expected.iter()) {
+            assert!(entry.contains(&format!(
+                " ;\n  gvo:homCount {} ;\n  gvo:hetCount {} ;\n  gvo:missingCount {}",
+                hom, het, missing
+            )));
+            if af.is_empty() {
+                assert!(!entry.contains("gvo:sampleAlleleFrequency"));
+            } else {
+                assert!(entry.contains(&format!(" ;\n  gvo:sampleAlleleFrequency {}", af)));
+            }
+            assert!(!entry.contains("gvo:genotype "));
+        }
+    }
+
+    #[test]
+    fn test_genotype_summary_with_info_af() {
+        let output = convert(|w| {
+            w.info_predicates(default_info_predicates());
+            w.genotype_summary(true);
+        });
+
+        // INFO/AF of the record and the frequency in the samples are distinct by default
+        assert_eq!(output.matches(" ;\n  gvo:alleleFrequency ").count(), 1);
+        assert!(output.contains(" ;\n  gvo:alleleFrequency \"0.5\"^^xsd:float"));
+        assert!(output.contains(" ;\n  gvo:sampleAlleleFrequency \"0.5\"^^xsd:float"));
+
+        let output = convert(|w| {
+            w.genotype_summary(true);
+            w.genotype_frequency_predicate("gvo:sampleFrequency".to_owned());
+        });
+        assert!(output.contains(" ;\n  gvo:sampleFrequency \"0.5\"^^xsd:float"));
+    }
+
+    #[test]
+    fn test_haplotypes() {
+        let (output, _) = render_output("ploidy_values", |w| {
+            w.genotypes(true);
+            w.plain_literals(true);
+        });
+
+        assert!(output.contains(concat!(
+            "    gvo:sample <sample/HAPLOID> ;\n    gvo:alleleCount 1 ;\n",
+            "    gvo:zygosity \"hemizygous\" ;\n    gvo:phased true ;\n    gvo:haplotypeIndex 1\n  ]"
+        )));
+        assert!(output.contains(concat!(
+            "    gvo:sample <sample/TRIPLOID> ;\n    gvo:alleleCount 2 ;\n",
+            "    gvo:zygosity \"heterozygous\" ;\n    gvo:phased true ;\n    gvo:haplotypeIndex 2, 3\n  ]"
+        )));
+        assert!(output.contains(concat!(
+            "    gvo:sample <sample/UNPHASED> ;\n    gvo:alleleCount 1 ;\n",
+            "    gvo:zygosity \"heterozygous\" ;\n    gvo:phased false\n  ]"
+        )));
+    }
+
+    #[test]
+    fn test_sample_iris() {
+        let ns = Namespace::default();
+        let mut writer = TurtleWriter::new(Vec::new());
+        writer.namespace(&ns);
+
+        let mut iris = BTreeMap::new();
+        iris.insert(
+            "NA00001".to_owned(),
+            "http://example.org/participant/1".to_owned(),
+        );
+        writer.sample_iris(iris);
+
+        assert_eq!(
+            writer.sample_iri("NA00001"),
+            "http://example.org/participant/1"
+        );
+        assert_eq!(writer.sample_iri("NA00002"), "sample/NA00002");
+    }
+
+    fn write_list_values(lists: bool) -> (String, u64) {
+        let (_dir, path) = fixture::bgzip("list_values");
+        let mut reader = ReaderBuilder::new()
+            .reference(reference())
+            .path(path)
+            .expect("Error opening file.");
+
+        let ns = Namespace::default();
+        let mut writer = TurtleWriter::new(Vec::new());
+        writer.namespace(&ns);
+        writer.info_lists(lists);
+
+        for record in reader.records() {
+            writer
+                .write_record(&record.expect("Error reading record."))
+                .expect("Error writing record.");
+        }
+
+        let triples = writer.void().triples();
+        let output = String::from_utf8(writer.into_inner().expect("Error flushing.")).unwrap();
+
+        (output, triples)
+    }
+
+    #[test]
+    fn test_info_lists() {
+        let (output, triples) = write_list_values(true);
+        let entries = entries(&output);
+
+        assert!(entries[0].contains(
+            "rdfs:label \"GP\" ;\n    rdf:value ( \"0.1\"^^xsd:float \"0.2\"^^xsd:float \"0.7\"^^xsd:float ) ;\n"
+        ));
+        assert!(entries[0].contains("rdfs:label \"DPS\" ;\n    rdf:value ()\n"));
+        assert!(entries[0].contains("rdfs:label \"AA\" ;\n    rdf:value \"G\"\n"));
+        // missing items keep their positions as blank nodes
+        assert!(entries[1].contains(
+            "rdfs:label \"GP\" ;\n    rdf:value ( [] \"0.3\"^^xsd:float \"0.7\"^^xsd:float ) ;\n"
+        ));
+        assert!(entries[1].contains(
+            "rdfs:label \"DPS\" ;\n    rdf:value ( \"12\"^^xsd:integer [] \"8\"^^xsd:integer )\n"
+        ));
+        // 14 of each entry, and a node with a label and a value for each key,
+        // where GP has a comment and each list has `rdf:first` and `rdf:rest` for each item
+        assert_eq!(
+            triples,
+            14 + (4 + 2 * 3) + 3 + 3 + 14 + (4 + 2 * 3) + (3 + 2 * 3)
+        );
+    }
+
+    #[test]
+    fn test_info_lists_disabled() {
+        let (output, triples) = write_list_values(false);
+        let entries = entries(&output);
+
+        assert!(entries[0].contains(
+            "rdfs:label \"GP\" ;\n    rdf:value \"0.1\"^^xsd:float, \"0.2\"^^xsd:float, \"0.7\"^^xsd:float ;\n"
+        ));
+        assert!(!entries[0].contains("rdfs:label \"DPS\""));
+        // missing items are dropped
+        assert!(entries[1].contains(
+            "rdfs:label \"GP\" ;\n    rdf:value \"0.3\"^^xsd:float, \"0.7\"^^xsd:float ;\n"
+        ));
+        assert!(entries[1].contains(
+            "rdfs:label \"DPS\" ;\n    rdf:value \"12\"^^xsd:integer, \"8\"^^xsd:integer\n"
+        ));
+        assert_eq!(triples, 14 + (3 + 3) + 3 + 14 + (3 + 2) + (2 + 2));
+    }
+
+    #[test]
+    fn test_allele_info_values() {
+        let (output, summary) = render_output("allele_values", |_| {});
+        assert_eq!(
+            summary.get("INFO values of Number=R skipped (too few values)"),
+            1
+        );
+
+        let entries = entries(&output);
+
+        let ad = |value: &str, reference: Option<&str>| {
+            match reference {
+            Some(r) => format!(
+                "rdfs:label \"AD\" ;\n    rdf:value \"{}\"^^xsd:integer ;\n    gvo:refValue \"{}\"^^xsd:integer\n  ]",
+                value, r
+            ),
+            None => format!(
+                "rdfs:label \"AD\" ;\n    rdf:value \"{}\"^^xsd:integer\n  ]",
+                value
+            ),
+        }
+        };
+
+        assert_eq!(entries.len(), 5);
+        assert!(entries[0].contains(&ad("5", Some("10"))));
+        assert!(entries[1].contains(&ad("5", Some("10"))));
+        assert!(!entries[2].contains("rdfs:label \"AD\""));
+        assert!(entries[3].contains(&ad("3", None)));
+        assert!(entries[4].contains(&ad("4", None)));
+        assert!(!output.contains("rdf:comment"));
+    }
+
+    fn write_alt_values(ns: &Namespace, strict: bool) -> (TurtleWriter<Vec<u8>>, Result<()>) {
+        let (_dir, path) = fixture::bgzip("alt_values");
+        let mut reader = ReaderBuilder::new()
+            .reference(reference())
+            .path(path)
+            .expect("Error opening file.");
+
+        let mut writer = TurtleWriter::new(Vec::new());
+        writer.namespace(ns);
+        writer.strict(strict);
+
+        let result = reader
+            .records()
+            .try_for_each(|record| writer.write_record(&record.expect("Error reading record.")));
+
+        (writer, result)
+    }
+
+    #[test]
+    fn test_alt_info_length() {
+        let ns = Namespace::default();
+        let (writer, result) = write_alt_values(&ns, false);
+        assert!(result.is_ok());
+        assert_eq!(
+            writer
+                .summary()
+                .get("Records with mismatched Number=A INFO/AC"),
+            2
+        );
+        assert_eq!(
+            writer
+                .summary()
+                .get("Records with mismatched Number=A INFO/AF"),
+            0
+        );
+
+        let output = String::from_utf8(writer.into_inner().expect("Error flushing.")).unwrap();
+
+        assert_eq!(output.matches("rdfs:label \"AC\"").count(), 1);
+        assert_eq!(output.matches("rdfs:label \"AF\"").count(), 5);
+    }
+
+    #[test]
+    fn test_alt_info_length_strict() {
+        let ns = Namespace::default();
+        let (_, result) = write_alt_values(&ns, true);
+
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "INFO/AC of Number=A has 1 values for 2 alternate alleles at 20:1110696"
+        );
+    }
+
+    #[test]
+    fn test_split_info_values() {
+        let mut separators = BTreeMap::new();
+        separators.insert("CLNDN".to_owned(), "|".to_owned());
+
+        let builder = ReaderBuilder::new()
+            .reference(reference())
+            .info_separators(separators);
+        let (output, _) = render_with("clinvar_example", builder, |_| {});
+
+        assert!(output.contains(
+            "rdfs:label \"CLNDN\" ;\n    rdf:value \"Myasthenic_syndrome,_congenital,_8\", \"not_provided\"\n"
+        ));
+        assert!(output.contains("rdfs:label \"CLNDN\" ;\n    rdf:value \"not_provided\"\n"));
+        assert!(output.contains("\"MedGen:C4225392|MedGen:CN517202\"\n"));
+    }
+
+    #[test]
+    fn test_rdf_star() {
+        let output = convert(|w| {
+            w.rdf_star(vec!["AF".to_owned()]);
+            w.rdf_star_mode(Some(RdfStarMode::Allele));
+        });
+
+        assert!(output.contains("\n_:b1 a gvo:SNV"));
+        assert!(output.contains("\n<< _:b1 gvo:alt \"A\" >> gvo:AF \"0.5\"^^xsd:float .\n"));
+        assert!(!output.contains("\n    rdfs:label \"AF\""));
+        assert!(output.contains("\n    rdfs:label \"DP\""));
+
+        // the predicate is defined once with the first annotation
+        let definition = output
+            .find("\ngvo:AF a rdf:Property ;\n  rdfs:label \"AF\" ;\n  rdfs:comment \"Allele Frequency\" .\n")
+            .unwrap();
+        assert!(definition > output.find(" >> gvo:AF ").unwrap());
+        assert_eq!(output.matches("\ngvo:AF a rdf:Property ;").count(), 1);
+    }
+
+    #[test]
+    fn test_rdf_star_with_subject() {
+        let output = convert(|w| {
+            w.subject_formatter(SubjectFormatter::from(&Subject::ID));
+            w.rdf_star(vec!["DP".to_owned()]);
+            w.rdf_star_mode(Some(RdfStarMode::Allele));
+        });
+
+        assert!(output.contains("\n<< <rs6054257> gvo:alt \"A\" >> gvo:DP \"14\"^^xsd:integer .\n"));
+    }
+
+    /// Parse `output` as Turtle-star, returning asserted triples and annotations of quoted triples in N-Triples.
+    fn parse_rdf_star(output: &str) -> (Vec<String>, Vec<(String, String, String)>) {
+        let mut asserted = Vec::new();
+        let mut annotations = Vec::new();
+
+        for triple in oxttl::TurtleParser::new()
+            .with_base_iri("http://example.org/")
+            .unwrap()
+            .parse_read(output.as_bytes())
+        {
+            let triple = triple.expect("Error parsing output.");
+            match &triple.subject {
+                oxrdf::Subject::Triple(quoted) => annotations.push((
+                    quoted.to_string(),
+                    triple.predicate.as_str().to_owned(),
+                    triple.object.to_string(),
+                )),
+                _ => asserted.push(triple.to_string()),
+            }
+        }
+
+        (asserted, annotations)
+    }
+
+    #[test]
+    fn test_rdf_star_statement() {
+        let output = convert(|w| {
+            w.subject_formatter(SubjectFormatter::from(&Subject::ID));
+            w.info_predicates(default_info_predicates());
+            w.rdf_star_mode(Some(RdfStarMode::Statement));
+        });
+
+        assert!(output.contains(" ;\n  gvo:alleleFrequency \"0.5\"^^xsd:float"));
+        assert!(output.contains(
+            "\n<< <rs6054257> gvo:alleleFrequency \"0.5\"^^xsd:float >> gvo:infoKey \"AF\" ;\n  gvo:alleleIndex 1 .\n"
+        ));
+        assert!(!output.contains("gvo:alt \"A\" >>"));
+        assert!(!output.contains("gvo:truncated"));
+
+        let (asserted, annotations) = parse_rdf_star(&output);
+        let af = |id: &str, value: &str| {
+            format!(
+                "<http://example.org/{}> <{}alleleFrequency> \"{}\"^^<http://www.w3.org/2001/XMLSchema#float>",
+                id, GVO, value
+            )
+        };
+        let annotation = |quoted: &str, name: &str, value: &str| {
+            (
+                quoted.to_owned(),
+                format!("{}{}", GVO, name),
+                value.to_owned(),
+            )
+        };
+        let index = |i: u32| format!("\"{}\"^^<http://www.w3.org/2001/XMLSchema#integer>", i);
+
+        // values of the mapped predicate are asserted and annotated with the same triples
+        assert!(asserted.contains(&af("rs6054257", "0.5")));
+        assert!(annotations.contains(&annotation(&af("rs6054257", "0.5"), "infoKey", "\"AF\"")));
+        assert!(annotations.contains(&annotation(
+            &af("rs6054257", "0.5"),
+            "alleleIndex",
+            &index(1)
+        )));
+        assert!(asserted.contains(&af("rs6040355", "0.667")));
+        assert!(annotations.contains(&annotation(&af("rs6040355", "0.667"), "infoKey", "\"AF\"")));
+        assert!(annotations.contains(&annotation(
+            &af("rs6040355", "0.667"),
+            "alleleIndex",
+            &index(2)
+        )));
+
+        // keys not mapped are kept in `gvo:info`
+        assert!(annotations.iter().all(|(_, _, x)| x != "\"DP\""));
+        assert!(output.contains("rdfs:label \"DP\""));
+    }
+
+    #[test]
+    fn test_rdf_star_statement_truncated() {
+        // AF of rs6040355 has more values than declared
+        let (_dir, path) = fixture::bcf_with_header("vcf_spec", |header| {
+            header.remove_info(b"AF").push_record(
+                b"##INFO=<ID=AF,Number=1,Type=Float,Description=\"Allele Frequency\">",
+            );
+        });
+        let mut reader = ReaderBuilder::new()
+            .reference(reference())
+            .path(path)
+            .expect("Error opening file.");
+
+        let ns = Namespace::default();
+        let mut writer = TurtleWriter::new(Vec::new());
+        writer.namespace(&ns);
+        writer.subject_formatter(SubjectFormatter::from(&Subject::ID));
+        writer.info_predicates(default_info_predicates());
+        writer.rdf_star_mode(Some(RdfStarMode::Statement));
+
+        for record in reader.records() {
+            writer
+                .write_record(&record.expect("Error reading record."))
+                .expect("Error writing record.");
+        }
+
+        let output = String::from_utf8(writer.into_inner().expect("Error flushing.")).unwrap();
+
+        let (_, annotations) = parse_rdf_star(&output);
+        let truncated: Vec<&str> = annotations
+            .iter()
+            .filter(|(_, p, o)| {
+                *p == format!("{}truncated", GVO)
+                    && o == "\"true\"^^<http://www.w3.org/2001/XMLSchema#boolean>"
+            })
+            .map(|(quoted, _, _)| quoted.as_str())
+            .collect();
+
+        // the first value is written for both alternate alleles
+        assert_eq!(truncated.len(), 2);
+        assert!(truncated
+            .iter()
+            .all(|x| x.starts_with("<http://example.org/rs6040355> ")
+                && x.ends_with(" \"0.333\"^^<http://www.w3.org/2001/XMLSchema#float>")));
+    }
+
+    #[test]
+    fn test_labeled_entry_nodes() {
+        let output = convert(|w| {
+            w.entry_nodes(EntryNodes::Labeled);
+        });
+
+        assert!(output.contains("\n_:r0_0 a gvo:SNV ;"));
+        assert!(output.contains("  faldo:location _:r0_0_loc ;\n"));
+        assert!(output.contains("\n_:r0_0_loc a faldo:ExactPosition ;\n"));
+        assert!(output.contains("\n_:r2_1 a gvo:SNV ;"));
+        assert!(!output.contains("[]"));
+        assert_eq!(
+            output,
+            convert(|w| {
+                w.entry_nodes(EntryNodes::Labeled);
+            })
+        );
+    }
+
+    #[test]
+    fn test_skolem_entry_nodes() {
+        let output = convert(|w| {
+            w.entry_nodes(EntryNodes::Skolem(
+                "http://example.org/.well-known/genid/".to_owned(),
+            ));
+        });
+
+        assert!(output.contains("\n<http://example.org/.well-known/genid/r0_0> a gvo:SNV ;"));
+        assert!(output.contains("  faldo:location <http://example.org/.well-known/genid/r0_0_loc>"));
+    }
+
+    #[test]
+    fn test_exclude() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(b"rsIDs:\n  - rs6054257\n  - rs6040355\npositions:\n  - contig: \"20\"\n    pos: 17330\n")
+            .unwrap();
+
+        let ns = Namespace::default();
+        let mut writer = TurtleWriter::new(Vec::new());
+        writer.namespace(&ns);
+        writer.subject_formatter(SubjectFormatter::from(&Subject::Reference));
+        writer.exclude(ExcludeList::from_path(file.path()).unwrap());
+        write_records(&mut writer);
+
+        assert_eq!(writer.summary().get("Entries excluded by policy"), 4);
+        assert_eq!(writer.records_written(), 1);
+
+        let output = String::from_utf8(writer.into_inner().expect("Error flushing.")).unwrap();
+        assert!(!output.contains("#14370-"));
+        assert!(!output.contains("#17330-"));
+        assert!(!output.contains("#1110696-"));
+        assert!(output.contains("#1234567-"));
+    }
+
+    #[test]
+    fn test_filters() {
+        let ns = Namespace::default();
+        let mut writer = TurtleWriter::new(Vec::new());
+        writer.namespace(&ns);
+        writer.subject_formatter(SubjectFormatter::from(&Subject::Reference));
+        writer.require_pass(true);
+        write_records(&mut writer);
+
+        assert_eq!(writer.summary().get("Records skipped by FILTER"), 1);
+        assert_eq!(writer.summary().get("Records skipped by FILTER q10"), 1);
+
+        let output = String::from_utf8(writer.into_inner().expect("Error flushing.")).unwrap();
+        assert!(output.contains("#14370-"));
+        assert!(!output.contains("#17330-"));
+
+        let ns = Namespace::default();
+
+        let mut writer = TurtleWriter::new(Vec::new());
+
+        writer.namespace(&ns);
+        writer.subject_formatter(SubjectFormatter::from(&Subject::Reference));
+        writer.exclude_filters(vec!["LowQual".to_owned()]);
+        write_records(&mut writer);
+
+        assert_eq!(writer.summary().get("Records skipped by FILTER"), 0);
+
+        let output = String::from_utf8(writer.into_inner().expect("Error flushing.")).unwrap();
+        assert!(output.contains("#17330-"));
+    }
+
+    #[test]
+    fn test_duplicate_subjects() {
+        let ns = Namespace::default();
+        let mut writer = TurtleWriter::new(Vec::new());
+        writer.namespace(&ns);
+        writer.subject_formatter(SubjectFormatter::info("NS".to_owned()));
+        write_records(&mut writer);
+
+        // NS=3 for the first, the second and the last two alleles
+        assert_eq!(writer.summary().get("Possible duplicate subjects"), 3);
+        assert!(writer.summary().is_warning("Possible duplicate subjects"));
+        assert_eq!(writer.summary().get("Duplicate subjects"), 0);
+    }
+
+    #[test]
+    fn test_duplicate_subjects_exact() {
+        let ns = Namespace::default();
+        let mut writer = TurtleWriter::new(Vec::new());
+        writer.namespace(&ns);
+        writer.subject_formatter(SubjectFormatter::info("NS".to_owned()));
+        writer.strict_duplicates(true);
+        write_records(&mut writer);
+
+        assert_eq!(writer.summary().get("Duplicate subjects"), 3);
+        assert!(writer.summary().is_warning("Duplicate subjects"));
+        assert_eq!(writer.summary().get("Possible duplicate subjects"), 0);
+    }
+
+    #[test]
+    fn test_duplicate_subjects_strict() {
+        let ns = Namespace::default();
+        let mut writer = TurtleWriter::new(Vec::new());
+        writer.namespace(&ns);
+        writer.subject_formatter(SubjectFormatter::info("NS".to_owned()));
+        writer.strict(true);
+
+        let write_all = |writer: &mut TurtleWriter<Vec<u8>>| {
+            let mut reader = ReaderBuilder::new()
+                .reference(reference())
+                .path("test/vcf_spec.vcf.gz")
+                .expect("Error opening file.");
+            reader
+                .records()
+                .map(|x| writer.write_record(&x.expect("Error reading record.")))
+                .collect::<Vec<_>>()
+        };
+
+        // subjects are tracked exactly without `strict_duplicates`
+        let results = write_all(&mut writer);
+        assert!(results[0].is_ok());
+        assert_eq!(
+            results[1].as_ref().unwrap_err().to_string(),
+            "Duplicate subject <3> at 20:14370 and 20:17330"
+        );
+    }
+
+    #[test]
+    fn test_vcf_roundtrip() {
+        let output = convert(|w| {
+            w.vcf_roundtrip(true);
+        });
+
+        let re = Regex::new(
+            r#"gvo:vcfLine \[\s*gvo:vcfChrom "([^"]*)" ;\s*gvo:vcfPos (\d+) ;\s*gvo:vcfId "([^"]*)" ;\s*gvo:vcfRef "([^"]*)" ;\s*gvo:vcfAlt "([^"]*)" ;\s*gvo:altIndex (\d+)\s*\]"#,
+        )
+        .unwrap();
+
+        let lines: Vec<String> = re
+            .captures_iter(&output)
+            .filter(|x| &x[6] == "1")
+            .map(|x| format!("{}\t{}\t{}\t{}\t{}", &x[1], &x[2], &x[3], &x[4], &x[5]))
+            .collect();
+
+        let source = std::fs::read_to_string("test/vcf_spec.vcf").unwrap();
+        let columns = |pos: &str| {
+            source
+                .lines()
+                .find(|x| x.split('\t').nth(1) == Some(pos))
+                .map(|x| x.split('\t').take(5).collect::<Vec<_>>().join("\t"))
+                .unwrap()
+        };
+
+        assert!(lines.contains(&columns("1110696")));
+        assert!(lines.contains(&columns("1234567")));
+        assert!(output.contains("gvo:vcfAlt \"G,GTCT\" ;\n    gvo:altIndex 2\n"));
+    }
+
+    #[test]
+    fn test_samples() {
+        let (_dir, path) = fixture::bgzip("trio");
+        let mut reader = ReaderBuilder::new()
+            .reference(reference())
+            .path(path)
+            .expect("Error opening file.");
+
+        let mut ns = Namespace::default();
+        ns.base = Some("http://example.org/".to_owned());
+
+        let mut writer = TurtleWriter::new(Vec::new());
+        writer.namespace(&ns);
+        writer.samples(sample::from_header(reader.header()));
+
+        for record in reader.records() {
+            writer
+                .write_record(&record.expect("Error reading record."))
+                .expect("Error writing record.");
+        }
+
+        let output = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+
+        assert_eq!(output.matches("a gvo:Sample").count(), 3);
+        assert!(output.contains(concat!(
+            "<http://example.org/sample/NA12878> a gvo:Sample ;\n",
+            "  rdfs:label \"NA12878\" ;\n",
+            "  dct:description \"Daughter\" ;\n",
+            "  gvo:father <http://example.org/sample/NA12891> ;\n",
+            "  gvo:mother <http://example.org/sample/NA12892> .\n"
+        )));
+        assert!(output.contains("   dct: <http://purl.org/dc/terms/> .\n"));
+    }
+
+    #[test]
+    fn test_set_output() {
+        let (_dir, path) = fixture::bgzip("trio");
+        let mut reader = ReaderBuilder::new()
+            .reference(reference())
+            .path(path)
+            .expect("Error opening file.");
+
+        let ns = Namespace::default();
+        let mut writer = TurtleWriter::new(Vec::new());
+        writer.namespace(&ns);
+        writer.samples(sample::from_header(reader.header()));
+        writer.info_key_iris(Some("gvo:infoField".to_owned()));
+
+        for (i, record) in reader.records().enumerate() {
+            // DP is defined on the first use in the former output
+            if i == 1 {
+                writer.set_output(Vec::new()).unwrap();
+            }
+            writer
+                .write_record(&record.expect("Error reading record."))
+                .expect("Error writing record.");
+        }
+
+        let output = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+        let first_entry = output.find(" a gvo:SNV").unwrap();
+
+        assert!(output.starts_with("@prefix"));
+        assert_eq!(output[..first_entry].matches(" a gvo:Sample ;").count(), 3);
+        assert!(output[..first_entry].contains("gvo:DP a rdf:Property ;"));
+        assert_eq!(output.matches(" a rdf:Property ;").count(), 1);
+    }
+
+    #[test]
+    fn test_sample_iri() {
+        let mut ns = Namespace::default();
+        let mut writer = TurtleWriter::new(Vec::new());
+
+        assert_eq!(writer.sample_iri("NA 12878"), "sample/NA%2012878");
+
+        ns.base = Some("http://example.org/variant/".to_owned());
+        writer.namespace(&ns);
+        assert_eq!(
+            writer.sample_iri("NA12878"),
+            "http://example.org/variant/sample/NA12878"
+        );
+    }
+
+    #[test]
+    fn test_record_comments() {
+        let output = convert(|w| {
+            w.record_comments(true);
+        });
+
+        assert!(output.contains("\n# 20:14370 rs6054257\n[] a gvo:SNV"));
+        assert!(output.contains("\n# 20:17330\n[] a gvo:SNV"));
+    }
+
+    #[test]
+    fn test_entry_separator_none() {
+        let output = convert(|w| {
+            w.entry_separator(EntrySeparator::None);
+        });
+
+        let (header, body) = output.split_once("\n\n").unwrap();
+
+        assert!(header.starts_with("@prefix"));
+        assert!(body.starts_with("[] a gvo:SNV"));
+        assert!(!body.contains("\n\n"));
+    }
+
+    #[test]
+    fn test_entry_separator_blank_line() {
+        let output = convert(|_| {});
+
+        assert!(output.contains(" .\n\n[] a gvo:SNV"));
     }
 }
@@ -0,0 +1,36 @@
+//! Module for metadata of the conversion written before the first entry
+use crate::vcf::record::as_turtle::Buffer;
+
+/// Metadata describing how the output is generated.
+#[derive(Debug, Clone)]
+pub struct Provenance {
+    /// File names of the inputs.
+    pub source: Vec<String>,
+    /// Timestamp in ISO 8601.
+    pub created: String,
+    /// Name and version of the converter.
+    pub version: String,
+    /// Strategy to generate subjects.
+    pub subject: String,
+}
+
+impl Provenance {
+    pub(super) fn push_turtle(&self, buf: &mut Buffer) {
+        buf.push_str("[]");
+        buf.push_class(" ", "prov:Activity");
+        buf.push_predicate(" ;\n  ", "dct:source");
+        for (i, source) in self.source.iter().enumerate() {
+            if i > 0 {
+                buf.push_triple(", ");
+            }
+            buf.push_quoted(source, '"');
+        }
+        buf.push_predicate(" ;\n  ", "dct:created");
+        buf.push_typed(&self.created, "xsd:dateTime");
+        buf.push_predicate(" ;\n  ", "dct:creator");
+        buf.push_quoted(&self.version, '"');
+        buf.push_predicate(" ;\n  ", "dct:description");
+        buf.push_quoted(&format!("subject: {}", self.subject), '"');
+        buf.push_str(" .\n");
+    }
+}
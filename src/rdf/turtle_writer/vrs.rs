@@ -0,0 +1,37 @@
+//! Module for subjects of GA4GH VRS computed identifiers
+use std::sync::Mutex;
+
+use crate::rdf::turtle_writer::SubjectFormatter;
+use crate::rdf::vrs::Allele;
+use crate::vcf::fasta::Fasta;
+use crate::vcf::record::Entry;
+
+impl SubjectFormatter {
+    /// Create a formatter of GA4GH VRS computed identifiers of alleles normalized by bases in `fasta`.
+    pub fn vrs(fasta: Fasta) -> Self {
+        let fasta = Mutex::new(fasta);
+
+        SubjectFormatter {
+            func: Box::new(move |entry: &Entry| {
+                let sequence_id = entry.record().sequence()?.refget.as_ref()?;
+                let fasta = fasta.lock().ok()?;
+                let name = fasta.resolve(entry.chromosome()?.ok()?)?;
+
+                Allele::from_vcf(
+                    sequence_id,
+                    entry.position(),
+                    entry.reference_bases(),
+                    entry.alternate_bases(),
+                )
+                .normalize(|start, length| fasta.fetch(&name, start + 1, length))
+                .ok()
+                .map(|x| x.identifier())
+            }),
+            relative: false,
+            missing: Some(
+                "Subjects fell back to blank node (missing refget or sequence in FASTA)".to_owned(),
+            ),
+            fallback: None,
+        }
+    }
+}
@@ -0,0 +1,111 @@
+//! Module for VoID description of converted data
+use std::collections::BTreeSet;
+
+use crate::rdf::namespace::{Namespace, DCT, VOID};
+use crate::rdf::turtle_writer::escape;
+
+/// Statistics accumulated during conversion to describe a `void:Dataset`.
+#[derive(Debug, Default)]
+pub struct Void {
+    triples: u64,
+    entities: u64,
+    vocabularies: BTreeSet<String>,
+    contigs: BTreeSet<String>,
+}
+
+impl Void {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Add counts of an entry written with `triples`.
+    pub fn add_entry(&mut self, triples: u64, contig: Option<&str>) {
+        self.triples += triples;
+        self.entities += 1;
+
+        if let Some(contig) = contig {
+            if !self.contigs.contains(contig) {
+                self.contigs.insert(contig.to_owned());
+            }
+        }
+    }
+
+    /// Add a prefix used in entries.
+    pub fn add_vocabulary(&mut self, prefix: &str) {
+        if !self.vocabularies.contains(prefix) {
+            self.vocabularies.insert(prefix.to_owned());
+        }
+    }
+
+    pub fn triples(&self) -> u64 {
+        self.triples
+    }
+
+    pub fn entities(&self) -> u64 {
+        self.entities
+    }
+
+    pub fn contigs(&self) -> &BTreeSet<String> {
+        &self.contigs
+    }
+
+    /// Returns a Turtle document describing the dataset.
+    ///
+    /// Vocabularies are resolved with prefixes in `ns`.
+    pub fn to_turtle(&self, ns: &Namespace) -> String {
+        let dct = ns.prefixes.get("dct").map_or(DCT, |x| x.as_str());
+        let void = ns.prefixes.get("void").map_or(VOID, |x| x.as_str());
+
+        let mut buf = String::new();
+
+        buf += &format!("@prefix  dct: <{}> .\n", dct);
+        buf += &format!("@prefix void: <{}> .\n\n", void);
+
+        buf += "[] a void:Dataset ;\n";
+        buf += &format!("  void:triples {} ;\n", self.triples);
+        buf += &format!("  void:entities {}", self.entities);
+
+        let vocabularies: Vec<_> = self
+            .vocabularies
+            .iter()
+            .filter_map(|x| ns.prefixes.get(x))
+            .map(|x| format!("<{}>", x))
+            .collect();
+        if !vocabularies.is_empty() {
+            buf += &format!(" ;\n  void:vocabulary {}", vocabularies.join(", "));
+        }
+
+        let contigs: Vec<_> = self
+            .contigs
+            .iter()
+            .map(|x| format!("\"{}\"", escape(x)))
+            .collect();
+        if !contigs.is_empty() {
+            buf += &format!(" ;\n  dct:coverage {}", contigs.join(", "));
+        }
+
+        buf += " .\n";
+
+        buf
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_turtle() {
+        let mut void = Void::new();
+        void.add_entry(9, Some("20"));
+        void.add_entry(9, Some("20"));
+        void.add_vocabulary("gvo");
+
+        let ttl = void.to_turtle(&Namespace::default());
+
+        assert!(ttl.contains("  void:triples 18 ;\n"));
+        assert!(ttl.contains("  void:entities 2 ;\n"));
+        assert!(ttl.contains("  void:vocabulary <http://genome-variation.org/resource#> ;\n"));
+        assert!(ttl.contains("  dct:coverage \"20\" .\n"));
+    }
+}
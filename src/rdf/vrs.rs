@@ -0,0 +1,346 @@
+//! Module for GA4GH VRS computed identifiers
+//!
+//! Implements the digest serialization of VRS 1.3 for `Allele` and `SequenceLocation`.
+use serde::Serialize;
+use sha2::{Digest, Sha512};
+
+use crate::errors::Result;
+
+const BASE64_URL: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Returns the truncated digest `sha512t24u` of `blob`.
+///
+/// The first 24 bytes of SHA-512 are encoded with URL-safe base64.
+pub fn sha512t24u(blob: &[u8]) -> String {
+    base64_url(&Sha512::digest(blob)[..24])
+}
+
+fn base64_url(bytes: &[u8]) -> String {
+    let mut buf = String::with_capacity((bytes.len() + 2) / 3 * 4);
+
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |acc, (i, &b)| acc | ((b as u32) << (16 - 8 * i)));
+
+        for i in 0..=chunk.len() {
+            buf.push(BASE64_URL[((n >> (18 - 6 * i)) & 0x3F) as usize] as char);
+        }
+    }
+
+    buf
+}
+
+/// Returns the digest part of a CURIE like `ga4gh:SQ.<digest>`.
+fn digest_of(id: &str) -> &str {
+    let id = id.strip_prefix("ga4gh:").unwrap_or(id);
+
+    match id.find('.') {
+        Some(i) if i == 2 || i == 3 => &id[i + 1..],
+        _ => id,
+    }
+}
+
+#[derive(Serialize)]
+struct Number {
+    #[serde(rename = "type")]
+    typ: &'static str,
+    value: u64,
+}
+
+impl From<u64> for Number {
+    fn from(value: u64) -> Self {
+        Number {
+            typ: "Number",
+            value,
+        }
+    }
+}
+
+// Fields of the following are declared in the order of their names, so that serde_json writes
+// the canonical form of the digest serialization, i.e. keys sorted without whitespace.
+
+#[derive(Serialize)]
+struct SequenceInterval {
+    end: Number,
+    start: Number,
+    #[serde(rename = "type")]
+    typ: &'static str,
+}
+
+#[derive(Serialize)]
+struct SequenceLocation<'a> {
+    interval: SequenceInterval,
+    sequence_id: &'a str,
+    #[serde(rename = "type")]
+    typ: &'static str,
+}
+
+#[derive(Serialize)]
+struct LiteralSequenceExpression<'a> {
+    sequence: &'a str,
+    #[serde(rename = "type")]
+    typ: &'static str,
+}
+
+#[derive(Serialize)]
+struct SerializedAllele<'a> {
+    location: &'a str,
+    state: LiteralSequenceExpression<'a>,
+    #[serde(rename = "type")]
+    typ: &'static str,
+}
+
+/// A VRS allele with a literal sequence expression on an interbase interval.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Allele {
+    /// Refget sequence identifier, e.g. `ga4gh:SQ.IIB53T8CNeJJdUqzn9V_JnRtQadwWCbl`.
+    pub sequence_id: String,
+    /// 0-based interbase start.
+    pub start: u64,
+    /// 0-based interbase end.
+    pub end: u64,
+    pub sequence: String,
+}
+
+impl Allele {
+    /// Create an allele from a 1-based VCF position and alleles.
+    ///
+    /// Common prefix and suffix of alleles are trimmed, which are fully justified by `normalize`.
+    pub fn from_vcf(sequence_id: &str, position: u64, reference: &str, alternate: &str) -> Allele {
+        let (r, a) = (reference.as_bytes(), alternate.as_bytes());
+
+        let prefix = r.iter().zip(a).take_while(|(x, y)| x == y).count();
+        let suffix = r[prefix..]
+            .iter()
+            .rev()
+            .zip(a[prefix..].iter().rev())
+            .take_while(|(x, y)| x == y)
+            .count();
+
+        let start = position - 1 + prefix as u64;
+
+        Allele {
+            sequence_id: sequence_id.to_owned(),
+            start,
+            end: start + (r.len() - prefix - suffix) as u64,
+            sequence: alternate[prefix..alternate.len() - suffix].to_owned(),
+        }
+    }
+
+    /// Fully justify the trimmed allele as the VRS normalization.
+    ///
+    /// Insertions and deletions are shuffled to the left and the right as far as the reference sequence repeats,
+    /// and the interval is expanded to cover the whole ambiguous region, e.g. deletion of `A` in `CAAAG` is
+    /// `AAA` to `AA`. Substitutions are returned as they are.
+    ///
+    /// `fetch(start, length)` returns bases of the reference sequence from the interbase `start`,
+    /// which are fewer than `length` if the range exceeds the end of the sequence.
+    pub fn normalize<F>(self, fetch: F) -> Result<Allele>
+    where
+        F: Fn(u64, usize) -> Result<String>,
+    {
+        let length = (self.end - self.start) as usize;
+
+        if (length == 0) == self.sequence.is_empty() {
+            return Ok(self);
+        }
+
+        // the inserted or deleted bases, rotated while shuffling
+        let mut bases = if length == 0 {
+            self.sequence.to_ascii_uppercase().into_bytes()
+        } else {
+            fetch(self.start, length)?.to_ascii_uppercase().into_bytes()
+        };
+
+        let mut start = self.start;
+        while start > 0 {
+            match fetch(start - 1, 1)?.to_ascii_uppercase().as_bytes() {
+                [b] if Some(b) == bases.last() => {
+                    bases.rotate_right(1);
+                    start -= 1;
+                }
+                _ => break,
+            }
+        }
+
+        let mut end = self.end;
+        let mut rotated = bases.clone();
+        loop {
+            match fetch(end, 1)?.to_ascii_uppercase().as_bytes() {
+                [b] if Some(b) == rotated.first() => {
+                    rotated.rotate_left(1);
+                    end += 1;
+                }
+                _ => break,
+            }
+        }
+
+        let region = fetch(start, (end - start) as usize)?.to_ascii_uppercase();
+        let sequence = if length == 0 {
+            format!("{}{}", String::from_utf8_lossy(&bases), region)
+        } else {
+            region[length..].to_owned()
+        };
+
+        Ok(Allele {
+            sequence_id: self.sequence_id,
+            start,
+            end,
+            sequence,
+        })
+    }
+
+    /// Returns the digest serialization of the `SequenceLocation`.
+    pub fn serialize_location(&self) -> String {
+        serde_json::to_string(&SequenceLocation {
+            interval: SequenceInterval {
+                end: Number::from(self.end),
+                start: Number::from(self.start),
+                typ: "SequenceInterval",
+            },
+            sequence_id: digest_of(&self.sequence_id),
+            typ: "SequenceLocation",
+        })
+        .expect("VRS objects are serializable")
+    }
+
+    /// Returns the digest serialization of the `Allele`.
+    ///
+    /// The nested location is replaced with its digest.
+    pub fn serialize(&self) -> String {
+        serde_json::to_string(&SerializedAllele {
+            location: &sha512t24u(self.serialize_location().as_bytes()),
+            state: LiteralSequenceExpression {
+                sequence: &self.sequence,
+                typ: "LiteralSequenceExpression",
+            },
+            typ: "Allele",
+        })
+        .expect("VRS objects are serializable")
+    }
+
+    /// Returns the computed identifier of the location, e.g. `ga4gh:VSL.<digest>`.
+    pub fn location_identifier(&self) -> String {
+        format!(
+            "ga4gh:VSL.{}",
+            sha512t24u(self.serialize_location().as_bytes())
+        )
+    }
+
+    /// Returns the computed identifier, e.g. `ga4gh:VA.<digest>`.
+    pub fn identifier(&self) -> String {
+        format!("ga4gh:VA.{}", sha512t24u(self.serialize().as_bytes()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEQUENCE_ID: &str = "ga4gh:SQ.IIB53T8CNeJJdUqzn9V_JnRtQadwWCbl";
+
+    #[test]
+    fn test_sha512t24u() {
+        // examples of the truncated digest in the VRS specification
+        assert_eq!(sha512t24u(b""), "z4PhNX7vuL3xVChQ1m2AB9Yg5AULVxXc");
+        assert_eq!(sha512t24u(b"ACGT"), "aKF498dAxcJAqme6QYQ7EZ07-fiw8Kw2");
+
+        // the example of computed identifiers in VRS 1.1, rs7412 (NC_000019.10:g.44908822C>T)
+        assert_eq!(
+            sha512t24u(br#"{"interval":{"end":44908822,"start":44908821,"type":"SimpleInterval"},"sequence_id":"IIB53T8CNeJJdUqzn9V_JnRtQadwWCbl","type":"SequenceLocation"}"#),
+            "u5fspwVbQ79QkX6GHLF8tXPCAXFJqRPx"
+        );
+        assert_eq!(
+            sha512t24u(br#"{"location":"u5fspwVbQ79QkX6GHLF8tXPCAXFJqRPx","state":{"sequence":"T","type":"SequenceState"},"type":"Allele"}"#),
+            "EgHPXXhULTwoP4-ACfs-YCXaeUQJBjH_"
+        );
+    }
+
+    #[test]
+    fn test_base64_url() {
+        assert_eq!(base64_url(b"f"), "Zg");
+        assert_eq!(base64_url(b"fo"), "Zm8");
+        assert_eq!(base64_url(b"foo"), "Zm9v");
+        assert_eq!(base64_url(&[0xfb, 0xff]), "-_8");
+    }
+
+    #[test]
+    fn test_from_vcf() {
+        let snv = Allele::from_vcf(SEQUENCE_ID, 44908822, "C", "T");
+        assert_eq!(
+            (snv.start, snv.end, snv.sequence.as_str()),
+            (44908821, 44908822, "T")
+        );
+
+        let ins = Allele::from_vcf(SEQUENCE_ID, 100, "A", "ATT");
+        assert_eq!(
+            (ins.start, ins.end, ins.sequence.as_str()),
+            (100, 100, "TT")
+        );
+
+        let del = Allele::from_vcf(SEQUENCE_ID, 100, "ATT", "A");
+        assert_eq!((del.start, del.end, del.sequence.as_str()), (100, 102, ""));
+    }
+
+    #[test]
+    fn test_normalize() {
+        let sequence = "CAAAGCTCTCTA";
+        let fetch = |start: u64, length: usize| -> Result<String> {
+            let start = (start as usize).min(sequence.len());
+            let end = (start + length).min(sequence.len());
+            Ok(sequence[start..end].to_owned())
+        };
+        let normalize = |position: u64, reference: &str, alternate: &str| {
+            let allele = Allele::from_vcf(SEQUENCE_ID, position, reference, alternate)
+                .normalize(fetch)
+                .unwrap();
+            (allele.start, allele.end, allele.sequence)
+        };
+
+        // substitution
+        assert_eq!(normalize(5, "G", "T"), (4, 5, "T".to_owned()));
+
+        // deletion of A in AAA
+        assert_eq!(normalize(1, "CA", "C"), (1, 4, "AA".to_owned()));
+
+        // insertion of A in AAA, of which the VCF position is not the leftmost
+        assert_eq!(normalize(4, "A", "AA"), (1, 4, "AAAA".to_owned()));
+
+        // insertion of CT in CTCTCT
+        assert_eq!(normalize(7, "T", "TCT"), (5, 11, "CTCTCTCT".to_owned()));
+
+        // deletion at the end of the sequence
+        assert_eq!(normalize(11, "TA", "T"), (11, 12, "".to_owned()));
+    }
+
+    #[test]
+    fn test_serialize() {
+        // the example of computed identifiers in VRS 1.3, rs7412 (NC_000019.10:g.44908822C>T)
+        let allele = Allele::from_vcf(SEQUENCE_ID, 44908822, "C", "T");
+
+        assert_eq!(
+            allele.serialize_location(),
+            r#"{"interval":{"end":{"type":"Number","value":44908822},"start":{"type":"Number","value":44908821},"type":"SequenceInterval"},"sequence_id":"IIB53T8CNeJJdUqzn9V_JnRtQadwWCbl","type":"SequenceLocation"}"#
+        );
+        assert_eq!(
+            allele.serialize(),
+            r#"{"location":"QrRSuBj-VScAGV_gEdxNgsnh41jYH1Kg","state":{"sequence":"T","type":"LiteralSequenceExpression"},"type":"Allele"}"#
+        );
+    }
+
+    #[test]
+    fn test_identifier() {
+        let allele = Allele::from_vcf(SEQUENCE_ID, 44908822, "C", "T");
+
+        assert_eq!(
+            allele.location_identifier(),
+            "ga4gh:VSL.QrRSuBj-VScAGV_gEdxNgsnh41jYH1Kg"
+        );
+        assert_eq!(
+            allele.identifier(),
+            "ga4gh:VA.CxiA_hvYbkD8Vqwjhx5AYuyul4mtlkpD"
+        );
+    }
+}
@@ -0,0 +1,173 @@
+//! Module for summarizing a conversion
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{Display, Formatter};
+use std::io::{self, Write};
+
+use log::*;
+
+/// Named counters collected during conversion and reported at the end of the run.
+///
+/// Counters added as warnings are marked in the report, which is printed only if there is any of them (see `report`).
+#[derive(Debug, Default, Clone)]
+pub struct Summary {
+    counters: BTreeMap<String, u64>,
+    warnings: BTreeSet<String>,
+}
+
+impl Summary {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Increment the counter named `key` by one.
+    pub fn increment(&mut self, key: &str) {
+        self.add(key, 1);
+    }
+
+    /// Increment the counter named `key` by `n`.
+    pub fn add(&mut self, key: &str, n: u64) {
+        match self.counters.get_mut(key) {
+            Some(v) => *v += n,
+            None => {
+                self.counters.insert(key.to_owned(), n);
+            }
+        }
+    }
+
+    /// Increment the counter named `key` by one as a warning.
+    pub fn warn(&mut self, key: &str) {
+        self.add_warning(key, 1);
+    }
+
+    /// Increment the counter named `key` by `n` as a warning, which is not raised if `n` is zero.
+    pub fn add_warning(&mut self, key: &str, n: u64) {
+        self.add(key, n);
+        if n > 0 {
+            self.warnings.insert(key.to_owned());
+        }
+    }
+
+    pub fn get(&self, key: &str) -> u64 {
+        self.counters.get(key).copied().unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counters.is_empty()
+    }
+
+    pub fn has_warnings(&self) -> bool {
+        !self.warnings.is_empty()
+    }
+
+    pub fn is_warning(&self, key: &str) -> bool {
+        self.warnings.contains(key)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &u64)> {
+        self.counters.iter()
+    }
+
+    /// Write the summary to `wtr` if it has warnings, otherwise log counters at the info level.
+    ///
+    /// A run without warnings thus prints nothing unless `RUST_LOG=info` is set.
+    pub fn report<W: Write>(&self, mut wtr: W) -> io::Result<()> {
+        if self.has_warnings() {
+            write!(wtr, "{}", self)?;
+        } else {
+            for (key, value) in self.iter() {
+                info!("{}: {}", key, value);
+            }
+        }
+
+        wtr.flush()
+    }
+
+    /// Add all counters of `other` to `self`, keeping warnings.
+    pub fn merge(&mut self, other: &Summary) {
+        for (k, v) in other.iter() {
+            if other.is_warning(k) {
+                self.add_warning(k, *v);
+            } else {
+                self.add(k, *v);
+            }
+        }
+    }
+}
+
+impl Display for Summary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (k, v) in self.counters.iter() {
+            if self.is_warning(k) {
+                writeln!(f, "{}: {} (warning)", k, v)?;
+            } else {
+                writeln!(f, "{}: {}", k, v)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add() {
+        let mut summary = Summary::new();
+
+        summary.increment("records");
+        summary.add("records", 2);
+
+        assert_eq!(summary.get("records"), 3);
+        assert_eq!(summary.get("entries"), 0);
+    }
+
+    #[test]
+    fn test_display() {
+        let mut summary = Summary::new();
+
+        summary.add("b", 2);
+        summary.add("a", 1);
+
+        assert_eq!(summary.to_string(), "a: 1\nb: 2\n");
+    }
+
+    #[test]
+    fn test_warnings() {
+        let mut summary = Summary::new();
+
+        summary.increment("records");
+        summary.add_warning("unmapped", 0);
+        assert!(!summary.has_warnings());
+
+        summary.warn("duplicates");
+        assert!(summary.has_warnings());
+        assert_eq!(
+            summary.to_string(),
+            "duplicates: 1 (warning)\nrecords: 1\nunmapped: 0\n"
+        );
+
+        let mut merged = Summary::new();
+        merged.merge(&summary);
+        assert!(merged.is_warning("duplicates"));
+        assert!(!merged.is_warning("records"));
+    }
+
+    #[test]
+    fn test_report() {
+        let mut summary = Summary::new();
+        summary.increment("records");
+
+        let mut out = Vec::new();
+        summary.report(&mut out).unwrap();
+        assert!(out.is_empty());
+
+        summary.warn("duplicates");
+        summary.report(&mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "duplicates: 1 (warning)\nrecords: 1\n"
+        );
+    }
+}
@@ -1,3 +1,8 @@
 //! Module for utilities
+pub mod bloom;
+pub mod file;
+#[cfg(test)]
+pub mod fixture;
 pub mod path;
+pub mod template;
 pub mod vcf;
@@ -0,0 +1,174 @@
+//! Module for a bloom filter of strings
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// A set of strings in fixed memory, which may answer that a string was inserted although it was not.
+#[derive(Debug, Clone)]
+pub struct BloomFilter {
+    bits: Vec<u64>,
+    hashes: u32,
+}
+
+impl BloomFilter {
+    /// Create a filter of `bits` (rounded up to a multiple of 64) probed by `hashes` hash functions.
+    pub fn new(bits: u64, hashes: u32) -> BloomFilter {
+        BloomFilter {
+            bits: vec![0; ((bits.max(1) - 1) / 64 + 1) as usize],
+            hashes: hashes.max(1),
+        }
+    }
+
+    /// Returns bit positions of `value` by double hashing.
+    fn positions(&self, value: &str) -> impl Iterator<Item = u64> {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        let hash = hasher.finish();
+
+        let len = self.bits.len() as u64 * 64;
+        let (h1, h2) = (hash & 0xFFFF_FFFF, (hash >> 32) | 1);
+
+        (0..self.hashes as u64).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % len)
+    }
+
+    /// Returns true if `value` may have been inserted.
+    pub fn contains(&self, value: &str) -> bool {
+        self.positions(value)
+            .all(|x| self.bits[(x / 64) as usize] & (1 << (x % 64)) != 0)
+    }
+
+    /// Insert `value` and returns true if it may have been inserted before.
+    pub fn insert(&mut self, value: &str) -> bool {
+        let mut found = true;
+
+        for x in self.positions(value).collect::<Vec<_>>() {
+            let word = &mut self.bits[(x / 64) as usize];
+            if *word & (1 << (x % 64)) == 0 {
+                *word |= 1 << (x % 64);
+                found = false;
+            }
+        }
+
+        found
+    }
+}
+
+/// Capacity of the first filter of `GrowingBloomFilter`.
+const INITIAL_CAPACITY: u64 = 1 << 20;
+
+/// False positive rate of the first filter of `GrowingBloomFilter`, multiplied by `TIGHTENING` for each following one.
+const INITIAL_ERROR_RATE: f64 = 0.002;
+const TIGHTENING: f64 = 0.8;
+
+/// A set of strings in memory growing with the number of them, which chains filters of doubling capacity.
+///
+/// The false positive rate stays below 1% in total, using about 1.6 MiB up to a million strings,
+/// 26 MiB up to 10 million and 240 MiB up to 100 million.
+#[derive(Debug, Clone)]
+pub struct GrowingBloomFilter {
+    filters: Vec<BloomFilter>,
+    capacity: u64,
+    error_rate: f64,
+    // strings inserted to the last filter
+    len: u64,
+}
+
+impl Default for GrowingBloomFilter {
+    fn default() -> Self {
+        Self::with_capacity(INITIAL_CAPACITY, INITIAL_ERROR_RATE)
+    }
+}
+
+impl GrowingBloomFilter {
+    /// Create a filter of which the first stage holds `capacity` strings at `error_rate`.
+    ///
+    /// Memory is not allocated until the first string is inserted.
+    pub fn with_capacity(capacity: u64, error_rate: f64) -> GrowingBloomFilter {
+        GrowingBloomFilter {
+            filters: Vec::new(),
+            capacity: capacity.max(1),
+            error_rate,
+            len: 0,
+        }
+    }
+
+    /// Returns true if `value` may have been inserted.
+    pub fn contains(&self, value: &str) -> bool {
+        self.filters.iter().any(|x| x.contains(value))
+    }
+
+    /// Insert `value` and returns true if it may have been inserted before.
+    pub fn insert(&mut self, value: &str) -> bool {
+        if self.contains(value) {
+            return true;
+        }
+
+        if self.filters.is_empty() || self.len >= self.capacity {
+            self.grow();
+        }
+        self.len += 1;
+
+        match self.filters.last_mut() {
+            Some(filter) => filter.insert(value),
+            None => false,
+        }
+    }
+
+    /// Add a filter of twice the capacity at a tighter false positive rate.
+    fn grow(&mut self) {
+        if !self.filters.is_empty() {
+            self.capacity *= 2;
+            self.error_rate *= TIGHTENING;
+        }
+        self.len = 0;
+
+        let ln2 = std::f64::consts::LN_2;
+        let bits = (self.capacity as f64 * -self.error_rate.ln() / (ln2 * ln2)).ceil() as u64;
+        let hashes = (-self.error_rate.log2()).ceil() as u32;
+
+        self.filters.push(BloomFilter::new(bits, hashes));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert() {
+        let mut filter = BloomFilter::new(1024, 3);
+
+        assert!(!filter.contains("rs6054257"));
+        assert!(!filter.insert("rs6054257"));
+        assert!(filter.contains("rs6054257"));
+        assert!(filter.insert("rs6054257"));
+        assert!(!filter.insert("rs6040355"));
+    }
+
+    #[test]
+    fn test_false_positives() {
+        let mut filter = BloomFilter::new(1 << 16, 7);
+
+        let hits = (0..4096)
+            .filter(|i| filter.insert(&format!("rs{}", i)))
+            .count();
+
+        // the false positive rate is below 0.1% up to 4096 values in 65536 bits
+        assert!(hits < 10, "{} false positives", hits);
+    }
+
+    #[test]
+    fn test_growing_filter() {
+        let mut filter = GrowingBloomFilter::with_capacity(1024, 0.01);
+
+        assert!(filter.filters.is_empty());
+
+        let hits = (0..16384)
+            .filter(|i| filter.insert(&format!("rs{}", i)))
+            .count();
+
+        // 1024 + 2048 + 4096 + 8192 + 16384 values
+        assert_eq!(filter.filters.len(), 5);
+        assert!((0..16384).all(|i| filter.contains(&format!("rs{}", i))));
+        assert!(hits < 164, "{} false positives", hits);
+    }
+}
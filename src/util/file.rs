@@ -0,0 +1,69 @@
+//! Module for file utilities
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// A file which is not created until something is written.
+#[derive(Debug)]
+pub struct LazyFile {
+    path: PathBuf,
+    file: Option<File>,
+}
+
+impl LazyFile {
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        LazyFile {
+            path: path.as_ref().to_path_buf(),
+            file: None,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns true if the file has been created.
+    pub fn is_created(&self) -> bool {
+        self.file.is_some()
+    }
+
+    fn file(&mut self) -> io::Result<&mut File> {
+        if self.file.is_none() {
+            self.file = Some(File::create(&self.path)?);
+        }
+
+        Ok(self.file.as_mut().unwrap())
+    }
+}
+
+impl Write for LazyFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.file()?.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self.file.as_mut() {
+            Some(file) => file.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lazy_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.ttl");
+
+        let mut file = LazyFile::new(&path);
+        file.flush().unwrap();
+        assert!(!path.exists());
+
+        file.write_all(b"data").unwrap();
+        file.flush().unwrap();
+        assert!(path.exists());
+    }
+}
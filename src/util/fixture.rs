@@ -0,0 +1,68 @@
+//! Test fixtures compressed at runtime
+use std::ffi::CString;
+use std::path::PathBuf;
+
+use rust_htslib::bcf;
+use rust_htslib::bcf::Read;
+use rust_htslib::htslib;
+use tempfile::TempDir;
+
+use crate::util::vcf::compress;
+
+/// Compress `test/{name}.vcf` with an index into a temporary directory.
+///
+/// Returns the directory, which is removed on drop, and the path to the compressed file.
+pub fn bgzip(name: &str) -> (TempDir, PathBuf) {
+    let dir = tempfile::tempdir().expect("Error creating directory.");
+    let output = dir.path().join(format!("{}.vcf.gz", name));
+
+    compress::from_path(
+        PathBuf::from(format!("test/{}.vcf", name)),
+        Some(output.clone()),
+        None,
+        true,
+    )
+    .expect("Error compressing fixture.");
+
+    (dir, output)
+}
+
+/// Convert `test/{name}.vcf` to BCF with a CSI index into a temporary directory.
+///
+/// Contigs missing in the header are declared in the order they appear, as BCF requires.
+pub fn bcf(name: &str) -> (TempDir, PathBuf) {
+    bcf_with_header(name, |_| {})
+}
+
+/// Convert `test/{name}.vcf` to BCF as [`bcf`] does, with the header modified by `edit`.
+///
+/// Records are written as they are, so values may be encoded in other types than declared.
+pub fn bcf_with_header<F: FnOnce(&mut bcf::Header)>(name: &str, edit: F) -> (TempDir, PathBuf) {
+    let dir = tempfile::tempdir().expect("Error creating directory.");
+    let output = dir.path().join(format!("{}.bcf", name));
+
+    let mut reader =
+        bcf::Reader::from_path(format!("test/{}.vcf", name)).expect("Error opening fixture.");
+    let records: Vec<bcf::Record> = reader
+        .records()
+        .map(|x| x.expect("Error reading fixture."))
+        .collect();
+
+    // the header has contigs of records read so far
+    let mut header = bcf::Header::from_template(reader.header());
+    edit(&mut header);
+    {
+        let mut writer = bcf::Writer::from_path(&output, &header, false, bcf::Format::BCF)
+            .expect("Error creating fixture.");
+        for mut record in records {
+            writer.translate(&mut record);
+            writer.write(&record).expect("Error writing fixture.");
+        }
+    }
+
+    let path = CString::new(output.to_string_lossy().as_bytes()).unwrap();
+    let ret = unsafe { htslib::bcf_index_build(path.as_ptr(), 14) };
+    assert_eq!(ret, 0, "Error indexing fixture.");
+
+    (dir, output)
+}
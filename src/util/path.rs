@@ -12,3 +12,108 @@ pub fn change_extension<P: AsRef<Path>, S: AsRef<OsStr>>(path: P, ext: S) -> Res
 
     Ok(path)
 }
+
+/// Expand `*` and `?` in the file name of `pattern` to existing files sorted by name,
+/// e.g. `data/chr*.vcf.gz`. Wildcards in directories are not expanded.
+///
+/// Returns `pattern` as is if the file name has no wildcards.
+pub fn glob<P: AsRef<Path>>(pattern: P) -> Result<Vec<PathBuf>> {
+    let pattern = pattern.as_ref();
+
+    let name = match pattern.file_name().and_then(|x| x.to_str()) {
+        Some(name) if name.contains(|c| c == '*' || c == '?') => name,
+        _ => return Ok(vec![pattern.to_path_buf()]),
+    };
+
+    let dir = match pattern.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+
+    let mut paths = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        if let Some(file_name) = entry?.file_name().to_str() {
+            if wildcard_match(name, file_name) {
+                paths.push(pattern.with_file_name(file_name));
+            }
+        }
+    }
+
+    if paths.is_empty() {
+        Err(Error::FileNotFoundError(
+            pattern.to_string_lossy().to_string(),
+        ))?
+    }
+
+    paths.sort();
+
+    Ok(paths)
+}
+
+/// Returns true if `name` matches `pattern` where `*` matches any characters and `?` matches one.
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let name: Vec<char> = name.chars().collect();
+
+    // whether the pattern read so far matches `name[..j]`
+    let mut matched = vec![false; name.len() + 1];
+    matched[0] = true;
+
+    for c in pattern.chars() {
+        let mut next = vec![false; name.len() + 1];
+
+        if c == '*' {
+            let mut any = false;
+            for j in 0..=name.len() {
+                any |= matched[j];
+                next[j] = any;
+            }
+        } else {
+            for j in 1..=name.len() {
+                next[j] = matched[j - 1] && (c == '?' || c == name[j - 1]);
+            }
+        }
+
+        matched = next;
+    }
+
+    matched[name.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob() {
+        assert_eq!(
+            glob("test/vcf_spec.vcf*").unwrap(),
+            vec![
+                PathBuf::from("test/vcf_spec.vcf"),
+                PathBuf::from("test/vcf_spec.vcf.gz"),
+                PathBuf::from("test/vcf_spec.vcf.gz.tbi"),
+            ]
+        );
+        assert_eq!(
+            glob("test/v?sc_spec.vcf.gz").unwrap(),
+            vec![PathBuf::from("test/visc_spec.vcf.gz")]
+        );
+        assert_eq!(
+            glob("test/not_exist.vcf").unwrap(),
+            vec![PathBuf::from("test/not_exist.vcf")]
+        );
+        assert!(glob("test/*.not_exist").is_err());
+    }
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match("chr*.vcf.gz", "chr1.vcf.gz"));
+        assert!(wildcard_match("chr*.vcf.gz", "chr.vcf.gz"));
+        assert!(wildcard_match("chr?.vcf.gz", "chrX.vcf.gz"));
+        assert!(wildcard_match("*", ""));
+        assert!(wildcard_match("a*b*c", "aXbYbc"));
+
+        assert!(!wildcard_match("chr?.vcf.gz", "chr10.vcf.gz"));
+        assert!(!wildcard_match("chr*.vcf.gz", "chr1.vcf.gz.tbi"));
+        assert!(!wildcard_match("?", ""));
+    }
+}
@@ -0,0 +1,155 @@
+//! Module for string templates with `{name}` placeholders
+use crate::errors::{Error, Result};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Part {
+    Literal(String),
+    /// A placeholder with optional zero-padded width, e.g. `{chunk:04}`.
+    Placeholder {
+        name: String,
+        width: Option<usize>,
+    },
+}
+
+/// A template compiled once and rendered many times.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    source: String,
+    parts: Vec<Part>,
+}
+
+impl Template {
+    /// Compile `template` accepting only `placeholders`.
+    ///
+    /// Fails on unknown placeholders, invalid widths or unbalanced braces.
+    pub fn parse(template: &str, placeholders: &[&str]) -> Result<Template> {
+        let mut parts = Vec::new();
+        let mut rest = template;
+
+        while !rest.is_empty() {
+            match (rest.find('{'), rest.find('}')) {
+                (Some(open), Some(close)) if open < close => {
+                    if open > 0 {
+                        parts.push(Part::Literal(rest[..open].to_owned()));
+                    }
+
+                    let (name, width) = match rest[open + 1..close].split_once(':') {
+                        Some((name, width)) => match width.parse::<usize>() {
+                            Ok(n) if width.starts_with('0') => (name, Some(n)),
+                            _ => Err(Error::InvalidTemplateError(format!(
+                                "invalid width {{{}:{}}}, use zero-padded width like {{{}:04}}",
+                                name, width, name
+                            )))?,
+                        },
+                        None => (&rest[open + 1..close], None),
+                    };
+
+                    if !placeholders.contains(&name) {
+                        Err(Error::InvalidTemplateError(format!(
+                            "unknown placeholder {{{}}}, available placeholders are {}",
+                            name,
+                            placeholders
+                                .iter()
+                                .map(|x| format!("{{{}}}", x))
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        )))?
+                    }
+
+                    parts.push(Part::Placeholder {
+                        name: name.to_owned(),
+                        width,
+                    });
+
+                    rest = &rest[close + 1..];
+                }
+                (None, None) => {
+                    parts.push(Part::Literal(rest.to_owned()));
+                    rest = "";
+                }
+                _ => Err(Error::InvalidTemplateError(format!(
+                    "unbalanced braces in {}",
+                    template
+                )))?,
+            }
+        }
+
+        Ok(Template {
+            source: template.to_owned(),
+            parts,
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Returns true if the template contains the placeholder `name`.
+    pub fn contains(&self, name: &str) -> bool {
+        self.parts.iter().any(|x| match x {
+            Part::Placeholder { name: n, .. } => n == name,
+            _ => false,
+        })
+    }
+
+    /// Returns true if the template contains any placeholder satisfying `f`.
+    pub fn any_placeholder<F: Fn(&str) -> bool>(&self, f: F) -> bool {
+        self.parts.iter().any(|x| match x {
+            Part::Placeholder { name, .. } => f(name),
+            _ => false,
+        })
+    }
+
+    /// Render the template with values returned by `value`.
+    ///
+    /// Returns `None` if `value` returns `None` for any placeholder.
+    pub fn render<F: FnMut(&str) -> Option<String>>(&self, mut value: F) -> Option<String> {
+        let mut buf = String::with_capacity(self.source.len());
+
+        for part in &self.parts {
+            match part {
+                Part::Literal(v) => buf.push_str(v),
+                Part::Placeholder { name, width } => {
+                    let v = value(name)?;
+                    match width {
+                        Some(width) => buf.push_str(&format!("{:0>width$}", v, width = width)),
+                        None => buf.push_str(&v),
+                    }
+                }
+            }
+        }
+
+        Some(buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLACEHOLDERS: &[&str] = &["a", "b"];
+
+    #[test]
+    fn test_parse() {
+        assert!(Template::parse("x/{a}/{b:04}", PLACEHOLDERS).is_ok());
+        assert!(Template::parse("no placeholders", PLACEHOLDERS).is_ok());
+        assert!(Template::parse("{c}", PLACEHOLDERS).is_err());
+        assert!(Template::parse("{a", PLACEHOLDERS).is_err());
+        assert!(Template::parse("a}", PLACEHOLDERS).is_err());
+        assert!(Template::parse("{a:4}", PLACEHOLDERS).is_err());
+        assert!(Template::parse("{a:x}", PLACEHOLDERS).is_err());
+    }
+
+    #[test]
+    fn test_render() {
+        let template = Template::parse("x/{a}/{b:04}", PLACEHOLDERS).unwrap();
+
+        assert!(template.contains("a"));
+        assert!(!template.contains("c"));
+        assert_eq!(
+            template.render(|name| Some(if name == "a" { "y" } else { "12" }.to_owned())),
+            Some("x/y/0012".to_owned())
+        );
+        assert_eq!(template.render(|_| None), None);
+    }
+}
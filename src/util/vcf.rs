@@ -1,11 +1,12 @@
 //! Module for utility functions with VCF
+use std::ffi::CString;
 use std::path::Path;
-use std::rc::Rc;
 
-use rust_htslib::bcf;
+use rust_htslib::bcf::{self, Read};
 use rust_htslib::htslib;
+use url::Url;
 
-use crate::errors::Result;
+use crate::errors::{Error, Result};
 
 pub mod compress;
 pub mod tabix;
@@ -74,25 +75,64 @@ pub mod tabix;
 /// pub type htsCompression = u32;
 /// ```
 pub fn get_format<P: AsRef<Path>>(path: P) -> Result<htslib::htsFormat> {
+    let path = path.as_ref();
+    let p = CString::new(
+        path.to_str()
+            .ok_or_else(|| Error::FilePathError(path.display().to_string()))?,
+    )?;
+    let mode = CString::new("r")?;
+
     let format = unsafe {
-        let bcf: Reader = std::mem::transmute(bcf::Reader::from_path(&path)?);
-        *htslib::hts_get_format(bcf.inner)
+        let fp = htslib::hts_open(p.as_ptr(), mode.as_ptr());
+        if fp.is_null() {
+            Err(Error::FileNotFoundError(path.display().to_string()))?
+        }
+        let format = *htslib::hts_get_format(fp);
+        htslib::hts_close(fp);
+        format
     };
 
     Ok(format)
 }
 
-struct Reader {
-    inner: *mut htslib::htsFile,
-    _header: Rc<bcf::header::HeaderView>,
+/// Returns true if `path` is a URL such as `https://...` or `s3://...`, which htslib reads remotely.
+pub fn is_url(path: &str) -> bool {
+    path.split_once("://").map_or(false, |(scheme, _)| {
+        !scheme.is_empty()
+            && scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "+.-".contains(c))
+    })
 }
 
-impl Drop for Reader {
-    fn drop(&mut self) {
-        unsafe {
-            htslib::hts_close(self.inner);
-        }
+/// Open a local file or a URL.
+///
+/// `bcf::Reader::from_path` refuses URLs since they do not exist on the local filesystem.
+pub fn open<P: AsRef<Path>>(path: P) -> Result<bcf::Reader> {
+    let url = match path.as_ref().to_str() {
+        Some(p) if is_url(p) => p,
+        _ => return Ok(bcf::Reader::from_path(path)?),
+    };
+
+    let reader = Url::parse(url)
+        .ok()
+        .and_then(|x| bcf::Reader::from_url(&x).ok())
+        .ok_or_else(|| {
+            Error::RemoteInputError(
+                url.to_owned(),
+                "could not connect or the file does not exist".to_owned(),
+            )
+        })?;
+
+    // `bcf::Reader` does not check the header
+    if reader.header().inner.is_null() {
+        Err(Error::RemoteInputError(
+            url.to_owned(),
+            "could not read the header".to_owned(),
+        ))?
     }
+
+    Ok(reader)
 }
 
 #[cfg(test)]
@@ -120,4 +160,38 @@ mod tests {
         assert_eq!(format.version.minor, 2);
         assert_eq!(format.compression, 2);
     }
+
+    #[test]
+    fn test_is_url() {
+        assert!(is_url(
+            "https://ftp.ncbi.nih.gov/snp/latest_release/VCF/GCF_000001405.25.gz"
+        ));
+        assert!(is_url("s3://bucket/input.vcf.gz"));
+        assert!(is_url("s3+https://bucket/input.vcf.gz"));
+
+        assert!(!is_url("input.vcf.gz"));
+        assert!(!is_url("/data/input.vcf.gz"));
+        assert!(!is_url("://input.vcf.gz"));
+        assert!(!is_url("data/a://b.vcf.gz"));
+    }
+
+    #[test]
+    fn test_open_url() {
+        let err = open("http://127.0.0.1:1/input.vcf.gz").expect_err("unexpected result");
+
+        assert_eq!(
+            err.to_string(),
+            "Failed to read remote input http://127.0.0.1:1/input.vcf.gz: could not connect or the file does not exist"
+        );
+    }
+
+    #[test]
+    fn test_get_format_bcf() {
+        let (_dir, path) = crate::util::fixture::bcf("dbsnp_example");
+        let format = get_format(path).unwrap();
+
+        assert_eq!(format.category, 2);
+        assert_eq!(format.format, 9);
+        assert_eq!(format.compression, 2);
+    }
 }
@@ -1,5 +1,15 @@
 //! Module for working with VCF
+pub mod alteration;
 pub mod assembly;
+pub mod breakend;
+pub mod exclude;
+pub mod fasta;
+pub mod genotype;
+pub mod header;
 pub mod reader;
 pub mod record;
+pub mod region;
+pub mod sample;
+pub mod sv;
 pub mod tabix;
+pub mod vep;
@@ -0,0 +1,349 @@
+//! Module for normalizing alterations of alleles
+use crate::errors::{Error, Result};
+use crate::vcf::fasta::Fasta;
+
+/// A type of alteration between normalized alleles.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum VariantType {
+    SNV,
+    MNV,
+    Insertion,
+    Deletion,
+    Indel,
+}
+
+impl VariantType {
+    /// Returns the name, e.g. `SNV` or `Deletion`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VariantType::SNV => "SNV",
+            VariantType::MNV => "MNV",
+            VariantType::Insertion => "Insertion",
+            VariantType::Deletion => "Deletion",
+            VariantType::Indel => "Indel",
+        }
+    }
+}
+
+/// An alteration of `reference` to `alternate` at `position`, trimmed of shared bases.
+///
+/// Subjects and locations of an entry must be derived from the same alteration.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Alteration<'a> {
+    pub position: u64,
+    pub reference: &'a str,
+    pub alternate: &'a str,
+    /// `None` if the alleles are the same.
+    pub typ: Option<VariantType>,
+}
+
+impl<'a> Alteration<'a> {
+    /// Trim bases shared by `reference` and `alternate` at `position` and classify the alteration.
+    ///
+    /// The common suffix is trimmed first and then the common prefix, while both alleles keep at least one base,
+    /// so that insertions and deletions stay anchored by the preceding base as in VCF,
+    /// e.g. `100 CTG CAG` to `101 T A` and `100 CAT CT` to `100 CA C`.
+    /// Alleles are not left-aligned through repeats, since the reference sequence is not available.
+    pub fn normalize(position: u64, reference: &'a str, alternate: &'a str) -> Result<Self> {
+        if reference.is_empty() || alternate.is_empty() {
+            Err(Error::InvalidRefAltError)?
+        }
+
+        let shared = |x: &u8, y: &u8| x == y && x.is_ascii();
+
+        let (r, a) = (reference.as_bytes(), alternate.as_bytes());
+        let suffix = r
+            .iter()
+            .rev()
+            .zip(a.iter().rev())
+            .take(r.len().min(a.len()) - 1)
+            .take_while(|(x, y)| shared(x, y))
+            .count();
+
+        let (r, a) = (&r[..r.len() - suffix], &a[..a.len() - suffix]);
+        let prefix = r
+            .iter()
+            .zip(a)
+            .take(r.len().min(a.len()) - 1)
+            .take_while(|(x, y)| shared(x, y))
+            .count();
+
+        let reference = &reference[prefix..reference.len() - suffix];
+        let alternate = &alternate[prefix..alternate.len() - suffix];
+
+        Ok(Alteration {
+            position: position + prefix as u64,
+            reference,
+            alternate,
+            typ: variant_type(reference, alternate),
+        })
+    }
+}
+
+/// A variant in the canonical form written by the converter, see `normalize_variant`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NormalizedVariant {
+    /// 1-based position of the first base of `reference`.
+    pub position: u64,
+    pub reference: String,
+    pub alternate: String,
+    /// `None` if the alleles are the same.
+    pub typ: Option<VariantType>,
+}
+
+/// Returns the canonical form of `reference` to `alternate` at `position` (1-based).
+///
+/// Alleles are uppercased and must consist of IUPAC codes. Shared bases are then trimmed by
+/// `Alteration::normalize`, keeping the preceding base of insertions and deletions as in VCF,
+/// e.g. `100 ctg cag` to `101 T A` (SNV) and `100 CAT CT` to `100 CA C` (Deletion).
+/// Indels are not shifted through repeats unless aligned by `left_align`.
+pub fn normalize_variant(
+    position: u64,
+    reference: &str,
+    alternate: &str,
+) -> Result<NormalizedVariant> {
+    let is_iupac = |x: &str| {
+        !x.is_empty()
+            && x.bytes()
+                .all(|b| b"ACGTURYKMSWBDHVN".contains(&b.to_ascii_uppercase()))
+    };
+    for allele in &[reference, alternate] {
+        if !is_iupac(allele) {
+            Err(Error::InvalidAlleleError(allele.to_string()))?
+        }
+    }
+
+    let (reference, alternate) = (
+        reference.to_ascii_uppercase(),
+        alternate.to_ascii_uppercase(),
+    );
+    let x = Alteration::normalize(position, &reference, &alternate)?;
+
+    Ok(NormalizedVariant {
+        position: x.position,
+        reference: x.reference.to_owned(),
+        alternate: x.alternate.to_owned(),
+        typ: x.typ,
+    })
+}
+
+/// Shift `variant` on `contig` to the leftmost position of the same alteration by bases in `fasta`, then normalize it.
+///
+/// E.g. deletion of `A` in `TAAA` at 98 is aligned from `100 AA A` to `98 TA T`.
+pub fn left_align(
+    fasta: &Fasta,
+    contig: &str,
+    variant: &NormalizedVariant,
+) -> Result<NormalizedVariant> {
+    let name = fasta
+        .resolve(contig)
+        .ok_or_else(|| Error::InvalidArgumentError(format!("{} is not found in FASTA", contig)))?;
+
+    let mut position = variant.position;
+    let mut reference = variant.reference.clone();
+    let mut alternate = variant.alternate.clone();
+
+    loop {
+        let single = reference.len() == 1 || alternate.len() == 1;
+
+        if !reference.is_empty()
+            && !alternate.is_empty()
+            && reference.as_bytes().last() == alternate.as_bytes().last()
+            && !(single && position == 1)
+        {
+            reference.pop();
+            alternate.pop();
+        } else if reference.is_empty() || alternate.is_empty() {
+            let base = fasta.fetch(&name, position - 1, 1)?.to_ascii_uppercase();
+            if base.len() != 1 {
+                Err(Error::InvalidArgumentError(format!(
+                    "{}:{} is not found in FASTA",
+                    contig,
+                    position - 1
+                )))?
+            }
+            reference.insert_str(0, &base);
+            alternate.insert_str(0, &base);
+            position -= 1;
+        } else {
+            break;
+        }
+    }
+
+    normalize_variant(position, &reference, &alternate)
+}
+
+/// Returns the type of alteration between normalized alleles, or `None` if they are the same.
+///
+/// Insertions and deletions are anchored by the first base, e.g. `A` to `AT`.
+pub fn variant_type(reference: &str, alternate: &str) -> Option<VariantType> {
+    if reference.is_empty() || alternate.is_empty() || reference == alternate {
+        return None;
+    }
+
+    let anchored = |short: &str, long: &str| short.len() == 1 && long.starts_with(short);
+
+    Some(match (reference.len(), alternate.len()) {
+        (1, 1) => VariantType::SNV,
+        (r, a) if r == a => VariantType::MNV,
+        (r, a) if r < a && anchored(reference, alternate) => VariantType::Insertion,
+        (r, a) if r > a && anchored(alternate, reference) => VariantType::Deletion,
+        _ => VariantType::Indel,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn normalize<'a>(
+        position: u64,
+        reference: &'a str,
+        alternate: &'a str,
+    ) -> (u64, &'a str, &'a str) {
+        let x = Alteration::normalize(position, reference, alternate).unwrap();
+        (x.position, x.reference, x.alternate)
+    }
+
+    fn typ(reference: &str, alternate: &str) -> Option<VariantType> {
+        Alteration::normalize(100, reference, alternate)
+            .unwrap()
+            .typ
+    }
+
+    #[test]
+    fn test_normalize() {
+        // SNV
+        assert_eq!(normalize(100, "G", "A"), (100, "G", "A"));
+        assert_eq!(normalize(100, "CTG", "CAG"), (101, "T", "A"));
+        // MNV
+        assert_eq!(normalize(100, "CTGA", "CAAA"), (101, "TG", "AA"));
+        // deletion
+        assert_eq!(normalize(100, "GTC", "G"), (100, "GTC", "G"));
+        assert_eq!(normalize(100, "CAT", "CT"), (100, "CA", "C"));
+        // insertion
+        assert_eq!(normalize(100, "GTC", "GTCT"), (102, "C", "CT"));
+        assert_eq!(normalize(100, "AT", "ATAT"), (100, "A", "ATA"));
+        // indel
+        assert_eq!(normalize(100, "ATGC", "AGGC"), (101, "T", "G"));
+        assert_eq!(normalize(100, "CATG", "CGG"), (101, "AT", "G"));
+
+        assert!(Alteration::normalize(100, "", "A").is_err());
+    }
+
+    #[test]
+    fn test_normalize_overlapping_prefix_and_suffix() {
+        // the shared prefix and suffix overlap in the repeat, but one base is kept
+        assert_eq!(normalize(100, "AA", "AAA"), (100, "A", "AA"));
+        assert_eq!(normalize(100, "AAA", "AA"), (100, "AA", "A"));
+        assert_eq!(normalize(100, "A", "A"), (100, "A", "A"));
+        assert_eq!(normalize(100, "ATA", "ATA"), (100, "A", "A"));
+    }
+
+    #[test]
+    fn test_variant_type() {
+        assert_eq!(typ("CTG", "CAG"), Some(VariantType::SNV));
+        assert_eq!(typ("CTGA", "CAAA"), Some(VariantType::MNV));
+        assert_eq!(typ("CAT", "CT"), Some(VariantType::Deletion));
+        assert_eq!(typ("GTC", "GTCT"), Some(VariantType::Insertion));
+        assert_eq!(typ("CATG", "CGG"), Some(VariantType::Indel));
+        assert_eq!(typ("ACG", "ACG"), None);
+    }
+
+    #[test]
+    fn test_normalize_variant() {
+        assert_eq!(
+            normalize_variant(100, "ctg", "cag").unwrap(),
+            NormalizedVariant {
+                position: 101,
+                reference: "T".to_owned(),
+                alternate: "A".to_owned(),
+                typ: Some(VariantType::SNV),
+            }
+        );
+        assert_eq!(
+            normalize_variant(100, "CAT", "CT").unwrap().typ,
+            Some(VariantType::Deletion)
+        );
+        assert!(normalize_variant(100, "A", "<DEL>").is_err());
+        assert!(normalize_variant(100, "A", "*").is_err());
+        assert!(normalize_variant(100, "", "A").is_err());
+    }
+
+    #[test]
+    fn test_left_align() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ref.fa");
+        // 1-based: T at 98, A at 99..=101, then G
+        std::fs::write(&path, format!(">chr20\n{}TAAAGCAGCAG\n", "C".repeat(97))).unwrap();
+        let fasta = Fasta::from_path(&path).unwrap();
+
+        let align = |position, reference, alternate| {
+            let variant = normalize_variant(position, reference, alternate).unwrap();
+            let x = left_align(&fasta, "20", &variant).unwrap();
+            (x.position, x.reference, x.alternate)
+        };
+
+        // deletion of A
+        assert_eq!(align(100, "AA", "A"), (98, "TA".to_owned(), "T".to_owned()));
+        // insertion of A
+        assert_eq!(align(101, "A", "AA"), (98, "T".to_owned(), "TA".to_owned()));
+        // deletion of GCA at 105 in AAAGCAGCAG, which is the same as AGC at 101
+        assert_eq!(
+            align(104, "AGCA", "A"),
+            (100, "AAGC".to_owned(), "A".to_owned())
+        );
+        // SNV is not moved
+        assert_eq!(align(99, "A", "G"), (99, "A".to_owned(), "G".to_owned()));
+
+        let variant = normalize_variant(100, "AA", "A").unwrap();
+        assert!(left_align(&fasta, "21", &variant).is_err());
+    }
+
+    /// Alleles of up to `length` bases of `A` and `C`.
+    fn alleles(length: usize) -> Vec<String> {
+        (1..=length)
+            .flat_map(|n| {
+                (0..1 << n).map(move |bits: u32| {
+                    (0..n)
+                        .map(|i| if bits >> i & 1 == 1 { 'C' } else { 'A' })
+                        .collect()
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_normalize_exhaustively() {
+        let alleles = alleles(4);
+
+        for r in &alleles {
+            for a in &alleles {
+                let x = Alteration::normalize(100, r, a).unwrap();
+                let offset = (x.position - 100) as usize;
+
+                // trimmed alleles are found at the same offset of the original
+                assert_eq!(&r[offset..offset + x.reference.len()], x.reference);
+                assert_eq!(&a[offset..offset + x.alternate.len()], x.alternate);
+                assert!(!x.reference.is_empty() && !x.alternate.is_empty());
+
+                // idempotent
+                assert_eq!(
+                    Alteration::normalize(x.position, x.reference, x.alternate).unwrap(),
+                    x,
+                    "{} {}",
+                    r,
+                    a
+                );
+
+                // nothing more to trim unless an allele is a single anchor base
+                if x.reference.len() > 1 && x.alternate.len() > 1 {
+                    assert_ne!(x.reference.as_bytes()[0], x.alternate.as_bytes()[0]);
+                    assert_ne!(x.reference.as_bytes().last(), x.alternate.as_bytes().last());
+                }
+
+                assert_eq!(x.typ.is_none(), r == a, "{} {}", r, a);
+            }
+        }
+    }
+}
@@ -1,4 +1,12 @@
+use std::fs;
+use std::io::BufRead;
+use std::path::Path;
+
 use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::errors::{Error, Result};
 
 #[derive(Debug, Clone)]
 pub struct Sequence<'a> {
@@ -18,11 +26,270 @@ pub struct Assembly<'a> {
     sequences: Vec<Sequence<'a>>,
 }
 
+/// An assembly defined in a YAML file, which owns its sequences unlike built-in [`Assembly`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AssemblyDefinition {
+    pub name: String,
+    #[serde(default)]
+    pub genbank: String,
+    #[serde(default)]
+    pub refseq: String,
+    pub sequences: Vec<SequenceDefinition>,
+}
+
+/// A sequence of [`AssemblyDefinition`]. Accessions and the UCSC name may be omitted.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SequenceDefinition {
+    pub name: String,
+    #[serde(default)]
+    pub genbank: String,
+    #[serde(default)]
+    pub refseq: String,
+    #[serde(default)]
+    pub ucsc_name: String,
+    pub reference: String,
+}
+
+impl AssemblyDefinition {
+    /// Read an assembly definition from a YAML file.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            Err(Error::FileNotFoundError(path.to_string_lossy().to_string()))?
+        }
+
+        Self::parse(&fs::read_to_string(path)?).map_err(|(line, message)| {
+            Error::AssemblyFileError(path.to_string_lossy().to_string(), line, message)
+        })
+    }
+
+    /// Parse YAML of an assembly, or returns the line number and the reason if malformed.
+    fn parse(yaml: &str) -> Result<Self, (usize, String)> {
+        serde_yaml::from_str(yaml).map_err(|e| {
+            let message = e.to_string();
+            // the location is given separately
+            let message = message.split(" at line ").next().unwrap_or_default();
+            (e.location().map_or(0, |x| x.line()), message.to_owned())
+        })
+    }
+
+    /// Parse an NCBI assembly report (`*_assembly_report.txt`).
+    ///
+    /// Sequences are referred to by RefSeq accessions, or GenBank accessions if RefSeq ones are not available.
+    pub fn from_assembly_report<R: BufRead>(reader: R) -> Result<Self> {
+        let mut definition = AssemblyDefinition::default();
+        let mut columns: Option<Vec<String>> = None;
+
+        for (i, line) in reader.lines().enumerate() {
+            let line = line?;
+            let line = line.trim_end_matches('\r');
+
+            if let Some(comment) = line.strip_prefix('#') {
+                let comment = comment.trim();
+                if let Some((key, value)) = comment.split_once(':') {
+                    let value = value.trim().to_owned();
+                    match key {
+                        "Assembly name" => definition.name = value,
+                        "GenBank assembly accession" => definition.genbank = value,
+                        "RefSeq assembly accession" => definition.refseq = value,
+                        _ => {}
+                    }
+                } else if comment.starts_with("Sequence-Name\t") {
+                    columns = Some(comment.split('\t').map(|x| x.to_owned()).collect());
+                }
+                continue;
+            }
+            if line.is_empty() {
+                continue;
+            }
+
+            let header = columns.as_ref().ok_or_else(|| {
+                Error::AssemblyReportError(i + 1, "missing header of columns".to_owned())
+            })?;
+            let values: Vec<&str> = line.split('\t').collect();
+            let value = |name: &str| -> Result<String> {
+                let value = header
+                    .iter()
+                    .position(|x| x == name)
+                    .and_then(|x| values.get(x))
+                    .ok_or_else(|| {
+                        Error::AssemblyReportError(i + 1, format!("missing {}", name))
+                    })?;
+                Ok(if *value == "na" {
+                    String::new()
+                } else {
+                    value.to_string()
+                })
+            };
+
+            let name = value("Sequence-Name")?;
+            let genbank = value("GenBank-Accn")?;
+            let refseq = value("RefSeq-Accn")?;
+            let reference = match (refseq.as_str(), genbank.as_str()) {
+                ("", "") => Err(Error::AssemblyReportError(
+                    i + 1,
+                    format!("no accession for {}", name),
+                ))?,
+                ("", x) => format!("https://identifiers.org/insdc/{}", x),
+                (x, _) => format!("https://identifiers.org/refseq/{}", x),
+            };
+
+            definition.sequences.push(SequenceDefinition {
+                name,
+                genbank,
+                refseq,
+                ucsc_name: value("UCSC-style-name")?,
+                reference,
+            });
+        }
+
+        if definition.sequences.is_empty() {
+            Err(Error::AssemblyReportError(0, "no sequences".to_owned()))?
+        }
+
+        Ok(definition)
+    }
+
+    /// Returns the assembly borrowing this definition, on which `find_sequence` works as on built-in ones.
+    pub fn as_assembly(&self) -> Assembly {
+        Assembly {
+            name: &self.name,
+            genbank: &self.genbank,
+            refseq: &self.refseq,
+            sequences: self
+                .sequences
+                .iter()
+                .map(|x| Sequence {
+                    name: &x.name,
+                    genbank: &x.genbank,
+                    refseq: &x.refseq,
+                    ucsc_name: &x.ucsc_name,
+                    reference: &x.reference,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Names of the mitochondrion, which are used interchangeably regardless of the naming convention.
+const MITOCHONDRION: [&str; 4] = ["MT", "M", "chrM", "chrMT"];
+
+/// Decoy contigs of hs37d5 (1000 Genomes b37 with decoys), which have no counterpart in GRCh37.
+const DECOYS: [(&str, &str); 2] = [
+    ("hs37d5", "decoy sequences of hs37d5, not part of GRCh37"),
+    (
+        "NC_007605",
+        "Epstein-Barr virus (NC_007605.1) included in hs37d5, not part of GRCh37",
+    ),
+];
+
 impl<'a> Assembly<'a> {
+    /// Returns the name of the assembly, e.g. `GRCh38`.
+    pub fn name(&self) -> &'a str {
+        self.name
+    }
+
+    /// Returns an owned copy of the assembly, e.g. to serialize it.
+    pub fn to_definition(&self) -> AssemblyDefinition {
+        AssemblyDefinition {
+            name: self.name.to_owned(),
+            genbank: self.genbank.to_owned(),
+            refseq: self.refseq.to_owned(),
+            sequences: self
+                .sequences
+                .iter()
+                .map(|x| SequenceDefinition {
+                    name: x.name.to_owned(),
+                    genbank: x.genbank.to_owned(),
+                    refseq: x.refseq.to_owned(),
+                    ucsc_name: x.ucsc_name.to_owned(),
+                    reference: x.reference.to_owned(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns sequences of the assembly.
+    pub fn sequences(&self) -> &[Sequence<'a>] {
+        &self.sequences
+    }
+
     pub fn find_sequence(&self, name: &String) -> Option<&Sequence> {
-        self.sequences.iter().find(|&x| {
-            x.name == name || x.genbank == name || x.refseq == name || x.ucsc_name == name
-        })
+        self.sequences
+            .iter()
+            .find(|&x| {
+                x.name == name || x.genbank == name || x.refseq == name || x.ucsc_name == name
+            })
+            .or_else(|| {
+                if MITOCHONDRION.contains(&name.as_str()) {
+                    self.sequences.iter().find(|&x| x.name == MITOCHONDRION[0])
+                } else {
+                    None
+                }
+            })
+    }
+}
+
+/// Returns the reason why `name` is not mapped to any sequence if it is a known decoy contig,
+/// i.e. one of hs37d5 or of the GRCh38 analysis set (`chrEBV`, `*_decoy` and `HLA-*`).
+pub fn decoy(name: &str) -> Option<&'static str> {
+    if let Some((_, x)) = DECOYS.iter().find(|(x, _)| *x == name) {
+        return Some(x);
+    }
+
+    if name == "chrEBV" {
+        Some("Epstein-Barr virus included in the GRCh38 analysis set, not part of GRCh38")
+    } else if name.ends_with("_decoy") {
+        Some("decoy sequence of the GRCh38 analysis set, not part of GRCh38")
+    } else if name.starts_with("HLA-") {
+        Some("HLA allele of the GRCh38 analysis set, not part of GRCh38")
+    } else {
+        None
+    }
+}
+
+/// UCSC names of unlocalized (`_random`) and unplaced (`chrUn_`) scaffolds, ALT loci (`_alt`) and
+/// patches (`_fix`), which encode GenBank accessions, e.g. `chr1_KI270706v1_random`.
+static REGEX_SCAFFOLD_UCSC: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"\A(?:chr(?:[0-9]+|X|Y)_|chrUn_)([A-Z]{2}[0-9]{6})v([0-9]+)(?:_random|_alt|_fix)?\z",
+    )
+    .unwrap()
+});
+
+/// GenBank accessions of scaffolds, e.g. `KI270706.1`.
+static REGEX_SCAFFOLD_GENBANK: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\A(?:GL|JH|KI|KN|KQ|KV|KZ|ML|MU)[0-9]{6}\.[0-9]+\z").unwrap());
+
+/// A scaffold outside the primary chromosomes, identified by its GenBank accession.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scaffold {
+    pub genbank: String,
+}
+
+impl Scaffold {
+    /// Returns a scaffold if `name` is its UCSC name or GenBank accession, e.g. `chr1_KI270706v1_random`.
+    ///
+    /// The RefSeq accession can not be derived from the name and is not given.
+    pub fn parse(name: &str) -> Option<Self> {
+        if let Some(cap) = REGEX_SCAFFOLD_UCSC.captures(name) {
+            return Some(Scaffold {
+                genbank: format!("{}.{}", &cap[1], &cap[2]),
+            });
+        }
+
+        if REGEX_SCAFFOLD_GENBANK.is_match(name) {
+            return Some(Scaffold {
+                genbank: name.to_owned(),
+            });
+        }
+
+        None
+    }
+
+    /// Returns the IRI of the scaffold, e.g. `https://identifiers.org/insdc/KI270706.1`.
+    pub fn reference(&self) -> String {
+        format!("https://identifiers.org/insdc/{}", self.genbank)
     }
 }
 
@@ -169,3 +436,442 @@ pub static GRCM39: Lazy<Assembly> = Lazy::new(|| Assembly {
         ("Y", "CM001014.3", "NC_000087.8", "chrY", "https://identifiers.org/refseq/NC_000087.8");
     },
 });
+
+/// T2T-CHM13v2.0, of which the mitochondrion has no RefSeq accession.
+pub static CHM13_V2: Lazy<Assembly> = Lazy::new(|| Assembly {
+    name: "T2T-CHM13v2.0",
+    genbank: "GCA_009914755.4",
+    refseq: "GCF_009914755.1",
+    sequences: sequences! {
+        ("1", "CP068277.2", "NC_060925.1", "chr1", "https://identifiers.org/refseq/NC_060925.1");
+        ("2", "CP068276.2", "NC_060926.1", "chr2", "https://identifiers.org/refseq/NC_060926.1");
+        ("3", "CP068275.2", "NC_060927.1", "chr3", "https://identifiers.org/refseq/NC_060927.1");
+        ("4", "CP068274.2", "NC_060928.1", "chr4", "https://identifiers.org/refseq/NC_060928.1");
+        ("5", "CP068273.2", "NC_060929.1", "chr5", "https://identifiers.org/refseq/NC_060929.1");
+        ("6", "CP068272.2", "NC_060930.1", "chr6", "https://identifiers.org/refseq/NC_060930.1");
+        ("7", "CP068271.2", "NC_060931.1", "chr7", "https://identifiers.org/refseq/NC_060931.1");
+        ("8", "CP068270.2", "NC_060932.1", "chr8", "https://identifiers.org/refseq/NC_060932.1");
+        ("9", "CP068269.2", "NC_060933.1", "chr9", "https://identifiers.org/refseq/NC_060933.1");
+        ("10", "CP068268.2", "NC_060934.1", "chr10", "https://identifiers.org/refseq/NC_060934.1");
+        ("11", "CP068267.2", "NC_060935.1", "chr11", "https://identifiers.org/refseq/NC_060935.1");
+        ("12", "CP068266.2", "NC_060936.1", "chr12", "https://identifiers.org/refseq/NC_060936.1");
+        ("13", "CP068265.2", "NC_060937.1", "chr13", "https://identifiers.org/refseq/NC_060937.1");
+        ("14", "CP068264.2", "NC_060938.1", "chr14", "https://identifiers.org/refseq/NC_060938.1");
+        ("15", "CP068263.2", "NC_060939.1", "chr15", "https://identifiers.org/refseq/NC_060939.1");
+        ("16", "CP068262.2", "NC_060940.1", "chr16", "https://identifiers.org/refseq/NC_060940.1");
+        ("17", "CP068261.2", "NC_060941.1", "chr17", "https://identifiers.org/refseq/NC_060941.1");
+        ("18", "CP068260.2", "NC_060942.1", "chr18", "https://identifiers.org/refseq/NC_060942.1");
+        ("19", "CP068259.2", "NC_060943.1", "chr19", "https://identifiers.org/refseq/NC_060943.1");
+        ("20", "CP068258.2", "NC_060944.1", "chr20", "https://identifiers.org/refseq/NC_060944.1");
+        ("21", "CP068257.2", "NC_060945.1", "chr21", "https://identifiers.org/refseq/NC_060945.1");
+        ("22", "CP068256.2", "NC_060946.1", "chr22", "https://identifiers.org/refseq/NC_060946.1");
+        ("X", "CP068255.2", "NC_060947.1", "chrX", "https://identifiers.org/refseq/NC_060947.1");
+        ("Y", "CP086569.2", "NC_060948.1", "chrY", "https://identifiers.org/refseq/NC_060948.1");
+        ("MT", "CP068254.1", "na", "chrM", "https://identifiers.org/insdc/CP068254.1");
+    },
+});
+
+/// Lengths of sequences by name in built-in assemblies, used to tell assemblies sharing names apart.
+const LENGTHS: [(&str, &[(&str, u64)]); 5] = [
+    (
+        "GRCh37",
+        &[
+            ("1", 249250621),
+            ("2", 243199373),
+            ("3", 198022430),
+            ("4", 191154276),
+            ("5", 180915260),
+            ("6", 171115067),
+            ("7", 159138663),
+            ("8", 146364022),
+            ("9", 141213431),
+            ("10", 135534747),
+            ("11", 135006516),
+            ("12", 133851895),
+            ("13", 115169878),
+            ("14", 107349540),
+            ("15", 102531392),
+            ("16", 90354753),
+            ("17", 81195210),
+            ("18", 78077248),
+            ("19", 59128983),
+            ("20", 63025520),
+            ("21", 48129895),
+            ("22", 51304566),
+            ("X", 155270560),
+            ("Y", 59373566),
+            ("MT", 16569),
+        ],
+    ),
+    (
+        "GRCh38",
+        &[
+            ("1", 248956422),
+            ("2", 242193529),
+            ("3", 198295559),
+            ("4", 190214555),
+            ("5", 181538259),
+            ("6", 170805979),
+            ("7", 159345973),
+            ("8", 145138636),
+            ("9", 138394717),
+            ("10", 133797422),
+            ("11", 135086622),
+            ("12", 133275309),
+            ("13", 114364328),
+            ("14", 107043718),
+            ("15", 101991189),
+            ("16", 90338345),
+            ("17", 83257441),
+            ("18", 80373285),
+            ("19", 58617616),
+            ("20", 64444167),
+            ("21", 46709983),
+            ("22", 50818468),
+            ("X", 156040895),
+            ("Y", 57227415),
+            ("MT", 16569),
+        ],
+    ),
+    ("GRCm38", &[("1", 195471971)]),
+    ("GRCm39", &[("1", 195154279)]),
+    (
+        "T2T-CHM13v2.0",
+        &[("1", 248387328), ("20", 66210255), ("X", 154259566)],
+    ),
+];
+
+/// Result of detecting an assembly from contigs.
+#[derive(Debug)]
+pub enum Detection {
+    Found(&'static Assembly<'static>),
+    /// Assemblies which can not be told apart by names and lengths of contigs.
+    Ambiguous(Vec<&'static Assembly<'static>>),
+    NotFound,
+}
+
+/// Detect the built-in assembly of `contigs` given as pairs of a name and a length if known.
+///
+/// Assemblies are scored by the number of contigs found in them, excluding those of which a known length differs.
+/// If several assemblies have the best score, ones with more contigs of the same length are preferred.
+pub fn detect(contigs: &[(String, Option<u64>)]) -> Detection {
+    let mut candidates: Vec<(&'static Assembly<'static>, usize, usize)> = builtin()
+        .iter()
+        .filter_map(|&assembly| {
+            let known = LENGTHS
+                .iter()
+                .find(|(x, _)| *x == assembly.name)
+                .map_or(&[][..], |(_, x)| *x);
+
+            let mut found = 0;
+            let mut same_length = 0;
+            for (name, length) in contigs {
+                let sequence = match assembly.find_sequence(name) {
+                    Some(x) => x,
+                    None => continue,
+                };
+                found += 1;
+
+                let expected = known.iter().find(|(x, _)| *x == sequence.name);
+                match (length, expected) {
+                    (Some(x), Some((_, y))) if x == y => same_length += 1,
+                    (Some(_), Some(_)) => return None,
+                    _ => {}
+                }
+            }
+
+            if found > 0 {
+                Some((assembly, found, same_length))
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    let best = candidates.iter().map(|&(_, x, y)| (x, y)).max();
+    candidates.retain(|&(_, x, y)| Some((x, y)) == best);
+
+    match candidates.len() {
+        0 => Detection::NotFound,
+        1 => Detection::Found(candidates[0].0),
+        _ => Detection::Ambiguous(candidates.into_iter().map(|(x, _, _)| x).collect()),
+    }
+}
+
+/// Returns built-in assemblies.
+pub fn builtin() -> [&'static Assembly<'static>; 5] {
+    [&*GRCH37_P13, &*GRCH38_P13, &*GRCM38, &*GRCM39, &*CHM13_V2]
+}
+
+/// Returns names of the sequence `name` in built-in assemblies,
+/// i.e. its name, GenBank and RefSeq accessions and UCSC name.
+pub fn aliases(name: &str) -> Vec<&'static str> {
+    builtin()
+        .iter()
+        .filter_map(|&assembly| assembly.find_sequence(&name.to_owned()))
+        .flat_map(|x| [x.name, x.genbank, x.refseq, x.ucsc_name])
+        .filter(|&x| x != "na")
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chm13_v2() {
+        let find = |name: &str| CHM13_V2.find_sequence(&name.to_owned());
+
+        assert_eq!(find("chr1").map(|x| x.refseq), Some("NC_060925.1"));
+        assert_eq!(find("NC_060925.1").map(|x| x.ucsc_name), Some("chr1"));
+        assert_eq!(find("1").map(|x| x.genbank), Some("CP068277.2"));
+        assert_eq!(find("chr22").map(|x| x.refseq), Some("NC_060946.1"));
+        assert_eq!(find("NC_060947.1").map(|x| x.name), Some("X"));
+        assert_eq!(find("chrY").map(|x| x.refseq), Some("NC_060948.1"));
+        assert_eq!(
+            find("NC_060948.1").map(|x| x.reference),
+            Some("https://identifiers.org/refseq/NC_060948.1")
+        );
+        assert_eq!(find("chrM").map(|x| x.genbank), Some("CP068254.1"));
+    }
+
+    const ASSEMBLY_YAML: &str = r#"---
+name: ASM2732v1
+genbank: GCA_000027325.1
+refseq: GCF_000027325.1
+sequences:
+  - name: ANONYMOUS
+    genbank: L43967.2
+    refseq: NC_000908.2
+    ucsc_name: chr
+    reference: https://identifiers.org/refseq/NC_000908.2
+  - name: pMG
+    reference: http://example.org/pMG
+"#;
+
+    #[test]
+    fn test_assembly_definition() {
+        let definition = AssemblyDefinition::parse(ASSEMBLY_YAML).unwrap();
+        let assembly = definition.as_assembly();
+        let find = |name: &str| {
+            assembly
+                .find_sequence(&name.to_owned())
+                .map(|x| x.reference)
+        };
+
+        for name in &["ANONYMOUS", "L43967.2", "NC_000908.2", "chr"] {
+            assert_eq!(
+                find(name),
+                Some("https://identifiers.org/refseq/NC_000908.2")
+            );
+        }
+        assert_eq!(find("pMG"), Some("http://example.org/pMG"));
+        assert_eq!(find("chr1"), None);
+    }
+
+    #[test]
+    fn test_to_definition() {
+        let definition = GRCH38_P13.to_definition();
+        assert_eq!(definition.name, "GRCh38");
+        assert_eq!(definition.sequences.len(), GRCH38_P13.sequences().len());
+
+        let yaml = serde_yaml::to_string(&definition).unwrap();
+        let assembly = AssemblyDefinition::parse(&yaml).unwrap();
+        assert_eq!(
+            assembly
+                .as_assembly()
+                .find_sequence(&"NC_000001.11".to_owned())
+                .map(|x| x.ucsc_name),
+            Some("chr1")
+        );
+    }
+
+    #[test]
+    fn test_from_assembly_report() {
+        let file = fs::File::open("test/GRCh38_assembly_report.txt").unwrap();
+        let definition =
+            AssemblyDefinition::from_assembly_report(std::io::BufReader::new(file)).unwrap();
+
+        assert_eq!(definition.name, "GRCh38.p13");
+        assert_eq!(definition.genbank, "GCA_000001405.28");
+        assert_eq!(definition.refseq, "GCF_000001405.39");
+        assert_eq!(definition.sequences.len(), 6);
+
+        let assembly = definition.as_assembly();
+        let find = |name: &str| {
+            assembly
+                .find_sequence(&name.to_owned())
+                .map(|x| x.reference)
+        };
+        for name in &["1", "CM000663.2", "NC_000001.11", "chr1"] {
+            assert_eq!(
+                find(name),
+                Some("https://identifiers.org/refseq/NC_000001.11")
+            );
+        }
+        for name in &[
+            "HSCHR1_RANDOM_CTG5",
+            "KI270706.1",
+            "NT_187361.1",
+            "chr1_KI270706v1_random",
+        ] {
+            assert_eq!(
+                find(name),
+                Some("https://identifiers.org/refseq/NT_187361.1")
+            );
+        }
+        assert_eq!(
+            find("chrM"),
+            Some("https://identifiers.org/refseq/NC_012920.1")
+        );
+    }
+
+    #[test]
+    fn test_from_assembly_report_na() {
+        let report = "# Sequence-Name\tGenBank-Accn\tRefSeq-Accn\tUCSC-style-name\r\n\
+                      HG1_PATCH\tKN196472.1\tna\tna\r\n";
+        let definition = AssemblyDefinition::from_assembly_report(report.as_bytes()).unwrap();
+
+        assert_eq!(definition.sequences[0].refseq, "");
+        assert_eq!(definition.sequences[0].ucsc_name, "");
+        assert_eq!(
+            definition.sequences[0].reference,
+            "https://identifiers.org/insdc/KN196472.1"
+        );
+
+        match AssemblyDefinition::from_assembly_report("1\tCM000663.2\n".as_bytes()) {
+            Err(Error::AssemblyReportError(1, _)) => {}
+            x => panic!("unexpected {:?}", x),
+        }
+        match AssemblyDefinition::from_assembly_report(
+            "# Sequence-Name\tGenBank-Accn\n1\tCM000663.2\n".as_bytes(),
+        ) {
+            Err(Error::AssemblyReportError(2, x)) => assert_eq!(x, "missing RefSeq-Accn"),
+            x => panic!("unexpected {:?}", x),
+        }
+    }
+
+    #[test]
+    fn test_assembly_definition_malformed() {
+        let yaml = ASSEMBLY_YAML.replace("    reference: http://example.org/pMG\n", "");
+        let (line, message) = AssemblyDefinition::parse(&yaml).unwrap_err();
+        assert!(line > 0);
+        assert!(message.contains("reference"), "{}", message);
+
+        let yaml = ASSEMBLY_YAML.replace("  - name: pMG", "  - name: [pMG");
+        let (line, _) = AssemblyDefinition::parse(&yaml).unwrap_err();
+        assert!(line >= 12);
+    }
+
+    #[test]
+    fn test_assembly_definition_from_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("assembly.yaml");
+        fs::write(
+            &path,
+            "name: x\nsequences:\n  - name: one\n    reference: [\n",
+        )
+        .unwrap();
+
+        match AssemblyDefinition::from_path(&path) {
+            Err(Error::AssemblyFileError(_, line, _)) => assert!(line >= 4),
+            x => panic!("unexpected {:?}", x),
+        }
+    }
+
+    fn detect_names(contigs: &[(&str, Option<u64>)]) -> Vec<&'static str> {
+        let contigs: Vec<_> = contigs.iter().map(|&(x, y)| (x.to_owned(), y)).collect();
+        match detect(&contigs) {
+            Detection::Found(x) => vec![x.name()],
+            Detection::Ambiguous(x) => x.iter().map(|x| x.name()).collect(),
+            Detection::NotFound => vec![],
+        }
+    }
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(
+            detect_names(&[("NC_000001.11", None), ("NC_000002.12", None)]),
+            vec!["GRCh38"]
+        );
+        assert_eq!(
+            detect_names(&[("NC_000001.10", None), ("chrUn", None)]),
+            vec!["GRCh37"]
+        );
+        assert_eq!(
+            detect_names(&[("1", None), ("20", None), ("MT", None)]),
+            vec!["GRCh37", "GRCh38", "T2T-CHM13v2.0"]
+        );
+        assert_eq!(
+            detect_names(&[("chr1", Some(248956422)), ("chr20", None)]),
+            vec!["GRCh38"]
+        );
+        assert_eq!(
+            detect_names(&[("1", Some(249250621)), ("2", Some(243199373))]),
+            vec!["GRCh37"]
+        );
+        assert_eq!(
+            detect_names(&[("chr1", Some(195154279)), ("chr19", None)]),
+            vec!["GRCm39"]
+        );
+        // NCBI36
+        assert!(detect_names(&[("20", Some(62435964))]).is_empty());
+        assert!(detect_names(&[("scaffold_1", None)]).is_empty());
+    }
+
+    #[test]
+    fn test_b37_contigs() {
+        let find = |name: &str| GRCH37_P13.find_sequence(&name.to_owned()).map(|x| x.name);
+
+        assert_eq!(find("1"), Some("1"));
+        assert_eq!(find("X"), Some("X"));
+        assert_eq!(find("MT"), Some("MT"));
+        assert_eq!(find("chrM"), Some("MT"));
+        assert_eq!(find("M"), Some("MT"));
+        assert_eq!(find("chrMT"), Some("MT"));
+        assert_eq!(find("hs37d5"), None);
+        assert_eq!(find("NC_007605"), None);
+    }
+
+    #[test]
+    fn test_decoy() {
+        assert!(decoy("hs37d5").is_some());
+        assert!(decoy("NC_007605").is_some());
+        assert!(decoy("chrEBV").is_some());
+        assert!(decoy("chrUn_JTFH01000001v1_decoy").is_some());
+        assert!(decoy("HLA-A*01:01:01:01").is_some());
+        assert_eq!(decoy("1"), None);
+        assert_eq!(decoy("GL000192.1"), None);
+        assert_eq!(decoy("chr1_KI270706v1_random"), None);
+    }
+
+    #[test]
+    fn test_scaffold() {
+        let genbank = |name: &str| Scaffold::parse(name).map(|x| x.genbank);
+
+        assert_eq!(
+            genbank("chr1_KI270706v1_random").as_deref(),
+            Some("KI270706.1")
+        );
+        assert_eq!(genbank("chrUn_KI270302v1").as_deref(), Some("KI270302.1"));
+        assert_eq!(genbank("chrUn_GL000220v1").as_deref(), Some("GL000220.1"));
+        assert_eq!(
+            genbank("chr6_GL000250v2_alt").as_deref(),
+            Some("GL000250.2")
+        );
+        assert_eq!(
+            genbank("chr1_KN196472v1_fix").as_deref(),
+            Some("KN196472.1")
+        );
+        assert_eq!(genbank("KI270706.1").as_deref(), Some("KI270706.1"));
+        assert_eq!(genbank("chr1"), None);
+        assert_eq!(genbank("CM000663.2"), None);
+        assert_eq!(genbank("chrUn_JTFH01000001v1_decoy"), None);
+        assert_eq!(
+            Scaffold::parse("chrUn_KI270302v1")
+                .map(|x| x.reference())
+                .as_deref(),
+            Some("https://identifiers.org/insdc/KI270302.1")
+        );
+    }
+}
@@ -0,0 +1,122 @@
+//! Module for breakends of rearrangements (e.g. `G]17:198982]`)
+
+/// A breakend joining the local position to a mate position, given in one of the four bracket forms.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Breakend<'a> {
+    pub mate_contig: &'a str,
+    pub mate_position: u64,
+    /// True if the mate is joined before the local base, i.e. `]p]t` or `[p[t`.
+    pub mate_first: bool,
+    /// True if the joined sequence extends to the right of the mate position, i.e. `t[p[` or `[p[t`.
+    pub mate_right: bool,
+    /// Bases inserted between the local base and the mate, excluding the reference base.
+    pub inserted: &'a str,
+}
+
+impl<'a> Breakend<'a> {
+    /// Parse a breakend `allele`, or returns `None` if it is not in any of the four bracket forms.
+    pub fn parse(allele: &'a str) -> Option<Self> {
+        let start = allele.find(|c| c == '[' || c == ']')?;
+        let bracket = allele[start..].chars().next()?;
+        let end = start + 1 + allele[start + 1..].find(bracket)?;
+
+        let (sequence, mate_first) = match (start, end + 1 == allele.len()) {
+            (0, false) => (&allele[end + 1..], true),
+            (s, true) if s > 0 => (&allele[..start], false),
+            _ => return None,
+        };
+        if !sequence.bytes().all(|b| b.is_ascii_alphabetic()) {
+            return None;
+        }
+
+        let (contig, position) = allele[start + 1..end].rsplit_once(':')?;
+        if contig.is_empty() {
+            return None;
+        }
+
+        Some(Breakend {
+            mate_contig: contig,
+            mate_position: position.parse().ok()?,
+            mate_first,
+            mate_right: bracket == '[',
+            inserted: if mate_first {
+                &sequence[..sequence.len() - 1]
+            } else {
+                &sequence[1..]
+            },
+        })
+    }
+
+    /// Returns the bracket form, e.g. `t]p]`.
+    pub fn orientation(&self) -> &'static str {
+        match (self.mate_first, self.mate_right) {
+            (false, true) => "t[p[",
+            (false, false) => "t]p]",
+            (true, false) => "]p]t",
+            (true, true) => "[p[t",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            Breakend::parse("G]17:198982]"),
+            Some(Breakend {
+                mate_contig: "17",
+                mate_position: 198982,
+                mate_first: false,
+                mate_right: false,
+                inserted: "",
+            })
+        );
+        assert_eq!(
+            Breakend::parse("G]17:198982]").map(|x| x.orientation()),
+            Some("t]p]")
+        );
+        assert_eq!(
+            Breakend::parse("A[2:321682[").map(|x| x.orientation()),
+            Some("t[p[")
+        );
+        assert_eq!(
+            Breakend::parse("]13:123456]T").map(|x| x.orientation()),
+            Some("]p]t")
+        );
+        assert_eq!(
+            Breakend::parse("[17:198983[A").map(|x| x.orientation()),
+            Some("[p[t")
+        );
+    }
+
+    #[test]
+    fn test_parse_inserted() {
+        assert_eq!(
+            Breakend::parse("CAGT[chr2:321682[").map(|x| x.inserted),
+            Some("AGT")
+        );
+        assert_eq!(
+            Breakend::parse("]chr13:123456]AGTNNNNNCAT").map(|x| x.inserted),
+            Some("AGTNNNNNCA")
+        );
+        assert_eq!(
+            Breakend::parse("G[HLA-A*01:01:01:01:100[").map(|x| x.mate_contig),
+            Some("HLA-A*01:01:01:01")
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert_eq!(Breakend::parse("A"), None);
+        assert_eq!(Breakend::parse("<DEL>"), None);
+        assert_eq!(Breakend::parse("G]17:198982["), None);
+        assert_eq!(Breakend::parse("]17:198982]"), None);
+        assert_eq!(Breakend::parse("G]17:198982]A"), None);
+        assert_eq!(Breakend::parse("G]17]"), None);
+        assert_eq!(Breakend::parse("G]:100]"), None);
+        assert_eq!(Breakend::parse("G]17:x]"), None);
+    }
+}
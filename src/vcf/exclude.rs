@@ -0,0 +1,166 @@
+//! Module for lists of variants excluded from conversion
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::errors::Result;
+use crate::vcf::record::Entry;
+
+/// A position in an exclude list.
+///
+/// `pos`, `ref` and `alt` are compared with the values as written in VCF, without normalization.
+/// If `ref` or `alt` is omitted, any allele at the position matches.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Position {
+    pub contig: String,
+    pub pos: u64,
+    #[serde(default, rename = "ref")]
+    pub reference: Option<String>,
+    #[serde(default, rename = "alt")]
+    pub alternate: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ExcludeFile {
+    #[serde(default, rename = "rsIDs")]
+    ids: Vec<String>,
+    #[serde(default)]
+    positions: Vec<Position>,
+}
+
+/// Variants to exclude from conversion, loaded from YAML like:
+///
+/// ```yaml
+/// rsIDs:
+///   - rs6054257
+/// positions:
+///   - contig: "20"
+///     pos: 17330
+///     ref: T   # optional
+///     alt: A   # optional
+/// ```
+#[derive(Debug, Default)]
+pub struct ExcludeList {
+    ids: HashSet<String>,
+    positions: HashMap<String, HashMap<u64, Vec<Position>>>,
+}
+
+impl ExcludeList {
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<ExcludeList> {
+        let file: ExcludeFile = serde_yaml::from_reader(File::open(path)?)?;
+
+        let mut list = ExcludeList {
+            ids: file.ids.into_iter().collect(),
+            ..Default::default()
+        };
+
+        for position in file.positions {
+            list.positions
+                .entry(position.contig.clone())
+                .or_default()
+                .entry(position.pos)
+                .or_default()
+                .push(position);
+        }
+
+        Ok(list)
+    }
+
+    pub fn len(&self) -> usize {
+        self.ids.len() + self.positions.values().map(|x| x.len()).sum::<usize>()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns true if `entry` should be excluded.
+    ///
+    /// An ID matches all alleles of the record if any of the semicolon separated IDs is listed.
+    pub fn matches(&self, entry: &Entry) -> bool {
+        if !self.ids.is_empty() {
+            let id = entry.record().inner().id();
+            if String::from_utf8_lossy(&id)
+                .split(';')
+                .any(|x| self.ids.contains(x))
+            {
+                return true;
+            }
+        }
+
+        let positions = match entry
+            .chromosome()
+            .and_then(|x| x.ok())
+            .and_then(|x| self.positions.get(x))
+            .and_then(|x| x.get(&entry.position()))
+        {
+            Some(v) => v,
+            None => return false,
+        };
+
+        positions.iter().any(|x| {
+            x.reference
+                .as_ref()
+                .map_or(true, |v| v == entry.reference_bases())
+                && x.alternate
+                    .as_ref()
+                    .map_or(true, |v| v == entry.alternate_bases())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+    use crate::vcf::reader::Reader;
+
+    fn excluded(yaml: &str) -> Vec<String> {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(yaml.as_bytes()).unwrap();
+        let list = ExcludeList::from_path(file.path()).expect("Error loading exclude list.");
+
+        let mut reader = Reader::from_path("test/vcf_spec.vcf.gz").expect("Error opening file.");
+        let mut vec = Vec::new();
+        for record in reader.records() {
+            let record = record.expect("Error reading record.");
+            for entry in record.each_alternate_alleles() {
+                if list.matches(&entry) {
+                    vec.push(format!("{}-{}", entry.position(), entry.alternate_bases()));
+                }
+            }
+        }
+        vec
+    }
+
+    #[test]
+    fn test_ids() {
+        assert_eq!(
+            excluded("rsIDs:\n  - rs6054257\n  - microsat1\n"),
+            vec!["14370-A", "1234567-G", "1234567-GTCT"]
+        );
+    }
+
+    #[test]
+    fn test_any_allele_at_position() {
+        assert_eq!(
+            excluded("positions:\n  - contig: \"20\"\n    pos: 1110696\n"),
+            vec!["1110696-G", "1110696-T"]
+        );
+    }
+
+    #[test]
+    fn test_exact_alleles() {
+        assert_eq!(
+            excluded("positions:\n  - contig: \"20\"\n    pos: 1110696\n    ref: A\n    alt: T\n"),
+            vec!["1110696-T"]
+        );
+        assert!(
+            excluded("positions:\n  - contig: \"20\"\n    pos: 1110696\n    ref: G\n").is_empty()
+        );
+        assert!(excluded("positions:\n  - contig: \"1\"\n    pos: 1110696\n").is_empty());
+    }
+}
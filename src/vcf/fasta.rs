@@ -0,0 +1,114 @@
+//! Module for reading reference sequences from FASTA indexed by faidx
+use std::collections::HashSet;
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use rust_htslib::faidx;
+
+use crate::errors::{Error, Result};
+use crate::vcf::assembly;
+
+/// A FASTA indexed by `samtools faidx`, of which sequences are also found by aliases in built-in assemblies.
+pub struct Fasta {
+    reader: faidx::Reader,
+    names: HashSet<String>,
+}
+
+// SAFETY: the faidx handle is not bound to the thread that opened it, and `&self` of `fetch` is not shared
+// across threads since `Fasta` is not `Sync`.
+unsafe impl Send for Fasta {}
+
+impl Fasta {
+    /// Open FASTA at `path`, of which the index (`.fai`) is built next to it if missing.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            Err(Error::FileNotFoundError(path.to_string_lossy().to_string()))?
+        }
+
+        let reader = faidx::Reader::from_path(path)?;
+
+        let mut fai = OsString::from(path.as_os_str());
+        fai.push(".fai");
+        let names = fs::read_to_string(PathBuf::from(fai))?
+            .lines()
+            .filter_map(|x| x.split('\t').next())
+            .filter(|x| !x.is_empty())
+            .map(|x| x.to_owned())
+            .collect();
+
+        Ok(Fasta { reader, names })
+    }
+
+    /// Returns the name of the sequence for `contig`, e.g. `chr20` for `20`.
+    pub fn resolve(&self, contig: &str) -> Option<String> {
+        if self.names.contains(contig) {
+            return Some(contig.to_owned());
+        }
+
+        assembly::aliases(contig)
+            .into_iter()
+            .find(|x| self.names.contains(*x))
+            .map(|x| x.to_owned())
+    }
+
+    /// Returns `length` bases from `position` (1-based) of the sequence `name`.
+    ///
+    /// Bases are fewer than `length` if the range exceeds the end of the sequence.
+    pub fn fetch(&self, name: &str, position: u64, length: usize) -> Result<String> {
+        if position == 0 || length == 0 {
+            return Ok(String::new());
+        }
+
+        let begin = position as usize - 1;
+        Ok(self
+            .reader
+            .fetch_seq_string(name, begin, begin + length - 1)?)
+    }
+}
+
+/// Returns true if `reference` is the same as `bases` ignoring case, where `N` matches any base.
+pub fn matches(reference: &str, bases: &str) -> bool {
+    reference.len() == bases.len()
+        && reference.bytes().zip(bases.bytes()).all(|(x, y)| {
+            x.eq_ignore_ascii_case(&y)
+                || x.eq_ignore_ascii_case(&b'N')
+                || y.eq_ignore_ascii_case(&b'N')
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+
+    #[test]
+    fn test_fasta() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ref.fa");
+        let mut file = fs::File::create(&path).unwrap();
+        writeln!(file, ">chr20 Homo sapiens chromosome 20\nACGTacgtNN\nGGCC").unwrap();
+        drop(file);
+
+        let fasta = Fasta::from_path(&path).unwrap();
+
+        assert_eq!(fasta.resolve("chr20").as_deref(), Some("chr20"));
+        assert_eq!(fasta.resolve("20").as_deref(), Some("chr20"));
+        assert_eq!(fasta.resolve("21"), None);
+
+        assert_eq!(fasta.fetch("chr20", 1, 4).unwrap(), "ACGT");
+        assert_eq!(fasta.fetch("chr20", 5, 2).unwrap(), "ac");
+        assert_eq!(fasta.fetch("chr20", 10, 3).unwrap(), "NGG");
+    }
+
+    #[test]
+    fn test_matches() {
+        assert!(matches("ACGT", "acgT"));
+        assert!(matches("ANGT", "ACGT"));
+        assert!(matches("ACGT", "ACnT"));
+        assert!(!matches("ACGT", "ACCT"));
+        assert!(!matches("ACGT", "ACG"));
+    }
+}
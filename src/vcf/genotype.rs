@@ -0,0 +1,176 @@
+//! Module for genotypes of samples
+use rust_htslib::bcf::record::GenotypeAllele;
+use strum::{AsRefStr, EnumString, EnumVariantNames};
+
+/// Zygosity of a sample for an alternate allele.
+#[derive(EnumString, EnumVariantNames, AsRefStr, Debug, Copy, Clone, PartialEq, Eq)]
+#[strum(serialize_all = "snake_case")]
+pub enum Zygosity {
+    HomozygousRef,
+    Heterozygous,
+    HomozygousAlt,
+    Hemizygous,
+    Unknown,
+}
+
+impl Zygosity {
+    /// Classify `genotype` relative to the alternate allele `allele` (1-based index in ALT).
+    ///
+    /// Other alternate alleles are regarded as reference as in decomposition of multi-allelic sites,
+    /// so that `1/2` is heterozygous for both alleles and `2/2` is homozygous reference for allele 1.
+    /// A genotype with any missing allele is unknown.
+    pub fn classify(genotype: &[GenotypeAllele], allele: u32) -> Zygosity {
+        let indices = match genotype
+            .iter()
+            .map(|x| x.index())
+            .collect::<Option<Vec<_>>>()
+        {
+            Some(v) if !v.is_empty() => v,
+            _ => return Zygosity::Unknown,
+        };
+
+        let copies = indices.iter().filter(|&&x| x == allele).count();
+
+        match (indices.len(), copies) {
+            (1, 1) => Zygosity::Hemizygous,
+            (_, 0) => Zygosity::HomozygousRef,
+            (n, k) if n == k => Zygosity::HomozygousAlt,
+            _ => Zygosity::Heterozygous,
+        }
+    }
+}
+
+/// Counts of genotypes of samples for an alternate allele.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct GenotypeCounts {
+    pub heterozygous: u64,
+    pub homozygous_alt: u64,
+    pub missing: u64,
+    /// Number of copies of the allele in called genotypes (`AC`).
+    pub allele_count: u64,
+    /// Number of called alleles (`AN`).
+    pub allele_number: u64,
+}
+
+impl GenotypeCounts {
+    /// Count `genotype` for the alternate allele `allele` (1-based index in ALT).
+    ///
+    /// Called alleles of partially missing genotypes are counted in `AC` and `AN` as bcftools does.
+    pub fn add(&mut self, genotype: &[GenotypeAllele], allele: u32) {
+        match Zygosity::classify(genotype, allele) {
+            Zygosity::Heterozygous => self.heterozygous += 1,
+            Zygosity::HomozygousAlt => self.homozygous_alt += 1,
+            Zygosity::Unknown => self.missing += 1,
+            _ => {}
+        }
+
+        for index in genotype.iter().filter_map(|x| x.index()) {
+            self.allele_number += 1;
+            if index == allele {
+                self.allele_count += 1;
+            }
+        }
+    }
+
+    /// Returns `AC / AN`, or `None` if no alleles are called.
+    pub fn allele_frequency(&self) -> Option<f32> {
+        if self.allele_number == 0 {
+            None
+        } else {
+            Some(self.allele_count as f32 / self.allele_number as f32)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(gt: &str) -> Vec<GenotypeAllele> {
+        let phased = gt.contains('|');
+
+        gt.split(|c| c == '/' || c == '|')
+            .enumerate()
+            .map(|(i, x)| match (x.parse::<i32>().ok(), phased && i > 0) {
+                (Some(v), true) => GenotypeAllele::Phased(v),
+                (Some(v), false) => GenotypeAllele::Unphased(v),
+                (None, true) => GenotypeAllele::PhasedMissing,
+                (None, false) => GenotypeAllele::UnphasedMissing,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_classify() {
+        use Zygosity::*;
+
+        let cases = [
+            ("0/0", [HomozygousRef, HomozygousRef]),
+            ("0/1", [Heterozygous, HomozygousRef]),
+            ("1/1", [HomozygousAlt, HomozygousRef]),
+            ("1/2", [Heterozygous, Heterozygous]),
+            ("2/2", [HomozygousRef, HomozygousAlt]),
+            ("1", [Hemizygous, HomozygousRef]),
+            ("0", [HomozygousRef, HomozygousRef]),
+            ("./.", [Unknown, Unknown]),
+            ("./1", [Unknown, Unknown]),
+            ("0|1", [Heterozygous, HomozygousRef]),
+            ("1|1", [HomozygousAlt, HomozygousRef]),
+        ];
+
+        for (gt, expected) in cases.iter() {
+            for (i, zygosity) in expected.iter().enumerate() {
+                assert_eq!(
+                    Zygosity::classify(&parse(gt), i as u32 + 1),
+                    *zygosity,
+                    "GT {} for allele {}",
+                    gt,
+                    i + 1
+                );
+            }
+        }
+
+        assert_eq!(Zygosity::classify(&[], 1), Unknown);
+    }
+
+    #[test]
+    fn test_genotype_counts() {
+        let mut counts = [GenotypeCounts::default(), GenotypeCounts::default()];
+
+        for gt in &["0/1", "1/1", "1/2", "./.", "./1", "0|0", "1", "2|2"] {
+            for (i, c) in counts.iter_mut().enumerate() {
+                c.add(&parse(gt), i as u32 + 1);
+            }
+        }
+
+        assert_eq!(
+            counts[0],
+            GenotypeCounts {
+                heterozygous: 2,
+                homozygous_alt: 1,
+                missing: 2,
+                allele_count: 6,
+                allele_number: 12,
+            }
+        );
+        assert_eq!(counts[0].allele_frequency(), Some(0.5));
+        assert_eq!(
+            counts[1],
+            GenotypeCounts {
+                heterozygous: 1,
+                homozygous_alt: 1,
+                missing: 2,
+                allele_count: 3,
+                allele_number: 12,
+            }
+        );
+        assert_eq!(counts[1].allele_frequency(), Some(0.25));
+        assert_eq!(GenotypeCounts::default().allele_frequency(), None);
+    }
+
+    #[test]
+    fn test_as_ref() {
+        assert_eq!(Zygosity::HomozygousAlt.as_ref(), "homozygous_alt");
+        assert_eq!(Zygosity::Unknown.as_ref(), "unknown");
+    }
+}
@@ -0,0 +1,125 @@
+//! Module for meta-information lines in VCF header
+use std::ffi::CStr;
+
+use rust_htslib::bcf;
+use rust_htslib::htslib;
+use serde::Serialize;
+
+/// A kind of definitions in the header.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DefinitionKind {
+    Info,
+    Format,
+    Filter,
+}
+
+/// A definition of an INFO, FORMAT or FILTER key, e.g. `##INFO=<ID=DP,Number=1,Type=Integer,Description="...">`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Definition {
+    pub id: String,
+    /// `Number` as written, e.g. `1`, `A` or `.`, which FILTER does not have.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub number: Option<String>,
+    /// `Type` as written, e.g. `Integer`, which FILTER does not have.
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub typ: Option<String>,
+    /// `Description` without surrounding quotes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// Returns the version in `##fileformat`, e.g. `VCFv4.3`.
+pub fn fileformat(header: &bcf::header::HeaderView) -> Option<String> {
+    let version = unsafe { htslib::bcf_hdr_get_version(header.inner) };
+    if version.is_null() {
+        return None;
+    }
+
+    Some(
+        unsafe { CStr::from_ptr(version) }
+            .to_string_lossy()
+            .to_string(),
+    )
+}
+
+/// Returns unstructured meta-information lines as pairs of key and value in order,
+/// e.g. `("source", "myImputationProgramV3.1")`. `##fileformat` is not included.
+pub fn meta(header: &bcf::header::HeaderView) -> Vec<(String, String)> {
+    header
+        .header_records()
+        .into_iter()
+        .filter_map(|x| match x {
+            bcf::HeaderRecord::Generic { key, value } if key != "fileformat" => Some((key, value)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Returns definitions of `kind` in order of the header.
+pub fn definitions(header: &bcf::header::HeaderView, kind: DefinitionKind) -> Vec<Definition> {
+    header
+        .header_records()
+        .into_iter()
+        .filter_map(|x| match (kind, x) {
+            (DefinitionKind::Info, bcf::HeaderRecord::Info { values, .. })
+            | (DefinitionKind::Format, bcf::HeaderRecord::Format { values, .. })
+            | (DefinitionKind::Filter, bcf::HeaderRecord::Filter { values, .. }) => {
+                Some(Definition {
+                    id: values.get("ID")?.to_owned(),
+                    number: values.get("Number").cloned(),
+                    typ: values.get("Type").cloned(),
+                    description: values.get("Description").map(|x| unquote(x).to_owned()),
+                })
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|x| x.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_htslib::bcf::Read;
+
+    use super::*;
+
+    #[test]
+    fn test_header() {
+        let reader = bcf::Reader::from_path("test/vcf_spec.vcf").expect("Error opening file.");
+        let header = reader.header();
+
+        assert_eq!(fileformat(header).as_deref(), Some("VCFv4.3"));
+
+        let meta = meta(header);
+        assert!(meta.contains(&("source".to_owned(), "myImputationProgramV3.1".to_owned())));
+        assert!(meta.iter().all(|(k, _)| k != "fileformat"));
+
+        assert_eq!(
+            definitions(header, DefinitionKind::Info)[1],
+            Definition {
+                id: "DP".to_owned(),
+                number: Some("1".to_owned()),
+                typ: Some("Integer".to_owned()),
+                description: Some("Total Depth".to_owned()),
+            }
+        );
+        assert_eq!(
+            definitions(header, DefinitionKind::Filter)
+                .iter()
+                .map(|x| x.id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["PASS", "q10", "s50"]
+        );
+        assert!(definitions(header, DefinitionKind::Filter)[1].typ.is_none());
+        assert_eq!(
+            definitions(header, DefinitionKind::Format)[0].id,
+            "GT".to_owned()
+        );
+    }
+}
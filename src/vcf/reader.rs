@@ -1,30 +1,69 @@
+use std::cell::{Ref, RefCell};
 use std::collections::BTreeMap;
-use std::ffi::{CString, OsString};
+use std::ffi::{CStr, CString, OsString};
+use std::fs::{self, File};
+use std::io;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::sync::Arc;
 
+use log::*;
 use rust_htslib::bcf;
 use rust_htslib::bcf::Read;
 use rust_htslib::errors::Error as htslib_error;
 use rust_htslib::htslib;
+use tempfile::TempDir;
 
-use crate::config::Sequence;
+use crate::config::{InfoType, Sequence};
 use crate::errors::{Error, Result};
+use crate::rdf::iri;
+use crate::util::template::Template;
+use crate::util::vcf::{self, compress, tabix};
+use crate::vcf::assembly;
+use crate::vcf::header::{self, Definition, DefinitionKind};
 use crate::vcf::record;
+use crate::vcf::record::{InfoDefinition, InfoStats};
+use crate::vcf::region::{self, Region};
+use crate::vcf::sample;
+
+/// Placeholders of `reference_default`.
+pub const REFERENCE_DEFAULT_PLACEHOLDERS: &[&str] = &["contig"];
 
 #[derive(Debug)]
 pub struct ReaderBuilder {
     info_keys: Option<Vec<String>>,
-    references: BTreeMap<String, Option<Sequence>>,
+    excluded_info_keys: Vec<String>,
+    info_separators: BTreeMap<String, String>,
+    info_types: BTreeMap<String, InfoType>,
+    references: Arc<BTreeMap<String, Option<Sequence>>>,
+    contig_aliases: BTreeMap<String, String>,
+    reference_default: Option<Template>,
     normalize: bool,
+    index_path: Option<PathBuf>,
+    samples: Option<Vec<String>>,
+    excluded_samples: Vec<String>,
+    regions: Vec<Region>,
+    require_index: bool,
+    threads: usize,
 }
 
 impl ReaderBuilder {
     pub fn new() -> Self {
         ReaderBuilder {
             info_keys: None,
+            excluded_info_keys: Vec::new(),
+            info_separators: Default::default(),
+            info_types: Default::default(),
             references: Default::default(),
+            contig_aliases: Default::default(),
+            reference_default: None,
             normalize: true,
+            index_path: None,
+            samples: None,
+            excluded_samples: Vec::new(),
+            regions: Vec::new(),
+            require_index: true,
+            threads: 1,
         }
     }
 
@@ -33,8 +72,43 @@ impl ReaderBuilder {
         self
     }
 
-    pub fn reference(mut self, reference: BTreeMap<String, Option<Sequence>>) -> Self {
-        self.references = reference;
+    /// Do not read INFO `keys`, which are removed from the keys to read.
+    pub fn exclude_info_keys(mut self, keys: Vec<String>) -> Self {
+        self.excluded_info_keys = keys;
+        self
+    }
+
+    /// Split String values of INFO keys by separators, e.g. `CLNDN` => `|`.
+    pub fn info_separators(mut self, separators: BTreeMap<String, String>) -> Self {
+        self.info_separators = separators;
+        self
+    }
+
+    /// Convert INFO values to datatypes overriding the header, e.g. `dbSNPBuildID` => `integer`.
+    pub fn info_types(mut self, types: BTreeMap<String, InfoType>) -> Self {
+        self.info_types = types;
+        self
+    }
+
+    /// Set sequences keyed by contig, which may be shared across readers in an `Arc`.
+    pub fn reference<R>(mut self, reference: R) -> Self
+    where
+        R: Into<Arc<BTreeMap<String, Option<Sequence>>>>,
+    {
+        self.references = reference.into();
+        self
+    }
+
+    /// Map contigs of the VCF to keys of `reference` not matching them, e.g. `chr1: NC_000001.10`.
+    pub fn contig_aliases(mut self, aliases: BTreeMap<String, String>) -> Self {
+        self.contig_aliases = aliases;
+        self
+    }
+
+    /// Synthesize sequences of contigs not in `reference` from `template` with `{contig}` placeholder,
+    /// e.g. `https://identifiers.org/insdc/{contig}`, which is filled with the percent-encoded contig.
+    pub fn reference_default(mut self, template: Template) -> Self {
+        self.reference_default = Some(template);
         self
     }
 
@@ -43,8 +117,46 @@ impl ReaderBuilder {
         self
     }
 
+    /// Load the index (tbi or csi) from `path` instead of the path derived from the data file.
+    pub fn index_path(mut self, path: PathBuf) -> Self {
+        self.index_path = Some(path);
+        self
+    }
+
+    /// Read only genotypes of `samples`.
+    pub fn samples(mut self, samples: Vec<String>) -> Self {
+        self.samples = Some(samples);
+        self
+    }
+
+    /// Do not read genotypes of `samples`.
+    pub fn exclude_samples(mut self, samples: Vec<String>) -> Self {
+        self.excluded_samples = samples;
+        self
+    }
+
+    /// Read only records overlapping `regions` through the index.
+    pub fn regions(mut self, regions: Vec<Region>) -> Self {
+        self.regions = regions;
+        self
+    }
+
+    /// Read records sequentially without loading the index if false, in which case `try_count()` fails.
+    pub fn require_index(mut self, flag: bool) -> Self {
+        self.require_index = flag;
+        self
+    }
+
+    /// Decompress BGZF blocks in a pool of `n` threads if greater than 1.
+    pub fn threads(mut self, n: usize) -> Self {
+        self.threads = n;
+        self
+    }
+
     pub fn path<P: AsRef<Path>>(&self, path: P) -> Result<Reader> {
         match path.as_ref().to_str() {
+            // htslib reads URLs and their indices remotely
+            Some(p) if vcf::is_url(p) => self.build(p),
             Some(p) if path.as_ref().exists() => self.build(p),
             Some(p) if !path.as_ref().exists() => Err(Error::FileNotFoundError(p.to_string()))?,
             _ => Err(Error::FilePathError(
@@ -53,102 +165,467 @@ impl ReaderBuilder {
         }
     }
 
+    /// Read records from `path`, or stdin if `-`.
+    pub fn open<P: AsRef<Path>>(&self, path: P) -> Result<Reader> {
+        if path.as_ref() == Path::new("-") {
+            self.stdin()
+        } else {
+            self.path(path)
+        }
+    }
+
+    /// Returns true if the index of `path` is passed by `index_path` or located next to it, tbi for VCF or csi for BCF.
+    ///
+    /// URLs are assumed to be indexed, of which htslib fetches the index remotely. Stdin (`-`) is not indexed.
+    pub fn is_indexed<P: AsRef<Path>>(&self, path: P) -> bool {
+        let path = match path.as_ref().to_str() {
+            Some("-") | None => return false,
+            Some(p) => p,
+        };
+
+        if self.index_path.is_some() || vcf::is_url(path) {
+            return true;
+        }
+
+        let extension = match vcf::get_format(path) {
+            Ok(x) if x.format == htslib::htsExactFormat_bcf => ".csi",
+            Ok(_) => ".tbi",
+            Err(_) => return false,
+        };
+
+        Self::default_index_path(path, extension).map_or(false, |x| x.exists())
+    }
+
+    /// Read records from stdin.
+    ///
+    /// Records are streamed if the index is not required,
+    /// otherwise stdin is copied into a temporary directory under `TMPDIR` to be indexed.
+    pub fn stdin(&self) -> Result<Reader> {
+        if !self.require_index {
+            return self.build_from(bcf::Reader::from_stdin()?, "-", None);
+        }
+
+        let (dir, path) = materialize(&mut io::stdin().lock())?;
+        let mut reader = self.path(&path)?;
+        reader.temp_dir = Some(dir);
+
+        Ok(reader)
+    }
+
     fn build(&self, path: &str) -> Result<Reader> {
-        if let Some(p) = Self::tbi_path(path) {
-            if !p.exists() {
-                Err(Error::IndexNotFoundError(p.to_string_lossy().to_string()))?;
-            }
+        // the header is read once so that the input may be a pipe
+        let reader = vcf::open(path)?;
+        let index = if self.require_index {
+            Some(self.index(path)?)
+        } else {
+            None
+        };
+
+        self.build_from(reader, path, index)
+    }
+
+    fn build_from(
+        &self,
+        mut reader: bcf::Reader,
+        path: &str,
+        index: Option<Index>,
+    ) -> Result<Reader> {
+        if self.threads > 1 {
+            reader.set_threads(self.threads)?;
         }
 
-        let p = CString::new(path)?;
-        let tbx: *mut htslib::tbx_t = unsafe { htslib::tbx_index_load(p.as_ptr()) };
+        let sequences = self.sequences(reader.header());
+        let (aliased_contigs, defaulted_contigs, unmapped_contigs) =
+            self.contig_mapping(reader.header());
+        let regions = self.resolve_regions(reader.header(), &sequences)?;
 
-        if tbx.is_null() {
-            Err(htslib_error::Fetch)?;
+        if index.is_none() && !regions.is_empty() {
+            Err(Error::InvalidArgumentError(
+                "regions can not be read without an index".to_owned(),
+            ))?
         }
 
-        let info = self.info(path);
+        let info_definitions = self.info_definitions(reader.header());
+        let info = info_definitions
+            .iter()
+            .map(|(k, v)| (k.to_owned(), (v.typ, v.length)))
+            .collect();
         let info_keys = match self.info_keys.as_ref() {
             Some(vec) => vec.clone(),
-            None => info.iter().map(|(k, _)| k.to_owned()).collect(),
-        };
+            None => info_definitions.keys().cloned().collect(),
+        }
+        .into_iter()
+        .filter(|k| !self.excluded_info_keys.contains(k))
+        .collect();
+
+        let filters = self.filters(reader.header());
+        self.select_samples(reader.header())?;
+        let samples = sample::names(reader.header());
 
         Ok(Reader {
-            reader: bcf::Reader::from_path(path)?,
-            references: self.references(path),
-            filters: self.filters(path),
+            reader,
+            path: CString::new(path)?,
+            regions,
+            sequences,
+            aliased_contigs,
+            defaulted_contigs,
+            unmapped_contigs,
+            filters,
             info,
+            info_definitions,
             info_keys,
+            samples,
+            info_separators: self.info_separators.clone(),
+            info_types: self.info_types.clone(),
+            info_stats: RefCell::new(InfoStats::new()),
             normalize: self.normalize,
-            tbx,
+            index,
+            threads: self.threads,
+            temp_dir: None,
         })
     }
 
-    fn tbi_path(path: &str) -> Option<PathBuf> {
+    /// Load the index of `path`, tabix for VCF or CSI for BCF.
+    fn index(&self, path: &str) -> Result<Index> {
+        let bcf = vcf::get_format(path)?.format == htslib::htsExactFormat_bcf;
+
+        let index = match self.index_path.as_ref() {
+            Some(index) => Self::load_index(path, index, bcf)?,
+            None => {
+                let extension = if bcf { ".csi" } else { ".tbi" };
+                match Self::default_index_path(path, extension) {
+                    _ if vcf::is_url(path) => {}
+                    Some(p) if !p.exists() => {
+                        Err(Error::IndexNotFoundError(p.to_string_lossy().to_string()))?
+                    }
+                    _ => {}
+                }
+
+                // flags of 0 not to save remote indices in the working directory
+                let p = CString::new(path)?;
+                if bcf {
+                    Index::Csi(unsafe {
+                        htslib::hts_idx_load3(
+                            p.as_ptr(),
+                            std::ptr::null(),
+                            htslib::HTS_FMT_CSI as i32,
+                            0,
+                        )
+                    })
+                } else {
+                    Index::Tabix(unsafe {
+                        htslib::tbx_index_load3(p.as_ptr(), std::ptr::null(), 0)
+                    })
+                }
+            }
+        };
+
+        if index.is_null() {
+            if vcf::is_url(path) {
+                Err(Error::RemoteInputError(
+                    path.to_owned(),
+                    "could not read the index".to_owned(),
+                ))?
+            }
+            Err(htslib_error::Fetch)?;
+        }
+
+        Ok(index)
+    }
+
+    /// Returns `regions` on contigs in the VCF, sorted in the order of the header and merged if overlapping.
+    fn resolve_regions(
+        &self,
+        header: &bcf::header::HeaderView,
+        sequences: &[Option<Sequence>],
+    ) -> Result<Vec<Region>> {
+        if self.regions.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let contigs = contig_names(header);
+
+        let mut regions = Vec::new();
+        for region in &self.regions {
+            match region::resolve_contig(&region.contig, &contigs, sequences) {
+                Some(contig) => regions.push(Region {
+                    contig: contig.to_owned(),
+                    ..region.clone()
+                }),
+                None => Err(Error::InvalidArgumentError(format!(
+                    "contig of region {}:{}-{} is not found in the header",
+                    region.contig, region.start, region.end
+                )))?,
+            }
+        }
+
+        let mut regions = region::merge(regions);
+        regions.sort_by_key(|x| contigs.iter().position(|c| *c == x.contig));
+
+        Ok(regions)
+    }
+
+    /// Restrict samples of which genotypes are parsed, warning about names not in the header.
+    fn select_samples(&self, header: &bcf::header::HeaderView) -> Result<()> {
+        if self.samples.is_none() && self.excluded_samples.is_empty() {
+            return Ok(());
+        }
+
+        let available = sample::names(header);
+
+        for name in self.samples.iter().flatten().chain(&self.excluded_samples) {
+            if !available.contains(name) {
+                match sample::near_matches(name, &available).as_slice() {
+                    [] => warn!("Sample {} is not found in the header", name),
+                    v => warn!(
+                        "Sample {} is not found in the header (did you mean {}?)",
+                        name,
+                        v.join(", ")
+                    ),
+                }
+            }
+        }
+
+        let selected = sample::select(&available, self.samples.as_deref(), &self.excluded_samples);
+        if selected.len() == available.len() {
+            return Ok(());
+        }
+
+        // an empty list is passed as NULL to exclude all samples
+        let list = CString::new(selected.join(","))?;
+        let ret = unsafe {
+            htslib::bcf_hdr_set_samples(
+                header.inner,
+                if selected.is_empty() {
+                    std::ptr::null()
+                } else {
+                    list.as_ptr()
+                },
+                0,
+            )
+        };
+        if ret != 0 {
+            Err(Error::InvalidArgumentError(format!(
+                "failed to select samples: {}",
+                selected.join(", ")
+            )))?
+        }
+
+        Ok(())
+    }
+
+    /// Load the index at `index` and check that its contigs are declared in the header of `path`.
+    ///
+    /// CSI indices of BCF refer to contigs by header order, so they can not be checked by name.
+    fn load_index(path: &str, index: &Path, bcf: bool) -> Result<Index> {
+        let index_str = match index.to_str() {
+            Some(p) if index.exists() => p,
+            Some(p) => Err(Error::ExplicitIndexNotFoundError(p.to_string()))?,
+            None => Err(Error::FilePathError(index.to_string_lossy().to_string()))?,
+        };
+
+        let p = CString::new(path)?;
+        let i = CString::new(index_str)?;
+
+        if bcf {
+            let idx = unsafe { htslib::hts_idx_load2(p.as_ptr(), i.as_ptr()) };
+            if idx.is_null() {
+                Err(htslib_error::Fetch)?;
+            }
+            return Ok(Index::Csi(idx));
+        }
+
+        let tbx: *mut htslib::tbx_t = unsafe { htslib::tbx_index_load2(p.as_ptr(), i.as_ptr()) };
+
+        if tbx.is_null() {
+            Err(htslib_error::Fetch)?;
+        }
+
+        if let Some(contig) = Self::undeclared_contig(path, tbx) {
+            unsafe {
+                htslib::tbx_destroy(tbx);
+            }
+            Err(Error::IndexMismatchError(
+                index_str.to_string(),
+                format!("contig {} is not declared in the header", contig),
+            ))?;
+        }
+
+        Ok(Index::Tabix(tbx))
+    }
+
+    /// Returns the first contig in the index that is missing in the header.
+    ///
+    /// Headers without `##contig` lines can not be checked.
+    fn undeclared_contig(path: &str, tbx: *mut htslib::tbx_t) -> Option<String> {
+        let reader = vcf::open(path).ok()?;
+        let header = reader.header();
+
+        let declared = header
+            .header_records()
+            .iter()
+            .any(|x| matches!(x, bcf::HeaderRecord::Contig { .. }));
+        if !declared {
+            return None;
+        }
+
+        let mut nseq: i32 = 0;
+        let seqs = unsafe { htslib::tbx_seqnames(tbx, &mut nseq) };
+        if seqs.is_null() {
+            return None;
+        }
+
+        let names: Vec<Vec<u8>> = (0..nseq as isize)
+            .map(|i| {
+                unsafe { CStr::from_ptr(*seqs.offset(i)) }
+                    .to_bytes()
+                    .to_vec()
+            })
+            .collect();
+
+        unsafe {
+            libc::free(seqs as *mut libc::c_void);
+        };
+
+        names
+            .into_iter()
+            .find(|x| header.name2rid(x).is_err())
+            .map(|x| String::from_utf8_lossy(&x).to_string())
+    }
+
+    /// Returns the path to the index next to `path` with `extension`, e.g. `.tbi`.
+    fn default_index_path(path: &str, extension: &str) -> Option<PathBuf> {
         let p = Path::new(path);
 
         match (p.parent(), p.file_name()) {
             (Some(parent), Some(file_name)) => {
                 let mut file = OsString::from(file_name);
-                file.push(".tbi");
+                file.push(extension);
                 Some(parent.join(file))
             }
             _ => None,
         }
     }
 
-    fn references(&self, path: &str) -> BTreeMap<u32, Sequence> {
-        let mut map = BTreeMap::new();
+    /// Resolve sequences of all contigs in the header at once, indexed by rid.
+    fn sequences(&self, header: &bcf::header::HeaderView) -> Vec<Option<Sequence>> {
+        contig_names(header)
+            .iter()
+            .map(|name| self.resolve(name))
+            .collect()
+    }
 
-        if let Ok(reader) = bcf::Reader::from_path(path) {
-            reader.header().header_records().iter().for_each(|x| {
-                if let bcf::HeaderRecord::Contig { key: _key, values } = x {
-                    if let Some(Ok(idx)) = values.get("IDX").map(|v| u32::from_str(v)) {
-                        if let Some(id) = values.get("ID") {
-                            if let Some(Some(seq)) = self.references.get(id) {
-                                map.insert(idx, seq.clone());
-                            }
-                        }
-                    }
+    /// Returns the sequence for `contig` in the configuration, or synthesized by `reference_default`.
+    ///
+    /// Keys of the configuration are tried in the order of `contig` as is, `contig_aliases` and names
+    /// in built-in assemblies. A key configured as null is not defaulted.
+    pub fn resolve(&self, contig: &str) -> Option<Sequence> {
+        match self.reference_key(contig) {
+            Some(key) => self.references.get(key).cloned().flatten(),
+            None => self.default_sequence(contig),
+        }
+    }
+
+    /// Returns a sequence of `contig` synthesized from `reference_default`.
+    fn default_sequence(&self, contig: &str) -> Option<Sequence> {
+        let template = self.reference_default.as_ref()?;
+
+        Some(Sequence {
+            name: Some(contig.to_owned()),
+            reference: template.render(|_| Some(iri::percent_encode(contig))),
+            ..Default::default()
+        })
+    }
+
+    /// Returns the key of `contig` in the configuration.
+    ///
+    /// If not configured as is, `contig_aliases` and then names of the contig in built-in assemblies
+    /// (name, GenBank and RefSeq accessions and UCSC name) are tried, e.g. `NC_000001.10` for `chr1`.
+    fn reference_key<'a>(&'a self, contig: &'a str) -> Option<&'a str> {
+        if self.references.contains_key(contig) {
+            return Some(contig);
+        }
+
+        if let Some(key) = self.contig_aliases.get(contig) {
+            if self.references.contains_key(key) {
+                return Some(key.as_str());
+            }
+        }
+
+        assembly::aliases(contig)
+            .into_iter()
+            .find(|x| self.references.contains_key(*x))
+    }
+
+    /// Returns contigs in the header resolved via alias with their keys in the configuration,
+    /// contigs resolved via `reference_default`, and contigs not found in the configuration.
+    fn contig_mapping(
+        &self,
+        header: &bcf::header::HeaderView,
+    ) -> (BTreeMap<String, String>, Vec<String>, Vec<String>) {
+        let mut aliased = BTreeMap::new();
+        let mut defaulted = Vec::new();
+        let mut unmapped = Vec::new();
+
+        for name in contig_names(header) {
+            match self.reference_key(&name) {
+                Some(key) if key != name => {
+                    aliased.insert(name.clone(), key.to_owned());
                 }
-            });
+                Some(_) => {}
+                None if self.reference_default.is_some() => defaulted.push(name),
+                None => unmapped.push(name),
+            }
         }
 
-        map
+        (aliased, defaulted, unmapped)
     }
 
-    fn filters(&self, path: &str) -> BTreeMap<u32, String> {
+    fn filters(&self, header: &bcf::header::HeaderView) -> BTreeMap<u32, String> {
         let mut map = BTreeMap::new();
 
-        if let Ok(reader) = bcf::Reader::from_path(path) {
-            reader.header().header_records().iter().for_each(|x| {
-                if let bcf::HeaderRecord::Filter { values, .. } = x {
-                    if let Some(v) = values.get("ID") {
-                        if let Ok(id) = reader.header().name_to_id(v.as_bytes()) {
-                            map.insert(id.0, v.to_owned());
-                        }
+        header.header_records().iter().for_each(|x| {
+            if let bcf::HeaderRecord::Filter { values, .. } = x {
+                if let Some(v) = values.get("ID") {
+                    if let Ok(id) = header.name_to_id(v.as_bytes()) {
+                        map.insert(id.0, v.to_owned());
                     }
                 }
-            });
-        }
+            }
+        });
 
         map
     }
 
-    fn info(&self, path: &str) -> BTreeMap<String, (bcf::header::TagType, bcf::header::TagLength)> {
+    fn info_definitions(
+        &self,
+        header: &bcf::header::HeaderView,
+    ) -> BTreeMap<String, InfoDefinition> {
         let mut map = BTreeMap::new();
 
-        if let Ok(reader) = bcf::Reader::from_path(path) {
-            reader.header().header_records().iter().for_each(|x| {
-                if let bcf::HeaderRecord::Info { values, .. } = x {
-                    if let Some(v) = values.get("ID") {
-                        if let Ok((typ, len)) = reader.header().info_type(v.as_bytes()) {
-                            map.insert(v.to_owned(), (typ, len));
-                        }
+        header.header_records().iter().for_each(|x| {
+            if let bcf::HeaderRecord::Info { values, .. } = x {
+                if let Some(v) = values.get("ID") {
+                    if let Ok((typ, length)) = header.info_type(v.as_bytes()) {
+                        let description = values.get("Description").map(|x| {
+                            x.strip_prefix('"')
+                                .and_then(|x| x.strip_suffix('"'))
+                                .unwrap_or(x)
+                                .to_owned()
+                        });
+
+                        map.insert(
+                            v.to_owned(),
+                            InfoDefinition {
+                                typ,
+                                length,
+                                description,
+                            },
+                        );
                     }
                 }
-            });
-        }
+            }
+        });
 
         map
     }
@@ -157,16 +634,39 @@ impl ReaderBuilder {
 #[derive(Debug)]
 pub struct Reader {
     reader: bcf::Reader,
-    // mapping contigs to references
-    references: BTreeMap<u32, Sequence>,
+    path: CString,
+    // merged regions to fetch, or all records if empty
+    regions: Vec<Region>,
+    // resolved sequences indexed by rid
+    sequences: Vec<Option<Sequence>>,
+    // contigs resolved via alias with their keys in the configuration
+    aliased_contigs: BTreeMap<String, String>,
+    // contigs resolved via the default template
+    defaulted_contigs: Vec<String>,
+    // contigs not found in the configuration
+    unmapped_contigs: Vec<String>,
     // header cache
     filters: BTreeMap<u32, String>,
     // header cache
     info: BTreeMap<String, (bcf::header::TagType, bcf::header::TagLength)>,
+    // header cache with descriptions
+    info_definitions: BTreeMap<String, InfoDefinition>,
     // list of keys to read
     info_keys: Vec<String>,
+    // header cache, which are those selected if restricted
+    samples: Vec<String>,
+    // separators of String values by key
+    info_separators: BTreeMap<String, String>,
+    // datatypes overriding the header by key
+    info_types: BTreeMap<String, InfoType>,
+    // counts of decoding INFO values
+    info_stats: RefCell<InfoStats>,
     normalize: bool,
-    tbx: *mut htslib::tbx_t,
+    // not loaded if read sequentially
+    index: Option<Index>,
+    threads: usize,
+    // copy of stdin removed on drop
+    temp_dir: Option<TempDir>,
 }
 
 impl Reader {
@@ -178,6 +678,44 @@ impl Reader {
         self.reader.header()
     }
 
+    /// Returns names of samples in the header, which are those selected if restricted.
+    pub fn samples(&self) -> Vec<String> {
+        self.samples.clone()
+    }
+
+    pub fn sample_count(&self) -> usize {
+        self.reader.header().sample_count() as usize
+    }
+
+    /// Returns the version in `##fileformat`, e.g. `VCFv4.3`.
+    pub fn fileformat(&self) -> Option<String> {
+        header::fileformat(self.reader.header())
+    }
+
+    /// Returns values of unstructured meta-information lines of `key`, e.g. `source` or `reference`.
+    pub fn meta(&self, key: &str) -> Vec<String> {
+        header::meta(self.reader.header())
+            .into_iter()
+            .filter(|(k, _)| k == key)
+            .map(|(_, v)| v)
+            .collect()
+    }
+
+    /// Returns definitions of FILTER in the header, including `PASS`.
+    pub fn filters_defs(&self) -> Vec<Definition> {
+        header::definitions(self.reader.header(), DefinitionKind::Filter)
+    }
+
+    /// Returns definitions of INFO in the header.
+    pub fn info_defs(&self) -> Vec<Definition> {
+        header::definitions(self.reader.header(), DefinitionKind::Info)
+    }
+
+    /// Returns definitions of FORMAT in the header.
+    pub fn format_defs(&self) -> Vec<Definition> {
+        header::definitions(self.reader.header(), DefinitionKind::Format)
+    }
+
     pub fn contigs(&self) -> BTreeMap<u32, String> {
         let mut map = BTreeMap::new();
 
@@ -194,47 +732,162 @@ impl Reader {
         map
     }
 
-    pub fn references(&self) -> &BTreeMap<u32, Sequence> {
-        &self.references
+    /// Returns names of contigs in the header with their lengths if given.
+    pub fn contig_lengths(&self) -> Vec<(String, Option<u64>)> {
+        self.reader
+            .header()
+            .header_records()
+            .iter()
+            .filter_map(|x| match x {
+                bcf::HeaderRecord::Contig { values, .. } => Some((
+                    values.get("ID")?.to_owned(),
+                    values.get("length").and_then(|x| x.parse::<u64>().ok()),
+                )),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns resolved sequences indexed by rid.
+    pub fn sequences(&self) -> &[Option<Sequence>] {
+        &self.sequences
+    }
+
+    /// Returns contigs in the header resolved via alias, with their keys in the configuration.
+    pub fn aliased_contigs(&self) -> &BTreeMap<String, String> {
+        &self.aliased_contigs
+    }
+
+    /// Returns contigs in the header not listed in the configuration, resolved via `reference_default`.
+    pub fn defaulted_contigs(&self) -> &[String] {
+        &self.defaulted_contigs
+    }
+
+    /// Returns contigs in the header not found in the configuration, of which records are ignored.
+    pub fn unmapped_contigs(&self) -> &[String] {
+        &self.unmapped_contigs
     }
 
     pub fn info(&self) -> &BTreeMap<String, (bcf::header::TagType, bcf::header::TagLength)> {
         &self.info
     }
 
+    /// Returns INFO definitions in the header with their descriptions.
+    pub fn info_definitions(&self) -> &BTreeMap<String, InfoDefinition> {
+        &self.info_definitions
+    }
+
     pub fn info_keys(&self) -> &Vec<String> {
         &self.info_keys
     }
 
+    /// Returns counts of attempts and failures to decode INFO values of records read so far.
+    pub fn info_stats(&self) -> Ref<'_, InfoStats> {
+        self.info_stats.borrow()
+    }
+
+    /// Returns the number of records in the index, or 0 if read without the index (see `try_count`).
     pub fn count(&self) -> u64 {
+        self.try_count().unwrap_or(0)
+    }
+
+    /// Returns the number of records in the index, which is not available if read without the index.
+    pub fn try_count(&self) -> Result<u64> {
         let mut sum = 0;
-        let mut nseq: i32 = 0;
-        let seqs = unsafe { htslib::tbx_seqnames(self.tbx, &mut nseq) };
+        let idx = match self.index {
+            Some(index) => index.inner(),
+            None => Err(Error::IndexNotFoundError(
+                self.path.to_string_lossy().to_string(),
+            ))?,
+        };
+        let nseq = unsafe { htslib::hts_idx_nseq(idx) };
 
         for i in 0..nseq {
             let mut records: u64 = 0;
             let mut v: u64 = 0;
 
             unsafe {
-                htslib::hts_idx_get_stat((*self.tbx).idx, i, &mut records, &mut v);
+                htslib::hts_idx_get_stat(idx, i, &mut records, &mut v);
             }
             sum += records;
         }
 
-        unsafe {
-            libc::free(seqs as *mut libc::c_void);
+        Ok(sum)
+    }
+
+    pub fn records(&mut self) -> Records<'_> {
+        let fetch = match self.index {
+            Some(index) if !self.regions.is_empty() => Some(Fetch::new(
+                &self.path,
+                index,
+                self.regions.clone(),
+                self.threads,
+            )),
+            _ => None,
         };
 
-        sum
+        self.records_of(fetch)
     }
 
-    pub fn records(&mut self) -> Records<'_> {
+    /// Returns records overlapping `start..=end` (1-based) on `contig` through the index.
+    ///
+    /// `contig` may be an alias resolved in the same way as regions, e.g. `chr20` for `20`.
+    pub fn fetch(&mut self, contig: &str, start: u64, end: u64) -> Result<Records<'_>> {
+        let index = match self.index {
+            Some(index) => index,
+            None => Err(Error::InvalidArgumentError(
+                "records can not be fetched without an index".to_owned(),
+            ))?,
+        };
+
+        let header = self.reader.header();
+        let contigs = contig_names(header);
+        let name = match region::resolve_contig(contig, &contigs, &self.sequences) {
+            Some(name) => name.to_owned(),
+            None => Err(Error::UnknownContigError(
+                contig.to_owned(),
+                contigs.join(", "),
+            ))?,
+        };
+
+        let length = header.header_records().iter().find_map(|x| match x {
+            bcf::HeaderRecord::Contig { values, .. } if values.get("ID") == Some(&name) => {
+                values.get("length").and_then(|x| x.parse::<u64>().ok())
+            }
+            _ => None,
+        });
+        if start == 0 || start > end || length.map_or(false, |x| start > x) {
+            Err(Error::InvalidArgumentError(format!(
+                "{}:{}-{} is out of range{}",
+                contig,
+                start,
+                end,
+                length.map_or_else(String::new, |x| format!(" (length: {})", x))
+            )))?
+        }
+
+        let region = Region {
+            contig: name,
+            start,
+            end,
+        };
+        let fetch = Fetch::new(&self.path, index, vec![region], self.threads);
+
+        Ok(self.records_of(Some(fetch)))
+    }
+
+    fn records_of(&mut self, fetch: Option<Fetch>) -> Records<'_> {
         Records {
             reader: &mut self.reader,
-            references: &self.references,
+            fetch,
+            sequences: &self.sequences,
             filters: &self.filters,
-            info: &self.info,
+            info: &self.info_definitions,
             info_keys: &self.info_keys,
+            samples: &self.samples,
+            info_separators: &self.info_separators,
+            info_types: &self.info_types,
+            info_stats: &self.info_stats,
             normalize: self.normalize,
         }
     }
@@ -243,17 +896,108 @@ impl Reader {
 impl Drop for Reader {
     fn drop(&mut self) {
         unsafe {
-            htslib::tbx_destroy(self.tbx);
+            match self.index {
+                Some(Index::Tabix(tbx)) => htslib::tbx_destroy(tbx),
+                Some(Index::Csi(idx)) => htslib::hts_idx_destroy(idx),
+                None => {}
+            }
+        }
+    }
+}
+
+/// Returns names of contigs in the header indexed by rid.
+fn contig_names(header: &bcf::header::HeaderView) -> Vec<String> {
+    (0..header.contig_count())
+        .map(|rid| {
+            header
+                .rid2name(rid)
+                .map(|x| String::from_utf8_lossy(x).to_string())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+/// Copy `input` into a temporary directory, compressed with bgzip if plain text, and index it.
+///
+/// Returns the directory, which is removed on drop, and the path to the copy.
+fn materialize<R: io::Read>(input: &mut R) -> Result<(TempDir, PathBuf)> {
+    let dir = tempfile::tempdir()?;
+    let raw = dir.path().join("stdin");
+    io::copy(input, &mut File::create(&raw)?)?;
+
+    let format = vcf::get_format(&raw)?;
+    let path = if format.format == htslib::htsExactFormat_bcf {
+        let path = dir.path().join("stdin.bcf");
+        fs::rename(&raw, &path)?;
+
+        let p = CString::new(path.to_string_lossy().as_bytes())?;
+        if unsafe { htslib::bcf_index_build(p.as_ptr(), 14) } != 0 {
+            Err(Error::BcfIndexBuildFailedError(
+                path.to_string_lossy().to_string(),
+            ))?
+        }
+        path
+    } else if format.compression == htslib::htsCompression_bgzf {
+        let path = dir.path().join("stdin.vcf.gz");
+        fs::rename(&raw, &path)?;
+        tabix::create(&path)?;
+        path
+    } else if format.compression == htslib::htsCompression_no_compression {
+        let path = dir.path().join("stdin.vcf.gz");
+        compress::from_path(raw.clone(), Some(path.clone()), None, true)?;
+        fs::remove_file(&raw)?;
+        path
+    } else {
+        Err(Error::NotBgzipFileError("stdin".to_owned()))?
+    };
+
+    Ok((dir, path))
+}
+
+/// An index of the data file, tabix for bgzipped VCF or CSI for BCF.
+#[derive(Debug, Copy, Clone)]
+enum Index {
+    Tabix(*mut htslib::tbx_t),
+    Csi(*mut htslib::hts_idx_t),
+}
+
+impl Index {
+    fn is_null(&self) -> bool {
+        match *self {
+            Index::Tabix(tbx) => tbx.is_null(),
+            Index::Csi(idx) => idx.is_null(),
+        }
+    }
+
+    fn inner(&self) -> *mut htslib::hts_idx_t {
+        match *self {
+            Index::Tabix(tbx) => unsafe { (*tbx).idx },
+            Index::Csi(idx) => idx,
+        }
+    }
+
+    /// Returns the id of `contig` in the index, or a negative value if not found.
+    fn tid(&self, header: &bcf::header::HeaderView, contig: &CStr) -> i32 {
+        match *self {
+            Index::Tabix(tbx) => unsafe { htslib::tbx_name2id(tbx, contig.as_ptr()) },
+            Index::Csi(_) => header
+                .name2rid(contig.to_bytes())
+                .map_or(-1, |rid| rid as i32),
         }
     }
 }
 
 pub struct Records<'a> {
     reader: &'a mut bcf::Reader,
-    references: &'a BTreeMap<u32, Sequence>,
+    fetch: Option<Fetch>,
+    sequences: &'a [Option<Sequence>],
     filters: &'a BTreeMap<u32, String>,
-    info: &'a BTreeMap<String, (bcf::header::TagType, bcf::header::TagLength)>,
+    info: &'a BTreeMap<String, InfoDefinition>,
     info_keys: &'a Vec<String>,
+    samples: &'a [String],
+    info_separators: &'a BTreeMap<String, String>,
+    info_types: &'a BTreeMap<String, InfoType>,
+    info_stats: &'a RefCell<InfoStats>,
     normalize: bool,
 }
 
@@ -262,21 +1006,196 @@ impl<'a> Iterator for Records<'a> {
 
     fn next(&mut self) -> Option<Self::Item> {
         let mut record = self.reader.empty_record();
-        match self.reader.read(&mut record) {
+        let read = match self.fetch.as_mut() {
+            Some(fetch) => fetch.read(self.reader.header(), &mut record),
+            None => self.reader.read(&mut record),
+        };
+        match read {
+            Some(Err(htslib_error::BcfInvalidRecord)) => Some(Err(malformed(&record))),
             Some(Err(e)) => Some(Err(e.into())),
             Some(Ok(_)) => Some(Ok(record::Record::new(
                 record,
-                self.references,
+                self.sequences,
                 self.filters,
                 self.info,
                 self.info_keys,
+                self.samples,
                 self.normalize,
-            ))),
+            )
+            .with_info_stats(self.info_stats)
+            .with_info_separators(self.info_separators)
+            .with_info_types(self.info_types))),
             None => None,
         }
     }
 }
 
+/// Returns an error of `record` failed to be parsed, located by the contig and the position parsed before the failure.
+fn malformed(record: &bcf::Record) -> Error {
+    let contig = record
+        .rid()
+        .filter(|&rid| rid < record.header().contig_count())
+        .and_then(|rid| record.header().rid2name(rid).ok())
+        .map_or_else(
+            || "unknown".to_owned(),
+            |x| String::from_utf8_lossy(x).to_string(),
+        );
+
+    Error::MalformedRecordError(
+        contig,
+        record.pos().max(0) as u64 + 1,
+        htslib_error::BcfInvalidRecord.to_string(),
+    )
+}
+
+/// Records overlapping regions read through the index.
+struct Fetch {
+    fp: *mut htslib::htsFile,
+    index: Index,
+    regions: std::vec::IntoIter<Region>,
+    itr: *mut htslib::hts_itr_t,
+    // contig and 0-based end of the region fetched before, of which records are already read
+    previous: Option<(String, u64)>,
+    current: Option<Region>,
+    line: htslib::kstring_t,
+}
+
+impl Fetch {
+    fn new(path: &CString, index: Index, regions: Vec<Region>, threads: usize) -> Self {
+        let mode = CString::new("r").unwrap();
+        let fp = unsafe { htslib::hts_open(path.as_ptr(), mode.as_ptr()) };
+
+        if !fp.is_null() && threads > 1 {
+            unsafe { htslib::hts_set_threads(fp, threads as i32) };
+        }
+
+        Fetch {
+            fp,
+            index,
+            regions: regions.into_iter(),
+            itr: std::ptr::null_mut(),
+            previous: None,
+            current: None,
+            line: htslib::kstring_t {
+                l: 0,
+                m: 0,
+                s: std::ptr::null_mut(),
+            },
+        }
+    }
+
+    fn read(
+        &mut self,
+        header: &bcf::header::HeaderView,
+        record: &mut bcf::Record,
+    ) -> Option<std::result::Result<(), htslib_error>> {
+        if self.fp.is_null() {
+            return Some(Err(htslib_error::Fetch));
+        }
+
+        loop {
+            if self.itr.is_null() && !self.next_region(header)? {
+                continue;
+            }
+
+            // BCF records are read in place, while VCF lines are parsed below
+            let ret = unsafe {
+                match self.index {
+                    Index::Tabix(tbx) => htslib::hts_itr_next(
+                        htslib::hts_get_bgzfp(self.fp),
+                        self.itr,
+                        &mut self.line as *mut htslib::kstring_t as *mut libc::c_void,
+                        tbx as *mut libc::c_void,
+                    ),
+                    Index::Csi(_) => htslib::hts_itr_next(
+                        htslib::hts_get_bgzfp(self.fp),
+                        self.itr,
+                        record.inner as *mut libc::c_void,
+                        std::ptr::null_mut(),
+                    ),
+                }
+            };
+
+            if ret < -1 {
+                return Some(Err(htslib_error::Fetch));
+            }
+            if ret == -1 {
+                unsafe { htslib::hts_itr_destroy(self.itr) };
+                self.itr = std::ptr::null_mut();
+                continue;
+            }
+
+            let parsed = unsafe {
+                match self.index {
+                    Index::Tabix(_) => {
+                        htslib::vcf_parse(&mut self.line, header.inner, record.inner)
+                    }
+                    Index::Csi(_) => htslib::bcf_subset_format(header.inner, record.inner),
+                }
+            };
+            if parsed != 0 {
+                return Some(Err(htslib_error::BcfInvalidRecord));
+            }
+
+            // records overlapping the previous region on the contig have been read
+            match (self.previous.as_ref(), self.current.as_ref()) {
+                (Some((contig, end)), Some(current))
+                    if *contig == current.contig && (record.pos() as u64) < *end =>
+                {
+                    continue
+                }
+                _ => return Some(Ok(())),
+            }
+        }
+    }
+
+    /// Start fetching the next region, returning `None` if no regions remain,
+    /// or false if the contig is not indexed.
+    fn next_region(&mut self, header: &bcf::header::HeaderView) -> Option<bool> {
+        if let Some(current) = self.current.take() {
+            self.previous = Some((current.contig, current.end));
+        }
+
+        let region = self.regions.next()?;
+        let name = CString::new(region.contig.as_str()).ok()?;
+        let tid = self.index.tid(header, &name);
+        self.current = Some(region.clone());
+
+        if tid < 0 {
+            return Some(false);
+        }
+
+        self.itr = unsafe {
+            htslib::hts_itr_query(
+                self.index.inner(),
+                tid,
+                (region.start - 1) as htslib::hts_pos_t,
+                region.end as htslib::hts_pos_t,
+                match self.index {
+                    Index::Tabix(_) => Some(htslib::tbx_readrec),
+                    Index::Csi(_) => Some(htslib::bcf_readrec),
+                },
+            )
+        };
+
+        Some(!self.itr.is_null())
+    }
+}
+
+impl Drop for Fetch {
+    fn drop(&mut self) {
+        unsafe {
+            if !self.itr.is_null() {
+                htslib::hts_itr_destroy(self.itr);
+            }
+            if !self.fp.is_null() {
+                htslib::hts_close(self.fp);
+            }
+            libc::free(self.line.s as *mut libc::c_void);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use anyhow::anyhow;
@@ -310,6 +1229,185 @@ mod tests {
         assert_eq!(expect.to_string(), err.to_string());
     }
 
+    #[test]
+    fn test_exclude_info_keys() {
+        let vcf = ReaderBuilder::new()
+            .exclude_info_keys(vec!["DP".to_owned(), "H2".to_owned()])
+            .path("test/vcf_spec.vcf.gz")
+            .expect("Error opening file.");
+
+        assert_eq!(vcf.info_keys(), &vec!["AA", "AF", "DB", "NS"]);
+
+        let vcf = ReaderBuilder::new()
+            .info_keys(vec!["DP".to_owned(), "AF".to_owned()])
+            .exclude_info_keys(vec!["DP".to_owned()])
+            .path("test/vcf_spec.vcf.gz")
+            .expect("Error opening file.");
+
+        assert_eq!(vcf.info_keys(), &vec!["AF"]);
+    }
+
+    /// Place data and index in separate directories like `data/file.vcf.gz` and `index/file.vcf.gz.tbi`.
+    #[test]
+    fn test_samples() {
+        let samples = |builder: ReaderBuilder| {
+            let mut reader = builder.path("test/vcf_spec.vcf.gz").unwrap();
+            let names = reader.samples();
+            assert_eq!(reader.sample_count(), names.len());
+            if let Some(last) = names.len().checked_sub(1) {
+                let record = reader.records().next().unwrap().unwrap();
+                let genotypes = record.inner().genotypes().unwrap();
+                assert!(!genotypes.get(last).is_empty());
+            }
+
+            names
+        };
+
+        assert_eq!(
+            samples(ReaderBuilder::new()),
+            vec!["NA00001", "NA00002", "NA00003"]
+        );
+        assert_eq!(
+            samples(ReaderBuilder::new().samples(vec!["NA00003".to_owned(), "NA0002".to_owned()])),
+            vec!["NA00003"]
+        );
+        assert_eq!(
+            samples(ReaderBuilder::new().exclude_samples(vec!["NA00001".to_owned()])),
+            vec!["NA00002", "NA00003"]
+        );
+        assert!(samples(ReaderBuilder::new().samples(Vec::new())).is_empty());
+    }
+
+    fn positions(builder: ReaderBuilder) -> Vec<i64> {
+        let mut reader = builder
+            .path("test/vcf_spec.vcf.gz")
+            .expect("Error opening file.");
+
+        reader
+            .records()
+            .map(|x| x.expect("Error reading record.").inner().pos() + 1)
+            .collect()
+    }
+
+    fn regions(regions: &[&str]) -> ReaderBuilder {
+        ReaderBuilder::new().regions(regions.iter().map(|x| x.parse().unwrap()).collect())
+    }
+
+    #[test]
+    fn test_regions() {
+        assert_eq!(
+            positions(ReaderBuilder::new()),
+            vec![14370, 17330, 1110696, 1230237, 1234567]
+        );
+        assert_eq!(
+            positions(regions(&["20:17000-1200000"])),
+            vec![17330, 1110696]
+        );
+        assert_eq!(positions(regions(&["20:14370"])), vec![14370]);
+        assert_eq!(positions(regions(&["20"])).len(), 5);
+        // overlapping and unordered regions
+        assert_eq!(
+            positions(regions(&["20:1230000-", "20:1-20000", "20:15000-18000"])),
+            vec![14370, 17330, 1230237, 1234567]
+        );
+        // the deletion at 1234567 spans 1234567-1234569
+        assert_eq!(
+            positions(regions(&["20:1234567", "20:1234569"])),
+            vec![1234567]
+        );
+        assert!(positions(regions(&["20:1-100"])).is_empty());
+    }
+
+    #[test]
+    fn test_regions_alias() {
+        let mut reference = BTreeMap::new();
+        reference.insert(
+            "20".to_owned(),
+            Some(Sequence {
+                name: Some("chr20".to_owned()),
+                ..Default::default()
+            }),
+        );
+
+        assert_eq!(
+            positions(regions(&["chr20:14370-17330"]).reference(reference)),
+            vec![14370, 17330]
+        );
+        assert!(regions(&["chr2:1-100"])
+            .path("test/vcf_spec.vcf.gz")
+            .is_err());
+    }
+
+    fn separate_index(name: &str) -> (tempfile::TempDir, PathBuf, PathBuf) {
+        let dir = tempfile::tempdir().expect("Error creating temporary directory.");
+        let data = dir.path().join("data");
+        let index = dir.path().join("index");
+        std::fs::create_dir(&data).unwrap();
+        std::fs::create_dir(&index).unwrap();
+
+        let file = format!("{}.vcf.gz", name);
+        let data = data.join(&file);
+        let index = index.join(format!("{}.tbi", file));
+        std::fs::copy(format!("test/{}", file), &data).unwrap();
+        std::fs::copy(format!("test/{}.tbi", file), &index).unwrap();
+
+        (dir, data, index)
+    }
+
+    #[test]
+    fn test_index_path() {
+        let (_dir, data, index) = separate_index("vcf_spec");
+
+        let mut vcf = ReaderBuilder::new()
+            .index_path(index)
+            .path(&data)
+            .expect("Error opening file.");
+
+        assert_eq!(vcf.count(), 5);
+        assert_eq!(vcf.records().count(), 5);
+    }
+
+    #[test]
+    fn test_index_path_is_not_derived() {
+        let (_dir, data, _index) = separate_index("vcf_spec");
+
+        let expect = anyhow!(Error::IndexNotFoundError(format!(
+            "{}.tbi",
+            data.to_string_lossy()
+        )));
+        let err = Reader::from_path(&data).expect_err("unexpected result");
+
+        assert_eq!(expect.to_string(), err.to_string());
+    }
+
+    #[test]
+    fn test_index_path_not_found() {
+        let (dir, data, _index) = separate_index("vcf_spec");
+        let index = dir.path().join("vcf_spec.vcf.gz.tbi");
+
+        let expect = anyhow!(Error::ExplicitIndexNotFoundError(
+            index.to_string_lossy().to_string()
+        ));
+        let err = ReaderBuilder::new()
+            .index_path(index)
+            .path(&data)
+            .expect_err("unexpected result");
+
+        assert_eq!(expect.to_string(), err.to_string());
+    }
+
+    #[test]
+    fn test_index_path_mismatch() {
+        let (_dir, data, _index) = separate_index("vcf_spec");
+
+        let err = ReaderBuilder::new()
+            .index_path(PathBuf::from("test/dbsnp_example.vcf.gz.tbi"))
+            .path(&data)
+            .expect_err("unexpected result");
+
+        assert!(err.to_string().contains("does not match"));
+    }
+
     #[test]
     fn test_info_keys() {
         let vcf = Reader::from_path("test/dbsnp_example.vcf.gz").expect("Error opening file.");
@@ -335,6 +1433,21 @@ mod tests {
         assert!(info_types.get("NOT_FOUND").is_none());
     }
 
+    #[test]
+    fn test_header_accessors() {
+        let vcf = Reader::from_path("test/vcf_spec.vcf.gz").expect("Error opening file.");
+
+        assert_eq!(vcf.fileformat().as_deref(), Some("VCFv4.3"));
+        assert_eq!(
+            vcf.meta("reference"),
+            vec!["file:///seq/references/1000GenomesPilot-NCBI36.fasta".to_owned()]
+        );
+        assert!(vcf.meta("unknown").is_empty());
+        assert_eq!(vcf.filters_defs().len(), 3);
+        assert_eq!(vcf.info_defs().len(), 6);
+        assert_eq!(vcf.format_defs()[3].number.as_deref(), Some("2"));
+    }
+
     #[test]
     fn test_contig() {
         let vcf = Reader::from_path("test/dbsnp_example.vcf.gz").expect("Error opening file.");
@@ -344,6 +1457,310 @@ mod tests {
         assert_eq!(contigs.get(&23).unwrap(), "NC_000024.9");
     }
 
+    #[test]
+    fn test_sequences() {
+        let mut reference = BTreeMap::new();
+        reference.insert(
+            "NC_000002.11".to_owned(),
+            Some(Sequence {
+                name: Some("2".to_owned()),
+                ..Default::default()
+            }),
+        );
+        reference.insert("NC_000003.11".to_owned(), None);
+
+        let vcf = ReaderBuilder::new()
+            .reference(reference)
+            .path("test/dbsnp_example.vcf.gz")
+            .expect("Error opening file.");
+        let sequences = vcf.sequences();
+
+        assert_eq!(sequences.len(), vcf.contigs().len());
+        assert!(sequences[0].is_none());
+        assert_eq!(
+            sequences[1].as_ref().and_then(|x| x.name.as_deref()),
+            Some("2")
+        );
+        assert!(sequences[2].is_none());
+    }
+
+    #[test]
+    fn test_contig_aliases() {
+        let mut reference = BTreeMap::new();
+        reference.insert(
+            "chr1".to_owned(),
+            Some(Sequence {
+                name: Some("1".to_owned()),
+                ..Default::default()
+            }),
+        );
+        reference.insert(
+            "2".to_owned(),
+            Some(Sequence {
+                name: Some("2".to_owned()),
+                ..Default::default()
+            }),
+        );
+        let mut aliases = BTreeMap::new();
+        aliases.insert("NC_000002.11".to_owned(), "2".to_owned());
+
+        let vcf = ReaderBuilder::new()
+            .reference(reference)
+            .contig_aliases(aliases)
+            .path("test/dbsnp_example.vcf.gz")
+            .expect("Error opening file.");
+        let sequences = vcf.sequences();
+
+        assert_eq!(
+            sequences[0].as_ref().and_then(|x| x.name.as_deref()),
+            Some("1")
+        );
+        assert_eq!(
+            sequences[1].as_ref().and_then(|x| x.name.as_deref()),
+            Some("2")
+        );
+        assert!(sequences[2].is_none());
+
+        assert_eq!(
+            vcf.aliased_contigs()
+                .get("NC_000001.10")
+                .map(|x| x.as_str()),
+            Some("chr1")
+        );
+        assert_eq!(
+            vcf.aliased_contigs()
+                .get("NC_000002.11")
+                .map(|x| x.as_str()),
+            Some("2")
+        );
+        assert_eq!(vcf.aliased_contigs().len(), 2);
+        assert_eq!(vcf.unmapped_contigs().len(), vcf.contigs().len() - 2);
+        assert!(vcf.unmapped_contigs().contains(&"NC_000003.11".to_owned()));
+    }
+
+    #[test]
+    fn test_resolve() {
+        let sequence = |name: &str| {
+            Some(Sequence {
+                name: Some(name.to_owned()),
+                ..Default::default()
+            })
+        };
+
+        let mut reference = BTreeMap::new();
+        reference.insert("chr1".to_owned(), sequence("key"));
+        reference.insert("1".to_owned(), sequence("assembly alias of chr1"));
+        reference.insert("custom2".to_owned(), sequence("contig alias"));
+        reference.insert("2".to_owned(), sequence("assembly alias of chr2"));
+        reference.insert("3".to_owned(), sequence("assembly alias of chr3"));
+        reference.insert("chrM".to_owned(), None);
+
+        let mut aliases = BTreeMap::new();
+        aliases.insert("chr1".to_owned(), "custom2".to_owned());
+        aliases.insert("chr2".to_owned(), "custom2".to_owned());
+        aliases.insert("chr3".to_owned(), "missing".to_owned());
+
+        let builder = ReaderBuilder::new()
+            .reference(reference)
+            .contig_aliases(aliases)
+            .reference_default(
+                Template::parse(
+                    "https://identifiers.org/insdc/{contig}",
+                    REFERENCE_DEFAULT_PLACEHOLDERS,
+                )
+                .unwrap(),
+            );
+        let name = |contig: &str| builder.resolve(contig).and_then(|x| x.name);
+
+        // the key takes precedence over contig_aliases and assembly aliases
+        assert_eq!(name("chr1").as_deref(), Some("key"));
+        // contig_aliases take precedence over assembly aliases
+        assert_eq!(name("chr2").as_deref(), Some("contig alias"));
+        // assembly aliases are tried if the alias is not a key
+        assert_eq!(name("chr3").as_deref(), Some("assembly alias of chr3"));
+        // null is not defaulted
+        assert!(builder.resolve("chrM").is_none());
+        // reference_default is the last resort
+        assert_eq!(
+            builder.resolve("MN908947.3").and_then(|x| x.reference),
+            Some("https://identifiers.org/insdc/MN908947.3".to_owned())
+        );
+        assert!(ReaderBuilder::new().resolve("MN908947.3").is_none());
+
+        // contigs are percent-encoded
+        assert_eq!(
+            builder.resolve("HLA-A*01:01").and_then(|x| x.reference),
+            Some("https://identifiers.org/insdc/HLA-A%2A01%3A01".to_owned())
+        );
+        assert_eq!(
+            builder.resolve("HLA-A*01:01").and_then(|x| x.name),
+            Some("HLA-A*01:01".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_reference_default() {
+        let mut reference = BTreeMap::new();
+        reference.insert(
+            "NC_000001.10".to_owned(),
+            Some(Sequence {
+                name: Some("1".to_owned()),
+                reference: Some("http://identifiers.org/hco/1/GRCh37".to_owned()),
+                ..Default::default()
+            }),
+        );
+        reference.insert("NC_000002.11".to_owned(), None);
+
+        let vcf = ReaderBuilder::new()
+            .reference(reference)
+            .reference_default(
+                Template::parse(
+                    "https://identifiers.org/insdc/{contig}",
+                    REFERENCE_DEFAULT_PLACEHOLDERS,
+                )
+                .unwrap(),
+            )
+            .path("test/dbsnp_example.vcf.gz")
+            .expect("Error opening file.");
+        let sequences = vcf.sequences();
+
+        assert_eq!(
+            sequences[0].as_ref().and_then(|x| x.reference.as_deref()),
+            Some("http://identifiers.org/hco/1/GRCh37")
+        );
+        assert!(sequences[1].is_none());
+        assert_eq!(
+            sequences[2].as_ref().and_then(|x| x.name.as_deref()),
+            Some("NC_000003.11")
+        );
+        assert_eq!(
+            sequences[2].as_ref().and_then(|x| x.reference.as_deref()),
+            Some("https://identifiers.org/insdc/NC_000003.11")
+        );
+
+        assert_eq!(vcf.defaulted_contigs().len(), vcf.contigs().len() - 2);
+        assert!(vcf.defaulted_contigs().contains(&"NC_000003.11".to_owned()));
+        assert!(vcf.unmapped_contigs().is_empty());
+    }
+
+    #[test]
+    fn test_record_sequence() {
+        let mut reference = BTreeMap::new();
+        reference.insert(
+            "NC_000002.11".to_owned(),
+            Some(Sequence {
+                name: Some("2".to_owned()),
+                ..Default::default()
+            }),
+        );
+
+        let mut vcf = ReaderBuilder::new()
+            .reference(reference)
+            .path("test/dbsnp_example.vcf.gz")
+            .expect("Error opening file.");
+        let records: Vec<_> = vcf
+            .records()
+            .map(|x| x.expect("Error reading record."))
+            .collect();
+
+        assert!(records[0].sequence().is_none());
+        assert_eq!(
+            records[14].sequence().and_then(|x| x.name.as_deref()),
+            Some("2")
+        );
+    }
+
+    #[test]
+    fn test_fetch() {
+        let mut reader = Reader::from_path("test/vcf_spec.vcf.gz").expect("Error opening file.");
+
+        let mut fetch = |contig: &str, start: u64, end: u64| -> Result<Vec<i64>> {
+            Ok(reader
+                .fetch(contig, start, end)?
+                .map(|x| x.expect("Error reading record.").inner().pos() + 1)
+                .collect())
+        };
+
+        assert_eq!(fetch("20", 1, 20000).unwrap(), vec![14370, 17330]);
+        assert_eq!(fetch("20", 1230237, 1230237).unwrap(), vec![1230237]);
+        // records before the previous fetch are read again
+        assert_eq!(fetch("20", 14370, 14370).unwrap(), vec![14370]);
+        assert!(fetch("20", 1, 100).unwrap().is_empty());
+
+        assert_eq!(
+            fetch("chr2", 1, 100).unwrap_err().to_string(),
+            "Unknown contig: chr2 (available: 20)"
+        );
+        assert_eq!(
+            fetch("20", 70000000, 70000100).unwrap_err().to_string(),
+            "Invalid argument: 20:70000000-70000100 is out of range (length: 62435964)"
+        );
+        assert!(fetch("20", 200, 100).is_err());
+        assert!(fetch("20", 0, 100).is_err());
+    }
+
+    #[test]
+    fn test_require_index() {
+        let p = "test/dbsnp_example.vcf";
+
+        let mut vcf = ReaderBuilder::new()
+            .require_index(false)
+            .path(p)
+            .expect("Error opening file.");
+
+        assert_eq!(vcf.count(), 0);
+        assert!(vcf.try_count().is_err());
+        assert_eq!(vcf.records().count(), 250);
+
+        assert!(ReaderBuilder::new()
+            .require_index(false)
+            .regions(vec!["NC_000001.10".parse().unwrap()])
+            .path(p)
+            .is_err());
+    }
+
+    #[test]
+    fn test_is_indexed() {
+        let builder = ReaderBuilder::new();
+
+        assert!(builder.is_indexed("test/vcf_spec.vcf.gz"));
+        assert!(!builder.is_indexed("test/vcf_spec.vcf"));
+        assert!(!builder.is_indexed("-"));
+        assert!(builder.is_indexed("https://example.org/input.vcf.gz"));
+
+        let (_dir, bcf) = crate::util::fixture::bcf("vcf_spec");
+        assert!(builder.is_indexed(&bcf));
+
+        assert!(ReaderBuilder::new()
+            .index_path(PathBuf::from("index/vcf_spec.vcf.gz.tbi"))
+            .is_indexed("data/vcf_spec.vcf.gz"));
+    }
+
+    #[test]
+    fn test_materialize() {
+        let records = |path: &Path| {
+            let mut reader = Reader::from_path(path).expect("Error opening file.");
+            assert_eq!(reader.count(), 5);
+            reader.records().count()
+        };
+
+        for input in &["test/vcf_spec.vcf", "test/vcf_spec.vcf.gz"] {
+            let (dir, path) = materialize(&mut File::open(input).unwrap()).unwrap();
+
+            assert_eq!(path, dir.path().join("stdin.vcf.gz"));
+            assert!(!dir.path().join("stdin").exists());
+            assert_eq!(records(&path), 5);
+
+            drop(dir);
+            assert!(!path.exists());
+        }
+
+        let (_dir, bcf) = crate::util::fixture::bcf("vcf_spec");
+        let (dir, path) = materialize(&mut File::open(bcf).unwrap()).unwrap();
+        assert_eq!(path, dir.path().join("stdin.bcf"));
+        assert_eq!(records(&path), 5);
+    }
+
     #[test]
     fn test_count() {
         let vcf = Reader::from_path("test/dbsnp_example.vcf.gz").expect("Error opening file.");
@@ -351,6 +1768,55 @@ mod tests {
         assert_eq!(vcf.count(), 250);
     }
 
+    #[test]
+    fn test_bcf() {
+        let (_dir, path) = crate::util::fixture::bcf("dbsnp_example");
+        let mut bcf = Reader::from_path(&path).expect("Error opening file.");
+        let mut vcf = Reader::from_path("test/dbsnp_example.vcf.gz").expect("Error opening file.");
+
+        assert_eq!(bcf.count(), 250);
+
+        let records = |reader: &mut Reader| -> Vec<(u32, i64, Vec<Vec<u8>>)> {
+            reader
+                .records()
+                .map(|x| {
+                    let record = x.expect("Error reading record.");
+                    let r = record.inner();
+                    (
+                        r.rid().unwrap(),
+                        r.pos(),
+                        r.alleles().iter().map(|x| x.to_vec()).collect(),
+                    )
+                })
+                .collect()
+        };
+        assert_eq!(records(&mut bcf), records(&mut vcf));
+
+        let positions: Vec<i64> = ReaderBuilder::new()
+            .regions(vec!["NC_000002.11:10007-10015".parse().unwrap()])
+            .path(&path)
+            .expect("Error opening file.")
+            .records()
+            .map(|x| x.expect("Error reading record.").inner().pos() + 1)
+            .collect();
+        assert_eq!(positions, vec![10007, 10015]);
+    }
+
+    #[test]
+    fn test_bcf_without_index() {
+        let (_dir, path) = crate::util::fixture::bcf("dbsnp_example");
+        let mut csi = path.clone().into_os_string();
+        csi.push(".csi");
+        std::fs::remove_file(&csi).unwrap();
+
+        let err = Reader::from_path(&path).expect_err("unexpected result");
+
+        assert_eq!(
+            err.to_string(),
+            Error::IndexNotFoundError(csi.to_string_lossy().to_string()).to_string()
+        );
+    }
+
     fn read_vcf_as_vec<P: AsRef<Path>>(path: P) -> Vec<bcf::Record> {
         let mut vcf = Reader::from_path(path).expect("Error opening file.");
         vcf.reader
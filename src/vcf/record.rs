@@ -1,10 +1,17 @@
+use std::borrow::Cow;
+use std::cell::RefCell;
 use std::collections::BTreeMap;
 use std::fmt::{Display, Formatter};
 
 use rust_htslib::bcf;
+use rust_htslib::errors::Error as htslib_error;
+use rust_htslib::htslib;
 
-use crate::config::Sequence;
+use crate::config::{InfoType, Sequence};
 use crate::errors;
+use crate::rdf::iri;
+use crate::vcf::breakend::Breakend;
+use crate::vcf::sv::{self, StructuralVariant};
 
 pub mod as_turtle;
 
@@ -14,21 +21,38 @@ pub struct Contig {
     pub name: String,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum InfoValue {
     Flag(bool),
     Integer(i32),
     Float(f32),
     String(String),
+    /// An absolute IRI given by `info_types` in the configuration.
+    Iri(String),
+    /// A missing value `.`, which keeps the position of other values in the array.
+    Missing,
 }
 
+// sentinels of htslib, see `bcf_int32_missing` and `bcf_float_missing` in vcf.h
+const INT32_MISSING: i32 = i32::MIN;
+const INT32_VECTOR_END: i32 = i32::MIN + 1;
+const FLOAT_MISSING: u32 = 0x7F80_0001;
+const FLOAT_VECTOR_END: u32 = 0x7F80_0002;
+
 impl Display for InfoValue {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self)
+        match self {
+            InfoValue::Flag(v) => write!(f, "{}", v),
+            InfoValue::Integer(v) => write!(f, "{}", v),
+            InfoValue::Float(v) => write!(f, "{}", v),
+            InfoValue::String(v) => write!(f, "{}", v),
+            InfoValue::Iri(v) => write!(f, "{}", v),
+            InfoValue::Missing => write!(f, "."),
+        }
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Info<'a> {
     pub key: &'a str,
     pub value: Vec<InfoValue>,
@@ -36,45 +60,143 @@ pub struct Info<'a> {
     pub length: bcf::header::TagLength,
 }
 
+/// Definition of an INFO key in the header.
+#[derive(Debug, Clone)]
+pub struct InfoDefinition {
+    pub typ: bcf::header::TagType,
+    pub length: bcf::header::TagLength,
+    /// `Description` without surrounding quotes.
+    pub description: Option<String>,
+}
+
+/// Counts of attempts and failures to decode INFO values by key.
+#[derive(Debug, Default)]
+pub struct InfoStats {
+    counts: BTreeMap<String, (u64, u64)>,
+    conversion_failures: BTreeMap<String, u64>,
+}
+
+impl InfoStats {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Record an attempt to decode a value of `key`.
+    pub fn add(&mut self, key: &str, failed: bool) {
+        if !self.counts.contains_key(key) {
+            self.counts.insert(key.to_owned(), (0, 0));
+        }
+
+        if let Some(count) = self.counts.get_mut(key) {
+            count.0 += 1;
+            if failed {
+                count.1 += 1;
+            }
+        }
+    }
+
+    /// Record `n` values of `key` which failed to be converted to the type in the configuration.
+    pub fn add_conversion_failures(&mut self, key: &str, n: u64) {
+        *self.conversion_failures.entry(key.to_owned()).or_insert(0) += n;
+    }
+
+    /// Returns the number of values which failed to be converted by key.
+    pub fn conversion_failures(&self) -> &BTreeMap<String, u64> {
+        &self.conversion_failures
+    }
+
+    /// Returns the number of attempts and failures for `key`.
+    pub fn get(&self, key: &str) -> Option<(u64, u64)> {
+        self.counts.get(key).copied()
+    }
+
+    /// Returns keys which failed to be decoded more than `threshold` (fraction) of attempts.
+    pub fn failing_keys(&self, threshold: f64) -> Vec<(&str, u64, u64)> {
+        self.counts
+            .iter()
+            .filter(|(_, &(attempts, failures))| {
+                failures > 0 && failures as f64 > attempts as f64 * threshold
+            })
+            .map(|(k, &(attempts, failures))| (k.as_str(), attempts, failures))
+            .collect()
+    }
+}
+
 #[derive(Debug)]
 pub struct Record<'a> {
     inner: bcf::record::Record,
-    references: &'a BTreeMap<u32, Sequence>,
+    sequences: &'a [Option<Sequence>],
     filters: &'a BTreeMap<u32, String>,
-    info: &'a BTreeMap<String, (bcf::header::TagType, bcf::header::TagLength)>,
+    info: &'a BTreeMap<String, InfoDefinition>,
     info_keys: &'a Vec<String>,
+    samples: &'a [String],
+    info_stats: Option<&'a RefCell<InfoStats>>,
+    info_separators: Option<&'a BTreeMap<String, String>>,
+    info_types: Option<&'a BTreeMap<String, InfoType>>,
     normalize: bool,
 }
 
 impl<'a> Record<'a> {
     pub fn new(
         inner: bcf::record::Record,
-        references: &'a BTreeMap<u32, Sequence>,
+        sequences: &'a [Option<Sequence>],
         filters: &'a BTreeMap<u32, String>,
-        info: &'a BTreeMap<String, (bcf::header::TagType, bcf::header::TagLength)>,
+        info: &'a BTreeMap<String, InfoDefinition>,
         info_keys: &'a Vec<String>,
+        samples: &'a [String],
         normalize: bool,
     ) -> Self {
         Self {
             inner,
-            references,
+            sequences,
             filters,
             info,
             info_keys,
+            samples,
+            info_stats: None,
+            info_separators: None,
+            info_types: None,
             normalize,
         }
     }
 
+    /// Count attempts and failures to decode INFO values of the keys to read into `stats`.
+    pub fn with_info_stats(mut self, stats: &'a RefCell<InfoStats>) -> Self {
+        self.info_stats = Some(stats);
+        self
+    }
+
+    /// Split String values of INFO keys by `separators`, e.g. `CLNDN` => `|`.
+    pub fn with_info_separators(mut self, separators: &'a BTreeMap<String, String>) -> Self {
+        self.info_separators = Some(separators);
+        self
+    }
+
+    /// Convert INFO values to `types` overriding the header, e.g. `dbSNPBuildID` => `integer`.
+    pub fn with_info_types(mut self, types: &'a BTreeMap<String, InfoType>) -> Self {
+        self.info_types = Some(types);
+        self
+    }
+
+    /// Returns the definition of INFO `key` in the header.
+    pub fn info_definition(&self, key: &str) -> Option<&InfoDefinition> {
+        self.info.get(key)
+    }
+
     pub fn inner(&self) -> &bcf::record::Record {
         &self.inner
     }
 
     pub fn sequence(&self) -> Option<&Sequence> {
-        if let Some(rid) = self.inner.rid() {
-            self.references.get(&rid)
-        } else {
-            None
-        }
+        self.inner
+            .rid()
+            .and_then(|rid| self.sequences.get(rid as usize)?.as_ref())
+    }
+
+    /// Returns the sequence of `contig` in the header, e.g. the mate of a breakend.
+    pub fn sequence_of(&self, contig: &str) -> Option<&Sequence> {
+        let rid = self.inner.header().name2rid(contig.as_bytes()).ok()?;
+        self.sequences.get(rid as usize)?.as_ref()
     }
 
     pub fn chromosome(&self) -> Option<errors::Result<&str>> {
@@ -87,6 +209,51 @@ impl<'a> Record<'a> {
         })
     }
 
+    /// Returns `length` of the contig of this record in the header.
+    pub fn contig_length(&self) -> Option<u64> {
+        let name = self.chromosome()?.ok()?;
+
+        self.inner
+            .header()
+            .header_records()
+            .into_iter()
+            .find_map(|x| match x {
+                bcf::HeaderRecord::Contig { values, .. }
+                    if values.get("ID").map(|x| x.as_str()) == Some(name) =>
+                {
+                    values.get("length")?.parse().ok()
+                }
+                _ => None,
+            })
+    }
+
+    /// Returns the INFO column formatted by htslib, e.g. `NS=3;DP=14;AF=0.5;DB;H2`.
+    pub fn info_string(&self) -> Option<String> {
+        let mut line = htslib::kstring_t {
+            l: 0,
+            m: 0,
+            s: std::ptr::null_mut(),
+        };
+
+        let ret =
+            unsafe { htslib::vcf_format(self.inner.header().inner, self.inner.inner, &mut line) };
+
+        let formatted = if ret == 0 && !line.s.is_null() {
+            let bytes = unsafe { std::slice::from_raw_parts(line.s as *const u8, line.l as usize) };
+            Some(String::from_utf8_lossy(bytes).into_owned())
+        } else {
+            None
+        };
+
+        unsafe { libc::free(line.s as *mut libc::c_void) };
+
+        formatted?
+            .trim_end_matches('\n')
+            .split('\t')
+            .nth(7)
+            .map(|x| x.to_owned())
+    }
+
     pub fn quality(&self) -> f32 {
         self.inner.qual()
     }
@@ -99,74 +266,185 @@ impl<'a> Record<'a> {
             .collect()
     }
 
+    /// Returns names of samples in the header, in the order of genotypes.
+    pub fn samples(&self) -> &[String] {
+        self.samples
+    }
+
     pub fn info(&self) -> Vec<Info> {
         self.info_keys
             .iter()
-            .filter_map(|key| match self.info.get(key.to_string().as_str()) {
-                Some(&(typ, length)) => match typ {
-                    bcf::header::TagType::Flag => self.extract_flag(key),
-                    bcf::header::TagType::Integer => self.extract_integer(key),
-                    bcf::header::TagType::Float => self.extract_float(key),
-                    bcf::header::TagType::String => self.extract_string(key),
+            .filter_map(|key| {
+                let info = self.decode_info(key);
+
+                if let Some(stats) = self.info_stats {
+                    match info {
+                        Ok(None) => {}
+                        _ => stats.borrow_mut().add(key, info.is_err()),
+                    }
                 }
-                .and_then(|value| {
-                    Some(Info {
-                        key,
-                        value,
-                        typ,
-                        length,
-                    })
-                }),
-                _ => self.extract_string(key).and_then(|value| {
-                    Some(Info {
-                        key,
-                        value,
-                        typ: bcf::header::TagType::String,
-                        length: bcf::header::TagLength::Fixed(1),
-                    })
-                }),
+
+                let (info, failures) = self.convert_info(info.ok().flatten()?);
+
+                if let (Some(stats), true) = (self.info_stats, failures > 0) {
+                    stats
+                        .borrow_mut()
+                        .add_conversion_failures(key, failures as u64);
+                }
+
+                Some(info)
             })
             .collect()
     }
 
-    fn extract_string<S: AsRef<str>>(&self, key: S) -> Option<Vec<InfoValue>> {
-        let info = self.inner.info(key.as_ref().as_bytes());
+    /// Extract a value of INFO field by `key` regardless of the keys to read.
+    pub fn info_by_key<'k>(&self, key: &'k str) -> Option<Info<'k>> {
+        self.decode_info(key)
+            .ok()
+            .flatten()
+            .map(|x| self.convert_info(x).0)
+    }
 
-        info.string().ok().and_then(|string| {
-            string.and_then(|v| {
-                Some(
-                    v.iter()
-                        .map(|&x| unsafe {
-                            InfoValue::String(String::from_utf8_unchecked(x.to_vec()))
-                        })
-                        .collect(),
-                )
+    /// Convert values of `info` to the type in `info_types` if configured.
+    ///
+    /// Values failing to be converted are regarded as missing, and the number of them is returned.
+    fn convert_info<'k>(&self, info: Info<'k>) -> (Info<'k>, usize) {
+        let typ = match self.info_types.and_then(|x| x.get(info.key)) {
+            Some(&v) => v,
+            None => return (info, 0),
+        };
+
+        let mut failures = 0;
+        let value = info
+            .value
+            .into_iter()
+            .map(|v| match v {
+                InfoValue::Missing => v,
+                _ => convert(v, typ).unwrap_or_else(|| {
+                    failures += 1;
+                    InfoValue::Missing
+                }),
             })
+            .collect();
+
+        let converted = Info {
+            key: info.key,
+            value,
+            typ: match (typ, info.typ) {
+                (InfoType::Integer, _) => bcf::header::TagType::Integer,
+                (InfoType::Float, _) => bcf::header::TagType::Float,
+                (InfoType::Boolean, bcf::header::TagType::Flag) => bcf::header::TagType::Flag,
+                _ => bcf::header::TagType::String,
+            },
+            length: info.length,
+        };
+
+        (converted, failures)
+    }
+
+    /// Decode a value of INFO field by `key`.
+    ///
+    /// Returns `Ok(None)` if the field is absent, or an error if htslib fails to decode it.
+    ///
+    /// String values of keys with a separator are split, and the length of `Number=<n>` is regarded as
+    /// variable.
+    fn decode_info<'k>(&self, key: &'k str) -> std::result::Result<Option<Info<'k>>, htslib_error> {
+        let split = self.info_separators.map_or(false, |x| x.contains_key(key));
+
+        Ok(match self.info.get(key).map(|x| (x.typ, x.length)) {
+            Some((typ, length)) => match typ {
+                bcf::header::TagType::Flag => self.extract_flag(key),
+                bcf::header::TagType::Integer => self.extract_integer(key),
+                bcf::header::TagType::Float => self.extract_float(key),
+                bcf::header::TagType::String => self.extract_string(key),
+            }?
+            .map(|value| Info {
+                key,
+                value,
+                typ,
+                length: match (typ, length) {
+                    (bcf::header::TagType::String, bcf::header::TagLength::Fixed(_)) if split => {
+                        bcf::header::TagLength::Variable
+                    }
+                    _ => length,
+                },
+            }),
+            _ => self.extract_string(key)?.map(|value| Info {
+                key,
+                value,
+                typ: bcf::header::TagType::String,
+                length: if split {
+                    bcf::header::TagLength::Variable
+                } else {
+                    bcf::header::TagLength::Fixed(1)
+                },
+            }),
         })
     }
 
-    fn extract_float<S: AsRef<str>>(&self, key: S) -> Option<Vec<InfoValue>> {
+    fn extract_string<S: AsRef<str>>(
+        &self,
+        key: S,
+    ) -> std::result::Result<Option<Vec<InfoValue>>, htslib_error> {
         let info = self.inner.info(key.as_ref().as_bytes());
+        let separator = self
+            .info_separators
+            .and_then(|x| x.get(key.as_ref()))
+            .map(|x| x.as_bytes());
+
+        let value = |x: &[u8]| match x {
+            b"." => InfoValue::Missing,
+            _ => unsafe { InfoValue::String(String::from_utf8_unchecked(x.to_vec())) },
+        };
+
+        // split before percent-decoding, so that encoded separators are kept in values
+        Ok(info.string()?.map(|v| match separator {
+            Some(sep) => v.iter().flat_map(|&x| split(x, sep)).map(value).collect(),
+            None => v.iter().map(|&x| value(x)).collect(),
+        }))
+    }
 
-        info.float().ok().and_then(|float| {
-            float.and_then(|v| Some(v.iter().map(|&x| InfoValue::Float(x)).collect()))
-        })
+    fn extract_float<S: AsRef<str>>(
+        &self,
+        key: S,
+    ) -> std::result::Result<Option<Vec<InfoValue>>, htslib_error> {
+        let info = self.inner.info(key.as_ref().as_bytes());
+
+        Ok(info.float()?.map(|v| {
+            v.iter()
+                .take_while(|x| x.to_bits() != FLOAT_VECTOR_END)
+                .map(|&x| match x.to_bits() {
+                    FLOAT_MISSING => InfoValue::Missing,
+                    _ => InfoValue::Float(x),
+                })
+                .collect()
+        }))
     }
 
-    fn extract_integer<S: AsRef<str>>(&self, key: S) -> Option<Vec<InfoValue>> {
+    fn extract_integer<S: AsRef<str>>(
+        &self,
+        key: S,
+    ) -> std::result::Result<Option<Vec<InfoValue>>, htslib_error> {
         let info = self.inner.info(key.as_ref().as_bytes());
 
-        info.integer().ok().and_then(|integer| {
-            integer.and_then(|v| Some(v.iter().map(|&x| InfoValue::Integer(x)).collect()))
-        })
+        Ok(info.integer()?.map(|v| {
+            v.iter()
+                .take_while(|&&x| x != INT32_VECTOR_END)
+                .map(|&x| match x {
+                    INT32_MISSING => InfoValue::Missing,
+                    _ => InfoValue::Integer(x),
+                })
+                .collect()
+        }))
     }
 
-    fn extract_flag<S: AsRef<str>>(&self, key: S) -> Option<Vec<InfoValue>> {
+    fn extract_flag<S: AsRef<str>>(
+        &self,
+        key: S,
+    ) -> std::result::Result<Option<Vec<InfoValue>>, htslib_error> {
         let mut info = self.inner.info(key.as_ref().as_bytes());
 
-        info.flag()
-            .ok()
-            .and_then(|flag| Some(vec![InfoValue::Flag(flag)]))
+        Ok(Some(vec![InfoValue::Flag(info.flag()?)]))
     }
 
     pub fn each_alternate_alleles(&self) -> Entries {
@@ -194,7 +472,8 @@ impl<'a> Iterator for Entries<'a> {
             .get(self.index + 1)
             .map(|&x| Entry {
                 record: self.record,
-                alternate_allele: x,
+                reference: uppercase(self.record.inner.alleles().first().map_or(&[][..], |&x| x)),
+                alternate: uppercase(x),
                 index: self.index,
             });
 
@@ -204,10 +483,26 @@ impl<'a> Iterator for Entries<'a> {
     }
 }
 
+/// Returns `allele` in uppercase if it consists of letters only, e.g. soft-masked `acgt`.
+///
+/// Symbolic alleles and breakends are kept as is.
+fn uppercase(allele: &[u8]) -> Cow<str> {
+    let allele = unsafe { std::str::from_utf8_unchecked(allele) };
+
+    if allele.bytes().any(|x| x.is_ascii_lowercase())
+        && allele.bytes().all(|x| x.is_ascii_alphabetic())
+    {
+        Cow::Owned(allele.to_ascii_uppercase())
+    } else {
+        Cow::Borrowed(allele)
+    }
+}
+
 #[derive(Debug)]
 pub struct Entry<'a> {
     record: &'a Record<'a>,
-    alternate_allele: &'a [u8],
+    reference: Cow<'a, str>,
+    alternate: Cow<'a, str>,
     index: usize,
 }
 
@@ -259,15 +554,310 @@ impl<'a> Entry<'a> {
         }
     }
 
+    /// Returns the reference allele, which is uppercased if written in lowercase.
     pub fn reference_bases(&self) -> &str {
-        self.record
-            .inner()
-            .alleles()
-            .first()
-            .map_or("", |&x| unsafe { std::str::from_utf8_unchecked(x) })
+        &self.reference
     }
 
+    /// Returns the alternate allele, which is uppercased if written in lowercase.
     pub fn alternate_bases(&self) -> &str {
-        unsafe { std::str::from_utf8_unchecked(self.alternate_allele) }
+        &self.alternate
+    }
+
+    /// Returns true if any allele is uppercased from lowercase bases.
+    pub fn is_case_normalized(&self) -> bool {
+        matches!(self.reference, Cow::Owned(_)) || matches!(self.alternate, Cow::Owned(_))
+    }
+
+    /// Returns the breakend if the alternate allele is in a bracket form, e.g. `G]17:198982]`.
+    pub fn breakend(&self) -> Option<Breakend> {
+        Breakend::parse(self.alternate_bases())
+    }
+
+    /// Returns `INFO/MATEID` of a breakend.
+    pub fn mate_id(&self) -> Option<String> {
+        let info = self.record.info_by_key("MATEID")?;
+        match info.value.into_iter().next() {
+            Some(InfoValue::String(v)) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Returns the structural variant if the alternate allele is symbolic, e.g. `<DEL>`.
+    ///
+    /// The extent is read from `INFO/END` and `INFO/SVLEN`, and the type from `INFO/SVTYPE` or the allele.
+    pub fn structural_variant(&self) -> Option<StructuralVariant> {
+        sv::symbolic_id(self.alternate_bases())?;
+
+        let integer = |key: &str, index: usize| {
+            let info = self.record.info_by_key(key)?;
+            match info.value.get(index).or_else(|| info.value.first())? {
+                InfoValue::Integer(v) => Some(*v as i64),
+                _ => None,
+            }
+        };
+        let svtype =
+            self.record
+                .info_by_key("SVTYPE")
+                .and_then(|x| match x.value.into_iter().next()? {
+                    InfoValue::String(v) => Some(v),
+                    _ => None,
+                });
+
+        let interval = |key: &str| Some((integer(key, 0)?, integer(key, 1)?));
+
+        Some(
+            StructuralVariant::new(
+                self.position(),
+                self.alternate_bases(),
+                svtype.as_deref(),
+                integer("END", 0),
+                integer("SVLEN", self.index),
+            )?
+            .with_confidence_intervals(
+                interval("CIPOS"),
+                interval("CIEND"),
+                self.record.contig_length(),
+            ),
+        )
+    }
+}
+
+/// Convert `value` to `typ`, or returns `None` if it can not be parsed.
+fn convert(value: InfoValue, typ: InfoType) -> Option<InfoValue> {
+    let string = value.to_string();
+
+    match typ {
+        InfoType::Integer => match value {
+            InfoValue::Integer(_) => Some(value),
+            _ => string.parse().ok().map(InfoValue::Integer),
+        },
+        InfoType::Float => match value {
+            InfoValue::Integer(v) => Some(InfoValue::Float(v as f32)),
+            InfoValue::Float(_) => Some(value),
+            _ => string.parse().ok().map(InfoValue::Float),
+        },
+        InfoType::Boolean => match string.as_str() {
+            "true" | "1" => Some(InfoValue::Flag(true)),
+            "false" | "0" => Some(InfoValue::Flag(false)),
+            _ => None,
+        },
+        InfoType::String => Some(InfoValue::String(string)),
+        InfoType::Iri if iri::is_absolute(&string) => Some(InfoValue::Iri(string)),
+        InfoType::Iri => None,
+    }
+}
+
+/// Split `bytes` by a non-empty `separator`.
+fn split<'b>(bytes: &'b [u8], separator: &'b [u8]) -> impl Iterator<Item = &'b [u8]> {
+    let mut rest = Some(bytes);
+
+    std::iter::from_fn(move || {
+        let current = rest?;
+        match current
+            .windows(separator.len())
+            .position(|x| x == separator)
+        {
+            Some(i) => {
+                rest = Some(&current[i + separator.len()..]);
+                Some(&current[..i])
+            }
+            None => {
+                rest = None;
+                Some(current)
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::fixture;
+    use crate::vcf::reader::ReaderBuilder;
+
+    #[test]
+    fn test_split() {
+        fn parts<'b>(bytes: &'b [u8], sep: &'b [u8]) -> Vec<&'b [u8]> {
+            split(bytes, sep).collect()
+        }
+
+        assert_eq!(parts(b"a|b||c", b"|"), vec![&b"a"[..], b"b", b"", b"c"]);
+        assert_eq!(parts(b"a%2Cb,c", b","), vec![&b"a%2Cb"[..], b"c"]);
+        assert_eq!(parts(b"a::b", b"::"), vec![&b"a"[..], b"b"]);
+        assert_eq!(parts(b"abc", b"|"), vec![&b"abc"[..]]);
+    }
+
+    #[test]
+    fn test_split_info_values() {
+        let (_dir, path) = fixture::bgzip("clinvar_example");
+        let mut separators = BTreeMap::new();
+        separators.insert("CLNDN".to_owned(), "|".to_owned());
+        separators.insert("CLNDISDB".to_owned(), ",".to_owned());
+
+        let mut reader = ReaderBuilder::new()
+            .info_separators(separators)
+            .path(path)
+            .expect("Error opening file.");
+
+        let info = reader
+            .records()
+            .nth(1)
+            .unwrap()
+            .expect("Error reading record.")
+            .info()
+            .into_iter()
+            .map(|x| (x.key.to_owned(), (x.value, x.length)))
+            .collect::<BTreeMap<_, _>>();
+
+        assert_eq!(
+            info["CLNDN"].0,
+            vec![
+                InfoValue::String("Myasthenic_syndrome%2C_congenital%2C_8".to_owned()),
+                InfoValue::String("not_provided".to_owned())
+            ]
+        );
+        assert!(matches!(info["CLNDN"].1, bcf::header::TagLength::Variable));
+        assert_eq!(
+            info["CLNDISDB"].0,
+            vec![
+                InfoValue::String("MONDO:MONDO:0014005".to_owned()),
+                InfoValue::String("MedGen:C4225392|MedGen:CN517202".to_owned())
+            ]
+        );
+        assert_eq!(
+            info["CLNSIG"].0,
+            vec![InfoValue::String("Pathogenic/Likely_pathogenic".to_owned())]
+        );
+    }
+
+    #[test]
+    fn test_missing_info_values() {
+        let (_dir, path) = fixture::bgzip("missing_values");
+        let mut reader = ReaderBuilder::new()
+            .path(path)
+            .expect("Error opening file.");
+
+        let values = reader
+            .records()
+            .map(|record| {
+                record
+                    .expect("Error reading record.")
+                    .info()
+                    .into_iter()
+                    .map(|x| (x.key.to_owned(), x.value))
+                    .collect::<BTreeMap<_, _>>()
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(values[0]["AF"], vec![InfoValue::Missing]);
+        assert_eq!(values[0]["AC"], vec![InfoValue::Missing]);
+        assert_eq!(
+            values[0]["HQ"],
+            vec![InfoValue::Missing, InfoValue::Missing]
+        );
+        assert_eq!(values[0]["AA"], vec![InfoValue::Missing]);
+
+        assert_eq!(
+            values[1]["AF"],
+            vec![InfoValue::Missing, InfoValue::Float(0.667)]
+        );
+        assert_eq!(
+            values[1]["AC"],
+            vec![InfoValue::Integer(3), InfoValue::Missing]
+        );
+        assert_eq!(
+            values[1]["HQ"],
+            vec![InfoValue::Integer(51), InfoValue::Missing]
+        );
+
+        assert_eq!(values[2]["AF"], vec![InfoValue::Missing]);
+    }
+
+    #[test]
+    fn test_convert() {
+        let string = |v: &str| InfoValue::String(v.to_owned());
+
+        assert_eq!(
+            convert(string("155"), InfoType::Integer),
+            Some(InfoValue::Integer(155))
+        );
+        assert_eq!(convert(string("abc"), InfoType::Integer), None);
+        assert_eq!(
+            convert(InfoValue::Integer(1), InfoType::Float),
+            Some(InfoValue::Float(1.0))
+        );
+        assert_eq!(
+            convert(string("0"), InfoType::Boolean),
+            Some(InfoValue::Flag(false))
+        );
+        assert_eq!(
+            convert(InfoValue::Float(0.5), InfoType::String),
+            Some(string("0.5"))
+        );
+        assert_eq!(
+            convert(string("https://example.org/1"), InfoType::Iri),
+            Some(InfoValue::Iri("https://example.org/1".to_owned()))
+        );
+        assert_eq!(convert(string("gene_1"), InfoType::Iri), None);
+    }
+
+    #[test]
+    fn test_info_types() {
+        let (_dir, path) = fixture::bgzip("typed_values");
+        let mut types = BTreeMap::new();
+        types.insert("dbSNPBuildID".to_owned(), InfoType::Integer);
+        types.insert("VALIDATED".to_owned(), InfoType::Boolean);
+        types.insert("GENEURL".to_owned(), InfoType::Iri);
+
+        let mut reader = ReaderBuilder::new()
+            .info_types(types)
+            .path(path)
+            .expect("Error opening file.");
+
+        let values = reader
+            .records()
+            .map(|record| {
+                record
+                    .expect("Error reading record.")
+                    .info()
+                    .into_iter()
+                    .map(|x| (x.key.to_owned(), x.value))
+                    .collect::<BTreeMap<_, _>>()
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(values[0]["dbSNPBuildID"], vec![InfoValue::Integer(155)]);
+        assert_eq!(values[0]["VALIDATED"], vec![InfoValue::Flag(true)]);
+        assert_eq!(
+            values[0]["GENEURL"],
+            vec![InfoValue::Iri("https://example.org/gene/1".to_owned())]
+        );
+
+        assert_eq!(values[1]["dbSNPBuildID"], vec![InfoValue::Missing]);
+        assert_eq!(values[1]["VALIDATED"], vec![InfoValue::Flag(false)]);
+        assert_eq!(values[1]["GENEURL"], vec![InfoValue::Missing]);
+
+        let stats = reader.info_stats();
+        assert_eq!(stats.conversion_failures()["dbSNPBuildID"], 1);
+        assert_eq!(stats.conversion_failures()["GENEURL"], 1);
+        assert!(!stats.conversion_failures().contains_key("VALIDATED"));
+    }
+
+    #[test]
+    fn test_info_stats() {
+        let mut stats = InfoStats::new();
+
+        for i in 0..10 {
+            stats.add("DP", true);
+            stats.add("AF", i == 0);
+            stats.add("NS", false);
+        }
+
+        assert_eq!(stats.get("DP"), Some((10, 10)));
+        assert_eq!(stats.get("AF"), Some((10, 1)));
+        assert_eq!(stats.get("XX"), None);
+        assert_eq!(stats.failing_keys(0.5), vec![("DP", 10, 10)]);
+        assert_eq!(stats.failing_keys(0.0), vec![("AF", 10, 1), ("DP", 10, 10)]);
     }
 }
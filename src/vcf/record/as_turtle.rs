@@ -1,29 +1,55 @@
+use std::collections::BTreeSet;
 use std::io::Write;
+use std::sync::Arc;
 
+use log::*;
 use rust_htslib::bcf;
-use vcf_lib::record::{normalize, variant_type};
-use vcf_lib::VariantType;
+use rust_htslib::bcf::record::GenotypeAllele;
 
-use crate::errors::Result;
-use crate::rdf::turtle_writer::{AsTurtle, TurtleWriter};
+use crate::config::InfoParser;
+use crate::errors::{Error, Result};
+use crate::rdf::iri;
+use crate::rdf::namespace::GVO;
+use crate::rdf::turtle_writer::{escape, AsTurtle, CrosscheckVC, RdfStarMode, TurtleWriter};
 use crate::rdf::writer::Writer;
-use crate::vcf::record::{Entry, InfoValue};
+use crate::vcf::alteration::{Alteration, VariantType};
+use crate::vcf::breakend::Breakend;
+use crate::vcf::genotype::Zygosity;
+use crate::vcf::record::{Entry, Info, InfoValue};
+use crate::vcf::sv::{StructuralVariant, SvType};
+use crate::vcf::vep;
+
+mod genotype_summary;
+mod rdf_star;
 
 const BUFFER_DEFAULT: usize = 40 * 1024;
 
-struct Buffer {
-    string: String,
+/// Buffer to build Turtle strings, counting triples written and collecting prefixes of terms pushed.
+pub struct Buffer {
+    pub(crate) string: String,
+    pub(crate) triples: u64,
+    pub(crate) prefixes: BTreeSet<String>,
 }
 
 impl Default for Buffer {
     fn default() -> Self {
         Buffer {
             string: String::with_capacity(BUFFER_DEFAULT),
+            triples: 0,
+            prefixes: BTreeSet::new(),
         }
     }
 }
 
 impl Buffer {
+    pub fn new() -> Self {
+        Buffer {
+            string: String::new(),
+            triples: 0,
+            prefixes: BTreeSet::new(),
+        }
+    }
+
     pub fn push_str(&mut self, string: &str) {
         self.string.push_str(string)
     }
@@ -34,15 +60,79 @@ impl Buffer {
         self.string.push('>')
     }
 
+    /// Push a prefixed name as is, or an absolute IRI enclosed in angle brackets.
+    ///
+    /// The prefix of a prefixed name is collected to be declared in the header.
+    pub fn push_term(&mut self, term: &str) {
+        if term.starts_with('<') {
+            self.string.push_str(term);
+        } else if term.contains("://") {
+            self.push_iri(term);
+        } else {
+            match term.split_once(':') {
+                Some(("_", _)) | None => {}
+                Some((prefix, _)) => {
+                    self.prefixes.insert(prefix.to_owned());
+                }
+            }
+            self.string.push_str(term);
+        }
+    }
+
     pub fn push_quoted(&mut self, string: &str, quote: char) -> () {
         self.string.push(quote);
-        self.string.push_str(string.replace("\"", "\\\"").as_str());
+        self.string.push_str(escape(string).as_str());
         self.string.push(quote);
     }
+
+    /// Push `string` starting a triple, e.g. ` ;\n  ` followed by the predicate, or `, ` of an object list.
+    pub fn push_triple(&mut self, string: &str) {
+        self.triples += 1;
+        self.string.push_str(string)
+    }
+
+    /// Push `predicate` after `separator` starting a triple, e.g. ` ;\n  gvo:pos `.
+    pub fn push_predicate(&mut self, separator: &str, predicate: &str) {
+        self.push_triple(separator);
+        self.push_term(predicate);
+        self.string.push(' ')
+    }
+
+    /// Push `class` after `separator` as a triple of `rdf:type`, e.g. ` a gvo:SNV`.
+    pub fn push_class(&mut self, separator: &str, class: &str) {
+        self.push_triple(separator);
+        self.string.push_str("a ");
+        self.push_term(class)
+    }
+
+    /// Push a literal of `datatype`, e.g. `"true"^^xsd:boolean`.
+    pub fn push_typed(&mut self, value: &str, datatype: &str) {
+        self.push_quoted(value, '"');
+        self.string.push_str("^^");
+        self.push_term(datatype)
+    }
+
+    /// Push `objects` as an object list, each of which is a triple.
+    pub fn push_objects(&mut self, objects: &[Buffer]) {
+        for (i, object) in objects.iter().enumerate() {
+            if i != 0 {
+                self.string.push_str(", ");
+            }
+            self.append(object);
+        }
+        self.triples += objects.len() as u64;
+    }
+
+    /// Push `other` with its triples and prefixes.
+    pub fn append(&mut self, other: &Buffer) {
+        self.triples += other.triples;
+        self.string.push_str(&other.string);
+        self.prefixes.extend(other.prefixes.iter().cloned());
+    }
 }
 
 impl<W: Write> AsTurtle<W> for Entry<'_> {
-    fn as_ttl_string(&self, wtr: &TurtleWriter<W>) -> Result<Option<String>>
+    fn as_ttl_string(&self, wtr: &TurtleWriter<W>) -> Result<Option<Buffer>>
     where
         W: Write,
     {
@@ -57,46 +147,104 @@ impl<W: Write> AsTurtle<W> for Entry<'_> {
             return Ok(None);
         }
 
-        match wtr.format_subject(&self) {
+        let statement = wtr.get_rdf_star_mode() == Some(RdfStarMode::Statement);
+        let rdf_star = wtr.get_rdf_star_mode().is_some();
+
+        let breakend = self.breakend();
+        let mate_id = breakend.and_then(|_| self.mate_id());
+
+        let subject = match wtr.format_subject(&self) {
             Some(v) => {
-                buf.push_str("<");
-                buf.push_str(v.as_str());
-                buf.push_str(">");
+                let v = format!("<{}>", v);
+                wtr.track_subject(&v, &self)?;
+                v
             }
-            None => buf.push_str("[]"),
-        }
+            None => match wtr.entry_node(self.index, "") {
+                Some(v) => v,
+                // quoted triples and mates can not refer to anonymous blank nodes
+                None if rdf_star || mate_id.is_some() => wtr.next_blank_node(),
+                None => "[]".to_owned(),
+            },
+        };
+        buf.push_str(&subject);
 
-        let (n_pos, n_reference, n_alternate) = normalize(
-            self.position(),
-            self.reference_bases(),
-            self.alternate_bases(),
-        )?;
+        let sv = self.structural_variant();
 
-        let variant_type = variant_type(n_reference, n_alternate);
+        // an allele overlapped by an upstream deletion
+        let star = self.alternate_bases() == "*";
 
-        if let Some(typ) = variant_type.as_ref() {
-            buf.push_str(" a gvo:");
-            buf.push_str(match typ {
-                VariantType::SNV => "SNV",
-                VariantType::Deletion => "Deletion",
-                VariantType::Insertion => "Insertion",
-                VariantType::Indel => "Indel",
-                VariantType::MNV => "MNV",
-            });
+        // symbolic alleles, breakends and `*` are not normalized
+        let alteration = if sv.is_some() || breakend.is_some() || star {
+            Alteration {
+                position: self.position(),
+                reference: self.reference_bases(),
+                alternate: self.alternate_bases(),
+                typ: None,
+            }
         } else {
-            buf.push_str(" a gvo:Variation");
+            Alteration::normalize(
+                self.position(),
+                self.reference_bases(),
+                self.alternate_bases(),
+            )?
         };
+        let (n_pos, n_reference, n_alternate, variant_type) = (
+            alteration.position,
+            alteration.reference,
+            alteration.alternate,
+            alteration.typ,
+        );
+
+        let mut class = match (sv.as_ref(), breakend.as_ref()) {
+            (Some(sv), _) => sv.typ.class(),
+            (None, Some(_)) => "Breakend",
+            (None, None) if star => "OverlapDeletion",
+            (None, None) => variant_type.as_ref().map_or("Variation", variant_class),
+        };
+
+        if wtr.get_crosscheck_vc() != CrosscheckVC::Disabled {
+            if let Some(vc) = self.crosscheck_vc(wtr, class) {
+                if wtr.get_crosscheck_vc() == CrosscheckVC::Trust {
+                    class = vc;
+                }
+            }
+        }
+
+        buf.push_class(" ", &format!("gvo:{}", class));
 
         let id = unsafe { String::from_utf8_unchecked(self.record.inner.id()) };
         if !id.is_empty() || id != "." {
-            buf.push_str(" ;\n  dct:identifier ");
+            buf.push_predicate(" ;\n  ", "dct:identifier");
             buf.push_quoted(&id, '"');
         }
 
-        self.write_location(&mut buf, n_pos, n_reference, n_alternate);
+        let location = match (sv.as_ref(), breakend.as_ref()) {
+            (Some(sv), _) => {
+                self.write_sv_location(&mut buf, wtr.entry_node(self.index, "_loc").as_deref(), sv)
+            }
+            (None, Some(_)) => self
+                .write_breakend_location(&mut buf, wtr.entry_node(self.index, "_loc").as_deref()),
+            (None, None) if star => self
+                .write_reference_location(&mut buf, wtr.entry_node(self.index, "_loc").as_deref()),
+            (None, None) => self.write_location(
+                &mut buf,
+                wtr.entry_node(self.index, "_loc").as_deref(),
+                &alteration,
+            ),
+        };
+
+        if let Some(breakend) = breakend.as_ref() {
+            self.write_breakend(&mut buf, wtr, breakend);
+
+            // mates are linked when written, since they may go to another output
+            if let (Some(id), Some(mate_id)) = (self.id(), mate_id) {
+                wtr.defer_mate(id, mate_id, subject.clone());
+            }
+        }
 
+        let alt;
         if self.record.normalize {
-            buf.push_str(" ;\n  gvo:pos ");
+            buf.push_predicate(" ;\n  ", "gvo:pos");
             buf.push_str(
                 match variant_type {
                     Some(VariantType::Insertion) | Some(VariantType::Deletion) => n_pos + 1,
@@ -106,7 +254,7 @@ impl<W: Write> AsTurtle<W> for Entry<'_> {
                 .as_str(),
             );
 
-            buf.push_str(" ;\n  gvo:ref ");
+            buf.push_predicate(" ;\n  ", "gvo:ref");
             buf.push_quoted(
                 match variant_type {
                     Some(VariantType::Insertion) => "",
@@ -116,81 +264,244 @@ impl<W: Write> AsTurtle<W> for Entry<'_> {
                 '"',
             );
 
-            buf.push_str(" ;\n  gvo:alt ");
-            buf.push_quoted(
-                match variant_type {
-                    Some(VariantType::Deletion) => "",
-                    Some(VariantType::Insertion) => &n_alternate[1..],
-                    _ => n_alternate,
-                },
-                '"',
-            );
+            alt = match variant_type {
+                Some(VariantType::Deletion) => "",
+                Some(VariantType::Insertion) => &n_alternate[1..],
+                _ => n_alternate,
+            };
+            buf.push_predicate(" ;\n  ", "gvo:alt");
+            buf.push_quoted(alt, '"');
 
-            buf.push_str(" ;\n  gvo:pos_vcf ");
+            buf.push_predicate(" ;\n  ", "gvo:pos_vcf");
             buf.push_str(n_pos.to_string().as_str());
 
-            buf.push_str(" ;\n  gvo:ref_vcf ");
+            buf.push_predicate(" ;\n  ", "gvo:ref_vcf");
             buf.push_quoted(n_reference, '"');
 
-            buf.push_str(" ;\n  gvo:alt_vcf ");
+            buf.push_predicate(" ;\n  ", "gvo:alt_vcf");
             buf.push_quoted(n_alternate, '"');
         } else {
-            buf.push_str(" ;\n  gvo:pos ");
+            buf.push_predicate(" ;\n  ", "gvo:pos");
             buf.push_str(self.position().to_string().as_str());
 
-            buf.push_str(" ;\n  gvo:ref ");
+            buf.push_predicate(" ;\n  ", "gvo:ref");
             buf.push_quoted(self.reference_bases(), '"');
 
-            buf.push_str(" ;\n  gvo:alt ");
-            buf.push_quoted(self.alternate_bases(), '"');
+            alt = self.alternate_bases();
+            buf.push_predicate(" ;\n  ", "gvo:alt");
+            buf.push_quoted(alt, '"');
         };
 
         let quality = self.record.quality();
         if quality.is_finite() {
-            buf.push_str(" ;\n  gvo:qual ");
+            buf.push_predicate(" ;\n  ", "gvo:qual");
             buf.push_str(quality.to_string().as_str());
         }
 
         let filters = self.record.filters();
         if !filters.is_empty() {
-            buf.push_str(" ;\n  gvo:filter ");
+            buf.push_predicate(" ;\n  ", "gvo:filter");
 
             for (i, filter) in filters.iter().enumerate() {
                 if i != 0 {
-                    buf.push_str(", ");
+                    buf.push_triple(", ");
                 };
                 buf.push_quoted(filter, '"');
             }
         }
 
-        self.write_info(&mut buf);
+        for flag in wtr.get_quality_flags() {
+            let value = flag.evaluate(quality, &filters);
+
+            if !value && flag.omit_when_false {
+                continue;
+            }
+
+            buf.push_predicate(" ;\n  ", &flag.predicate);
+            buf.push_typed(if value { "true" } else { "false" }, "xsd:boolean");
+        }
+
+        let plain = wtr.get_plain_literals();
+
+        let info = self.record.info();
+        for x in &info {
+            self.check_alt_length(wtr, x)?;
+        }
+
+        // values of `rdf_star` annotate `gvo:alt` in the allele mode, and triples of `info_predicates` in the statement mode
+        let (annotations, info): (Vec<_>, Vec<_>) = info
+            .into_iter()
+            .filter(|x| !self.is_mismatched(x) && !self.is_missing(wtr, x))
+            .partition(|x| {
+                wtr.get_rdf_star_mode() == Some(RdfStarMode::Allele)
+                    && wtr.get_rdf_star().iter().any(|k| k == x.key)
+            });
+        let (consequences, info): (Vec<_>, Vec<_>) = info
+            .into_iter()
+            .partition(|x| self.vep_format(wtr, x.key).is_some());
+        let (direct, info): (Vec<_>, Vec<_>) = if wtr.get_keep_raw_info() {
+            let direct = info
+                .iter()
+                .filter(|x| wtr.get_info_predicate(x.key).is_some())
+                .cloned()
+                .collect();
+            (direct, info)
+        } else {
+            info.into_iter()
+                .partition(|x| wtr.get_info_predicate(x.key).is_some())
+        };
+
+        let key_predicate = wtr.get_info_key_predicate();
+
+        self.write_info(&mut buf, &info, wtr);
+        self.write_consequences(&mut buf, &consequences, wtr);
+
+        for info in &direct {
+            if let Some(predicate) = wtr.get_info_predicate(info.key) {
+                let objects = self.info_objects(info, plain, false);
+                if !objects.is_empty() {
+                    buf.push_str(" ;\n  ");
+                    buf.push_term(predicate);
+                    buf.push_str(" ");
+                    buf.push_objects(&objects);
+                }
+            }
+        }
+
+        if wtr.get_info_string() {
+            if let Some(info_string) = self.record.info_string() {
+                buf.push_predicate(" ;\n  ", "gvo:infoString");
+                buf.push_quoted(&info_string, '"');
+            }
+        }
+
+        if wtr.get_genotypes() {
+            self.write_genotypes(&mut buf, wtr);
+        }
+
+        if wtr.get_genotype_summary() {
+            self.write_genotype_summary(&mut buf, wtr);
+        }
+
+        if wtr.get_vcf_roundtrip() {
+            self.write_vcf_line(&mut buf);
+        }
+
+        buf.push_str(" .\n");
+
+        if let Some(location) = location {
+            buf.append(&location);
+        }
+
+        if key_predicate.is_some() {
+            for info in &info {
+                if !wtr.is_info_key_defined(info.key) {
+                    let description = self.info_description(info.key);
+                    wtr.define_info_key(info.key, description.as_deref());
+                    push_info_key_definition(&mut buf, info.key, description.as_deref());
+                }
+            }
+        }
+
+        if statement {
+            self.write_rdf_star_statements(&mut buf, &subject, &direct, wtr);
+        }
+
+        self.write_rdf_star_annotations(&mut buf, &subject, alt, &annotations, wtr);
+
+        Ok(Some(buf))
+    }
+}
 
-        buf.push_str(" .\n\n");
+/// Returns the name of gvo class for `typ`.
+fn variant_class(typ: &VariantType) -> &'static str {
+    match typ {
+        VariantType::SNV => "SNV",
+        VariantType::Deletion => "Deletion",
+        VariantType::Insertion => "Insertion",
+        VariantType::Indel => "Indel",
+        VariantType::MNV => "MNV",
+    }
+}
 
-        Ok(Some(buf.string))
+/// Returns names of gvo class consistent with the value of dbSNP `VC` tag.
+///
+/// The first element is used if the class of `VC` is trusted.
+/// dbSNP assigns `INDEL` to simple insertions and deletions as well.
+fn vc_classes(vc: &str) -> Option<&'static [&'static str]> {
+    match vc {
+        "SNV" => Some(&["SNV"]),
+        "MNV" => Some(&["MNV"]),
+        "INS" => Some(&["Insertion"]),
+        "DEL" => Some(&["Deletion"]),
+        "INDEL" => Some(&["Indel", "Insertion", "Deletion"]),
+        _ => None,
     }
 }
 
 impl Entry<'_> {
-    fn write_location(&self, buf: &mut Buffer, position: u64, reference: &str, alternate: &str) {
-        let typ = variant_type(reference, alternate);
+    /// Compare `class` with the dbSNP `VC` tag and returns the class of `VC` if they disagree.
+    fn crosscheck_vc<W: Write>(&self, wtr: &TurtleWriter<W>, class: &str) -> Option<&'static str> {
+        let vc = match self
+            .record
+            .info_by_key("VC")
+            .and_then(|x| x.value.into_iter().next())
+        {
+            Some(InfoValue::String(v)) => v,
+            _ => {
+                wtr.count("VC crosscheck: VC missing");
+                return None;
+            }
+        };
+
+        let classes = vc_classes(&vc);
+        let agreed = classes.map_or(false, |x| x.iter().any(|&c| c == class));
+
+        if agreed {
+            wtr.count(&format!(
+                "VC crosscheck: agreed (computed {}, VC {})",
+                class, vc
+            ));
+            None
+        } else {
+            wtr.count_warning(&format!(
+                "VC crosscheck: disagreed (computed {}, VC {})",
+                class, vc
+            ));
+            classes.and_then(|x| x.first().copied())
+        }
+    }
+
+    /// Write `faldo:location` nested in the entry, or referring to `node` if given.
+    ///
+    /// Returns the description of `node` to be written after the entry.
+    fn write_location(
+        &self,
+        out: &mut Buffer,
+        node: Option<&str>,
+        alteration: &Alteration,
+    ) -> Option<Buffer> {
+        let typ = alteration.typ;
+        let position = alteration.position;
+        let reference = alteration.reference;
 
         if typ.is_none() {
-            return;
+            return None;
         }
 
         let seq = self.record.sequence().map(|x| x.reference.as_ref());
 
-        buf.push_str(" ;\n  faldo:location [");
+        let buf = &mut Buffer::new();
 
         match typ {
             Some(VariantType::SNV) => {
                 // SNV
-                buf.push_str("\n    a faldo:ExactPosition ;");
-                buf.push_str("\n    faldo:position ");
+                buf.push_class("\n    ", "faldo:ExactPosition");
+                buf.push_str(" ;");
+                buf.push_predicate("\n    ", "faldo:position");
                 buf.push_str(position.to_string().as_str());
                 if let Some(Some(seq)) = seq {
-                    buf.push_str(" ;\n    faldo:reference ");
+                    buf.push_predicate(" ;\n    ", "faldo:reference");
                     buf.push_iri(seq);
                 }
             }
@@ -198,25 +509,27 @@ impl Entry<'_> {
                 // MNV
                 let p1 = position;
                 let p2 = position + reference.len() as u64 - 1;
-                buf.push_str("\n    a faldo:Region ;");
-                buf.push_str("\n    faldo:begin ");
+                buf.push_class("\n    ", "faldo:Region");
+                buf.push_str(" ;");
+                buf.push_predicate("\n    ", "faldo:begin");
                 buf.push_str(p1.to_string().as_str());
-                buf.push_str(" ;\n    faldo:end ");
+                buf.push_predicate(" ;\n    ", "faldo:end");
                 buf.push_str(p2.to_string().as_str());
                 if let Some(Some(seq)) = seq {
-                    buf.push_str(" ;\n    faldo:reference ");
+                    buf.push_predicate(" ;\n    ", "faldo:reference");
                     buf.push_iri(seq);
                 }
             }
             Some(VariantType::Insertion) => {
                 // Insertion
-                buf.push_str("\n    a faldo:InBetweenPosition ;");
-                buf.push_str("\n    faldo:after ");
+                buf.push_class("\n    ", "faldo:InBetweenPosition");
+                buf.push_str(" ;");
+                buf.push_predicate("\n    ", "faldo:after");
                 buf.push_str(position.to_string().as_str());
-                buf.push_str(" ;\n    faldo:before ");
+                buf.push_predicate(" ;\n    ", "faldo:before");
                 buf.push_str((position + 1).to_string().as_str());
                 if let Some(Some(seq)) = seq {
-                    buf.push_str(" ;\n    faldo:reference ");
+                    buf.push_predicate(" ;\n    ", "faldo:reference");
                     buf.push_iri(seq);
                 }
             }
@@ -224,27 +537,32 @@ impl Entry<'_> {
                 // Deletion
                 let p1 = position;
                 let p2 = position + reference.len() as u64 - 1;
-                buf.push_str("\n    a faldo:Region ;");
-                buf.push_str("\n    faldo:begin [");
-                buf.push_str("\n      a faldo:InBetweenPosition ;");
-                buf.push_str("\n      faldo:after ");
+                buf.push_class("\n    ", "faldo:Region");
+                buf.push_str(" ;");
+                buf.push_predicate("\n    ", "faldo:begin");
+                buf.push_str("[");
+                buf.push_class("\n      ", "faldo:InBetweenPosition");
+                buf.push_str(" ;");
+                buf.push_predicate("\n      ", "faldo:after");
                 buf.push_str(p1.to_string().as_str());
-                buf.push_str(" ;\n      faldo:before ");
+                buf.push_predicate(" ;\n      ", "faldo:before");
                 buf.push_str((p1 + 1).to_string().as_str());
                 if let Some(Some(seq)) = seq {
-                    buf.push_str(" ;\n      faldo:reference ");
+                    buf.push_predicate(" ;\n      ", "faldo:reference");
                     buf.push_iri(seq);
                 }
                 buf.push_str("\n    ] ;");
 
-                buf.push_str("\n    faldo:end [");
-                buf.push_str("\n      a faldo:InBetweenPosition ;");
-                buf.push_str("\n      faldo:after ");
+                buf.push_predicate("\n    ", "faldo:end");
+                buf.push_str("[");
+                buf.push_class("\n      ", "faldo:InBetweenPosition");
+                buf.push_str(" ;");
+                buf.push_predicate("\n      ", "faldo:after");
                 buf.push_str(p2.to_string().as_str());
-                buf.push_str(" ;\n      faldo:before ");
+                buf.push_predicate(" ;\n      ", "faldo:before");
                 buf.push_str((p2 + 1).to_string().as_str());
                 if let Some(Some(seq)) = seq {
-                    buf.push_str(" ;\n      faldo:reference ");
+                    buf.push_predicate(" ;\n      ", "faldo:reference");
                     buf.push_iri(seq);
                 }
                 buf.push_str("\n    ]");
@@ -253,91 +571,440 @@ impl Entry<'_> {
                 // Indel
                 let p1 = position;
                 let p2 = position + reference.len() as u64 - 1;
-                buf.push_str("\n    a faldo:Region ;");
-                buf.push_str("\n    faldo:begin [");
-                buf.push_str("\n      a faldo:InBetweenPosition ;");
-                buf.push_str("\n      faldo:after ");
+                buf.push_class("\n    ", "faldo:Region");
+                buf.push_str(" ;");
+                buf.push_predicate("\n    ", "faldo:begin");
+                buf.push_str("[");
+                buf.push_class("\n      ", "faldo:InBetweenPosition");
+                buf.push_str(" ;");
+                buf.push_predicate("\n      ", "faldo:after");
                 buf.push_str((p1 - 1).to_string().as_str());
-                buf.push_str(" ;\n      faldo:before ");
+                buf.push_predicate(" ;\n      ", "faldo:before");
                 buf.push_str(p1.to_string().as_str());
                 if let Some(Some(seq)) = seq {
-                    buf.push_str(" ;\n      faldo:reference ");
+                    buf.push_predicate(" ;\n      ", "faldo:reference");
                     buf.push_iri(seq);
                 }
                 buf.push_str("\n    ] ;");
 
-                buf.push_str("\n    faldo:end [");
-                buf.push_str("\n      a faldo:InBetweenPosition ;");
-                buf.push_str("\n      faldo:after ");
+                buf.push_predicate("\n    ", "faldo:end");
+                buf.push_str("[");
+                buf.push_class("\n      ", "faldo:InBetweenPosition");
+                buf.push_str(" ;");
+                buf.push_predicate("\n      ", "faldo:after");
                 buf.push_str(p2.to_string().as_str());
-                buf.push_str(" ;\n      faldo:before ");
+                buf.push_predicate(" ;\n      ", "faldo:before");
                 buf.push_str((p2 + 1).to_string().as_str());
                 if let Some(Some(seq)) = seq {
-                    buf.push_str(" ;\n      faldo:reference ");
+                    buf.push_predicate(" ;\n      ", "faldo:reference");
                     buf.push_iri(seq);
                 }
                 buf.push_str("\n    ]");
             }
         };
 
+        Self::push_location(out, node, buf)
+    }
+
+    /// Write the location of a structural variant as a region spanning POS to END.
+    ///
+    /// Insertions are located between POS and the next base.
+    fn write_sv_location(
+        &self,
+        out: &mut Buffer,
+        node: Option<&str>,
+        sv: &StructuralVariant,
+    ) -> Option<Buffer> {
+        let seq = self.record.sequence().and_then(|x| x.reference.as_ref());
+
+        let buf = &mut Buffer::new();
+
+        match sv.typ {
+            SvType::Insertion => {
+                buf.push_class("\n    ", "faldo:InBetweenPosition");
+                buf.push_str(" ;");
+                buf.push_predicate("\n    ", "faldo:after");
+                buf.push_str(sv.begin.to_string().as_str());
+                buf.push_predicate(" ;\n    ", "faldo:before");
+                buf.push_str((sv.begin + 1).to_string().as_str());
+            }
+            _ => {
+                buf.push_class("\n    ", "faldo:Region");
+                buf.push_str(" ;");
+                buf.push_predicate("\n    ", "faldo:begin");
+                Self::push_sv_position(buf, sv.begin, sv.begin_interval);
+                buf.push_predicate(" ;\n    ", "faldo:end");
+                Self::push_sv_position(buf, sv.end, sv.end_interval);
+            }
+        }
+        if let Some(seq) = seq {
+            buf.push_predicate(" ;\n    ", "faldo:reference");
+            buf.push_iri(seq);
+        }
+
+        Self::push_location(out, node, buf)
+    }
+
+    /// Write `faldo:location` of the local position of a breakend.
+    fn write_breakend_location(&self, out: &mut Buffer, node: Option<&str>) -> Option<Buffer> {
+        let seq = self.record.sequence().and_then(|x| x.reference.as_ref());
+
+        let buf = &mut Buffer::new();
+
+        buf.push_class("\n    ", "faldo:ExactPosition");
+        buf.push_str(" ;");
+        buf.push_predicate("\n    ", "faldo:position");
+        buf.push_str(self.position().to_string().as_str());
+        if let Some(seq) = seq {
+            buf.push_predicate(" ;\n    ", "faldo:reference");
+            buf.push_iri(seq);
+        }
+
+        Self::push_location(out, node, buf)
+    }
+
+    /// Write `faldo:location` spanning the reference bases, e.g. of a `*` allele.
+    fn write_reference_location(&self, out: &mut Buffer, node: Option<&str>) -> Option<Buffer> {
+        let seq = self.record.sequence().and_then(|x| x.reference.as_ref());
+
+        let buf = &mut Buffer::new();
+
+        let p1 = self.position();
+        let p2 = p1 + self.reference_bases().len().max(1) as u64 - 1;
+        if p1 == p2 {
+            buf.push_class("\n    ", "faldo:ExactPosition");
+            buf.push_str(" ;");
+            buf.push_predicate("\n    ", "faldo:position");
+            buf.push_str(p1.to_string().as_str());
+        } else {
+            buf.push_class("\n    ", "faldo:Region");
+            buf.push_str(" ;");
+            buf.push_predicate("\n    ", "faldo:begin");
+            buf.push_str(p1.to_string().as_str());
+            buf.push_predicate(" ;\n    ", "faldo:end");
+            buf.push_str(p2.to_string().as_str());
+        }
+        if let Some(seq) = seq {
+            buf.push_predicate(" ;\n    ", "faldo:reference");
+            buf.push_iri(seq);
+        }
+
+        Self::push_location(out, node, buf)
+    }
+
+    /// Write the mate position, the orientation and the inserted sequence of `breakend`.
+    ///
+    /// The contig of the mate is resolved by the reference of the configuration.
+    fn write_breakend<W: Write>(
+        &self,
+        buf: &mut Buffer,
+        wtr: &TurtleWriter<W>,
+        breakend: &Breakend,
+    ) {
+        buf.push_predicate(" ;\n  ", "gvo:mateLocation");
+        buf.push_str("[");
+        buf.push_class("\n    ", "faldo:ExactPosition");
+        buf.push_str(" ;");
+        buf.push_predicate("\n    ", "faldo:position");
+        buf.push_str(breakend.mate_position.to_string().as_str());
+        match self
+            .record
+            .sequence_of(breakend.mate_contig)
+            .and_then(|x| x.reference.as_ref())
+        {
+            Some(seq) => {
+                buf.push_predicate(" ;\n    ", "faldo:reference");
+                buf.push_iri(seq);
+            }
+            None => {
+                debug!(
+                    "Mate contig {} is not found in reference. {}",
+                    breakend.mate_contig, self
+                );
+                wtr.count_warning("Breakends with unresolved mate contig");
+            }
+        }
         buf.push_str("\n  ]");
+
+        buf.push_predicate(" ;\n  ", "gvo:orientation");
+        buf.push_quoted(breakend.orientation(), '"');
+
+        if !breakend.inserted.is_empty() {
+            buf.push_predicate(" ;\n  ", "gvo:insertedSequence");
+            buf.push_quoted(breakend.inserted, '"');
+        }
     }
 
-    fn write_info(&self, buf: &mut Buffer) {
-        let info = self.record.info();
+    /// Push `position`, or a fuzzy position with the confidence `interval` if given.
+    fn push_sv_position(buf: &mut Buffer, position: u64, interval: Option<(u64, u64)>) {
+        match interval {
+            Some((start, end)) => {
+                buf.push_str("[");
+                buf.push_class("\n      ", "faldo:FuzzyPosition");
+                buf.push_str(" ;");
+                buf.push_predicate("\n      ", "faldo:position");
+                buf.push_str(position.to_string().as_str());
+                buf.push_predicate(" ;\n      ", "gvo:confidenceIntervalStart");
+                buf.push_str(start.to_string().as_str());
+                buf.push_predicate(" ;\n      ", "gvo:confidenceIntervalEnd");
+                buf.push_str(end.to_string().as_str());
+                buf.push_str("\n    ]");
+            }
+            None => buf.push_str(position.to_string().as_str()),
+        }
+    }
+
+    /// Push `faldo:location` with the description of the location in `location`.
+    ///
+    /// Returns the description to be written after the entry if the location is a labeled `node`.
+    fn push_location(out: &mut Buffer, node: Option<&str>, location: &Buffer) -> Option<Buffer> {
+        match node {
+            Some(node) => {
+                out.push_predicate(" ;\n  ", "faldo:location");
+                out.push_str(node);
+
+                let mut description = Buffer::new();
+                description.push_str(node);
+                description.push_str(" ");
+                description.push_str(location.string.trim_start());
+                description.push_str(" .\n");
+                description.triples = location.triples;
+                description.prefixes = location.prefixes.clone();
+                Some(description)
+            }
+            None => {
+                out.push_predicate(" ;\n  ", "faldo:location");
+                out.push_str("[");
+                out.append(location);
+                out.push_str("\n  ]");
+                None
+            }
+        }
+    }
+
+    /// Write columns of the original VCF line needed to reverse splitting and trimming.
+    fn write_vcf_line(&self, buf: &mut Buffer) {
+        let inner = self.record.inner();
+        let alleles = inner.alleles();
+
+        buf.push_predicate(" ;\n  ", "gvo:vcfLine");
+        buf.push_str("[");
+        if let Some(Ok(chrom)) = self.chromosome() {
+            buf.push_predicate("\n    ", "gvo:vcfChrom");
+            buf.push_quoted(chrom, '"');
+            buf.push_str(" ;");
+        }
+        buf.push_predicate("\n    ", "gvo:vcfPos");
+        buf.push_str(self.position().to_string().as_str());
+        buf.push_predicate(" ;\n    ", "gvo:vcfId");
+        buf.push_quoted(&String::from_utf8_lossy(&inner.id()), '"');
+        buf.push_predicate(" ;\n    ", "gvo:vcfRef");
+        buf.push_quoted(self.reference_bases(), '"');
+        buf.push_predicate(" ;\n    ", "gvo:vcfAlt");
+        buf.push_quoted(
+            &alleles
+                .iter()
+                .skip(1)
+                .map(|x| String::from_utf8_lossy(x))
+                .collect::<Vec<_>>()
+                .join(","),
+            '"',
+        );
+        buf.push_predicate(" ;\n    ", "gvo:altIndex");
+        buf.push_str((self.index + 1).to_string().as_str());
+        buf.push_str("\n  ]");
+    }
+
+    /// Write `gvo:genotype` of samples carrying the alternate allele of this entry.
+    ///
+    /// Homozygous reference and missing genotypes are written only if `ref_genotypes` is set.
+    fn write_genotypes<W: Write>(&self, buf: &mut Buffer, wtr: &TurtleWriter<W>) {
+        let inner = self.record.inner();
+        let genotypes = match inner.genotypes() {
+            Ok(v) => v,
+            Err(_) => return,
+        };
+        let allele = self.index as u32 + 1;
+        let plain = wtr.get_plain_literals();
+        let format = |key: &str| {
+            if wtr.get_format_keys().iter().any(|k| k == key) {
+                let values = inner.format(key.as_bytes()).integer().ok()?;
+                Some(values.iter().map(|x| x.to_vec()).collect::<Vec<_>>())
+            } else {
+                None
+            }
+        };
+        let depths = format("DP");
+        let qualities = format("GQ");
+        let allelic_depths = format("AD");
+        let mut written = 0;
+        let iris = wtr.header_sample_iris(self.record.samples());
+
+        for (i, iri) in iris.iter().enumerate() {
+            let genotype = genotypes.get(i);
+            let zygosity = Zygosity::classify(&genotype, allele);
+
+            if !wtr.get_ref_genotypes()
+                && matches!(zygosity, Zygosity::HomozygousRef | Zygosity::Unknown)
+            {
+                continue;
+            }
+
+            let count = genotype
+                .iter()
+                .filter(|x| x.index() == Some(allele))
+                .count();
+            // a haploid call is regarded as phased
+            let phased = !genotype.is_empty()
+                && genotype.iter().skip(1).all(|x| {
+                    matches!(x, GenotypeAllele::Phased(_) | GenotypeAllele::PhasedMissing)
+                });
+
+            if written == 0 {
+                buf.push_predicate(" ;\n  ", "gvo:genotype");
+                buf.push_str("[");
+            } else {
+                buf.push_triple(", [");
+            }
+            buf.push_predicate("\n    ", "gvo:sample");
+            buf.push_iri(iri);
+            buf.push_predicate(" ;\n    ", "gvo:alleleCount");
+            buf.push_str(count.to_string().as_str());
+            buf.push_predicate(" ;\n    ", "gvo:zygosity");
+            wtr.push_zygosity_object(buf, zygosity);
+            buf.push_predicate(" ;\n    ", "gvo:phased");
+            Self::push_typed(
+                buf,
+                if phased { "true" } else { "false" },
+                "xsd:boolean",
+                plain,
+            );
+
+            // 1-based positions of the allele in the genotype of any ploidy
+            if phased {
+                let slots = genotype
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, x)| x.index() == Some(allele))
+                    .map(|(j, _)| j + 1)
+                    .collect::<Vec<_>>();
+
+                for (j, slot) in slots.iter().enumerate() {
+                    if j == 0 {
+                        buf.push_predicate(" ;\n    ", "gvo:haplotypeIndex");
+                    } else {
+                        buf.push_triple(", ");
+                    }
+                    Self::push_typed(buf, slot.to_string().as_str(), "xsd:integer", plain);
+                }
+            }
+
+            let values = [
+                (
+                    "gvo:readDepth",
+                    depths.as_ref().and_then(|x| x.get(i)?.get(0)),
+                ),
+                (
+                    "gvo:genotypeQuality",
+                    qualities.as_ref().and_then(|x| x.get(i)?.get(0)),
+                ),
+                (
+                    "gvo:allelicDepth",
+                    allelic_depths
+                        .as_ref()
+                        .and_then(|x| x.get(i)?.get(allele as usize)),
+                ),
+            ];
+            for (predicate, value) in values.iter() {
+                // htslib represents missing values and the end of vectors by sentinels
+                if let Some(&v) = value.filter(|&&x| x != i32::MIN && x != i32::MIN + 1) {
+                    buf.push_predicate(" ;\n    ", predicate);
+                    Self::push_typed(buf, v.to_string().as_str(), "xsd:integer", plain);
+                }
+            }
+
+            buf.push_str("\n  ]");
+
+            written += 1;
+        }
+    }
+
+    fn write_info<W: Write>(&self, buf: &mut Buffer, info: &[Info], wtr: &TurtleWriter<W>) {
+        let plain = wtr.get_plain_literals();
+        let lists = wtr.get_info_lists();
+        let key_predicate = wtr.get_info_key_predicate();
+
         if !info.is_empty() {
-            buf.push_str(" ;\n  gvo:info");
+            buf.push_str(" ;\n  ");
+            buf.push_term("gvo:info");
 
             for (i, info) in info.iter().enumerate() {
-                buf.push_str(if i == 0 { " [" } else { ", [" });
-                buf.push_str("\n    rdfs:label ");
-                buf.push_quoted(info.key, '"');
-                buf.push_str(" ;\n    rdf:value ");
-
-                match (&info.value, &info.length) {
-                    (vs, bcf::header::TagLength::Fixed(n)) => {
-                        let n = match &info.typ {
-                            bcf::header::TagType::Flag => 1,
-                            _ => *n,
-                        };
-                        for (i, v) in vs.iter().take(n as usize).enumerate() {
-                            if i != 0 {
-                                buf.push_str(", ");
-                            };
-                            self.push_info_value(buf, v);
-                        }
+                buf.push_triple(if i == 0 { " [" } else { ", [" });
+                // the label is written once in the definition of the key
+                match key_predicate {
+                    Some(predicate) => {
+                        buf.push_predicate("\n    ", predicate);
+                        buf.push_term(&gvo_term(info.key));
                     }
-                    (vs, bcf::header::TagLength::AltAlleles) => {
-                        for (i, v) in vs.iter().enumerate() {
-                            if i == self.index {
-                                self.push_info_value(buf, v);
-                            }
-                        }
+                    None => {
+                        buf.push_predicate("\n    ", "rdfs:label");
+                        buf.push_quoted(info.key, '"');
                     }
-                    (vs, bcf::header::TagLength::Alleles) => {
-                        let r = &vs.get(0);
-                        let a = &vs.get(self.index + 1);
+                }
+                buf.push_str(" ;\n    ");
+                buf.push_term("rdf:value");
+                buf.push_str(" ");
 
-                        match (&r, &a) {
-                            (Some(r), Some(a)) => {
-                                buf.push_quoted(format!("{},{}", r, a).as_str(), '"')
-                            }
-                            _ => panic!("failed to obtain value"),
+                let options = wtr.get_info_options(info.key);
+                let template = options.and_then(|x| x.as_iri_template.as_deref());
+                let keep_literal = options.map_or(false, |x| x.keep_literal);
+
+                let list = lists && is_list(info);
+                let objects = match template {
+                    Some(template) if !keep_literal => self.info_iris(info, template, list),
+                    _ => self.info_objects(info, plain, list),
+                };
+
+                if list {
+                    // each item of a list is a node of `rdf:first` and `rdf:rest`
+                    buf.triples += 1 + 2 * objects.len() as u64;
+                    if objects.is_empty() {
+                        buf.push_str("()");
+                    } else {
+                        buf.push_str("(");
+                        for object in &objects {
+                            buf.push_str(" ");
+                            buf.append(object);
                         }
-                        buf.push_str(" ;\n    rdf:comment \"This field contains two values, the first is the value for the reference allele and the second is the value for the alternate allele.\"");
+                        buf.push_str(" )");
                     }
-                    (vs, len) => {
-                        for (i, v) in vs.iter().enumerate() {
-                            if i != 0 {
-                                buf.push_str(", ");
-                            };
-                            self.push_info_value(buf, v);
-                        }
+                } else {
+                    buf.push_objects(&objects);
+                }
+
+                if let (Some(template), true) = (template, keep_literal) {
+                    let iris = self.info_iris(info, template, false);
+                    if !iris.is_empty() {
+                        buf.push_str(" ;\n    ");
+                        buf.push_term("rdfs:seeAlso");
+                        buf.push_str(" ");
+                        buf.push_objects(&iris);
+                    }
+                }
 
-                        if len == &bcf::header::TagLength::Genotypes {
-                            buf.push_str(" ;\n    rdf:comment \"The field has one value for each possible genotype.\"");
+                match info.length {
+                    bcf::header::TagLength::Alleles => match info.value.get(0) {
+                        Some(v) if *v != InfoValue::Missing => {
+                            buf.push_predicate(" ;\n    ", "gvo:refValue");
+                            self.push_info_value(buf, v, plain);
                         }
+                        _ => {}
+                    },
+                    bcf::header::TagLength::Genotypes => {
+                        buf.push_predicate(" ;\n    ", "rdf:comment");
+                        buf.push_quoted("The field has one value for each possible genotype.", '"');
                     }
+                    _ => {}
                 }
 
                 buf.push_str("\n  ]");
@@ -345,16 +1012,283 @@ impl Entry<'_> {
         }
     }
 
-    fn push_info_value(&self, buf: &mut Buffer, v: &InfoValue) {
+    /// Returns columns of VEP consequences if INFO `key` is parsed by VEP.
+    fn vep_format<W: Write>(&self, wtr: &TurtleWriter<W>, key: &str) -> Option<Arc<Vec<String>>> {
+        match wtr.get_info_parser(key)? {
+            InfoParser::Vep => wtr.vep_format(key, || {
+                self.info_description(key).and_then(|x| vep::format(&x))
+            }),
+        }
+    }
+
+    /// Write consequences in `info` for this alternate allele as blank nodes with a predicate per column.
+    ///
+    /// Consequences are matched to the allele by the `Allele` column, and values separated by `&` are
+    /// written as an object list.
+    fn write_consequences<W: Write>(&self, buf: &mut Buffer, info: &[Info], wtr: &TurtleWriter<W>) {
+        if info.is_empty() {
+            return;
+        }
+
+        let alleles = self.record.inner().alleles();
+        let reference = String::from_utf8_lossy(alleles[0]);
+        let alternates = alleles[1..]
+            .iter()
+            .map(|x| String::from_utf8_lossy(x))
+            .collect::<Vec<_>>();
+        let alternates = vep::alleles(
+            &reference,
+            &alternates.iter().map(|x| x.as_ref()).collect::<Vec<_>>(),
+        );
+        let allele = match alternates.get(self.index) {
+            Some(v) => v,
+            None => return,
+        };
+
+        let mut first = true;
+
+        for info in info {
+            let format = match self.vep_format(wtr, info.key) {
+                Some(v) => v,
+                None => continue,
+            };
+
+            for value in &info.value {
+                let value = match value {
+                    InfoValue::String(v) => v,
+                    _ => continue,
+                };
+
+                for fields in vep::parse(value, &format, allele) {
+                    if first {
+                        buf.push_predicate(" ;\n  ", wtr.get_consequence_predicate());
+                        buf.push_str("[");
+                        first = false;
+                    } else {
+                        buf.push_triple(", [");
+                    }
+
+                    for (i, (column, value)) in fields.iter().enumerate() {
+                        buf.push_str(if i == 0 { "\n    " } else { " ;\n    " });
+                        buf.push_term(&gvo_term(column));
+                        buf.push_str(" ");
+
+                        for (j, v) in value.split('&').enumerate() {
+                            buf.push_triple(if j == 0 { "" } else { ", " });
+                            if v.contains('%') {
+                                buf.push_quoted(&Self::percent_decode(v), '"');
+                            } else {
+                                buf.push_quoted(v, '"');
+                            }
+                        }
+                    }
+
+                    buf.push_str("\n  ]");
+                }
+            }
+        }
+    }
+
+    /// Returns `Description` of INFO `key` in the header.
+    fn info_description(&self, key: &str) -> Option<String> {
+        self.record.info_definition(key)?.description.clone()
+    }
+
+    /// Push values of `info` for this alternate allele as an object list.
+    fn push_info_objects(&self, buf: &mut Buffer, info: &Info, plain: bool) {
+        buf.push_objects(&self.info_objects(info, plain, false));
+    }
+
+    /// Returns values of `info` for this alternate allele as Turtle objects.
+    ///
+    /// Numbers and flags are typed literals unless `plain`. Missing values are blank nodes `[]` in a `list`.
+    fn info_objects(&self, info: &Info, plain: bool, list: bool) -> Vec<Buffer> {
+        self.info_values(info, list)
+            .into_iter()
+            .map(|v| {
+                let mut buf = Buffer::new();
+                match v {
+                    InfoValue::Missing => buf.push_str("[]"),
+                    _ => self.push_info_value(&mut buf, v, plain),
+                }
+                buf
+            })
+            .collect()
+    }
+
+    /// Returns values of `info` for this alternate allele as IRIs expanded from `template`.
+    ///
+    /// `{value}` in the template is replaced with the percent-encoded value.
+    /// Missing values are blank nodes `[]` in a `list`.
+    fn info_iris(&self, info: &Info, template: &str, list: bool) -> Vec<Buffer> {
+        self.info_values(info, list)
+            .into_iter()
+            .map(|v| {
+                let mut buf = Buffer::new();
+                let value = match v {
+                    InfoValue::Missing => {
+                        buf.push_str("[]");
+                        return buf;
+                    }
+                    InfoValue::String(x) if x.contains('%') => Self::percent_decode(x),
+                    _ => v.to_string(),
+                };
+                buf.push_iri(&template.replace("{value}", &iri::percent_encode(&value)));
+                buf
+            })
+            .collect()
+    }
+
+    /// Returns non-missing values of `info` for this alternate allele.
+    ///
+    /// Missing values are kept in a `list` to keep positions of the others, e.g. `.,0.667`,
+    /// unless all values are missing, e.g. `.`, which is an empty list.
+    fn info_values<'i>(&self, info: &'i Info, list: bool) -> Vec<&'i InfoValue> {
+        let values: Vec<&InfoValue> = match (&info.value, &info.length) {
+            (vs, bcf::header::TagLength::Fixed(n)) => {
+                let n = match &info.typ {
+                    bcf::header::TagType::Flag => 1,
+                    _ => *n,
+                };
+                vs.iter().take(n as usize).collect()
+            }
+            (vs, bcf::header::TagLength::AltAlleles) => vs.get(self.index).into_iter().collect(),
+            // the value for the reference allele is written as `gvo:refValue` by `write_info`
+            (vs, bcf::header::TagLength::Alleles) => vs.get(self.index + 1).into_iter().collect(),
+            (vs, _) => vs.iter().collect(),
+        };
+
+        if list && values.iter().any(|v| **v != InfoValue::Missing) {
+            return values;
+        }
+
+        values
+            .into_iter()
+            .filter(|v| **v != InfoValue::Missing)
+            .collect()
+    }
+
+    /// Returns true if `info` of Number=A does not have a value for each alternate allele.
+    ///
+    /// A single `.` is missing for all alternate alleles rather than mismatched.
+    fn is_mismatched(&self, info: &Info) -> bool {
+        match info.length {
+            bcf::header::TagLength::AltAlleles => {
+                info.value != [InfoValue::Missing]
+                    && info.value.len() + 1 != self.record.inner().allele_count() as usize
+            }
+            _ => false,
+        }
+    }
+
+    /// Report `info` of Number=A whose length does not match the number of alternate alleles.
+    ///
+    /// Returns an error under `--strict`, otherwise the key is skipped for all alleles of the record,
+    /// with a warning and a summary count once per record.
+    fn check_alt_length<W: Write>(&self, wtr: &TurtleWriter<W>, info: &Info) -> Result<()> {
+        if !self.is_mismatched(info) {
+            return Ok(());
+        }
+
+        let location = format!(
+            "{}:{}",
+            self.chromosome().and_then(|x| x.ok()).unwrap_or("?"),
+            self.position()
+        );
+        let alternates = self.record.inner().allele_count() as usize - 1;
+
+        if wtr.get_strict() {
+            Err(Error::InfoLengthError(
+                info.key.to_owned(),
+                info.value.len(),
+                alternates,
+                location.clone(),
+            ))?
+        }
+
+        if self.index == 0 {
+            warn!(
+                "INFO/{} of Number=A has {} values for {} alternate alleles at {}, skipped",
+                info.key,
+                info.value.len(),
+                alternates,
+                location
+            );
+            wtr.count_warning(&format!(
+                "Records with mismatched Number=A INFO/{}",
+                info.key
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Returns true if `info` has no values for this alternate allele, e.g. all values are `.`.
+    ///
+    /// Number=R values without one for this allele are counted in the summary.
+    /// Keys written as lists are never missing, since an empty list is written as `()`.
+    fn is_missing<W: Write>(&self, wtr: &TurtleWriter<W>, info: &Info) -> bool {
+        if wtr.get_info_lists() && is_list(info) {
+            return false;
+        }
+
+        let present = |v: Option<&InfoValue>| v.map_or(false, |x| *x != InfoValue::Missing);
+
+        match (&info.typ, &info.length) {
+            (bcf::header::TagType::Flag, _) => info.value.is_empty(),
+            (_, bcf::header::TagLength::Fixed(n)) => !info
+                .value
+                .iter()
+                .take(*n as usize)
+                .any(|x| present(Some(x))),
+            (_, bcf::header::TagLength::AltAlleles) => !present(info.value.get(self.index)),
+            (_, bcf::header::TagLength::Alleles) => {
+                if info.value.len() < self.index + 2 {
+                    warn!(
+                        "INFO/{} of Number=R has {} values for {} alleles at {}",
+                        info.key,
+                        info.value.len(),
+                        self.record.inner().allele_count(),
+                        self.position()
+                    );
+                    wtr.count_warning("INFO values of Number=R skipped (too few values)");
+                }
+                !present(info.value.get(self.index + 1))
+            }
+            _ => !info.value.iter().any(|x| present(Some(x))),
+        }
+    }
+
+    /// Returns true if `info` has more values than declared in the header.
+    fn is_truncated(info: &Info) -> bool {
+        match (&info.typ, &info.length) {
+            (bcf::header::TagType::Flag, _) => false,
+            (_, bcf::header::TagLength::Fixed(n)) => info.value.len() > *n as usize,
+            _ => false,
+        }
+    }
+
+    /// Push `v` as a typed literal, e.g. `"0.013"^^xsd:float`, or a bare token if `plain`.
+    ///
+    /// Non-finite floats are always typed literals `"NaN"`, `"INF"` or `"-INF"` of `xsd:float`,
+    /// since they have no bare token in Turtle.
+    fn push_info_value(&self, buf: &mut Buffer, v: &InfoValue, plain: bool) {
         match v {
             InfoValue::Flag(x) => {
-                buf.push_str(x.to_string().as_str());
+                Self::push_typed(buf, x.to_string().as_str(), "xsd:boolean", plain);
             }
             InfoValue::Integer(x) => {
-                buf.push_str(x.to_string().as_str());
+                Self::push_typed(buf, x.to_string().as_str(), "xsd:integer", plain);
+            }
+            InfoValue::Float(x) if x.is_nan() => {
+                Self::push_typed(buf, "NaN", "xsd:float", false);
+            }
+            InfoValue::Float(x) if x.is_infinite() => {
+                let value = if x.is_sign_positive() { "INF" } else { "-INF" };
+                Self::push_typed(buf, value, "xsd:float", false);
             }
             InfoValue::Float(x) => {
-                buf.push_str(x.to_string().as_str());
+                Self::push_typed(buf, x.to_string().as_str(), "xsd:float", plain);
             }
             InfoValue::String(str) => {
                 if str.contains("%") {
@@ -363,18 +1297,160 @@ impl Entry<'_> {
                     buf.push_quoted(str, '"');
                 }
             }
+            InfoValue::Iri(v) => buf.push_iri(v),
+            // skipped by `info_objects` to keep positions of other values
+            InfoValue::Missing => {}
         };
     }
 
+    fn push_typed(buf: &mut Buffer, value: &str, datatype: &str, plain: bool) {
+        if plain {
+            buf.push_str(value);
+        } else {
+            buf.push_typed(value, datatype);
+        }
+    }
+
+    /// Decode all `%XX` sequences in a single pass, so that `%2525` is decoded to `%25`.
+    ///
+    /// Malformed sequences are left as is. The value is returned as is if the result is not UTF-8.
     fn percent_decode<T: AsRef<str>>(str: T) -> String {
-        str.as_ref()
-            .replace("%3A", ":")
-            .replace("%3B", ";")
-            .replace("%3D", "=")
-            .replace("%25", "%")
-            .replace("%2C", ",")
-            .replace("%0D", "\r")
-            .replace("%0A", "\n")
-            .replace("%09", "\t")
+        let str = str.as_ref();
+        let bytes = str.as_bytes();
+
+        let mut buf = Vec::with_capacity(bytes.len());
+        let mut malformed = false;
+        let mut i = 0;
+
+        while i < bytes.len() {
+            if bytes[i] == b'%' {
+                match bytes.get(i + 1..i + 3) {
+                    Some(&[h, l]) if h.is_ascii_hexdigit() && l.is_ascii_hexdigit() => {
+                        buf.push((hex_value(h) << 4) | hex_value(l));
+                        i += 3;
+                        continue;
+                    }
+                    _ => malformed = true,
+                }
+            }
+
+            buf.push(bytes[i]);
+            i += 1;
+        }
+
+        if malformed {
+            warn!("Malformed percent-encoding is left as is: {}", str);
+        }
+
+        match String::from_utf8(buf) {
+            Ok(v) => v,
+            Err(_) => {
+                warn!("Percent-decoded value is not valid UTF-8: {}", str);
+                str.to_owned()
+            }
+        }
+    }
+}
+
+/// Returns true if the order of values of `info` carries meaning, i.e. Number=G or Number=.
+fn is_list(info: &Info) -> bool {
+    matches!(
+        info.length,
+        bcf::header::TagLength::Genotypes | bcf::header::TagLength::Variable
+    )
+}
+
+/// Push the definition of INFO `key` as a property labeled with the key and commented with `description`.
+pub(crate) fn push_info_key_definition(buf: &mut Buffer, key: &str, description: Option<&str>) {
+    buf.push_term(&gvo_term(key));
+    buf.push_class(" ", "rdf:Property");
+    buf.push_str(" ;");
+    buf.push_predicate("\n  ", "rdfs:label");
+    buf.push_quoted(key, '"');
+
+    if let Some(description) = description {
+        buf.push_predicate(" ;\n  ", "rdfs:comment");
+        buf.push_quoted(description, '"');
+    }
+
+    buf.push_str(" .\n");
+}
+
+/// Returns the term of `name` in `gvo:`, e.g. of an INFO key or a VEP column.
+fn gvo_term(name: &str) -> String {
+    if iri::is_local_name(name) {
+        format!("gvo:{}", name)
+    } else {
+        format!("<{}{}>", GVO, iri::percent_encode(name))
+    }
+}
+
+fn hex_value(digit: u8) -> u8 {
+    match digit {
+        b'0'..=b'9' => digit - b'0',
+        b'a'..=b'f' => digit - b'a' + 10,
+        _ => digit - b'A' + 10,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn agrees(vc: &str, class: &str) -> bool {
+        vc_classes(vc).map_or(false, |x| x.iter().any(|&c| c == class))
+    }
+
+    fn decode(str: &str) -> String {
+        Entry::percent_decode(str)
+    }
+
+    #[test]
+    fn test_percent_decode_reserved() {
+        assert_eq!(decode("%3A%3B%3D%25%2C%0D%0A%09"), ":;=%,\r\n\t".to_owned());
+        assert_eq!(decode("%3a%3b"), ":;");
+    }
+
+    #[test]
+    fn test_percent_decode_other() {
+        assert_eq!(decode("Benign%7CLikely_benign"), "Benign|Likely_benign");
+        assert_eq!(decode("not%20provided"), "not provided");
+        assert_eq!(decode("caf%C3%A9"), "café");
+    }
+
+    #[test]
+    fn test_percent_decode_nested() {
+        assert_eq!(decode("%2525"), "%25");
+        assert_eq!(decode("%253A"), "%3A");
+        assert_eq!(decode("%25%3A"), "%:");
+    }
+
+    #[test]
+    fn test_percent_decode_malformed() {
+        assert_eq!(decode("100%"), "100%");
+        assert_eq!(decode("%G1%3A"), "%G1:");
+        assert_eq!(decode("%+1"), "%+1");
+        assert_eq!(decode("%FF"), "%FF");
+    }
+
+    #[test]
+    fn test_vc_classes_agreement() {
+        assert!(agrees("SNV", variant_class(&VariantType::SNV)));
+        assert!(agrees("MNV", variant_class(&VariantType::MNV)));
+        assert!(agrees("INS", variant_class(&VariantType::Insertion)));
+        assert!(agrees("DEL", variant_class(&VariantType::Deletion)));
+        assert!(agrees("INDEL", variant_class(&VariantType::Deletion)));
+        assert!(agrees("INDEL", variant_class(&VariantType::Indel)));
+    }
+
+    #[test]
+    fn test_vc_classes_disagreement() {
+        assert!(!agrees("SNV", variant_class(&VariantType::MNV)));
+        assert!(!agrees("DEL", variant_class(&VariantType::Insertion)));
+        assert!(!agrees("UNKNOWN", variant_class(&VariantType::SNV)));
+        assert_eq!(
+            vc_classes("DEL").and_then(|x| x.first().copied()),
+            Some("Deletion")
+        );
     }
 }
@@ -0,0 +1,41 @@
+//! Module for counts of genotypes written instead of each sample
+use std::io::Write;
+
+use crate::rdf::turtle_writer::TurtleWriter;
+use crate::vcf::genotype::GenotypeCounts;
+use crate::vcf::record::as_turtle::Buffer;
+use crate::vcf::record::Entry;
+
+impl Entry<'_> {
+    /// Write counts of genotypes for the alternate allele of this entry instead of each sample.
+    ///
+    /// The allele frequency is written with the configured `genotype_frequency_predicate`.
+    pub(super) fn write_genotype_summary<W: Write>(&self, buf: &mut Buffer, wtr: &TurtleWriter<W>) {
+        let inner = self.record.inner();
+        let genotypes = match inner.genotypes() {
+            Ok(v) => v,
+            Err(_) => return,
+        };
+        let allele = self.index as u32 + 1;
+        let plain = wtr.get_plain_literals();
+
+        let mut counts = GenotypeCounts::default();
+        for i in 0..inner.sample_count() as usize {
+            counts.add(&genotypes.get(i), allele);
+        }
+
+        for (predicate, count) in &[
+            ("gvo:homCount", counts.homozygous_alt),
+            ("gvo:hetCount", counts.heterozygous),
+            ("gvo:missingCount", counts.missing),
+        ] {
+            buf.push_predicate(" ;\n  ", predicate);
+            Self::push_typed(buf, count.to_string().as_str(), "xsd:integer", plain);
+        }
+
+        if let Some(frequency) = counts.allele_frequency() {
+            buf.push_predicate(" ;\n  ", wtr.get_genotype_frequency_predicate());
+            Self::push_typed(buf, frequency.to_string().as_str(), "xsd:float", plain);
+        }
+    }
+}
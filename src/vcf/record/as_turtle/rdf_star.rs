@@ -0,0 +1,80 @@
+//! Module for RDF-star annotations of entries, see `RdfStarMode`
+use std::io::Write;
+
+use crate::rdf::turtle_writer::TurtleWriter;
+use crate::vcf::record::as_turtle::{gvo_term, push_info_key_definition, Buffer};
+use crate::vcf::record::{Entry, Info};
+
+impl Entry<'_> {
+    /// Write triples of `direct` INFO values quoted and annotated with the INFO key and the allele index,
+    /// e.g. `<< s gvo:alleleFrequency 0.5 >> gvo:infoKey "AF" ; gvo:alleleIndex 1 .`
+    pub(super) fn write_rdf_star_statements<W: Write>(
+        &self,
+        buf: &mut Buffer,
+        subject: &str,
+        direct: &[Info],
+        wtr: &TurtleWriter<W>,
+    ) {
+        let plain = wtr.get_plain_literals();
+
+        for info in direct {
+            let predicate = match wtr.get_info_predicate(info.key) {
+                Some(v) => v,
+                None => continue,
+            };
+            let truncated = Self::is_truncated(info);
+
+            for object in self.info_objects(info, plain, false) {
+                buf.push_str("<< ");
+                buf.push_str(subject);
+                buf.push_str(" ");
+                buf.push_term(predicate);
+                buf.push_str(" ");
+                buf.append(&object);
+                buf.push_predicate(" >> ", "gvo:infoKey");
+                buf.push_quoted(info.key, '"');
+                buf.push_predicate(" ;\n  ", "gvo:alleleIndex");
+                buf.push_str((self.index + 1).to_string().as_str());
+                if truncated {
+                    buf.push_predicate(" ;\n  ", "gvo:truncated");
+                    buf.push_str("true");
+                }
+                buf.push_str(" .\n");
+            }
+        }
+    }
+
+    /// Write values of `annotations` on the `gvo:alt` triple, e.g. `<< s gvo:alt "A" >> gvo:AF 0.5 .`
+    ///
+    /// Each key is the predicate of its annotation, which is defined before the first use.
+    pub(super) fn write_rdf_star_annotations<W: Write>(
+        &self,
+        buf: &mut Buffer,
+        subject: &str,
+        alt: &str,
+        annotations: &[Info],
+        wtr: &TurtleWriter<W>,
+    ) {
+        let plain = wtr.get_plain_literals();
+
+        for info in annotations {
+            buf.push_str("<< ");
+            buf.push_str(subject);
+            buf.push_str(" ");
+            buf.push_term("gvo:alt");
+            buf.push_str(" ");
+            buf.push_quoted(alt, '"');
+            buf.push_str(" >> ");
+            buf.push_term(&gvo_term(info.key));
+            buf.push_str(" ");
+            self.push_info_objects(buf, info, plain);
+            buf.push_str(" .\n");
+
+            if !wtr.is_info_key_defined(info.key) {
+                let description = self.info_description(info.key);
+                wtr.define_info_key(info.key, description.as_deref());
+                push_info_key_definition(buf, info.key, description.as_deref());
+            }
+        }
+    }
+}
@@ -0,0 +1,285 @@
+//! Module for genomic regions to restrict records, e.g. `chr1:1000-2000`
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::config::Sequence;
+use crate::errors::{Error, Result};
+use crate::vcf::assembly;
+
+/// The largest position in htslib (`HTS_POS_MAX`).
+pub const MAX_POSITION: u64 = (i32::MAX as u64) << 32 | i32::MAX as u64;
+
+/// A region of `contig` from `start` to `end` (1-based, inclusive).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region {
+    pub contig: String,
+    pub start: u64,
+    pub end: u64,
+}
+
+impl FromStr for Region {
+    type Err = Error;
+
+    /// Parse `chr`, `chr:pos` or `chr:start-end`.
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || Error::InvalidArgumentError(format!("invalid region: {}", s));
+
+        let (contig, range) = match s.rsplit_once(':') {
+            Some((contig, range)) if !range.is_empty() => (contig, Some(range)),
+            _ => (s, None),
+        };
+        if contig.is_empty() {
+            Err(invalid())?
+        }
+
+        let position = |x: &str| x.trim().parse::<u64>().ok().filter(|&x| x > 0);
+
+        let (start, end) = match range {
+            None => (1, MAX_POSITION),
+            Some(range) => match range.split_once('-') {
+                Some((start, "")) => (position(start).ok_or_else(invalid)?, MAX_POSITION),
+                Some((start, end)) => (
+                    position(start).ok_or_else(invalid)?,
+                    position(end).ok_or_else(invalid)?,
+                ),
+                None => {
+                    let pos = position(range).ok_or_else(invalid)?;
+                    (pos, pos)
+                }
+            },
+        };
+        if start > end {
+            Err(invalid())?
+        }
+
+        Ok(Region {
+            contig: contig.to_owned(),
+            start,
+            end,
+        })
+    }
+}
+
+/// Read regions from a BED file of 0-based half-open intervals.
+pub fn read_bed<P: AsRef<Path>>(path: P) -> Result<Vec<Region>> {
+    let file = File::open(path.as_ref())?;
+
+    parse_bed(BufReader::new(file), &path.as_ref().to_string_lossy())
+}
+
+/// Parse BED lines in `reader`, skipping blank lines, comments and `track` or `browser` lines.
+fn parse_bed<R: BufRead>(reader: R, source: &str) -> Result<Vec<Region>> {
+    let mut regions = Vec::new();
+
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        let error =
+            |message: &str| Error::BedFormatError(source.to_owned(), i + 1, message.to_owned());
+
+        if line.trim().is_empty()
+            || line.starts_with('#')
+            || line.starts_with("track")
+            || line.starts_with("browser")
+        {
+            continue;
+        }
+
+        let columns: Vec<&str> = line.split('\t').collect();
+        if columns.len() < 3 {
+            Err(error("expected at least 3 tab-separated columns"))?
+        }
+
+        let start: u64 = columns[1]
+            .parse()
+            .map_err(|_| error(&format!("invalid start {}", columns[1])))?;
+        let end: u64 = columns[2]
+            .parse()
+            .map_err(|_| error(&format!("invalid end {}", columns[2])))?;
+        if end <= start {
+            Err(error(&format!(
+                "end {} is not greater than start {}",
+                end, start
+            )))?
+        }
+
+        regions.push(Region {
+            contig: columns[0].to_owned(),
+            start: start + 1,
+            end,
+        });
+    }
+
+    Ok(regions)
+}
+
+/// Returns the contig in `contigs` of the VCF named `name`.
+///
+/// `name` may also be the name or the reference of a sequence in the configuration,
+/// or an alias of the contig in built-in assemblies, e.g. `chr1` for `NC_000001.10`.
+pub fn resolve_contig<'a>(
+    name: &str,
+    contigs: &'a [String],
+    sequences: &[Option<Sequence>],
+) -> Option<&'a str> {
+    if let Some(contig) = contigs.iter().find(|x| *x == name) {
+        return Some(contig);
+    }
+
+    let configured = contigs.iter().zip(sequences).find(|(_, seq)| {
+        seq.as_ref().map_or(false, |x| {
+            x.name.as_deref() == Some(name) || x.reference.as_deref() == Some(name)
+        })
+    });
+    if let Some((contig, _)) = configured {
+        return Some(contig);
+    }
+
+    assembly::builtin()
+        .iter()
+        .filter_map(|assembly| assembly.find_sequence(&name.to_owned()))
+        .find_map(|seq| {
+            let aliases = [seq.name, seq.genbank, seq.refseq, seq.ucsc_name];
+            contigs.iter().find(|x| aliases.contains(&x.as_str()))
+        })
+        .map(|x| x.as_str())
+}
+
+/// Sort regions and merge overlapping or adjacent ones on the same contig.
+pub fn merge(mut regions: Vec<Region>) -> Vec<Region> {
+    regions.sort_by(|a, b| (&a.contig, a.start).cmp(&(&b.contig, b.start)));
+
+    let mut merged: Vec<Region> = Vec::new();
+    for region in regions {
+        match merged.last_mut() {
+            Some(last)
+                if last.contig == region.contig && region.start <= last.end.saturating_add(1) =>
+            {
+                last.end = last.end.max(region.end);
+            }
+            _ => merged.push(region),
+        }
+    }
+
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn region(contig: &str, start: u64, end: u64) -> Region {
+        Region {
+            contig: contig.to_owned(),
+            start,
+            end,
+        }
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!(
+            "chr1:100-200".parse::<Region>().unwrap(),
+            region("chr1", 100, 200)
+        );
+        assert_eq!(
+            "chr1:100".parse::<Region>().unwrap(),
+            region("chr1", 100, 100)
+        );
+        assert_eq!(
+            "chr1:100-".parse::<Region>().unwrap(),
+            region("chr1", 100, MAX_POSITION)
+        );
+        assert_eq!(
+            "chr1".parse::<Region>().unwrap(),
+            region("chr1", 1, MAX_POSITION)
+        );
+
+        assert!("chr1:200-100".parse::<Region>().is_err());
+        assert!("chr1:0-100".parse::<Region>().is_err());
+        assert!("chr1:a-b".parse::<Region>().is_err());
+        assert!(":100-200".parse::<Region>().is_err());
+    }
+
+    #[test]
+    fn test_parse_bed() {
+        let bed = "track name=targets\n# comment\nchr1\t99\t200\tGENE1\n\nchr2\t0\t1\n";
+
+        assert_eq!(
+            parse_bed(bed.as_bytes(), "targets.bed").unwrap(),
+            vec![region("chr1", 100, 200), region("chr2", 1, 1)]
+        );
+    }
+
+    #[test]
+    fn test_parse_bed_malformed() {
+        let message = |bed: &str| {
+            parse_bed(bed.as_bytes(), "targets.bed")
+                .unwrap_err()
+                .to_string()
+        };
+
+        assert_eq!(
+            message("chr1\t0\t10\nchr1 10 20\n"),
+            "Invalid BED at line 2 of targets.bed: expected at least 3 tab-separated columns"
+        );
+        assert_eq!(
+            message("chr1\t-1\t10\n"),
+            "Invalid BED at line 1 of targets.bed: invalid start -1"
+        );
+        assert_eq!(
+            message("#header\nchr1\t10\t10\n"),
+            "Invalid BED at line 2 of targets.bed: end 10 is not greater than start 10"
+        );
+    }
+
+    #[test]
+    fn test_merge() {
+        assert_eq!(
+            merge(vec![
+                region("2", 10, 20),
+                region("1", 300, 400),
+                region("1", 100, 200),
+                region("1", 150, 250),
+                region("1", 401, 500),
+                region("1", 600, 700),
+            ]),
+            vec![
+                region("1", 100, 250),
+                region("1", 300, 500),
+                region("1", 600, 700),
+                region("2", 10, 20),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_resolve_contig() {
+        let contigs = vec!["NC_000001.10".to_owned(), "20".to_owned()];
+        let sequences = vec![
+            None,
+            Some(Sequence {
+                name: Some("chr20".to_owned()),
+                reference: Some("http://identifiers.org/hco/20/GRCh37".to_owned()),
+                ..Default::default()
+            }),
+        ];
+
+        assert_eq!(resolve_contig("20", &contigs, &sequences), Some("20"));
+        assert_eq!(resolve_contig("chr20", &contigs, &sequences), Some("20"));
+        assert_eq!(
+            resolve_contig("http://identifiers.org/hco/20/GRCh37", &contigs, &sequences),
+            Some("20")
+        );
+        assert_eq!(
+            resolve_contig("chr1", &contigs, &sequences),
+            Some("NC_000001.10")
+        );
+        assert_eq!(
+            resolve_contig("1", &contigs, &sequences),
+            Some("NC_000001.10")
+        );
+        assert_eq!(resolve_contig("chr2", &contigs, &sequences), None);
+    }
+}
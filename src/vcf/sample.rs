@@ -0,0 +1,264 @@
+//! Module for sample metadata in VCF header
+use std::collections::BTreeMap;
+
+use rust_htslib::bcf;
+use sha2::{Digest, Sha256};
+
+/// A sample described by `##SAMPLE` and `##PEDIGREE` lines.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Sample {
+    pub id: String,
+    pub description: Option<String>,
+    /// Relationships in `##PEDIGREE`, e.g. `Father` => `NA12891`.
+    pub pedigree: BTreeMap<String, String>,
+}
+
+/// Collect samples in the header.
+///
+/// Samples in the header line come first in order, followed by those only found in meta-information lines.
+pub fn from_header(header: &bcf::header::HeaderView) -> Vec<Sample> {
+    let mut samples: Vec<Sample> = header
+        .samples()
+        .iter()
+        .map(|x| Sample {
+            id: String::from_utf8_lossy(x).to_string(),
+            ..Default::default()
+        })
+        .collect();
+
+    for record in header.header_records() {
+        if let bcf::HeaderRecord::Structured { key, values } = record {
+            let id = match values.get("ID") {
+                Some(id) => unquote(id),
+                None => continue,
+            };
+
+            let sample = match samples.iter().position(|x| x.id == id) {
+                Some(i) => &mut samples[i],
+                None => {
+                    samples.push(Sample {
+                        id: id.to_owned(),
+                        ..Default::default()
+                    });
+                    samples.last_mut().unwrap()
+                }
+            };
+
+            match key.as_str() {
+                "SAMPLE" => {
+                    sample.description = values.get("Description").map(|x| unquote(x).to_owned())
+                }
+                "PEDIGREE" => {
+                    for (k, v) in values.iter().filter(|(k, _)| k.as_str() != "ID") {
+                        sample.pedigree.insert(k.to_owned(), unquote(v).to_owned());
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    samples
+}
+
+/// Returns names of samples in the header line.
+pub fn names(header: &bcf::header::HeaderView) -> Vec<String> {
+    header
+        .samples()
+        .iter()
+        .map(|x| String::from_utf8_lossy(x).to_string())
+        .collect()
+}
+
+/// Select samples in `available` listed in `samples` (all if `None`) and not in `exclude`.
+pub fn select(available: &[String], samples: Option<&[String]>, exclude: &[String]) -> Vec<String> {
+    available
+        .iter()
+        .filter(|x| samples.map_or(true, |v| v.contains(x)) && !exclude.contains(x))
+        .cloned()
+        .collect()
+}
+
+/// Returns names in `available` similar to `name`, ignoring case or within an edit distance of 2.
+pub fn near_matches<'a>(name: &str, available: &'a [String]) -> Vec<&'a str> {
+    available
+        .iter()
+        .filter(|x| x.eq_ignore_ascii_case(name) || edit_distance(x, name) <= 2)
+        .map(|x| x.as_str())
+        .collect()
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, x) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+
+        for (j, y) in b.iter().enumerate() {
+            let cost = if x == *y { prev } else { prev + 1 };
+            prev = row[j + 1];
+            row[j + 1] = cost.min(row[j] + 1).min(prev + 1);
+        }
+    }
+
+    row[b.len()]
+}
+
+/// Returns a pseudonym of the sample `id`, the HMAC-SHA256 keyed by `secret` truncated to 16 hex digits.
+pub fn pseudonym(secret: &str, id: &str) -> String {
+    hmac_sha256(secret.as_bytes(), id.as_bytes())[..8]
+        .iter()
+        .map(|x| format!("{:02x}", x))
+        .collect()
+}
+
+/// HMAC-SHA256 (RFC 2104).
+fn hmac_sha256(key: &[u8], message: &[u8]) -> Vec<u8> {
+    const BLOCK_SIZE: usize = 64;
+
+    let mut block = [0u8; BLOCK_SIZE];
+    if key.len() > BLOCK_SIZE {
+        let digest = Sha256::digest(key);
+        block[..digest.len()].copy_from_slice(&digest);
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+
+    let pad = |byte: u8| block.iter().map(|x| x ^ byte).collect::<Vec<_>>();
+
+    let inner = Sha256::new()
+        .chain_update(pad(0x36))
+        .chain_update(message)
+        .finalize();
+
+    Sha256::new()
+        .chain_update(pad(0x5c))
+        .chain_update(inner)
+        .finalize()
+        .to_vec()
+}
+
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|x| x.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::fixture;
+    use crate::vcf::reader::Reader;
+
+    #[test]
+    fn test_from_header() {
+        let (_dir, path) = fixture::bgzip("trio");
+        let reader = Reader::from_path(path).unwrap();
+
+        let samples = from_header(reader.header());
+
+        assert_eq!(samples.len(), 3);
+        assert_eq!(samples[0].id, "NA12878");
+        assert_eq!(samples[0].description.as_deref(), Some("Daughter"));
+        assert_eq!(
+            samples[0].pedigree.get("Father").map(|x| x.as_str()),
+            Some("NA12891")
+        );
+        assert_eq!(
+            samples[0].pedigree.get("Mother").map(|x| x.as_str()),
+            Some("NA12892")
+        );
+        assert!(samples[1].pedigree.is_empty());
+    }
+
+    #[test]
+    fn test_select() {
+        let available = vec!["A".to_owned(), "B".to_owned(), "C".to_owned()];
+        let names = |v: &[&str]| v.iter().map(|x| x.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(select(&available, None, &[]), available);
+        assert_eq!(
+            select(&available, Some(&names(&["C", "A", "X"])), &[]),
+            names(&["A", "C"])
+        );
+        assert_eq!(select(&available, None, &names(&["B"])), names(&["A", "C"]));
+        assert_eq!(
+            select(&available, Some(&names(&["A", "B"])), &names(&["B"])),
+            names(&["A"])
+        );
+    }
+
+    #[test]
+    fn test_near_matches() {
+        let available = vec![
+            "NA12878".to_owned(),
+            "NA12891".to_owned(),
+            "HG002".to_owned(),
+        ];
+
+        assert_eq!(near_matches("NA128788", &available), vec!["NA12878"]);
+        assert_eq!(near_matches("na12891", &available), vec!["NA12891"]);
+        assert_eq!(
+            near_matches("NA12871", &available),
+            vec!["NA12878", "NA12891"]
+        );
+        assert!(near_matches("SAMPLE1", &available).is_empty());
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("abc", ""), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("NA12878", "NA12887"), 2);
+    }
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|x| format!("{:02x}", x)).collect()
+    }
+
+    #[test]
+    fn test_hmac_sha256() {
+        // RFC 4231 test cases 2 and 6
+        assert_eq!(
+            hex(&hmac_sha256(b"Jefe", b"what do ya want for nothing?")),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        assert_eq!(
+            hex(&hmac_sha256(
+                &[0xaa; 131],
+                b"Test Using Larger Than Block-Size Key - Hash Key First"
+            )),
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+        );
+    }
+
+    #[test]
+    fn test_pseudonym() {
+        assert_eq!(
+            pseudonym("Jefe", "what do ya want for nothing?"),
+            "5bdcc146bf60754e"
+        );
+        assert_eq!(
+            pseudonym("secret", "NA12878"),
+            pseudonym("secret", "NA12878")
+        );
+        assert_ne!(
+            pseudonym("secret", "NA12878"),
+            pseudonym("other", "NA12878")
+        );
+        assert_ne!(
+            pseudonym("secret", "NA12878"),
+            pseudonym("secret", "NA12891")
+        );
+    }
+
+    #[test]
+    fn test_unquote() {
+        assert_eq!(unquote("\"Daughter\""), "Daughter");
+        assert_eq!(unquote("NA12878"), "NA12878");
+    }
+}
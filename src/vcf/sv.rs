@@ -0,0 +1,200 @@
+//! Module for structural variants with symbolic alternate alleles (e.g. `<DEL>`)
+
+/// A type of structural variant given by `INFO/SVTYPE` or the ID of a symbolic allele.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SvType {
+    Deletion,
+    Duplication,
+    Inversion,
+    CopyNumberVariation,
+    Insertion,
+}
+
+impl SvType {
+    /// Returns the type for `id` such as `DEL` or `DUP:TANDEM`, where subtypes after `:` are ignored.
+    ///
+    /// Copy numbers such as `CN0` are typed as `CNV`.
+    pub fn from_id(id: &str) -> Option<Self> {
+        match id.split(':').next()? {
+            x if x.len() > 2
+                && x.starts_with("CN")
+                && x[2..].bytes().all(|b| b.is_ascii_digit()) =>
+            {
+                Some(SvType::CopyNumberVariation)
+            }
+            "DEL" => Some(SvType::Deletion),
+            "DUP" => Some(SvType::Duplication),
+            "INV" => Some(SvType::Inversion),
+            "CNV" => Some(SvType::CopyNumberVariation),
+            "INS" => Some(SvType::Insertion),
+            _ => None,
+        }
+    }
+
+    /// Returns the name of gvo class.
+    pub fn class(&self) -> &'static str {
+        match self {
+            SvType::Deletion => "Deletion",
+            SvType::Duplication => "Duplication",
+            SvType::Inversion => "Inversion",
+            SvType::CopyNumberVariation => "CopyNumberVariation",
+            SvType::Insertion => "Insertion",
+        }
+    }
+}
+
+/// A structural variant spanning `begin..=end` (1-based).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct StructuralVariant {
+    pub typ: SvType,
+    pub begin: u64,
+    pub end: u64,
+    /// Confidence interval around `begin` given by `CIPOS`.
+    pub begin_interval: Option<(u64, u64)>,
+    /// Confidence interval around `end` given by `CIEND`.
+    pub end_interval: Option<(u64, u64)>,
+}
+
+/// Returns the ID of a symbolic allele, e.g. `DEL` for `<DEL>`.
+pub fn symbolic_id(allele: &str) -> Option<&str> {
+    allele.strip_prefix('<')?.strip_suffix('>')
+}
+
+impl StructuralVariant {
+    /// Returns a structural variant of the symbolic `allele` at `position`.
+    ///
+    /// The type is taken from `svtype` if given, and the end from `end` or `position + |svlen|`.
+    /// Insertions end at `position`.
+    pub fn new(
+        position: u64,
+        allele: &str,
+        svtype: Option<&str>,
+        end: Option<i64>,
+        svlen: Option<i64>,
+    ) -> Option<Self> {
+        let id = symbolic_id(allele)?;
+        let typ = svtype
+            .and_then(SvType::from_id)
+            .or_else(|| SvType::from_id(id))?;
+
+        let end = match (typ, end, svlen) {
+            (SvType::Insertion, _, _) => position,
+            (_, Some(end), _) if end >= position as i64 => end as u64,
+            (_, _, Some(len)) => position + len.abs() as u64,
+            _ => position,
+        };
+
+        Some(StructuralVariant {
+            typ,
+            begin: position,
+            end,
+            begin_interval: None,
+            end_interval: None,
+        })
+    }
+
+    /// Set confidence intervals by offsets from begin and end, e.g. `CIPOS=-10,20`.
+    ///
+    /// Intervals are clamped to the contig of `length` if known.
+    pub fn with_confidence_intervals(
+        mut self,
+        cipos: Option<(i64, i64)>,
+        ciend: Option<(i64, i64)>,
+        length: Option<u64>,
+    ) -> Self {
+        self.begin_interval = cipos.map(|x| interval(self.begin, x, length));
+        self.end_interval = ciend.map(|x| interval(self.end, x, length));
+        self
+    }
+}
+
+/// Returns `position` plus `offsets` clamped to `1..=length`.
+fn interval(position: u64, offsets: (i64, i64), length: Option<u64>) -> (u64, u64) {
+    let clamp = |offset: i64| {
+        let x = (position as i64).saturating_add(offset).max(1) as u64;
+        length.map_or(x, |l| x.min(l))
+    };
+
+    (
+        clamp(offsets.0.min(offsets.1)),
+        clamp(offsets.0.max(offsets.1)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_symbolic_id() {
+        assert_eq!(symbolic_id("<DEL>"), Some("DEL"));
+        assert_eq!(symbolic_id("<DUP:TANDEM>"), Some("DUP:TANDEM"));
+        assert_eq!(symbolic_id("A"), None);
+    }
+
+    #[test]
+    fn test_sv_type() {
+        assert_eq!(SvType::from_id("DEL:ME:ALU"), Some(SvType::Deletion));
+        assert_eq!(SvType::from_id("DUP:TANDEM"), Some(SvType::Duplication));
+        assert_eq!(SvType::from_id("CN0"), Some(SvType::CopyNumberVariation));
+        assert_eq!(SvType::from_id("CN12"), Some(SvType::CopyNumberVariation));
+        assert_eq!(SvType::from_id("CNX"), None);
+        assert_eq!(SvType::from_id("NON_REF"), None);
+        assert_eq!(SvType::from_id("*"), None);
+    }
+
+    #[test]
+    fn test_structural_variant() {
+        let sv =
+            |allele, svtype, end, svlen| StructuralVariant::new(100, allele, svtype, end, svlen);
+
+        assert_eq!(
+            sv("<DEL>", None, Some(250), Some(-150)),
+            Some(StructuralVariant {
+                typ: SvType::Deletion,
+                begin: 100,
+                end: 250,
+                begin_interval: None,
+                end_interval: None,
+            })
+        );
+        assert_eq!(
+            sv("<DEL>", None, None, Some(-150)).map(|x| x.end),
+            Some(250)
+        );
+        assert_eq!(
+            sv("<CN0>", Some("CNV"), Some(300), None).map(|x| x.typ),
+            Some(SvType::CopyNumberVariation)
+        );
+        assert_eq!(sv("<INS>", None, None, Some(300)).map(|x| x.end), Some(100));
+        assert_eq!(sv("<INV>", None, None, None).map(|x| x.end), Some(100));
+        assert_eq!(sv("<NON_REF>", None, None, None), None);
+        assert_eq!(sv("A", Some("DEL"), Some(250), None), None);
+    }
+
+    #[test]
+    fn test_confidence_intervals() {
+        let sv = StructuralVariant::new(100, "<DEL>", None, Some(250), None)
+            .unwrap()
+            .with_confidence_intervals(Some((-10, 25)), Some((-5, 5)), None);
+
+        assert_eq!(sv.begin_interval, Some((90, 125)));
+        assert_eq!(sv.end_interval, Some((245, 255)));
+    }
+
+    #[test]
+    fn test_interval() {
+        // asymmetric
+        assert_eq!(interval(100, (-1, 300), None), (99, 400));
+        assert_eq!(interval(100, (0, 0), None), (100, 100));
+        // reversed offsets
+        assert_eq!(interval(100, (10, -10), None), (90, 110));
+        // start of contig
+        assert_eq!(interval(5, (-10, 10), None), (1, 15));
+        assert_eq!(interval(1, (-100, -50), None), (1, 1));
+        // end of contig
+        assert_eq!(interval(995, (-10, 10), Some(1000)), (985, 1000));
+        assert_eq!(interval(1000, (50, 100), Some(1000)), (1000, 1000));
+        assert_eq!(interval(100, (i64::MIN, i64::MAX), Some(1000)), (1, 1000));
+    }
+}
@@ -0,0 +1,123 @@
+//! Module for consequence annotations of Ensembl VEP (`INFO/CSQ`)
+
+/// Returns column names listed after `Format: ` in the description of CSQ in the header.
+pub fn format(description: &str) -> Option<Vec<String>> {
+    let (_, format) = description.split_once("Format: ")?;
+
+    Some(
+        format
+            .trim()
+            .trim_end_matches('"')
+            .split('|')
+            .map(|x| x.trim().to_owned())
+            .collect(),
+    )
+}
+
+/// Returns alternate alleles as written in the `Allele` column by VEP.
+///
+/// The first base is removed from all alleles if it is shared by them, and an empty allele is `-`.
+pub fn alleles(reference: &str, alternates: &[&str]) -> Vec<String> {
+    let first = reference.chars().next();
+    let shared = first.is_some()
+        && alternates
+            .iter()
+            .all(|x| !x.is_empty() && x.chars().next() == first)
+        && alternates.iter().any(|x| x.len() != reference.len());
+
+    alternates
+        .iter()
+        .map(|x| {
+            let allele = if shared { &x[1..] } else { x };
+            if allele.is_empty() {
+                "-".to_owned()
+            } else {
+                allele.to_owned()
+            }
+        })
+        .collect()
+}
+
+/// Returns consequences in `value` of CSQ for `allele`, as pairs of a column name and a non-empty value.
+///
+/// All consequences are returned if `format` does not have the `Allele` column.
+pub fn parse<'a>(
+    value: &'a str,
+    format: &'a [String],
+    allele: &str,
+) -> Vec<Vec<(&'a str, &'a str)>> {
+    let index = format.iter().position(|x| x == "Allele");
+
+    value
+        .split(',')
+        .map(|x| x.split('|').collect::<Vec<_>>())
+        .filter(|fields| index.map_or(true, |i| fields.get(i) == Some(&allele)))
+        .map(|fields| {
+            format
+                .iter()
+                .zip(fields)
+                .filter(|(_, v)| !v.is_empty())
+                .map(|(k, v)| (k.as_str(), v))
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn columns() -> Vec<String> {
+        format("Consequence annotations from Ensembl VEP. Format: Allele|Consequence|IMPACT|SYMBOL")
+            .unwrap()
+    }
+
+    #[test]
+    fn test_format() {
+        assert_eq!(columns(), vec!["Allele", "Consequence", "IMPACT", "SYMBOL"]);
+        assert_eq!(format("Predicted consequences"), None);
+    }
+
+    #[test]
+    fn test_alleles() {
+        assert_eq!(alleles("G", &["A"]), vec!["A"]);
+        assert_eq!(alleles("A", &["G", "T"]), vec!["G", "T"]);
+        assert_eq!(alleles("GTC", &["G", "GTCT"]), vec!["-", "TCT"]);
+        assert_eq!(alleles("G", &["GT", "C"]), vec!["GT", "C"]);
+        assert_eq!(alleles("AT", &["AG"]), vec!["AG"]);
+        assert_eq!(alleles("A", &["<DEL>"]), vec!["<DEL>"]);
+    }
+
+    #[test]
+    fn test_parse() {
+        let columns = columns();
+        let value = "G|synonymous_variant|LOW|GENE1,T|stop_gained&splice_region_variant|HIGH|GENE1,T|upstream_gene_variant|MODIFIER|";
+
+        assert_eq!(
+            parse(value, &columns, "G"),
+            vec![vec![
+                ("Allele", "G"),
+                ("Consequence", "synonymous_variant"),
+                ("IMPACT", "LOW"),
+                ("SYMBOL", "GENE1")
+            ]]
+        );
+
+        let t = parse(value, &columns, "T");
+        assert_eq!(t.len(), 2);
+        assert_eq!(
+            t[0][1],
+            ("Consequence", "stop_gained&splice_region_variant")
+        );
+        assert_eq!(
+            t[1],
+            vec![
+                ("Allele", "T"),
+                ("Consequence", "upstream_gene_variant"),
+                ("IMPACT", "MODIFIER")
+            ]
+        );
+
+        assert!(parse(value, &columns, "C").is_empty());
+    }
+}